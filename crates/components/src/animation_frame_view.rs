@@ -0,0 +1,411 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_data::rpg::animation::Frame;
+
+/// Columns of `Frame::cell_data`. Every cell is one row of the table.
+pub mod cell {
+    /// Index of the cell graphic in the animation sheet, or -1 if the cell is unused.
+    pub const PATTERN: usize = 0;
+    pub const X: usize = 1;
+    pub const Y: usize = 2;
+    /// Zoom in percent.
+    pub const ZOOM: usize = 3;
+    /// Counterclockwise rotation in degrees.
+    pub const ANGLE: usize = 4;
+    /// 1 if the cell is flipped horizontally.
+    pub const MIRROR: usize = 5;
+    pub const OPACITY: usize = 6;
+    /// 0 for normal, 1 for additive and 2 for subtractive blending.
+    pub const BLEND_TYPE: usize = 7;
+
+    /// The number of columns every cell has.
+    pub const COLUMNS: usize = 8;
+}
+
+/// The size of one cell in the animation sheet.
+pub const CELL_SIZE: f32 = 192.;
+/// The number of cells in every row of the animation sheet.
+pub const SHEET_COLUMNS: i16 = 5;
+/// The size of the area animations are edited in.
+pub const CANVAS_SIZE: egui::Vec2 = egui::vec2(640., 320.);
+
+/// Canvas for placing the cells of one animation frame.
+#[derive(Default)]
+pub struct AnimationFrameView {
+    /// The index of the selected cell, if any.
+    pub selected_cell: Option<usize>,
+    /// Offset of the pointer from the center of the cell being dragged.
+    drag_offset: Option<egui::Vec2>,
+}
+
+/// Onion skin for a neighbouring frame, drawn underneath the frame being edited.
+pub struct Ghost<'a> {
+    pub frame: &'a Frame,
+    pub tint: egui::Color32,
+}
+
+impl AnimationFrameView {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns true if the cell with the given index exists and is in use.
+    pub fn is_cell_used(frame: &Frame, index: usize) -> bool {
+        index < frame.cell_data.xsize() && frame.cell_data[(index, cell::PATTERN)] >= 0
+    }
+
+    /// Adds a cell to a frame, reusing the first unused cell if there is one, and returns its
+    /// index.
+    pub fn add_cell(frame: &mut Frame, pattern: i16, x: i16, y: i16) -> usize {
+        if frame.cell_data.ysize() < cell::COLUMNS {
+            frame
+                .cell_data
+                .resize(frame.cell_data.xsize(), cell::COLUMNS);
+        }
+
+        let index = (0..frame.cell_data.xsize())
+            .find(|&i| !Self::is_cell_used(frame, i))
+            .unwrap_or_else(|| {
+                let index = frame.cell_data.xsize();
+                frame.cell_data.resize(index + 1, cell::COLUMNS);
+                index
+            });
+
+        let data = &mut frame.cell_data;
+        data[(index, cell::PATTERN)] = pattern;
+        data[(index, cell::X)] = x;
+        data[(index, cell::Y)] = y;
+        data[(index, cell::ZOOM)] = 100;
+        data[(index, cell::ANGLE)] = 0;
+        data[(index, cell::MIRROR)] = 0;
+        data[(index, cell::OPACITY)] = 255;
+        data[(index, cell::BLEND_TYPE)] = 1;
+
+        frame.cell_max = frame.cell_max.max(index as i32 + 1);
+        index
+    }
+
+    /// Marks a cell as unused and drops any unused cells at the end of the frame.
+    pub fn remove_cell(frame: &mut Frame, index: usize) {
+        if index >= frame.cell_data.xsize() {
+            return;
+        }
+        frame.cell_data[(index, cell::PATTERN)] = -1;
+
+        let len = (0..frame.cell_data.xsize())
+            .rev()
+            .find(|&i| Self::is_cell_used(frame, i))
+            .map_or(0, |i| i + 1);
+        frame.cell_data.resize(len, cell::COLUMNS);
+        frame.cell_max = len as i32;
+    }
+
+    /// The area covered by a cell on the screen, ignoring rotation.
+    fn cell_rect(frame: &Frame, index: usize, origin: egui::Pos2, scale: f32) -> egui::Rect {
+        let data = &frame.cell_data;
+        let center = origin
+            + egui::vec2(data[(index, cell::X)] as f32, data[(index, cell::Y)] as f32) * scale;
+        let size = CELL_SIZE * data[(index, cell::ZOOM)] as f32 / 100. * scale;
+        egui::Rect::from_center_size(center, egui::Vec2::splat(size))
+    }
+
+    /// Builds the mesh for one cell of a frame.
+    fn cell_mesh(
+        sheet: &luminol_graphics::Texture,
+        frame: &Frame,
+        index: usize,
+        origin: egui::Pos2,
+        scale: f32,
+        tint: egui::Color32,
+    ) -> egui::Mesh {
+        let data = &frame.cell_data;
+        let pattern = data[(index, cell::PATTERN)];
+        let sheet_size = sheet.size_vec2();
+
+        let uv_min = egui::pos2(
+            (pattern % SHEET_COLUMNS) as f32 * CELL_SIZE / sheet_size.x,
+            (pattern / SHEET_COLUMNS) as f32 * CELL_SIZE / sheet_size.y,
+        );
+        let uv_size = egui::vec2(CELL_SIZE / sheet_size.x, CELL_SIZE / sheet_size.y);
+        let (left, right) = if data[(index, cell::MIRROR)] == 1 {
+            (uv_min.x + uv_size.x, uv_min.x)
+        } else {
+            (uv_min.x, uv_min.x + uv_size.x)
+        };
+        let (top, bottom) = (uv_min.y, uv_min.y + uv_size.y);
+
+        let center = Self::cell_rect(frame, index, origin, scale).center();
+        let half = CELL_SIZE * data[(index, cell::ZOOM)] as f32 / 200. * scale;
+        let (sin, cos) = (data[(index, cell::ANGLE)] as f32).to_radians().sin_cos();
+        // Angles are counterclockwise while the y axis points down
+        let rotate = |x: f32, y: f32| center + egui::vec2(x * cos + y * sin, y * cos - x * sin);
+
+        // egui blends with premultiplied alpha, so a vertex colour with zero alpha results in
+        // additive blending. There's no way to get subtractive blending here, so those cells are
+        // drawn normally.
+        let opacity = data[(index, cell::OPACITY)].clamp(0, 255) as f32 / 255.;
+        let color = egui::Rgba::from(tint).multiply(opacity);
+        let color = if data[(index, cell::BLEND_TYPE)] == 1 {
+            egui::Rgba::from_rgba_premultiplied(color.r(), color.g(), color.b(), 0.)
+        } else {
+            color
+        };
+        let color = egui::Color32::from(color);
+
+        let mut mesh = egui::Mesh::with_texture(sheet.texture_id);
+        for (pos, uv) in [
+            (rotate(-half, -half), egui::pos2(left, top)),
+            (rotate(half, -half), egui::pos2(right, top)),
+            (rotate(half, half), egui::pos2(right, bottom)),
+            (rotate(-half, half), egui::pos2(left, bottom)),
+        ] {
+            mesh.vertices.push(egui::epaint::Vertex { pos, uv, color });
+        }
+        mesh.add_triangle(0, 1, 2);
+        mesh.add_triangle(0, 2, 3);
+        mesh
+    }
+
+    /// Shows the canvas. Clicking on an empty spot adds a cell with the given pattern, and cells
+    /// can be selected and dragged around. The response is marked as changed if the frame was
+    /// modified.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        sheet: Option<&luminol_graphics::Texture>,
+        frame: &mut Frame,
+        ghosts: &[Ghost<'_>],
+        pattern: i16,
+        flash: Option<(i32, egui::Color32)>,
+    ) -> egui::Response {
+        let width = ui.available_width().min(CANVAS_SIZE.x);
+        let scale = width / CANVAS_SIZE.x;
+        let (canvas_rect, mut response) =
+            ui.allocate_exact_size(CANVAS_SIZE * scale, egui::Sense::click_and_drag());
+        let origin = canvas_rect.center();
+        let painter = ui.painter_at(canvas_rect);
+
+        painter.rect_filled(canvas_rect, 0., egui::Color32::from_gray(24));
+        let guide_stroke = egui::Stroke::new(1., egui::Color32::from_gray(64));
+        painter.hline(canvas_rect.x_range(), origin.y, guide_stroke);
+        painter.vline(origin.x, canvas_rect.y_range(), guide_stroke);
+
+        if self
+            .selected_cell
+            .is_some_and(|i| !Self::is_cell_used(frame, i))
+        {
+            self.selected_cell = None;
+        }
+
+        if let Some(sheet) = sheet {
+            for ghost in ghosts {
+                for i in 0..ghost.frame.cell_data.xsize() {
+                    if Self::is_cell_used(ghost.frame, i) {
+                        painter.add(Self::cell_mesh(
+                            sheet,
+                            ghost.frame,
+                            i,
+                            origin,
+                            scale,
+                            ghost.tint,
+                        ));
+                    }
+                }
+            }
+
+            for i in 0..frame.cell_data.xsize() {
+                if Self::is_cell_used(frame, i) {
+                    painter.add(Self::cell_mesh(
+                        sheet,
+                        frame,
+                        i,
+                        origin,
+                        scale,
+                        egui::Color32::WHITE,
+                    ));
+                }
+            }
+        }
+
+        match flash {
+            Some((1, color)) => {
+                painter.rect_filled(
+                    egui::Rect::from_center_size(origin, egui::Vec2::splat(CELL_SIZE * scale)),
+                    0.,
+                    color,
+                );
+            }
+            Some((2, color)) => painter.rect_filled(canvas_rect, 0., color),
+            _ => {}
+        }
+
+        // Cell numbers, drawn on top of everything so they can always be read
+        for i in 0..frame.cell_data.xsize() {
+            if !Self::is_cell_used(frame, i) {
+                continue;
+            }
+            let rect = Self::cell_rect(frame, i, origin, scale);
+            if self.selected_cell == Some(i) {
+                painter.rect_stroke(
+                    rect,
+                    0.,
+                    egui::Stroke::new(2., ui.visuals().selection.stroke.color),
+                );
+            }
+            painter.text(
+                rect.left_top() + egui::vec2(2., 2.),
+                egui::Align2::LEFT_TOP,
+                (i + 1).to_string(),
+                egui::FontId::monospace(12.),
+                egui::Color32::WHITE,
+            );
+        }
+
+        let mut modified = false;
+        let to_cell_space = |pos: egui::Pos2| (pos - origin) / scale;
+        // The topmost cell under the pointer
+        let cell_at = |frame: &Frame, pos: egui::Pos2| {
+            (0..frame.cell_data.xsize()).rev().find(|&i| {
+                Self::is_cell_used(frame, i)
+                    && Self::cell_rect(frame, i, origin, scale).contains(pos)
+            })
+        };
+
+        if response.drag_started() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                self.selected_cell = cell_at(frame, pos);
+                self.drag_offset = self.selected_cell.map(|i| {
+                    to_cell_space(pos)
+                        - egui::vec2(
+                            frame.cell_data[(i, cell::X)] as f32,
+                            frame.cell_data[(i, cell::Y)] as f32,
+                        )
+                });
+            }
+        }
+
+        if response.dragged() {
+            if let (Some(i), Some(offset), Some(pos)) = (
+                self.selected_cell,
+                self.drag_offset,
+                response.interact_pointer_pos(),
+            ) {
+                let pos = to_cell_space(pos) - offset;
+                let (x, y) = (pos.x.round() as i16, pos.y.round() as i16);
+                if frame.cell_data[(i, cell::X)] != x || frame.cell_data[(i, cell::Y)] != y {
+                    frame.cell_data[(i, cell::X)] = x;
+                    frame.cell_data[(i, cell::Y)] = y;
+                    modified = true;
+                }
+            }
+        }
+
+        if response.drag_stopped() {
+            self.drag_offset = None;
+        }
+
+        if response.clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                self.selected_cell = cell_at(frame, pos);
+                if self.selected_cell.is_none() && pattern >= 0 {
+                    let pos = to_cell_space(pos);
+                    self.selected_cell = Some(Self::add_cell(
+                        frame,
+                        pattern,
+                        pos.x.round() as i16,
+                        pos.y.round() as i16,
+                    ));
+                    modified = true;
+                }
+            }
+        }
+
+        if response.hovered()
+            && ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Delete))
+        {
+            if let Some(i) = self.selected_cell.take() {
+                Self::remove_cell(frame, i);
+                modified = true;
+            }
+        }
+
+        if modified {
+            response.mark_changed();
+        }
+        response
+    }
+
+    /// Shows the animation sheet so a pattern can be picked from it. The response is marked as
+    /// changed if the selected pattern changed.
+    pub fn sheet_ui(
+        ui: &mut egui::Ui,
+        sheet: &luminol_graphics::Texture,
+        pattern: &mut i16,
+        cell_size: f32,
+    ) -> egui::Response {
+        let sheet_size = sheet.size_vec2();
+        let scale = cell_size / CELL_SIZE;
+        let (rect, mut response) = ui.allocate_exact_size(sheet_size * scale, egui::Sense::click());
+
+        ui.painter().image(
+            sheet.texture_id,
+            rect,
+            egui::Rect::from_min_max(egui::pos2(0., 0.), egui::pos2(1., 1.)),
+            egui::Color32::WHITE,
+        );
+
+        let rows = (sheet_size.y / CELL_SIZE) as i16;
+        let selected_rect = |pattern: i16| {
+            egui::Rect::from_min_size(
+                rect.min
+                    + egui::vec2(
+                        (pattern % SHEET_COLUMNS) as f32,
+                        (pattern / SHEET_COLUMNS) as f32,
+                    ) * cell_size,
+                egui::Vec2::splat(cell_size),
+            )
+        };
+        if (0..rows * SHEET_COLUMNS).contains(pattern) {
+            ui.painter().rect_stroke(
+                selected_rect(*pattern),
+                0.,
+                egui::Stroke::new(2., ui.visuals().selection.stroke.color),
+            );
+        }
+
+        if response.clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let pos = (pos - rect.min) / cell_size;
+                let new_pattern = (pos.y as i16) * SHEET_COLUMNS + pos.x as i16;
+                if (0..rows * SHEET_COLUMNS).contains(&new_pattern) && new_pattern != *pattern {
+                    *pattern = new_pattern;
+                    response.mark_changed();
+                }
+            }
+        }
+
+        response
+    }
+}
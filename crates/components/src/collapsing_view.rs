@@ -31,6 +31,7 @@ pub struct CollapsingView {
     depersisted_entries: usize,
     expanded_entry: luminol_data::OptionVec<Option<usize>>,
     disable_animations: bool,
+    deleted_entry: Option<usize>,
 }
 
 impl CollapsingView {
@@ -44,6 +45,11 @@ impl CollapsingView {
         self.disable_animations = true;
     }
 
+    /// The index of the entry that was deleted by the last call to [`Self::show`], if any.
+    pub fn deleted_entry(&self) -> Option<usize> {
+        self.deleted_entry
+    }
+
     pub fn show<T>(
         &mut self,
         ui: &mut egui::Ui,
@@ -145,6 +151,7 @@ impl CollapsingView {
 
                 vec.remove(i);
             }
+            self.deleted_entry = deleted_entry;
 
             self.depersisted_entries = vec.len();
             if new_entry {
@@ -0,0 +1,452 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Helpers for working with blocks of event commands.
+//!
+//! A block is a visible command followed by everything that belongs to it: the commands nested inside of it
+//! (indented further) and the hidden commands at the same indent (continuation lines, `Else`, `Branch End`, etc).
+
+use luminol_config::command_db::CommandDB;
+use luminol_data::commands::{CommandDescription, CommandKind, Parameter, ParameterKind};
+use luminol_data::rpg::{EventCommand, MoveCommand, MoveRoute};
+use luminol_data::ParameterType;
+
+type Section = (EventCommand, Vec<EventCommand>);
+
+/// Is this command a part of the block of a command before it?
+pub fn is_hidden(db: &CommandDB, command: &EventCommand) -> bool {
+    command.code != 0 && db.get(command.code).is_some_and(|desc| desc.hidden)
+}
+
+/// Finds the index of the command that the block containing `index` starts with.
+pub fn owner(db: &CommandDB, commands: &[EventCommand], index: usize) -> usize {
+    let indent = commands[index].indent;
+    let mut owner = index;
+    while is_hidden(db, &commands[owner]) {
+        let Some(prev) = commands[..owner].iter().rposition(|c| c.indent <= indent) else {
+            break;
+        };
+        if commands[prev].indent < indent {
+            break;
+        }
+        owner = prev;
+    }
+    owner
+}
+
+/// The same as calling [`owner`] for every command, but in one pass over the list.
+pub fn owners(db: &CommandDB, commands: &[EventCommand]) -> Vec<usize> {
+    // the owner of the last command at each indent, as long as no command with a lower indent came after it
+    let mut last_owners: Vec<Option<usize>> = Vec::new();
+    commands
+        .iter()
+        .enumerate()
+        .map(|(index, command)| {
+            let indent = command.indent;
+            last_owners.resize(indent + 1, None);
+            let owner = if is_hidden(db, command) {
+                last_owners[indent].unwrap_or(index)
+            } else {
+                index
+            };
+            last_owners[indent] = Some(owner);
+            owner
+        })
+        .collect()
+}
+
+/// The length of the block starting at `index`.
+pub fn block_len(db: &CommandDB, commands: &[EventCommand], index: usize) -> usize {
+    let head = &commands[index];
+    if head.code == 0 {
+        return 1;
+    }
+
+    1 + commands[index + 1..]
+        .iter()
+        .take_while(|c| c.indent > head.indent || (c.indent == head.indent && is_hidden(db, c)))
+        .count()
+}
+
+/// The range of the block containing `index`.
+pub fn block_range(
+    db: &CommandDB,
+    commands: &[EventCommand],
+    index: usize,
+) -> std::ops::Range<usize> {
+    let start = owner(db, commands, index);
+    start..start + block_len(db, commands, start)
+}
+
+/// Finds the start of the block directly above the block starting at `start`, if it has the same indent.
+pub fn previous_sibling(db: &CommandDB, commands: &[EventCommand], start: usize) -> Option<usize> {
+    let indent = commands[start].indent;
+    let prev = commands[..start].iter().rposition(|c| c.indent <= indent)?;
+    let prev = owner(db, commands, prev);
+
+    (commands[prev].indent == indent
+        && commands[prev].code != 0
+        && prev + block_len(db, commands, prev) == start)
+        .then_some(prev)
+}
+
+/// Finds the start of the block directly below the block starting at `start`, if it has the same indent.
+pub fn next_sibling(db: &CommandDB, commands: &[EventCommand], start: usize) -> Option<usize> {
+    let indent = commands[start].indent;
+    let next = start + block_len(db, commands, start);
+    let command = commands.get(next)?;
+
+    (command.indent == indent && command.code != 0 && !is_hidden(db, command)).then_some(next)
+}
+
+/// Creates the block of commands that should be inserted for a new command.
+pub fn new_block(desc: &CommandDescription, indent: usize) -> Vec<EventCommand> {
+    let mut head = EventCommand::new(desc.code, indent, vec![]);
+
+    let mut block = match desc.kind {
+        CommandKind::Single(ref parameters) => {
+            default_parameters(parameters, &mut head.parameters);
+            vec![head]
+        }
+        CommandKind::Branch {
+            end_code,
+            ref parameters,
+        } => {
+            default_parameters(parameters, &mut head.parameters);
+            let mut block = vec![head];
+            block.extend(empty_body(indent));
+            block.push(EventCommand::new(end_code, indent, vec![]));
+            block
+        }
+        CommandKind::Multi { .. } => {
+            head.parameters.push(ParameterType::String(String::new()));
+            vec![head]
+        }
+    };
+
+    sync_block(desc, &mut block);
+    block
+}
+
+/// Sets every parameter described by `parameters` to its default value.
+pub fn default_parameters(parameters: &[Parameter], values: &mut Vec<ParameterType>) {
+    for parameter in parameters {
+        default_parameter(parameter, values);
+    }
+}
+
+/// Sets the parameters described by `parameter` to their default values.
+///
+/// Only the first option of a selection is filled in, as every option shares the same indices.
+pub fn default_parameter(parameter: &Parameter, values: &mut Vec<ParameterType>) {
+    match parameter {
+        Parameter::Single { index, kind, .. } => {
+            *get_or_resize!(values, index.as_usize()) = default_value(kind);
+        }
+        Parameter::Selection {
+            index, parameters, ..
+        } => {
+            if let Some((value, parameter)) = parameters.first() {
+                *get_or_resize!(values, index.as_usize()) = ParameterType::Integer(*value as i32);
+                default_parameter(parameter, values);
+            }
+        }
+        Parameter::Group { parameters, .. } => default_parameters(parameters, values),
+        Parameter::Dummy | Parameter::Label(_) => {}
+    }
+}
+
+/// The value a freshly inserted parameter of this kind has.
+pub fn default_value(kind: &ParameterKind) -> ParameterType {
+    match kind {
+        ParameterKind::Int | ParameterKind::IntBool | ParameterKind::Character => {
+            ParameterType::Integer(0)
+        }
+        ParameterKind::Switch
+        | ParameterKind::Variable
+        | ParameterKind::Actor
+        | ParameterKind::Class
+        | ParameterKind::Skill
+        | ParameterKind::Item
+        | ParameterKind::Enemy
+        | ParameterKind::Troop
+        | ParameterKind::State
+        | ParameterKind::Animation
+        | ParameterKind::CommonEvent
+        | ParameterKind::Map => ParameterType::Integer(1),
+        // 0 means no equipment
+        ParameterKind::Weapon | ParameterKind::Armor => ParameterType::Integer(0),
+        ParameterKind::Bool => ParameterType::Bool(false),
+        ParameterKind::String => ParameterType::String(String::new()),
+        ParameterKind::SelfSwitch => ParameterType::String("A".to_string()),
+        ParameterKind::AudioFile => ParameterType::AudioFile(Default::default()),
+        ParameterKind::Color => ParameterType::Color(Default::default()),
+        ParameterKind::Tone => ParameterType::Tone(Default::default()),
+        ParameterKind::MoveRoute => ParameterType::MoveRoute(MoveRoute {
+            list: vec![MoveCommand::new(0, vec![])],
+            ..Default::default()
+        }),
+        ParameterKind::Choices => ParameterType::Array(vec!["Yes".into(), "No".into()]),
+        ParameterKind::Enum { variants } => {
+            ParameterType::Integer(variants.first().map_or(0, |(_, value)| *value as i32))
+        }
+    }
+}
+
+/// The body of a branch with nothing in it.
+pub fn empty_body(indent: usize) -> Vec<EventCommand> {
+    vec![EventCommand::new(0, indent + 1, vec![])]
+}
+
+/// Splits a block into the commands nested directly under the head and the sections that follow it.
+///
+/// Each section is a hidden command at the indent of the head (like `Else` or `Branch End`) paired with the commands nested under it.
+fn split_sections(block: &[EventCommand]) -> (Vec<EventCommand>, Vec<Section>) {
+    let indent = block[0].indent;
+    let mut body = vec![];
+    let mut sections: Vec<Section> = vec![];
+
+    for command in &block[1..] {
+        if command.indent == indent {
+            sections.push((command.clone(), vec![]));
+        } else if let Some((_, section_body)) = sections.last_mut() {
+            section_body.push(command.clone());
+        } else {
+            body.push(command.clone());
+        }
+    }
+
+    (body, sections)
+}
+
+/// Removes the first section with the given code (and optionally a matching first parameter) and returns its body.
+fn take_body(
+    sections: &mut [Section],
+    code: u16,
+    first_parameter: Option<i32>,
+    indent: usize,
+) -> Vec<EventCommand> {
+    sections
+        .iter_mut()
+        .find(|(header, body)| {
+            header.code == code
+                && !body.is_empty()
+                && first_parameter.map_or(true, |value| {
+                    header
+                        .parameters
+                        .first()
+                        .and_then(ParameterType::as_integer)
+                        == Some(&value)
+                })
+        })
+        .map(|(header, body)| {
+            // Don't match this section again
+            header.code = 0;
+            std::mem::take(body)
+        })
+        .unwrap_or_else(|| empty_body(indent))
+}
+
+/// Takes the end of the block (like `Branch End`), or creates a new one.
+fn take_end(sections: &mut Vec<Section>, code: u16, indent: usize) -> EventCommand {
+    sections
+        .iter()
+        .rposition(|(header, _)| header.code == code)
+        .map(|index| sections.remove(index).0)
+        .unwrap_or_else(|| EventCommand::new(code, indent, vec![]))
+}
+
+/// Updates the hidden commands of a block to match the parameters of its head.
+///
+/// Some commands (like Show Choices) store a part of their parameters as separate commands in the list.
+pub fn sync_block(desc: &CommandDescription, block: &mut Vec<EventCommand>) {
+    match desc.code {
+        102 => sync_choices(block),
+        209 => sync_move_route(block),
+        301 => sync_battle_branches(block),
+        _ => {}
+    }
+}
+
+/// Show Choices has a `When [Choice]` branch for every choice, and a `When Cancel` branch if cancelling is a branch.
+fn sync_choices(block: &mut Vec<EventCommand>) {
+    let head = block[0].clone();
+    let indent = head.indent;
+
+    let choices: Vec<String> = head
+        .parameters
+        .first()
+        .and_then(ParameterType::as_array)
+        .map(|choices| {
+            choices
+                .iter()
+                .map(|c| c.text().unwrap_or_default().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    let cancel_type = head
+        .parameters
+        .get(1)
+        .and_then(ParameterType::as_integer)
+        .copied()
+        .unwrap_or_default();
+
+    let (_, mut sections) = split_sections(block);
+    let end = take_end(&mut sections, 404, indent);
+
+    let mut new_block = vec![head];
+    for (index, text) in choices.into_iter().enumerate() {
+        let body = take_body(&mut sections, 402, Some(index as i32), indent);
+        new_block.push(EventCommand::new(
+            402,
+            indent,
+            vec![(index as i32).into(), text.into()],
+        ));
+        new_block.extend(body);
+    }
+    if cancel_type == 5 {
+        let body = take_body(&mut sections, 403, None, indent);
+        new_block.push(EventCommand::new(403, indent, vec![]));
+        new_block.extend(body);
+    }
+    new_block.push(end);
+
+    *block = new_block;
+}
+
+/// Set Move Route lists every move command in the route as a separate command.
+fn sync_move_route(block: &mut Vec<EventCommand>) {
+    let head = block[0].clone();
+    let indent = head.indent;
+
+    let move_commands: Vec<EventCommand> = head
+        .parameters
+        .get(1)
+        .and_then(ParameterType::as_moveroute)
+        .map(|route| {
+            route
+                .list
+                .iter()
+                .filter(|command| command.code != 0)
+                .map(|command| {
+                    EventCommand::new(
+                        509,
+                        indent,
+                        vec![ParameterType::MoveCommand(command.clone())],
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    block.truncate(1);
+    block.extend(move_commands);
+}
+
+/// Battle Processing branches on the outcome of the battle if escaping or losing is allowed.
+fn sync_battle_branches(block: &mut Vec<EventCommand>) {
+    let head = block[0].clone();
+    let indent = head.indent;
+
+    let can_escape = head.parameters.get(1).is_some_and(ParameterType::truthy);
+    let can_lose = head.parameters.get(2).is_some_and(ParameterType::truthy);
+
+    let (_, mut sections) = split_sections(block);
+
+    let mut new_block = vec![head];
+    if can_escape || can_lose {
+        let end = take_end(&mut sections, 604, indent);
+
+        new_block.push(EventCommand::new(601, indent, vec![]));
+        new_block.extend(take_body(&mut sections, 601, None, indent));
+        if can_escape {
+            new_block.push(EventCommand::new(602, indent, vec![]));
+            new_block.extend(take_body(&mut sections, 602, None, indent));
+        }
+        if can_lose {
+            new_block.push(EventCommand::new(603, indent, vec![]));
+            new_block.extend(take_body(&mut sections, 603, None, indent));
+        }
+        new_block.push(end);
+    }
+
+    *block = new_block;
+}
+
+/// Does this Conditional Branch have an `Else` branch?
+pub fn has_else(block: &[EventCommand]) -> bool {
+    let indent = block[0].indent;
+    block
+        .iter()
+        .any(|command| command.indent == indent && command.code == 411)
+}
+
+/// Adds or removes the `Else` branch of a Conditional Branch.
+pub fn set_else(block: &mut Vec<EventCommand>, has_else: bool) {
+    let head = block[0].clone();
+    let indent = head.indent;
+
+    let (mut body, mut sections) = split_sections(block);
+    if body.is_empty() {
+        body = empty_body(indent);
+    }
+    let end = take_end(&mut sections, 412, indent);
+
+    let mut new_block = vec![head];
+    new_block.extend(body);
+    if has_else {
+        new_block.push(EventCommand::new(411, indent, vec![]));
+        new_block.extend(take_body(&mut sections, 411, None, indent));
+    }
+    new_block.push(end);
+
+    *block = new_block;
+}
+
+/// Joins the lines of a multiline command together.
+pub fn multi_text(block: &[EventCommand], code: u16) -> String {
+    let line = |command: &EventCommand| {
+        command
+            .parameters
+            .first()
+            .and_then(ParameterType::text)
+            .unwrap_or_default()
+            .into_owned()
+    };
+
+    std::iter::once(line(&block[0]))
+        .chain(block[1..].iter().filter(|c| c.code == code).map(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Splits text into the lines of a multiline command.
+pub fn set_multi_text(block: &mut Vec<EventCommand>, code: u16, text: &str) {
+    let indent = block[0].indent;
+    let mut lines = text.split('\n');
+
+    block.truncate(1);
+    block[0].parameters = vec![lines.next().unwrap_or_default().into()];
+    block.extend(lines.map(|line| EventCommand::new(code, indent, vec![line.into()])));
+}
@@ -22,16 +22,390 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-impl super::CommandView {
-    pub fn command_ui<'i, I>(
+use luminol_config::command_db::CommandDB;
+use luminol_data::commands::{CommandDescription, CommandKind, Parameter, ParameterKind};
+use luminol_data::rpg::{EventCommand, MoveCommand};
+use luminol_data::ParameterType;
+
+use super::{block, parameter_ui::database_name, CommandView};
+
+const COMMENT_COLOR: egui::Color32 = egui::Color32::from_rgb(110, 190, 110);
+const BRANCH_COLOR: egui::Color32 = egui::Color32::from_rgb(110, 170, 230);
+
+impl CommandView {
+    /// Displays the editor for a block of commands, returning whether anything was changed.
+    ///
+    /// The first command of the block is the one described by `desc`.
+    pub fn command_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        desc: &CommandDescription,
+        commands: &mut Vec<EventCommand>,
+    ) -> bool {
+        let mut changed = false;
+
+        match desc.kind {
+            CommandKind::Multi { code, highlight } => {
+                let mut text = block::multi_text(commands, code);
+
+                let theme = update_state.global_config.theme;
+                let mut layouter = |ui: &egui::Ui, string: &str, wrap_width: f32| {
+                    let mut layout_job =
+                        crate::syntax_highlighting::highlight(ui.ctx(), theme, string, "rb");
+                    layout_job.wrap.max_width = wrap_width;
+                    ui.fonts(|f| f.layout_job(layout_job))
+                };
+
+                let text_edit = egui::TextEdit::multiline(&mut text)
+                    .font(egui::TextStyle::Monospace)
+                    .desired_rows(8)
+                    .desired_width(f32::INFINITY);
+                let response = if highlight {
+                    ui.add(text_edit.code_editor().layouter(&mut layouter))
+                } else {
+                    ui.add(text_edit)
+                };
+
+                if response.changed() {
+                    block::set_multi_text(commands, code, &text);
+                    changed = true;
+                }
+            }
+            CommandKind::Single(ref parameters) | CommandKind::Branch { ref parameters, .. } => {
+                for parameter in parameters {
+                    changed |= self.parameter_ui(ui, update_state, parameter, &mut commands[0]);
+                }
+
+                match desc.code {
+                    // Conditional Branch
+                    111 => {
+                        let mut has_else = block::has_else(commands);
+                        if ui.checkbox(&mut has_else, "Else Branch").changed() {
+                            block::set_else(commands, has_else);
+                            changed = true;
+                        }
+                    }
+                    // Shop Processing
+                    302 => changed |= self.shop_goods_ui(ui, update_state, commands),
+                    _ => {}
+                }
+
+                if changed {
+                    block::sync_block(desc, commands);
+                }
+            }
+        }
+
+        changed
+    }
+
+    /// Shop Processing sells anything after the first item through extra commands.
+    fn shop_goods_ui(
         &mut self,
-        _ui: &mut egui::Ui,
-        _db: &luminol_config::command_db::CommandDB,
-        (_index, _command): (usize, &'i mut luminol_data::rpg::EventCommand),
-        _iter: &mut std::iter::Peekable<I>,
-    ) where
-        I: Iterator<Item = (usize, &'i mut luminol_data::rpg::EventCommand)>,
-    {
-        todo!()
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        commands: &mut Vec<EventCommand>,
+    ) -> bool {
+        let Some(goods_desc) = update_state
+            .project_config
+            .as_ref()
+            .and_then(|config| config.command_db.get(605))
+            .cloned()
+        else {
+            return false;
+        };
+        let CommandKind::Single(ref parameters) = goods_desc.kind else {
+            return false;
+        };
+
+        let mut changed = false;
+        let mut remove = None;
+
+        for (index, command) in commands.iter_mut().enumerate().skip(1) {
+            if command.code != 605 {
+                continue;
+            }
+
+            ui.group(|ui| {
+                for parameter in parameters {
+                    changed |= self.parameter_ui(ui, update_state, parameter, command);
+                }
+
+                if ui.button("Remove").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+
+        if let Some(index) = remove {
+            commands.remove(index);
+            changed = true;
+        }
+
+        if ui.button("Add Goods").clicked() {
+            let mut command = EventCommand::new(605, commands[0].indent, vec![]);
+            block::default_parameters(parameters, &mut command.parameters);
+            commands.push(command);
+            changed = true;
+        }
+
+        changed
+    }
+}
+
+/// The text shown for a command in the command list. `owner` is the command the block it's a
+/// part of starts with, see [`block::owners`].
+pub(super) fn command_text(
+    db: &CommandDB,
+    data: &luminol_core::Data,
+    command: &EventCommand,
+    owner: &EventCommand,
+) -> egui::RichText {
+    if command.code == 0 {
+        return egui::RichText::new("@>").monospace();
+    }
+
+    let Some(desc) = db.get(command.code) else {
+        return error!(format!(
+            "@>Unknown Command {}: {:?}",
+            command.code, command.parameters
+        ));
+    };
+
+    let (text, color) = if desc.hidden {
+        let owner_desc = db.get(owner.code);
+        let text = match owner_desc.map(|desc| &desc.kind) {
+            Some(CommandKind::Multi { code, .. }) if *code == command.code => {
+                format!(" : {}", first_string(command))
+            }
+            _ => hidden_text(data, desc, command),
+        };
+        (text, owner_desc.and_then(command_color))
+    } else {
+        let text = match desc.kind {
+            CommandKind::Multi { .. } => format!("@>{}: {}", desc.name, first_string(command)),
+            CommandKind::Single(ref parameters) | CommandKind::Branch { ref parameters, .. } => {
+                let mut parts = vec![];
+                parameter_parts(data, parameters, command, &mut parts);
+                if parts.is_empty() {
+                    format!("@>{}", desc.name)
+                } else {
+                    let parts = parts
+                        .into_iter()
+                        .map(|(name, _, value)| format!("{name}: {value}"))
+                        .collect::<Vec<_>>();
+                    format!("@>{}: {}", desc.name, parts.join(", "))
+                }
+            }
+        };
+        (text, command_color(desc))
+    };
+
+    match color {
+        Some(color) => color_text!(text, color),
+        None => egui::RichText::new(text).monospace(),
+    }
+}
+
+/// The text for hidden commands like `Else`, `When [Yes]` or the move commands of Set Move Route.
+fn hidden_text(
+    data: &luminol_core::Data,
+    desc: &CommandDescription,
+    command: &EventCommand,
+) -> String {
+    // Set Move Route
+    if command.code == 509 {
+        return match command.parameters.first() {
+            Some(ParameterType::MoveCommand(move_command)) => {
+                format!("  $>{}", move_command_text(move_command))
+            }
+            _ => "  $>".to_string(),
+        };
+    }
+
+    let mut parts = vec![];
+    if let CommandKind::Single(ref parameters) = desc.kind {
+        parameter_parts(data, parameters, command, &mut parts);
+    }
+
+    let mut text = format!(" : {}", desc.name);
+    for (_, kind, value) in parts {
+        // Integers here are usually indices that mean nothing to the user
+        if !matches!(kind, ParameterKind::Int) {
+            text.push_str(&format!(" [{value}]"));
+        }
+    }
+    text
+}
+
+fn command_color(desc: &CommandDescription) -> Option<egui::Color32> {
+    // Comment
+    if desc.code == 108 {
+        Some(COMMENT_COLOR)
+    } else if matches!(desc.kind, CommandKind::Branch { .. }) {
+        Some(BRANCH_COLOR)
+    } else {
+        None
+    }
+}
+
+fn first_string(command: &EventCommand) -> std::borrow::Cow<'_, str> {
+    command
+        .parameters
+        .first()
+        .and_then(ParameterType::text)
+        .unwrap_or_default()
+}
+
+/// Collects the name, kind and text of every parameter that applies to this command.
+///
+/// Only the chosen option of a selection is included.
+fn parameter_parts<'a>(
+    data: &luminol_core::Data,
+    parameters: &'a [Parameter],
+    command: &EventCommand,
+    parts: &mut Vec<(&'a str, &'a ParameterKind, String)>,
+) {
+    for parameter in parameters {
+        match parameter {
+            Parameter::Single {
+                index, name, kind, ..
+            } => {
+                let text = command
+                    .parameters
+                    .get(index.as_usize())
+                    .map_or_else(|| "?".to_string(), |value| value_text(data, kind, value));
+                parts.push((name.as_str(), kind, text));
+            }
+            Parameter::Selection {
+                index, parameters, ..
+            } => {
+                let selected = command.parameters.get(index.as_usize()).map_or(0, integer);
+                if let Some((_, parameter)) = parameters
+                    .iter()
+                    .find(|(value, _)| *value as i32 == selected)
+                {
+                    parameter_parts(data, std::slice::from_ref(parameter), command, parts);
+                }
+            }
+            Parameter::Group { parameters, .. } => {
+                parameter_parts(data, parameters, command, parts);
+            }
+            Parameter::Dummy | Parameter::Label(_) => {}
+        }
+    }
+}
+
+fn integer(value: &ParameterType) -> i32 {
+    match value {
+        ParameterType::Integer(v) => *v,
+        ParameterType::Bool(v) => *v as i32,
+        ParameterType::Float(v) => *v as i32,
+        _ => 0,
+    }
+}
+
+/// A human readable version of a parameter.
+pub(super) fn value_text(
+    data: &luminol_core::Data,
+    kind: &ParameterKind,
+    value: &ParameterType,
+) -> String {
+    match kind {
+        ParameterKind::Int => integer(value).to_string(),
+        ParameterKind::String | ParameterKind::SelfSwitch => {
+            value.text().unwrap_or_default().into_owned()
+        }
+        ParameterKind::IntBool | ParameterKind::Bool => {
+            (if value.truthy() { "Yes" } else { "No" }).to_string()
+        }
+        ParameterKind::Character => match integer(value) {
+            -1 => "Player".to_string(),
+            0 => "This Event".to_string(),
+            id => format!("[EV{id:0>3}]"),
+        },
+        ParameterKind::AudioFile => match value.as_audiofile() {
+            Some(audio_file) => format!(
+                "'{}', {}, {}",
+                audio_file
+                    .name
+                    .as_ref()
+                    .map_or("(None)", |name| name.as_str()),
+                audio_file.volume,
+                audio_file.pitch
+            ),
+            None => "?".to_string(),
+        },
+        ParameterKind::Color => match value.as_color() {
+            Some(color) => format!(
+                "({}, {}, {}, {})",
+                color.red, color.green, color.blue, color.alpha
+            ),
+            None => "?".to_string(),
+        },
+        ParameterKind::Tone => match value.as_tone() {
+            Some(tone) => format!(
+                "({}, {}, {}, {})",
+                tone.red, tone.green, tone.blue, tone.gray
+            ),
+            None => "?".to_string(),
+        },
+        ParameterKind::MoveRoute => match value.as_moveroute() {
+            Some(route) => {
+                let len = route.list.iter().filter(|c| c.code != 0).count();
+                format!("{len} command(s)")
+            }
+            None => "?".to_string(),
+        },
+        ParameterKind::Choices => value
+            .as_array()
+            .map(|choices| {
+                choices
+                    .iter()
+                    .map(|choice| choice.text().unwrap_or_default())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default(),
+        ParameterKind::Enum { variants } => {
+            let value = integer(value);
+            variants
+                .iter()
+                .find(|(_, v)| *v as i32 == value)
+                .map_or_else(|| value.to_string(), |(name, _)| name.clone())
+        }
+        _ => {
+            let id = integer(value);
+            match database_name(data, kind, id) {
+                Some(name) => format!("[{id:0>4}: {name}]"),
+                None if id == 0 => "(None)".to_string(),
+                None => format!("[{id:0>4}]"),
+            }
+        }
+    }
+}
+
+/// The text shown for a move command, like `Jump: 1, 0`.
+pub(crate) fn move_command_text(command: &MoveCommand) -> String {
+    let parameters = command
+        .parameters
+        .iter()
+        .map(|parameter| match parameter {
+            ParameterType::Integer(v) => v.to_string(),
+            ParameterType::AudioFile(audio_file) => audio_file
+                .name
+                .as_ref()
+                .map_or("(None)".to_string(), |name| name.to_string()),
+            parameter => parameter
+                .text()
+                .map_or_else(|| format!("{parameter:?}"), |text| text.into_owned()),
+        })
+        .collect::<Vec<_>>();
+
+    if parameters.is_empty() {
+        command.name().to_string()
+    } else {
+        format!("{}: {}", command.name(), parameters.join(", "))
     }
 }
@@ -24,30 +24,54 @@
 
 #[macro_use]
 mod macros;
+mod block;
 mod command_ui;
 mod parameter_ui;
 mod ui;
 
+pub(crate) use command_ui::move_command_text;
+pub(crate) use parameter_ui::kind_ui;
+
 use std::collections::HashMap;
 
+use luminol_data::rpg::EventCommand;
+
 pub struct CommandView {
-    _selected_index: usize,
-    _window_state: WindowState,
-    _id: egui::Id,
-    _modals: HashMap<u64, bool>, // todo find a better way to handle modals
+    selected_index: Option<usize>,
+    window_state: WindowState,
+    id: egui::Id,
+    /// The sound tabs of the audio file parameters in the edit window
+    sound_tabs: HashMap<egui::Id, crate::SoundTab>,
+    /// The move route views of the move route parameters in the edit window
+    move_route_views: HashMap<egui::Id, crate::MoveRouteView>,
 }
 
 enum WindowState {
     None,
+    /// Picking a command to insert at `index`.
+    Insert {
+        index: usize,
+        indent: usize,
+        search: String,
+    },
+    /// Editing a copy of the commands at `index..index + replace_len`.
+    ///
+    /// `replace_len` is 0 for commands that haven't been inserted yet.
+    Edit {
+        index: usize,
+        replace_len: usize,
+        commands: Vec<EventCommand>,
+    },
 }
 
 impl Default for CommandView {
     fn default() -> Self {
         Self {
-            _selected_index: 0,
-            _window_state: WindowState::None,
-            _id: egui::Id::new("command_view"),
-            _modals: HashMap::new(),
+            selected_index: None,
+            window_state: WindowState::None,
+            id: egui::Id::new("command_view"),
+            sound_tabs: HashMap::new(),
+            move_route_views: HashMap::new(),
         }
     }
 }
@@ -55,7 +79,7 @@ impl Default for CommandView {
 impl CommandView {
     pub fn new(id: impl std::hash::Hash) -> Self {
         Self {
-            _id: egui::Id::new(id),
+            id: egui::Id::new(id),
             ..Default::default()
         }
     }
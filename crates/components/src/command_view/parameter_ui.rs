@@ -22,16 +22,436 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use super::CommandView;
+use itertools::Itertools;
+use luminol_data::commands::{Parameter, ParameterKind};
+use luminol_data::rpg::EventCommand;
+use luminol_data::ParameterType;
+use strum::IntoEnumIterator;
+
+use super::{block, CommandView};
+use crate::{Field, OptionalIdComboBox};
 
 impl CommandView {
-    #[allow(clippy::only_used_in_recursion)]
+    /// Displays the widgets for a parameter of `command`, returning whether anything was changed.
     pub fn parameter_ui(
         &mut self,
-        _ui: &mut egui::Ui,
-        _parameter: &luminol_data::commands::Parameter,
-        _command: &mut luminol_data::rpg::EventCommand,
-    ) {
-        todo!()
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        parameter: &Parameter,
+        command: &mut EventCommand,
+    ) -> bool {
+        let mut changed = false;
+
+        match parameter {
+            Parameter::Single {
+                index,
+                description,
+                name,
+                kind: ParameterKind::AudioFile,
+                guid,
+            } => {
+                let id = self.id.with(command.guid).with(guid);
+                let value = get_or_resize!(command.parameters, index.as_usize()).into_audiofile();
+
+                // The parameter name doubles as the folder the file is from
+                let source = luminol_audio::Source::iter()
+                    .find(|source| source.to_string() == *name)
+                    .unwrap_or(luminol_audio::Source::SE);
+                let tab = self.sound_tabs.entry(id).or_insert_with(|| {
+                    crate::SoundTab::new(update_state.filesystem, source, value.clone())
+                });
+
+                ui.label(format!("{name}:")).on_hover_text(description);
+                ui.group(|ui| {
+                    ui.set_height(300.);
+                    tab.ui(ui, update_state);
+                });
+
+                if tab.audio_file != *value {
+                    *value = tab.audio_file.clone();
+                    changed = true;
+                }
+            }
+            Parameter::Single {
+                index,
+                description,
+                name,
+                kind: ParameterKind::MoveRoute,
+                guid,
+            } => {
+                let id = self.id.with(command.guid).with(guid);
+                let value = get_or_resize!(command.parameters, index.as_usize()).into_moveroute();
+                let view = self
+                    .move_route_views
+                    .entry(id)
+                    .or_insert_with(|| crate::MoveRouteView::new(id));
+
+                ui.label(format!("{name}:")).on_hover_text(description);
+                ui.group(|ui| {
+                    changed = view.ui(ui, update_state, value);
+                });
+            }
+            Parameter::Single {
+                index,
+                description,
+                name,
+                kind,
+                guid,
+            } => {
+                let id = self.id.with(command.guid).with(guid);
+                let value = get_or_resize!(command.parameters, index.as_usize());
+
+                changed = ui
+                    .add(Field::new(name, |ui: &mut egui::Ui| {
+                        kind_ui(ui, update_state, id, kind, value)
+                    }))
+                    .on_hover_text(description)
+                    .changed();
+            }
+            Parameter::Selection {
+                index, parameters, ..
+            } => {
+                let index = index.as_usize();
+                for (selection_value, parameter) in parameters {
+                    let selection_value = *selection_value as i32;
+                    let selected = *get_or_resize!(command.parameters, index).into_integer();
+
+                    ui.horizontal(|ui| {
+                        if ui.radio(selected == selection_value, "").clicked()
+                            && selected != selection_value
+                        {
+                            *command.parameters[index].into_integer() = selection_value;
+                            // The options share indices, so reset them to values that make sense for this option
+                            block::default_parameter(parameter, &mut command.parameters);
+                            changed = true;
+                        }
+
+                        ui.add_enabled_ui(selected == selection_value, |ui| {
+                            ui.vertical(|ui| {
+                                changed |= self.parameter_ui(ui, update_state, parameter, command);
+                            });
+                        });
+                    });
+                }
+            }
+            Parameter::Group { parameters, .. } => {
+                ui.group(|ui| {
+                    for parameter in parameters {
+                        changed |= self.parameter_ui(ui, update_state, parameter, command);
+                    }
+                });
+            }
+            Parameter::Label(text) => {
+                ui.label(text);
+            }
+            Parameter::Dummy => {}
+        }
+
+        changed
     }
 }
+
+/// Displays the widget used to edit a single parameter.
+pub(crate) fn kind_ui(
+    ui: &mut egui::Ui,
+    update_state: &luminol_core::UpdateState<'_>,
+    id: egui::Id,
+    kind: &ParameterKind,
+    value: &mut ParameterType,
+) -> egui::Response {
+    let old_value = value.clone();
+
+    let mut response = match kind {
+        ParameterKind::Int => ui.add(egui::DragValue::new(value.into_integer())),
+        ParameterKind::String => string_edit(ui, value),
+        ParameterKind::IntBool => {
+            let value = value.into_integer();
+            let mut checked = *value != 0;
+            let response = ui.checkbox(&mut checked, "");
+            *value = checked as i32;
+            response
+        }
+        ParameterKind::Bool => ui.checkbox(value.into_bool(), ""),
+        ParameterKind::SelfSwitch => {
+            let mut self_switch = value
+                .text()
+                .map_or("A".to_string(), |text| text.into_owned());
+            let combo_box = egui::ComboBox::from_id_source(id)
+                .selected_text(self_switch.as_str())
+                .show_ui(ui, |ui| {
+                    for letter in ["A", "B", "C", "D"] {
+                        ui.selectable_value(&mut self_switch, letter.to_string(), letter);
+                    }
+                });
+            if value.text().as_deref() != Some(self_switch.as_str()) {
+                value.set_text(self_switch);
+            }
+            combo_box.response
+        }
+        ParameterKind::Enum { variants } => {
+            let value = value.into_integer();
+            let selected_text = variants
+                .iter()
+                .find(|(_, v)| *v as i32 == *value)
+                .map_or_else(|| value.to_string(), |(name, _)| name.clone());
+            egui::ComboBox::from_id_source(id)
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (name, v) in variants {
+                        ui.selectable_value(value, *v as i32, name.as_str());
+                    }
+                })
+                .response
+        }
+        ParameterKind::Character => {
+            let value = value.into_integer();
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source(id)
+                    .selected_text(match *value {
+                        -1 => "Player",
+                        0 => "This Event",
+                        _ => "Event",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(value, -1, "Player");
+                        ui.selectable_value(value, 0, "This Event");
+                        if ui.selectable_label(*value > 0, "Event").clicked() && *value <= 0 {
+                            *value = 1;
+                        }
+                    });
+                if *value > 0 {
+                    ui.add(
+                        egui::DragValue::new(value)
+                            .clamp_range(1..=999)
+                            .prefix("EV"),
+                    );
+                }
+            })
+            .response
+        }
+        ParameterKind::Color => {
+            let color = value.into_color();
+            ui.horizontal(|ui| {
+                for (label, channel) in [
+                    ("R", &mut color.red),
+                    ("G", &mut color.green),
+                    ("B", &mut color.blue),
+                    ("A", &mut color.alpha),
+                ] {
+                    ui.label(label);
+                    ui.add(egui::DragValue::new(channel).clamp_range(0.0..=255.0));
+                }
+            })
+            .response
+        }
+        ParameterKind::Tone => {
+            let tone = value.into_tone();
+            ui.horizontal(|ui| {
+                for (label, channel, min) in [
+                    ("R", &mut tone.red, -255.0),
+                    ("G", &mut tone.green, -255.0),
+                    ("B", &mut tone.blue, -255.0),
+                    ("Gray", &mut tone.gray, 0.0),
+                ] {
+                    ui.label(label);
+                    ui.add(egui::DragValue::new(channel).clamp_range(min..=255.0));
+                }
+            })
+            .response
+        }
+        ParameterKind::Choices => {
+            let choices = value.into_array();
+            ui.vertical(|ui| {
+                let mut remove = None;
+                for (index, choice) in choices.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        string_edit(ui, choice);
+                        if ui.button("-").clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = remove {
+                    choices.remove(index);
+                }
+
+                // RPG Maker XP only has room for 4 choices
+                if choices.len() < 4 && ui.button("+").clicked() {
+                    choices.push(ParameterType::String(String::new()));
+                }
+            })
+            .response
+        }
+        ParameterKind::MoveRoute => {
+            // Move routes are edited by `parameter_ui` as they need a move route view
+            ui.label(super::command_ui::value_text(
+                update_state.data,
+                kind,
+                value,
+            ))
+        }
+        ParameterKind::AudioFile => {
+            // Audio files are edited by `parameter_ui` as they need a sound tab
+            ui.label(super::command_ui::value_text(
+                update_state.data,
+                kind,
+                value,
+            ))
+        }
+        _ => {
+            let value = value.into_integer();
+            let entries = database_entries(update_state.data, kind);
+            // Equipment can be removed by setting it to 0
+            let allow_none = matches!(kind, ParameterKind::Weapon | ParameterKind::Armor);
+
+            let mut selected = entries.iter().position(|(id, _)| *id as i32 == *value);
+            let response = ui.add(
+                OptionalIdComboBox::new(update_state, id, &mut selected, 0..entries.len(), |i| {
+                    format!("{:0>4}: {}", entries[i].0, entries[i].1)
+                })
+                .allow_none(allow_none),
+            );
+            if response.changed() {
+                *value = selected.map_or(0, |i| entries[i].0 as i32);
+            }
+            response
+        }
+    };
+
+    if *value != old_value {
+        response.mark_changed();
+    }
+    response
+}
+
+/// Edits a string parameter. Raw strings, like ones that aren't UTF-8 or that are saved with their
+/// encoding, are left alone until their text is actually changed.
+fn string_edit(ui: &mut egui::Ui, value: &mut ParameterType) -> egui::Response {
+    if !value.is_raw() {
+        return ui.text_edit_singleline(value.into_string());
+    }
+
+    let mut text = value.text().unwrap_or_default().into_owned();
+    let response = ui.text_edit_singleline(&mut text);
+    if response.changed() {
+        value.set_text(text);
+    }
+    response
+}
+
+/// Calls `f` with the ids and names of everything a database parameter of this kind can refer to.
+///
+/// Returns `None` if this kind of parameter doesn't refer to the database.
+fn with_database_names<R>(
+    data: &luminol_core::Data,
+    kind: &ParameterKind,
+    f: impl FnOnce(&mut dyn Iterator<Item = (usize, &str)>) -> R,
+) -> Option<R> {
+    // RPG Maker VX and VX Ace projects keep their database in different types
+    let editor_ver = data.editor_ver();
+    macro_rules! entries {
+        ($getter:ident, $vx_getter:ident, $ace_getter:ident) => {
+            match editor_ver {
+                Some(luminol_config::RMVer::VX) => f(&mut data
+                    .$vx_getter()
+                    .data
+                    .iter()
+                    .enumerate()
+                    .map(|(index, entry)| (index + 1, entry.name.as_str()))),
+                Some(luminol_config::RMVer::Ace) => f(&mut data
+                    .$ace_getter()
+                    .data
+                    .iter()
+                    .enumerate()
+                    .map(|(index, entry)| (index + 1, entry.name.as_str()))),
+                _ => f(&mut data
+                    .$getter()
+                    .data
+                    .iter()
+                    .enumerate()
+                    .map(|(index, entry)| (index + 1, entry.name.as_str()))),
+            }
+        };
+    }
+    macro_rules! system_names {
+        ($field:ident) => {
+            match editor_ver {
+                Some(luminol_config::RMVer::VX) => f(&mut data
+                    .vx_system()
+                    .$field
+                    .iter()
+                    .enumerate()
+                    .map(|(index, name)| (index + 1, name.as_str()))),
+                Some(luminol_config::RMVer::Ace) => f(&mut data
+                    .ace_system()
+                    .$field
+                    .iter()
+                    .enumerate()
+                    .map(|(index, name)| (index + 1, name.as_str()))),
+                _ => f(&mut data
+                    .system()
+                    .$field
+                    .iter()
+                    .enumerate()
+                    .map(|(index, name)| (index + 1, name.as_str()))),
+            }
+        };
+    }
+
+    Some(match kind {
+        ParameterKind::Switch => system_names!(switches),
+        ParameterKind::Variable => system_names!(variables),
+        ParameterKind::Actor => entries!(actors, vx_actors, ace_actors),
+        ParameterKind::Class => entries!(classes, vx_classes, ace_classes),
+        ParameterKind::Skill => entries!(skills, vx_skills, ace_skills),
+        ParameterKind::Item => entries!(items, vx_items, ace_items),
+        ParameterKind::Weapon => entries!(weapons, vx_weapons, ace_weapons),
+        ParameterKind::Armor => entries!(armors, vx_armors, ace_armors),
+        ParameterKind::Enemy => entries!(enemies, vx_enemies, ace_enemies),
+        ParameterKind::Troop => entries!(troops, vx_troops, ace_troops),
+        ParameterKind::State => entries!(states, vx_states, ace_states),
+        ParameterKind::Animation => entries!(animations, vx_animations, ace_animations),
+        ParameterKind::CommonEvent => f(&mut data
+            .common_events()
+            .data
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| (index + 1, entry.name.as_str()))),
+        ParameterKind::Map => {
+            let map_infos = data.map_infos();
+            let mut entries = map_infos
+                .data
+                .keys()
+                .copied()
+                .sorted_unstable()
+                .map(|id| (id, map_infos.data[&id].name.as_str()));
+            f(&mut entries)
+        }
+        _ => return None,
+    })
+}
+
+/// The ids and names of everything a database parameter of this kind can refer to.
+pub(super) fn database_entries(
+    data: &luminol_core::Data,
+    kind: &ParameterKind,
+) -> Vec<(usize, String)> {
+    with_database_names(data, kind, |entries| {
+        entries.map(|(id, name)| (id, name.to_string())).collect()
+    })
+    .unwrap_or_default()
+}
+
+/// The name of the database entry a parameter of this kind refers to.
+pub(super) fn database_name(
+    data: &luminol_core::Data,
+    kind: &ParameterKind,
+    id: i32,
+) -> Option<String> {
+    with_database_names(data, kind, |entries| {
+        entries
+            .find(|(entry_id, _)| *entry_id as i32 == id)
+            .map(|(_, name)| name.to_string())
+    })
+    .flatten()
+}
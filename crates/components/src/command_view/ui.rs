@@ -22,14 +22,485 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-impl super::CommandView {
-    #[allow(clippy::ptr_arg)]
+use luminol_config::command_db::CommandDB;
+use luminol_data::commands::{CommandDescription, CommandKind};
+use luminol_data::rpg::EventCommand;
+
+use super::{block, command_ui::command_text, CommandView, WindowState};
+use crate::UiExt;
+
+/// Where copied commands are stored in egui memory, so they can be pasted into any command view.
+const CLIPBOARD_ID: &str = "luminol_command_clipboard";
+
+enum Action {
+    Insert(usize),
+    Edit(usize),
+    Delete(usize),
+    Copy(usize),
+    Cut(usize),
+    Paste(usize),
+    MoveUp(usize),
+    MoveDown(usize),
+}
+
+impl CommandView {
+    /// Displays the command list.
+    ///
+    /// The returned response is marked as changed if any of the commands were modified.
     pub fn ui(
         &mut self,
-        _ui: &mut egui::Ui,
-        _db: &luminol_config::command_db::CommandDB,
-        _commands: &mut Vec<luminol_data::rpg::EventCommand>,
-    ) {
-        todo!()
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        commands: &mut Vec<EventCommand>,
+    ) -> egui::Response {
+        // Lists always end with an empty command, which is where new commands get inserted
+        if commands
+            .last()
+            .map_or(true, |command| command.code != 0 || command.indent != 0)
+        {
+            commands.push(EventCommand::new(0, 0, vec![]));
+        }
+        if self
+            .selected_index
+            .is_some_and(|index| index >= commands.len())
+        {
+            self.selected_index = None;
+        }
+
+        let db = &update_state
+            .project_config
+            .as_ref()
+            .expect("project not loaded")
+            .command_db;
+        let data = &*update_state.data;
+
+        let owners = block::owners(db, commands);
+        let selected_range = self
+            .selected_index
+            .map(|index| block::block_range(db, commands, index));
+        let clipboard_full = ui.data(|d| {
+            d.get_temp::<Vec<EventCommand>>(egui::Id::new(CLIPBOARD_ID))
+                .is_some()
+        });
+
+        let mut action = None;
+        let mut has_focus = false;
+
+        // The list can't change while a command is being edited, or the edit would be saved in the wrong place
+        let is_enabled = matches!(self.window_state, WindowState::None);
+        let mut response = ui
+            .add_enabled_ui(is_enabled, |ui| {
+                ui.vertical(|ui| {
+                    for (index, command) in commands.iter().enumerate() {
+                        let is_selected = selected_range
+                            .as_ref()
+                            .is_some_and(|range| range.contains(&index));
+                        let text = command_text(db, data, command, &commands[owners[index]]);
+
+                        let response = ui
+                            .horizontal(|ui| {
+                                ui.add_space(command.indent as f32 * ui.spacing().indent);
+                                ui.selectable_label(is_selected, text)
+                            })
+                            .inner;
+
+                        if response.clicked() || response.secondary_clicked() {
+                            self.selected_index = Some(index);
+                            response.request_focus();
+                        }
+                        has_focus |= response.has_focus();
+
+                        if response.double_clicked() {
+                            action = Some(if command.code == 0 {
+                                Action::Insert(index)
+                            } else {
+                                Action::Edit(index)
+                            });
+                        }
+
+                        // The empty command at the end of a block can't be removed
+                        let is_removable = command.code != 0;
+                        response.context_menu(|ui| {
+                            let mut menu_action = None;
+
+                            if ui.button("Insert...").clicked() {
+                                menu_action = Some(Action::Insert(index));
+                            }
+                            if ui
+                                .add_enabled(is_removable, egui::Button::new("Edit..."))
+                                .clicked()
+                            {
+                                menu_action = Some(Action::Edit(index));
+                            }
+
+                            ui.separator();
+
+                            if ui
+                                .add_enabled(is_removable, egui::Button::new("Cut"))
+                                .clicked()
+                            {
+                                menu_action = Some(Action::Cut(index));
+                            }
+                            if ui
+                                .add_enabled(is_removable, egui::Button::new("Copy"))
+                                .clicked()
+                            {
+                                menu_action = Some(Action::Copy(index));
+                            }
+                            if ui
+                                .add_enabled(clipboard_full, egui::Button::new("Paste"))
+                                .clicked()
+                            {
+                                menu_action = Some(Action::Paste(index));
+                            }
+
+                            ui.separator();
+
+                            if ui
+                                .add_enabled(is_removable, egui::Button::new("Move Up"))
+                                .clicked()
+                            {
+                                menu_action = Some(Action::MoveUp(index));
+                            }
+                            if ui
+                                .add_enabled(is_removable, egui::Button::new("Move Down"))
+                                .clicked()
+                            {
+                                menu_action = Some(Action::MoveDown(index));
+                            }
+                            if ui
+                                .add_enabled(is_removable, egui::Button::new("Delete"))
+                                .clicked()
+                            {
+                                menu_action = Some(Action::Delete(index));
+                            }
+
+                            if menu_action.is_some() {
+                                action = menu_action;
+                                ui.close_menu();
+                            }
+                        });
+                    }
+                });
+            })
+            .response;
+
+        if let Some(index) = self.selected_index.filter(|_| has_focus && is_enabled) {
+            ui.input_mut(|i| {
+                for event in &i.events {
+                    match event {
+                        egui::Event::Copy => action = Some(Action::Copy(index)),
+                        egui::Event::Cut => action = Some(Action::Cut(index)),
+                        egui::Event::Paste(_) => action = Some(Action::Paste(index)),
+                        _ => {}
+                    }
+                }
+
+                if i.consume_key(egui::Modifiers::ALT, egui::Key::ArrowUp) {
+                    action = Some(Action::MoveUp(index));
+                } else if i.consume_key(egui::Modifiers::ALT, egui::Key::ArrowDown) {
+                    action = Some(Action::MoveDown(index));
+                } else if i.key_pressed(egui::Key::Delete) {
+                    action = Some(Action::Delete(index));
+                } else if i.key_pressed(egui::Key::Insert) {
+                    action = Some(Action::Insert(index));
+                } else if i.key_pressed(egui::Key::Enter) {
+                    action = Some(if commands[index].code == 0 {
+                        Action::Insert(index)
+                    } else {
+                        Action::Edit(index)
+                    });
+                }
+            });
+        }
+
+        let mut changed = false;
+        if let Some(action) = action {
+            changed |= self.apply_action(ui.ctx(), db, commands, action);
+        }
+        changed |= self.show_windows(ui.ctx(), update_state, commands);
+
+        if changed {
+            response.mark_changed();
+        }
+        response
+    }
+
+    /// Applies an action from the context menu or a shortcut, returning whether the commands were modified.
+    fn apply_action(
+        &mut self,
+        ctx: &egui::Context,
+        db: &CommandDB,
+        commands: &mut Vec<EventCommand>,
+        action: Action,
+    ) -> bool {
+        match action {
+            Action::Insert(index) => {
+                let (index, indent) = insertion_point(db, commands, index);
+                self.window_state = WindowState::Insert {
+                    index,
+                    indent,
+                    search: String::new(),
+                };
+                false
+            }
+            Action::Edit(index) => {
+                let range = block::block_range(db, commands, index);
+                if commands[range.start].code == 0 {
+                    return false;
+                }
+
+                self.sound_tabs.clear();
+                self.move_route_views.clear();
+                self.window_state = WindowState::Edit {
+                    index: range.start,
+                    replace_len: range.len(),
+                    commands: commands[range].to_vec(),
+                };
+                false
+            }
+            Action::Copy(index) => {
+                let range = block::block_range(db, commands, index);
+                if commands[range.start].code != 0 {
+                    copy_commands(ctx, &commands[range]);
+                }
+                false
+            }
+            Action::Cut(index) | Action::Delete(index) => {
+                let range = block::block_range(db, commands, index);
+                if commands[range.start].code == 0 {
+                    return false;
+                }
+
+                if matches!(action, Action::Cut(_)) {
+                    copy_commands(ctx, &commands[range.clone()]);
+                }
+                self.selected_index = Some(range.start);
+                commands.drain(range);
+                true
+            }
+            Action::Paste(index) => {
+                let Some(clipboard) =
+                    ctx.data(|d| d.get_temp::<Vec<EventCommand>>(egui::Id::new(CLIPBOARD_ID)))
+                else {
+                    return false;
+                };
+
+                let (index, indent) = insertion_point(db, commands, index);
+                commands.splice(
+                    index..index,
+                    clipboard.into_iter().map(|command| {
+                        EventCommand::new(command.code, command.indent + indent, command.parameters)
+                    }),
+                );
+                self.selected_index = Some(index);
+                true
+            }
+            Action::MoveUp(index) => {
+                let range = block::block_range(db, commands, index);
+                // The empty command at the end of a list has to stay there
+                if commands[range.start].code == 0 {
+                    return false;
+                }
+                let Some(prev) = block::previous_sibling(db, commands, range.start) else {
+                    return false;
+                };
+
+                commands[prev..range.end].rotate_left(range.start - prev);
+                self.selected_index = Some(prev);
+                true
+            }
+            Action::MoveDown(index) => {
+                let range = block::block_range(db, commands, index);
+                if commands[range.start].code == 0 {
+                    return false;
+                }
+                let Some(next) = block::next_sibling(db, commands, range.start) else {
+                    return false;
+                };
+
+                let next_len = block::block_len(db, commands, next);
+                commands[range.start..next + next_len].rotate_left(range.len());
+                self.selected_index = Some(range.start + next_len);
+                true
+            }
+        }
+    }
+
+    /// Shows the insert and edit windows, returning whether the commands were modified.
+    fn show_windows(
+        &mut self,
+        ctx: &egui::Context,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        commands: &mut Vec<EventCommand>,
+    ) -> bool {
+        let mut changed = false;
+
+        match std::mem::replace(&mut self.window_state, WindowState::None) {
+            WindowState::None => {}
+            WindowState::Insert {
+                index,
+                indent,
+                mut search,
+            } => {
+                let mut win_open = true;
+                let mut chosen = None;
+
+                let db = &update_state
+                    .project_config
+                    .as_ref()
+                    .expect("project not loaded")
+                    .command_db;
+
+                egui::Window::new("Insert Command")
+                    .id(self.id.with("insert_window"))
+                    .open(&mut win_open)
+                    .show(ctx, |ui| {
+                        ui.add(egui::TextEdit::singleline(&mut search).hint_text("Search 🔎"));
+                        ui.separator();
+
+                        let matcher = fuzzy_matcher::skim::SkimMatcherV2::default();
+                        egui::ScrollArea::vertical()
+                            .max_height(400.)
+                            .show(ui, |ui| {
+                                ui.with_cross_justify(|ui| {
+                                    for (i, desc) in db
+                                        .iter()
+                                        .filter(|desc| {
+                                            !desc.hidden
+                                                && matcher
+                                                    .fuzzy(&desc.name, &search, false)
+                                                    .is_some()
+                                        })
+                                        .enumerate()
+                                    {
+                                        ui.with_stripe(i % 2 != 0, |ui| {
+                                            if ui
+                                                .selectable_label(false, desc.name.as_str())
+                                                .on_hover_text(desc.description.as_str())
+                                                .clicked()
+                                            {
+                                                chosen = Some(desc.clone());
+                                            }
+                                        });
+                                    }
+                                });
+                            });
+                    });
+
+                if let Some(desc) = chosen {
+                    let new_commands = block::new_block(&desc, indent);
+                    if has_parameters(&desc) {
+                        self.sound_tabs.clear();
+                        self.move_route_views.clear();
+                        self.window_state = WindowState::Edit {
+                            index,
+                            replace_len: 0,
+                            commands: new_commands,
+                        };
+                    } else {
+                        commands.splice(index..index, new_commands);
+                        self.selected_index = Some(index);
+                        changed = true;
+                    }
+                } else if win_open {
+                    self.window_state = WindowState::Insert {
+                        index,
+                        indent,
+                        search,
+                    };
+                }
+            }
+            WindowState::Edit {
+                index,
+                mut replace_len,
+                commands: mut edited_commands,
+            } => {
+                let Some(desc) = update_state
+                    .project_config
+                    .as_ref()
+                    .and_then(|config| config.command_db.get(edited_commands[0].code))
+                    .cloned()
+                else {
+                    return false;
+                };
+
+                let mut win_open = true;
+                let mut keep_open = true;
+                let mut needs_save = false;
+
+                egui::Window::new(desc.name.as_str())
+                    .id(self.id.with("edit_window"))
+                    .open(&mut win_open)
+                    .show(ctx, |ui| {
+                        ui.label(desc.description.as_str());
+                        ui.separator();
+
+                        egui::ScrollArea::vertical()
+                            .max_height(500.)
+                            .show(ui, |ui| {
+                                self.command_ui(ui, update_state, &desc, &mut edited_commands);
+                            });
+
+                        ui.separator();
+                        crate::close_options_ui(ui, &mut keep_open, &mut needs_save);
+                    });
+
+                if needs_save {
+                    commands.splice(index..index + replace_len, edited_commands.iter().cloned());
+                    replace_len = edited_commands.len();
+                    self.selected_index = Some(index);
+                    changed = true;
+                }
+
+                if win_open && keep_open {
+                    self.window_state = WindowState::Edit {
+                        index,
+                        replace_len,
+                        commands: edited_commands,
+                    };
+                } else {
+                    self.sound_tabs.clear();
+                    self.move_route_views.clear();
+                }
+            }
+        }
+
+        changed
+    }
+}
+
+/// Where commands inserted at `index` should go, and at which indent.
+///
+/// Commands can't be inserted inside of another block, so hidden commands insert before the block they're a part of.
+fn insertion_point(db: &CommandDB, commands: &[EventCommand], index: usize) -> (usize, usize) {
+    let index = if block::is_hidden(db, &commands[index]) {
+        block::owner(db, commands, index)
+    } else {
+        index
+    };
+    (index, commands[index].indent)
+}
+
+/// Stores commands in egui memory with indents relative to the first command.
+fn copy_commands(ctx: &egui::Context, commands: &[EventCommand]) {
+    let base_indent = commands[0].indent;
+    let commands = commands
+        .iter()
+        .map(|command| EventCommand {
+            indent: command.indent - base_indent,
+            ..command.clone()
+        })
+        .collect::<Vec<_>>();
+    ctx.data_mut(|d| d.insert_temp(egui::Id::new(CLIPBOARD_ID), commands));
+}
+
+/// Does this command need the edit window when it's inserted?
+fn has_parameters(desc: &CommandDescription) -> bool {
+    match desc.kind {
+        CommandKind::Multi { .. } => true,
+        CommandKind::Single(ref parameters) | CommandKind::Branch { ref parameters, .. } => {
+            !parameters.is_empty()
+        }
     }
 }
@@ -27,7 +27,7 @@ use itertools::Itertools;
 
 pub struct DatabaseViewResponse<R> {
     /// The returned value of the `inner` closure passed to `show` if the editor pane was rendered,
-    /// otherwise `None`.
+    /// otherwise `None`. For `show` this is whether the closure modified the selected entry.
     pub inner: Option<R>,
     /// Was any individual entry or the number of entries modified by us?
     pub modified: bool,
@@ -38,6 +38,8 @@ pub struct DatabaseView {
     show_called_at_least_once: bool,
     selected_id: usize,
     maximum: Option<usize>,
+    /// A snapshot of the selected entry, with its index and the history revision it was taken at.
+    before: Option<(usize, u64, Vec<u8>)>,
 }
 
 impl DatabaseView {
@@ -45,19 +47,32 @@ impl DatabaseView {
         Default::default()
     }
 
-    pub fn show<T, R>(
+    /// Selects the entry with the given index the next time the view is shown.
+    pub fn select(&mut self, id: usize) {
+        self.selected_id = id;
+    }
+
+    /// Shows the list of entries next to an editor for the selected one. `inner` shows the editor
+    /// and returns whether it modified the entry, which is then recorded in the history.
+    pub fn show<T>(
         &mut self,
         ui: &mut egui::Ui,
         update_state: &mut luminol_core::UpdateState<'_>,
         label: impl Into<egui::WidgetText>,
         vec: &mut Vec<T>,
         formatter: impl Fn(&T) -> String,
-        inner: impl FnOnce(&mut egui::Ui, &mut Vec<T>, usize, &mut luminol_core::UpdateState<'_>) -> R,
-    ) -> egui::InnerResponse<DatabaseViewResponse<R>>
+        inner: impl FnOnce(
+            &mut egui::Ui,
+            &mut Vec<T>,
+            usize,
+            &mut luminol_core::UpdateState<'_>,
+        ) -> bool,
+    ) -> egui::InnerResponse<DatabaseViewResponse<bool>>
     where
-        T: luminol_data::rpg::DatabaseEntry,
+        T: luminol_data::rpg::DatabaseEntry + luminol_core::history::Tracked,
     {
         let mut modified = false;
+        let mut changes = Vec::new();
 
         let p = update_state
             .project_config
@@ -93,12 +108,21 @@ impl DatabaseView {
                                     .clicked()
                                 {
                                     modified = true;
+                                    let before = luminol_core::history::snapshot(vec).ok();
                                     let mut index = vec.len();
                                     vec.resize_with(self.maximum.unwrap(), || {
                                         let item = T::default_with_id(index);
                                         index += 1;
                                         item
                                     });
+                                    changes.push((
+                                        format!("Set maximum to {}", vec.len()),
+                                        luminol_core::history::Diff::new(
+                                            luminol_core::history::Target::Entries(T::CONTAINER),
+                                            before,
+                                            luminol_core::history::snapshot(vec).ok(),
+                                        ),
+                                    ));
                                 };
                             });
 
@@ -217,8 +241,29 @@ impl DatabaseView {
                                                                     )
                                                             })
                                                         {
+                                                            let description =
+                                                                format!("Reset {}", formatter(entry));
+                                                            let before =
+                                                                luminol_core::history::snapshot(
+                                                                    entry,
+                                                                )
+                                                                .ok();
                                                             *entry = T::default_with_id(id);
                                                             modified = true;
+                                                            changes.push((
+                                                                description,
+                                                                luminol_core::history::Diff::new(
+                                                                    luminol_core::history::Target::Entry(
+                                                                        T::CONTAINER,
+                                                                        id,
+                                                                    ),
+                                                                    before,
+                                                                    luminol_core::history::snapshot(
+                                                                        entry,
+                                                                    )
+                                                                    .ok(),
+                                                                ),
+                                                            ));
                                                         }
                                                     });
 
@@ -246,29 +291,61 @@ impl DatabaseView {
             });
         });
 
-        ui.with_left_margin(ui.spacing().window_margin.left, |ui| {
-            ui.with_cross_justify(|ui| {
-                egui::ScrollArea::vertical()
-                    .id_source(p)
-                    .show(ui, |ui| {
-                        ui.set_width(ui.available_width());
-                        ui.set_min_width(
-                            2. * (ui.spacing().slider_width + ui.spacing().interact_size.x)
-                                + ui.spacing().indent
-                                + 12. // `egui::Frame::group` inner margins are hardcoded to 6
-                                      // points on each side
-                                + 5. * ui.spacing().item_spacing.x,
-                        );
-
-                        DatabaseViewResponse {
-                            inner: (self.selected_id < vec.len())
-                                .then(|| inner(ui, vec, self.selected_id, update_state)),
-                            modified,
-                        }
-                    })
-                    .inner
+        let response = ui
+            .with_left_margin(ui.spacing().window_margin.left, |ui| {
+                ui.with_cross_justify(|ui| {
+                    egui::ScrollArea::vertical()
+                        .id_source(p)
+                        .show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            ui.set_min_width(
+                                2. * (ui.spacing().slider_width + ui.spacing().interact_size.x)
+                                    + ui.spacing().indent
+                                    + 12. // `egui::Frame::group` inner margins are hardcoded to 6
+                                          // points on each side
+                                    + 5. * ui.spacing().item_spacing.x,
+                            );
+
+                            let id = self.selected_id;
+                            let inner = (id < vec.len()).then(|| {
+                                // Edits made in the editor pane are recorded by comparing the entry
+                                // with a snapshot from before the edit. The snapshot is only taken
+                                // again once the selection or the history changes
+                                let revision = update_state.history.revision();
+                                let before = match self.before.take() {
+                                    Some((before_id, before_revision, before))
+                                        if before_id == id && before_revision == revision =>
+                                    {
+                                        Some(before)
+                                    }
+                                    _ => luminol_core::history::snapshot(&vec[id]).ok(),
+                                };
+                                let modified = inner(ui, vec, id, update_state);
+                                match vec.get(id) {
+                                    Some(entry) if modified => changes.push((
+                                        format!("Edit {}", formatter(entry)),
+                                        luminol_core::history::Diff::new(
+                                            luminol_core::history::Target::Entry(T::CONTAINER, id),
+                                            before,
+                                            luminol_core::history::snapshot(entry).ok(),
+                                        ),
+                                    )),
+                                    _ => self.before = before.map(|before| (id, revision, before)),
+                                }
+                                modified
+                            });
+
+                            DatabaseViewResponse { inner, modified }
+                        })
+                        .inner
+                })
             })
-        })
-        .inner
+            .inner;
+
+        for (description, diff) in changes {
+            update_state.record_change(description, vec![diff]);
+        }
+
+        response
     }
 }
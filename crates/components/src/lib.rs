@@ -33,13 +33,27 @@ mod map_view;
 pub use map_view::{MapView, SelectedLayer};
 mod tilepicker;
 pub use tilepicker::{SelectedTile, Tilepicker};
+mod tileset_view;
+pub use tileset_view::{TileFlag, TilesetView};
+mod map_position_view;
+pub use map_position_view::MapPositionView;
 
 mod sound_tab;
 pub use sound_tab::SoundTab;
 
+mod troop_view;
+pub use troop_view::TroopView;
+
+/// The animation frame editor and the layout of animation cells.
+pub mod animation_frame_view;
+pub use animation_frame_view::AnimationFrameView;
+
 mod command_view;
 pub use command_view::CommandView;
 
+mod move_route_view;
+pub use move_route_view::MoveRouteView;
+
 mod filesystem_view;
 pub use filesystem_view::FileSystemView;
 
@@ -55,6 +69,9 @@ pub use id_vec::{IdVecPlusMinusSelection, IdVecSelection, RankSelection};
 mod ui_ext;
 pub use ui_ext::UiExt;
 
+/// Finding where switches and variables are used.
+pub mod usages;
+
 pub struct EnumMenuButton<'e, T> {
     current_value: &'e mut T,
     id: egui::Id,
@@ -0,0 +1,133 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_graphics::Renderable;
+
+/// A scaled down, read-only view of a map where a tile can be picked by clicking on it.
+pub struct MapPositionView {
+    /// The ID of the map being shown.
+    pub map_id: usize,
+    /// The size the map is drawn at, relative to its actual size.
+    pub scale: f32,
+
+    map: luminol_graphics::Map,
+    size: (usize, usize),
+}
+
+impl MapPositionView {
+    pub fn new(
+        update_state: &luminol_core::UpdateState<'_>,
+        map_id: usize,
+    ) -> color_eyre::Result<Self> {
+        let mut map = crate::MapView::load_map(update_state, map_id)?;
+        map.fog_enabled = false;
+        map.grid_enabled = false;
+        map.coll_enabled = false;
+
+        let size = {
+            let map = update_state.data.get_map(map_id);
+            (map.data.xsize(), map.data.ysize())
+        };
+
+        Ok(Self {
+            map_id,
+            scale: 0.5,
+
+            map,
+            size,
+        })
+    }
+
+    /// Shows the map with the given position highlighted. The response is marked as changed if a
+    /// different tile was clicked.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        position: (&mut i32, &mut i32),
+    ) -> egui::Response {
+        let (x, y) = position;
+        let tile_size = 32. * self.scale;
+        let (canvas_rect, mut response) = ui.allocate_exact_size(
+            egui::vec2(self.size.0 as f32, self.size.1 as f32) * tile_size,
+            egui::Sense::click(),
+        );
+
+        // Only the visible part of the canvas can be used as the viewport
+        let paint_rect = canvas_rect.intersect(ui.clip_rect());
+        if paint_rect.is_positive() {
+            let translation = canvas_rect.min - paint_rect.min;
+            self.map.viewport.set(
+                &update_state.graphics.render_state,
+                glam::vec2(paint_rect.width(), paint_rect.height()),
+                glam::vec2(translation.x, translation.y),
+                glam::Vec2::splat(self.scale),
+            );
+            self.map
+                .update_animation(&update_state.graphics.render_state, ui.input(|i| i.time));
+
+            ui.painter()
+                .add(luminol_egui_wgpu::Callback::new_paint_callback(
+                    paint_rect,
+                    luminol_graphics::Painter::new(self.map.prepare(&update_state.graphics)),
+                ));
+        }
+
+        let tile_rect = |x: i32, y: i32| {
+            egui::Rect::from_min_size(
+                canvas_rect.min + egui::vec2(x as f32, y as f32) * tile_size,
+                egui::Vec2::splat(tile_size),
+            )
+        };
+
+        if let Some(pos) = response.hover_pos() {
+            let pos = (pos - canvas_rect.min) / tile_size;
+            ui.painter().rect_stroke(
+                tile_rect(pos.x as i32, pos.y as i32),
+                0.,
+                egui::Stroke::new(1., egui::Color32::WHITE),
+            );
+        }
+
+        ui.painter().rect_stroke(
+            tile_rect(*x, *y),
+            0.,
+            egui::Stroke::new(2., ui.visuals().selection.stroke.color),
+        );
+
+        if response.clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let pos = (pos - canvas_rect.min) / tile_size;
+                let (new_x, new_y) = (pos.x as i32, pos.y as i32);
+                if (new_x, new_y) != (*x, *y) {
+                    *x = new_x.clamp(0, self.size.0 as i32 - 1);
+                    *y = new_y.clamp(0, self.size.1 as i32 - 1);
+                    response.mark_changed();
+                }
+            }
+        }
+
+        response
+    }
+}
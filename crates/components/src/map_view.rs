@@ -15,9 +15,8 @@
 // You should have received a copy of the GNU General Public License
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 
-use color_eyre::eyre::{ContextCompat, WrapErr};
-use itertools::Itertools;
-use luminol_graphics::{Drawable, Renderable};
+use color_eyre::eyre::WrapErr;
+use luminol_graphics::Renderable;
 use std::collections::HashMap;
 use std::io::Write;
 
@@ -54,6 +53,11 @@ pub struct MapView {
     /// Whether to display the tile IDs on the map
     pub display_tile_ids: bool,
 
+    /// The tiles selected by the selection tool, in map coordinates
+    pub selection_rect: Option<egui::Rect>,
+    /// The tiles the clipboard would be pasted over, in map coordinates
+    pub paste_rect: Option<egui::Rect>,
+
     pub scale: f32,
     pub previous_scale: f32,
 
@@ -81,29 +85,7 @@ impl MapView {
         update_state: &luminol_core::UpdateState<'_>,
         map_id: usize,
     ) -> color_eyre::Result<MapView> {
-        let map = update_state
-            .data
-            .get_or_load_map(map_id, update_state.filesystem);
-        let tilesets = update_state.data.tilesets();
-        let tileset = &tilesets.data[map.tileset_id];
-
-        let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
-        luminol_graphics::Collision::calculate_passages(
-            &tileset.passages,
-            &tileset.priorities,
-            &map.data,
-            Some(&map.events),
-            (0..map.data.zsize()).rev(),
-            |x, y, passage| passages[(x, y)] = passage,
-        );
-
-        let map = luminol_graphics::Map::new(
-            &update_state.graphics,
-            update_state.filesystem,
-            &map,
-            tileset,
-            &passages,
-        )?;
+        let map = Self::load_map(update_state, map_id)?;
 
         let data_id = egui::Id::new("luminol_map_view")
             .with(
@@ -145,6 +127,9 @@ impl MapView {
 
             display_tile_ids: false,
 
+            selection_rect: None,
+            paste_rect: None,
+
             scale,
             previous_scale: scale,
 
@@ -154,13 +139,126 @@ impl MapView {
         })
     }
 
+    pub(crate) fn load_map(
+        update_state: &luminol_core::UpdateState<'_>,
+        map_id: usize,
+    ) -> color_eyre::Result<luminol_graphics::Map> {
+        if update_state.data.editor_ver() == Some(luminol_config::RMVer::Ace) {
+            return Self::load_ace_map(update_state, map_id);
+        }
+        if update_state.data.editor_ver() == Some(luminol_config::RMVer::VX) {
+            return Self::load_vx_map(update_state, map_id);
+        }
+
+        let map = update_state
+            .data
+            .get_or_load_map(map_id, update_state.filesystem);
+        let tilesets = update_state.data.tilesets();
+        let tileset = &tilesets.data[map.tileset_id];
+
+        let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
+        luminol_graphics::Collision::calculate_passages(
+            &tileset.passages,
+            &tileset.priorities,
+            &map.data,
+            Some(&map.events),
+            (0..map.data.zsize()).rev(),
+            |x, y, passage| passages[(x, y)] = passage,
+        );
+
+        luminol_graphics::Map::new(
+            &update_state.graphics,
+            update_state.filesystem,
+            &map,
+            tileset,
+            &passages,
+        )
+    }
+
+    fn load_ace_map(
+        update_state: &luminol_core::UpdateState<'_>,
+        map_id: usize,
+    ) -> color_eyre::Result<luminol_graphics::Map> {
+        let map = update_state
+            .data
+            .get_or_load_ace_map(map_id, update_state.filesystem)?;
+        let tilesets = update_state.data.ace_tilesets();
+        let tileset = &tilesets.data[map.tileset_id];
+
+        // The 4th layer holds shadows and regions, which don't affect passability here
+        let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
+        luminol_graphics::Collision::calculate_passages_ace(
+            &tileset.flags,
+            &map.data,
+            (0..map.data.zsize().min(3)).rev(),
+            |x, y, passage| passages[(x, y)] = passage,
+        );
+
+        luminol_graphics::Map::new_ace(
+            &update_state.graphics,
+            update_state.filesystem,
+            &map,
+            tileset,
+            &passages,
+        )
+    }
+
+    fn load_vx_map(
+        update_state: &luminol_core::UpdateState<'_>,
+        map_id: usize,
+    ) -> color_eyre::Result<luminol_graphics::Map> {
+        let map = update_state
+            .data
+            .get_or_load_vx_map(map_id, update_state.filesystem)?;
+        let system = update_state.data.vx_system();
+
+        let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
+        luminol_graphics::Collision::calculate_passages_ace(
+            &system.passages,
+            &map.data,
+            (0..map.data.zsize()).rev(),
+            |x, y, passage| passages[(x, y)] = passage,
+        );
+
+        luminol_graphics::Map::new_vx(
+            &update_state.graphics,
+            update_state.filesystem,
+            &map,
+            &passages,
+        )
+    }
+
+    /// Recreates the graphics of the map, e.g. after its tileset was edited. Which layers are
+    /// enabled is kept as-is.
+    pub fn reload(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map_id: usize,
+    ) -> color_eyre::Result<()> {
+        let mut map = Self::load_map(update_state, map_id)?;
+        map.tiles
+            .enabled_layers
+            .clone_from(&self.map.tiles.enabled_layers);
+        map.fog_enabled = self.map.fog_enabled;
+        map.pano_enabled = self.map.pano_enabled;
+        map.coll_enabled = self.map.coll_enabled;
+        map.grid_enabled = self.map.grid_enabled;
+        map.event_enabled = self.map.event_enabled;
+
+        self.map = map;
+        self.preview_events.clear();
+        self.last_events.clear();
+        Ok(())
+    }
+
     // FIXME lots of arguments
     #[allow(clippy::too_many_arguments)]
     pub fn ui(
         &mut self,
         ui: &mut egui::Ui,
         update_state: &luminol_core::UpdateState<'_>,
-        map: &luminol_data::rpg::Map,
+        map_data: &luminol_data::Table3,
+        events: Option<&luminol_data::OptionVec<luminol_data::rpg::Event>>,
         tilepicker: &crate::Tilepicker,
         dragging_event: bool,
         drawing_shape: bool,
@@ -183,8 +281,8 @@ impl MapView {
         self.cursor_pos = self.cursor_pos.clamp(
             egui::Pos2::ZERO,
             egui::pos2(
-                map.data.xsize().saturating_sub(1) as f32,
-                map.data.ysize().saturating_sub(1) as f32,
+                map_data.xsize().saturating_sub(1) as f32,
+                map_data.ysize().saturating_sub(1) as f32,
             ),
         );
 
@@ -230,7 +328,7 @@ impl MapView {
                     self.cursor_pos.y -= 1.0;
                 }
                 if i.key_pressed(egui::Key::ArrowDown)
-                    && self.cursor_pos.y < map.data.ysize() as f32 - 1.
+                    && self.cursor_pos.y < map_data.ysize() as f32 - 1.
                 {
                     self.cursor_pos.y += 1.0;
                 }
@@ -238,7 +336,7 @@ impl MapView {
                     self.cursor_pos.x -= 1.0;
                 }
                 if i.key_pressed(egui::Key::ArrowRight)
-                    && self.cursor_pos.x < map.data.xsize() as f32 - 1.
+                    && self.cursor_pos.x < map_data.xsize() as f32 - 1.
                 {
                     self.cursor_pos.x += 1.0;
                 }
@@ -279,10 +377,10 @@ impl MapView {
         self.hover_tile = None;
         if let Some(pos) = response.hover_pos() {
             let mut pos_tile = (pos - self.pan - canvas_center) / tile_size
-                + egui::Vec2::new(map.width as f32 / 2., map.height as f32 / 2.);
+                + egui::Vec2::new(map_data.xsize() as f32 / 2., map_data.ysize() as f32 / 2.);
             // Force the cursor to a tile instead of in-between
-            pos_tile.x = pos_tile.x.floor().clamp(0., map.width as f32 - 1.);
-            pos_tile.y = pos_tile.y.floor().clamp(0., map.height as f32 - 1.);
+            pos_tile.x = pos_tile.x.floor().clamp(0., map_data.xsize() as f32 - 1.);
+            pos_tile.y = pos_tile.y.floor().clamp(0., map_data.ysize() as f32 - 1.);
             self.hover_tile = Some(pos_tile.to_pos2());
             // Handle input
             if matches!(self.selected_layer, SelectedLayer::Tiles(_))
@@ -292,8 +390,8 @@ impl MapView {
             }
         }
 
-        let width2 = map.width as f32 / 2.;
-        let height2 = map.height as f32 / 2.;
+        let width2 = map_data.xsize() as f32 / 2.;
+        let height2 = map_data.ysize() as f32 / 2.;
 
         let pos = egui::Vec2::new(width2 * tile_size, height2 * tile_size);
         let map_rect = egui::Rect {
@@ -366,11 +464,11 @@ impl MapView {
         }
         self.selected_event_is_hovered = false;
 
-        if self.map.event_enabled {
+        if let Some(events) = events.filter(|_| self.map.event_enabled) {
             let mut selected_event = None;
             let mut selected_event_rect = None;
 
-            for (_, event) in map.events.iter() {
+            for (_, event) in events.iter() {
                 if event.extra_data.graphic_modified.get() {
                     event.extra_data.graphic_modified.set(false);
                     let sprite = luminol_graphics::Event::new_map(
@@ -610,9 +708,9 @@ impl MapView {
         // FIXME: If we want to be fast, we should be rendering all the tile ids to a texture once and then just rendering that texture here
         if self.display_tile_ids {
             if let SelectedLayer::Tiles(layer) = self.selected_layer {
-                for (i, id) in map.data.layer_as_slice(layer).iter().copied().enumerate() {
-                    let x = i % map.data.xsize();
-                    let y = i / map.data.xsize();
+                for (i, id) in map_data.layer_as_slice(layer).iter().copied().enumerate() {
+                    let x = i % map_data.xsize();
+                    let y = i / map_data.xsize();
 
                     let tile_x = x as f32 * tile_size;
                     let tile_y = y as f32 * tile_size;
@@ -665,8 +763,34 @@ impl MapView {
             }
         }
 
+        // Display the selection and where it would be pasted
+        let tile_rect = |rect: egui::Rect| {
+            egui::Rect::from_min_max(
+                map_rect.min + rect.min.to_vec2() * tile_size,
+                map_rect.min + rect.max.to_vec2() * tile_size,
+            )
+            .intersect(map_rect)
+        };
+        if let Some(rect) = self.selection_rect {
+            ui.painter().rect_stroke(
+                tile_rect(rect),
+                5.,
+                egui::Stroke::new(2., egui::Color32::LIGHT_BLUE),
+            );
+        }
+        if let Some(rect) = self.paste_rect {
+            ui.painter().rect(
+                tile_rect(rect),
+                5.,
+                egui::Color32::from_rgba_unmultiplied(255, 255, 255, 32),
+                egui::Stroke::new(2., egui::Color32::LIGHT_GREEN),
+            );
+        }
+
         // Display cursor.
-        if matches!(self.selected_layer, SelectedLayer::Tiles(_)) {
+        if matches!(self.selected_layer, SelectedLayer::Tiles(_))
+            && update_state.toolbar.pencil != luminol_core::Pencil::Select
+        {
             ui.painter().rect_stroke(
                 pattern_rect,
                 5.,
@@ -696,205 +820,35 @@ impl MapView {
     pub fn save_as_image(
         &mut self,
         graphics_state: &std::sync::Arc<luminol_graphics::GraphicsState>,
-        map: &luminol_data::rpg::Map,
+        map_size: (usize, usize),
     ) -> impl std::future::Future<Output = color_eyre::Result<()>> {
         let c = "While screenshotting the map";
 
-        let max_texture_dimension_2d = graphics_state
-            .render_state
-            .device
-            .limits()
-            .max_texture_dimension_2d
-            / wgpu::COPY_BYTES_PER_ROW_ALIGNMENT
-            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
-        let max_buffer_size = graphics_state.render_state.device.limits().max_buffer_size as u32
-            / wgpu::COPY_BYTES_PER_ROW_ALIGNMENT
-            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
-
-        let screenshot_width = map.width as u32 * 32;
-        let screenshot_height = map.height as u32 * 32;
-
-        let max_texture_width = screenshot_width
-            .min(max_texture_dimension_2d)
-            .min(max_buffer_size);
-        let max_texture_height = screenshot_height
-            .min(max_texture_dimension_2d)
-            .min(max_buffer_size / (max_texture_width * 4));
-
-        let mut command_encoder = graphics_state
-            .render_state
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
-
-        let buffers = (0..screenshot_height)
-            .step_by(max_texture_height as usize)
-            .cartesian_product((0..screenshot_width).step_by(max_texture_width as usize))
-            .map(|(y_offset, x_offset)| {
-                let width = max_texture_width.min(screenshot_width - x_offset);
-                let height = max_texture_height.min(screenshot_height - y_offset);
-                let width_padded = width.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT / 4);
-
-                let texture =
-                    graphics_state
-                        .render_state
-                        .device
-                        .create_texture(&wgpu::TextureDescriptor {
-                            label: Some("map editor screenshot texture"),
-                            size: wgpu::Extent3d {
-                                width,
-                                height,
-                                depth_or_array_layers: 1,
-                            },
-                            mip_level_count: 1,
-                            sample_count: 1,
-                            dimension: wgpu::TextureDimension::D2,
-                            format: graphics_state.render_state.target_format,
-                            usage: wgpu::TextureUsages::COPY_SRC
-                                | wgpu::TextureUsages::RENDER_ATTACHMENT,
-                            view_formats: &[],
-                        });
-                let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-                let buffer =
-                    graphics_state
-                        .render_state
-                        .device
-                        .create_buffer(&wgpu::BufferDescriptor {
-                            label: Some("map editor screenshot buffer"),
-                            size: width_padded as u64 * height as u64 * 4,
-                            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-                            mapped_at_creation: false,
-                        });
-
-                self.map.viewport.set(
-                    &graphics_state.render_state,
-                    glam::vec2(width as f32, height as f32),
-                    glam::vec2(x_offset as f32, y_offset as f32),
-                    glam::Vec2::ONE,
-                );
-
-                self.map.tiles.selected_layer = match self.selected_layer {
-                    SelectedLayer::Events => None,
-                    SelectedLayer::Tiles(selected_layer) if self.darken_unselected_layers => {
-                        Some(selected_layer)
-                    }
-                    SelectedLayer::Tiles(_) => None,
-                };
-
-                for (_, event) in map.events.iter() {
-                    if let Some(sprite) = self.map.events.get_mut(event.id) {
-                        sprite.sprite.graphic.set_opacity_multiplier(
-                            &graphics_state.render_state,
-                            if self.darken_unselected_layers
-                                && !matches!(self.selected_layer, SelectedLayer::Events)
-                            {
-                                0.5
-                            } else {
-                                1.
-                            },
-                        );
-                    }
-                }
-
-                // we probably don't need to prepare the map every time, but it's not that expensive
-                let prepared = self.map.prepare(graphics_state);
-
-                let mut render_pass =
-                    command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                        label: Some("map editor screenshot render pass"),
-                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                            view: &view,
-                            resolve_target: None,
-                            ops: wgpu::Operations::default(),
-                        })],
-                        depth_stencil_attachment: None,
-                        timestamp_writes: None,
-                        occlusion_query_set: None,
-                    });
-
-                prepared.draw(&mut render_pass);
-
-                drop(render_pass);
-
-                command_encoder.copy_texture_to_buffer(
-                    wgpu::ImageCopyTexture {
-                        texture: &texture,
-                        mip_level: 0,
-                        origin: wgpu::Origin3d::ZERO,
-                        aspect: wgpu::TextureAspect::All,
-                    },
-                    wgpu::ImageCopyBuffer {
-                        buffer: &buffer,
-                        layout: wgpu::ImageDataLayout {
-                            offset: 0,
-                            bytes_per_row: Some(width_padded * 4),
-                            rows_per_image: Some(height),
-                        },
-                    },
-                    wgpu::Extent3d {
-                        width,
-                        height,
-                        depth_or_array_layers: 1,
-                    },
-                );
-
-                buffer
-            })
-            .collect_vec();
-
-        graphics_state
-            .render_state
-            .queue
-            .submit(std::iter::once(command_encoder.finish()));
-
-        let graphics_state = graphics_state.clone();
-        let mut vec = vec![0; screenshot_width as usize * screenshot_height as usize * 4];
-        async move {
-            for ((y_offset, x_offset), buffer) in (0..screenshot_height)
-                .step_by(max_texture_height as usize)
-                .cartesian_product((0..screenshot_width).step_by(max_texture_width as usize))
-                .zip(buffers)
-            {
-                let width = max_texture_width.min(screenshot_width - x_offset);
-                let width_padded = width.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT / 4);
-
-                let (tx, rx) = oneshot::channel();
-                buffer
-                    .slice(..)
-                    .map_async(wgpu::MapMode::Read, move |result| {
-                        let _ = tx.send(result);
-                    });
-                if !graphics_state
-                    .render_state
-                    .device
-                    .poll(wgpu::Maintain::Wait)
-                    .is_queue_empty()
-                {
-                    return Err(color_eyre::eyre::eyre!("wgpu::Device::poll timed out").wrap_err(c));
-                }
-                rx.await.unwrap().wrap_err(c)?;
+        self.map.tiles.selected_layer = match self.selected_layer {
+            SelectedLayer::Events => None,
+            SelectedLayer::Tiles(selected_layer) if self.darken_unselected_layers => {
+                Some(selected_layer)
+            }
+            SelectedLayer::Tiles(_) => None,
+        };
 
-                for (i, row) in buffer
-                    .slice(..)
-                    .get_mapped_range()
-                    .chunks_exact(width_padded as usize * 4)
-                    .enumerate()
+        for (_, sprite) in self.map.events.iter_mut() {
+            sprite.sprite.graphic.set_opacity_multiplier(
+                &graphics_state.render_state,
+                if self.darken_unselected_layers
+                    && !matches!(self.selected_layer, SelectedLayer::Events)
                 {
-                    let offset = ((y_offset as usize + i) * screenshot_width as usize
-                        + x_offset as usize)
-                        * 4;
-                    vec[offset..offset + width as usize * 4]
-                        .copy_from_slice(&row[..width as usize * 4]);
-                }
-            }
+                    0.5
+                } else {
+                    1.
+                },
+            );
+        }
 
-            if graphics_state.render_state.target_format == wgpu::TextureFormat::Bgra8Unorm {
-                for (b, _g, r, _a) in vec.iter_mut().tuples() {
-                    std::mem::swap(b, r);
-                }
-            }
+        let screenshot = self.map.render_to_image(graphics_state, map_size);
 
-            let screenshot =
-                image::RgbaImage::from_raw(screenshot_width, screenshot_height, vec).wrap_err(c)?;
+        async move {
+            let screenshot = screenshot.await.wrap_err(c)?;
             let mut file = luminol_filesystem::host::File::new().wrap_err(c)?;
             screenshot
                 .write_to(
@@ -0,0 +1,380 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_data::commands::ParameterKind;
+use luminol_data::rpg::{AudioFile, MoveCommand, MoveRoute};
+use luminol_data::ParameterType;
+use luminol_filesystem::FileSystem;
+
+use crate::UiExt;
+
+/// The palette of move commands, in the order RPG Maker XP shows them.
+const PALETTE: [&[u16]; 3] = [
+    &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    &[16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30],
+    &[31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45],
+];
+
+/// An editor for a move route, used by event pages and the Set Move Route command.
+pub struct MoveRouteView {
+    id: egui::Id,
+    selected: usize,
+    /// The sound tab of the selected Play SE command, along with its guid
+    sound_tab: Option<(u16, crate::SoundTab)>,
+    /// The names of the character graphics, loaded when a Change Graphic command is first edited
+    characters: Option<Vec<String>>,
+}
+
+impl MoveRouteView {
+    pub fn new(id_source: impl std::hash::Hash) -> Self {
+        Self {
+            id: egui::Id::new(id_source),
+            selected: 0,
+            sound_tab: None,
+            characters: None,
+        }
+    }
+
+    /// Displays the editor, returning whether the route was changed.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        route: &mut MoveRoute,
+    ) -> bool {
+        // Routes always end with an empty command, which is where new commands are inserted by default
+        if route.list.last().map_or(true, |command| command.code != 0) {
+            route.list.push(MoveCommand::new(0, vec![]));
+        }
+        self.selected = self.selected.min(route.list.len() - 1);
+        let old_route = route.clone();
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut route.repeat, "Repeat Action");
+            ui.checkbox(&mut route.skippable, "Ignore If Can't Move");
+        });
+
+        ui.horizontal_top(|ui| {
+            ui.vertical(|ui| {
+                ui.set_width(240.);
+                self.list_ui(ui, route);
+            });
+
+            ui.vertical(|ui| {
+                ui.horizontal_top(|ui| {
+                    for column in PALETTE {
+                        ui.vertical(|ui| {
+                            for &code in column {
+                                let name = MoveCommand::new(code, vec![]).name();
+                                if ui.button(name).clicked() {
+                                    let command = MoveCommand::new(code, default_parameters(code));
+                                    route.list.insert(self.selected, command);
+                                    // Keep the cursor where it was, so commands are added in order
+                                    self.selected += 1;
+                                }
+                            }
+                        });
+                    }
+                });
+            });
+        });
+
+        ui.separator();
+        self.parameters_ui(ui, update_state, &mut route.list[self.selected]);
+
+        *route != old_route
+    }
+
+    fn list_ui(&mut self, ui: &mut egui::Ui, route: &mut MoveRoute) {
+        let last = route.list.len() - 1;
+
+        ui.group(|ui| {
+            egui::ScrollArea::vertical()
+                .id_source(self.id.with("list"))
+                .auto_shrink([false, false])
+                .max_height(300.)
+                .show(ui, |ui| {
+                    for (index, command) in route.list.iter().enumerate() {
+                        let text = if command.code == 0 {
+                            "$>".to_string()
+                        } else {
+                            format!("$>{}", crate::command_view::move_command_text(command))
+                        };
+                        ui.with_stripe(index % 2 == 1, |ui| {
+                            ui.with_cross_justify(|ui| {
+                                ui.selectable_value(
+                                    &mut self.selected,
+                                    index,
+                                    egui::RichText::new(text).monospace(),
+                                );
+                            });
+                        });
+                    }
+                });
+        });
+
+        // The empty command at the end can't be moved or deleted
+        ui.add_enabled_ui(self.selected != last, |ui| {
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(self.selected > 0, egui::Button::new("⏶"))
+                    .on_hover_text("Move up")
+                    .clicked()
+                {
+                    route.list.swap(self.selected, self.selected - 1);
+                    self.selected -= 1;
+                }
+                if ui
+                    .add_enabled(self.selected + 1 < last, egui::Button::new("⏷"))
+                    .on_hover_text("Move down")
+                    .clicked()
+                {
+                    route.list.swap(self.selected, self.selected + 1);
+                    self.selected += 1;
+                }
+                if ui.button("Duplicate").clicked() {
+                    let command = &route.list[self.selected];
+                    let command = MoveCommand::new(command.code, command.parameters.clone());
+                    route.list.insert(self.selected + 1, command);
+                    self.selected += 1;
+                }
+                if ui.button("Delete").clicked() {
+                    route.list.remove(self.selected);
+                }
+            });
+        });
+        ui.add_enabled_ui(last > 0, |ui| {
+            if ui.button("Clear").clicked() {
+                route.list.drain(..last);
+                self.selected = 0;
+            }
+        });
+    }
+
+    fn parameters_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        command: &mut MoveCommand,
+    ) {
+        let id = self.id.with(command.guid);
+
+        match command.code {
+            // Play SE
+            44 => {
+                let value = crate::get_or_resize!(command.parameters, 0).into_audiofile();
+                if self
+                    .sound_tab
+                    .as_ref()
+                    .is_some_and(|(guid, _)| *guid != command.guid)
+                {
+                    self.sound_tab = None;
+                }
+                let (_, tab) = self.sound_tab.get_or_insert_with(|| {
+                    let tab = crate::SoundTab::new(
+                        update_state.filesystem,
+                        luminol_audio::Source::SE,
+                        value.clone(),
+                    );
+                    (command.guid, tab)
+                });
+
+                ui.group(|ui| {
+                    ui.set_height(300.);
+                    tab.ui(ui, update_state);
+                });
+                if tab.audio_file != *value {
+                    *value = tab.audio_file.clone();
+                }
+            }
+            // Change Graphic
+            41 => {
+                let characters = self.characters.get_or_insert_with(|| {
+                    let mut characters: Vec<_> = update_state
+                        .filesystem
+                        .read_dir("Graphics/Characters")
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter_map(|entry| entry.path.file_stem().map(str::to_string))
+                        .collect();
+                    characters.sort_unstable();
+                    characters
+                });
+
+                // the name is only written back when a graphic is picked, so that names that
+                // aren't UTF-8 survive opening the editor
+                let parameter = crate::get_or_resize!(command.parameters, 0);
+                let mut name = parameter.text().unwrap_or_default().into_owned();
+                let response = ui.add(crate::Field::new("Graphic", |ui: &mut egui::Ui| {
+                    let mut combo_box = egui::ComboBox::from_id_source(id.with("graphic"))
+                        .selected_text(if name.is_empty() {
+                            "(None)"
+                        } else {
+                            name.as_str()
+                        })
+                        .show_ui(ui, |ui| {
+                            let mut changed = ui
+                                .selectable_value(&mut name, String::new(), "(None)")
+                                .changed();
+                            for character in characters.iter() {
+                                changed |= ui
+                                    .selectable_value(&mut name, character.clone(), character)
+                                    .changed();
+                            }
+                            changed
+                        });
+                    if combo_box.inner == Some(true) {
+                        combo_box.response.mark_changed();
+                    }
+                    combo_box.response
+                }));
+                if response.changed() || parameter.text().is_none() {
+                    parameter.set_text(name);
+                }
+                self.fields_ui(ui, update_state, command, 1);
+            }
+            _ => self.fields_ui(ui, update_state, command, 0),
+        }
+    }
+
+    /// Displays the parameters of `command` that can be edited with a plain widget, starting from
+    /// the one at `start`.
+    fn fields_ui(
+        &self,
+        ui: &mut egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        command: &mut MoveCommand,
+        start: usize,
+    ) {
+        let fields = parameter_fields(command.code);
+        if fields.is_empty() {
+            ui.weak(format!("{} has no parameters", command.name()));
+            return;
+        }
+
+        let id = self.id.with(command.guid);
+        for (index, (name, kind)) in fields.iter().enumerate().skip(start) {
+            let value = crate::get_or_resize!(command.parameters, index);
+            ui.add(crate::Field::new(*name, |ui: &mut egui::Ui| {
+                crate::command_view::kind_ui(ui, update_state, id.with(index), kind, value)
+            }));
+        }
+    }
+}
+
+/// The parameters a move command starts with when it's added from the palette.
+fn default_parameters(code: u16) -> Vec<ParameterType> {
+    match code {
+        // Jump
+        14 => vec![ParameterType::Integer(0), ParameterType::Integer(0)],
+        // Wait
+        15 => vec![ParameterType::Integer(1)],
+        // Switch ON, Switch OFF
+        27 | 28 => vec![ParameterType::Integer(1)],
+        // Change Speed, Change Frequency
+        29 | 30 => vec![ParameterType::Integer(3)],
+        // Change Graphic
+        41 => vec![
+            ParameterType::String(String::new()),
+            ParameterType::Integer(0),
+            ParameterType::Integer(2),
+            ParameterType::Integer(0),
+        ],
+        // Change Opacity
+        42 => vec![ParameterType::Integer(255)],
+        // Change Blending
+        43 => vec![ParameterType::Integer(0)],
+        // Play SE
+        44 => vec![ParameterType::AudioFile(AudioFile::default())],
+        // Script
+        45 => vec![ParameterType::String(String::new())],
+        _ => vec![],
+    }
+}
+
+/// The name and kind of each parameter of a move command.
+fn parameter_fields(code: u16) -> Vec<(&'static str, ParameterKind)> {
+    let variants = |names: &[&str], first: i8| ParameterKind::Enum {
+        variants: names
+            .iter()
+            .zip(first..)
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+    };
+
+    match code {
+        14 => vec![("X", ParameterKind::Int), ("Y", ParameterKind::Int)],
+        15 => vec![("Frames", ParameterKind::Int)],
+        27 | 28 => vec![("Switch", ParameterKind::Switch)],
+        29 => vec![(
+            "Speed",
+            variants(
+                &[
+                    "1: Slowest",
+                    "2: Slower",
+                    "3: Slow",
+                    "4: Fast",
+                    "5: Faster",
+                    "6: Fastest",
+                ],
+                1,
+            ),
+        )],
+        30 => vec![(
+            "Frequency",
+            variants(
+                &[
+                    "1: Lowest",
+                    "2: Lower",
+                    "3: Low",
+                    "4: High",
+                    "5: Higher",
+                    "6: Highest",
+                ],
+                1,
+            ),
+        )],
+        41 => vec![
+            ("Graphic", ParameterKind::String),
+            ("Hue", ParameterKind::Int),
+            (
+                "Direction",
+                ParameterKind::Enum {
+                    variants: vec![
+                        ("Down".to_string(), 2),
+                        ("Left".to_string(), 4),
+                        ("Right".to_string(), 6),
+                        ("Up".to_string(), 8),
+                    ],
+                },
+            ),
+            ("Pattern", variants(&["1", "2", "3", "4"], 0)),
+        ],
+        42 => vec![("Opacity", ParameterKind::Int)],
+        43 => vec![("Blending", variants(&["Normal", "Add", "Subtract"], 0))],
+        44 => vec![("SE", ParameterKind::AudioFile)],
+        45 => vec![("Script", ParameterKind::String)],
+        _ => vec![],
+    }
+}
@@ -40,18 +40,26 @@ pub enum SelectedTile {
 }
 
 impl SelectedTile {
-    pub fn from_id(id: i16) -> Self {
-        if id < 384 {
-            SelectedTile::Autotile(id / 48)
-        } else {
-            SelectedTile::Tile(id)
+    pub fn from_id(id: i16, layout: luminol_graphics::AtlasLayout) -> Self {
+        match layout {
+            luminol_graphics::AtlasLayout::XP if id < 384 => SelectedTile::Autotile(id / 48),
+            luminol_graphics::AtlasLayout::XP => SelectedTile::Tile(id),
+            luminol_graphics::AtlasLayout::Ace => {
+                match luminol_data::rmvxace::Tileset::autotile_kind(id) {
+                    Some((index, _)) => SelectedTile::Autotile(index as i16),
+                    None => SelectedTile::Tile(id),
+                }
+            }
         }
     }
 
-    pub fn to_id(&self) -> i16 {
-        match *self {
-            Self::Autotile(tile) => tile * 48,
-            Self::Tile(tile) => tile,
+    pub fn to_id(&self, layout: luminol_graphics::AtlasLayout) -> i16 {
+        match (*self, layout) {
+            (Self::Autotile(tile), luminol_graphics::AtlasLayout::XP) => tile * 48,
+            (Self::Autotile(tile), luminol_graphics::AtlasLayout::Ace) => {
+                luminol_data::rmvxace::Tileset::TILE_ID_A1 + tile * 48
+            }
+            (Self::Tile(tile), _) => tile,
         }
     }
 }
@@ -67,18 +75,7 @@ impl Tilepicker {
         update_state: &luminol_core::UpdateState<'_>,
         map_id: usize, // FIXME
     ) -> color_eyre::Result<Tilepicker> {
-        let map = update_state
-            .data
-            .get_or_load_map(map_id, update_state.filesystem);
-        let tilesets = update_state.data.tilesets();
-        let tileset = &tilesets.data[map.tileset_id];
-
-        let view = luminol_graphics::Tilepicker::new(
-            &update_state.graphics,
-            tileset,
-            update_state.filesystem,
-            false,
-        )?;
+        let view = Self::load_view(update_state, map_id)?;
 
         let mut brush_seed = [0u8; 16];
         brush_seed[0..8].copy_from_slice(
@@ -106,6 +103,70 @@ impl Tilepicker {
         })
     }
 
+    /// Recreates the graphics of the tilepicker, e.g. after the tileset was edited.
+    pub fn reload(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map_id: usize,
+    ) -> color_eyre::Result<()> {
+        let mut view = Self::load_view(update_state, map_id)?;
+        view.coll_enabled = self.view.coll_enabled;
+        view.grid_enabled = self.view.grid_enabled;
+        self.view = view;
+
+        // The tileset graphic may have gotten shorter
+        let bottom = self.bottom();
+        self.selected_tiles_top = self.selected_tiles_top.min(bottom);
+        self.selected_tiles_bottom = self.selected_tiles_bottom.min(bottom);
+        Ok(())
+    }
+
+    fn load_view(
+        update_state: &luminol_core::UpdateState<'_>,
+        map_id: usize,
+    ) -> color_eyre::Result<luminol_graphics::Tilepicker> {
+        if update_state.data.editor_ver() == Some(luminol_config::RMVer::Ace) {
+            let map = update_state
+                .data
+                .get_or_load_ace_map(map_id, update_state.filesystem)?;
+            let tilesets = update_state.data.ace_tilesets();
+            let tileset = &tilesets.data[map.tileset_id];
+
+            return luminol_graphics::Tilepicker::new_ace(
+                &update_state.graphics,
+                tileset,
+                update_state.filesystem,
+            );
+        }
+        if update_state.data.editor_ver() == Some(luminol_config::RMVer::VX) {
+            // Every RPG Maker VX map uses the same tileset
+            let system = update_state.data.vx_system();
+            return luminol_graphics::Tilepicker::new_vx(
+                &update_state.graphics,
+                &system.passages,
+                update_state.filesystem,
+            );
+        }
+
+        let map = update_state
+            .data
+            .get_or_load_map(map_id, update_state.filesystem);
+        let tilesets = update_state.data.tilesets();
+        let tileset = &tilesets.data[map.tileset_id];
+
+        luminol_graphics::Tilepicker::new(
+            &update_state.graphics,
+            tileset,
+            update_state.filesystem,
+            false,
+        )
+    }
+
+    /// The index of the last row of the tilepicker.
+    fn bottom(&self) -> i16 {
+        self.view.data.ysize().saturating_sub(1) as i16
+    }
+
     pub fn get_tile_from_offset(
         &self,
         absolute_x: i16,
@@ -139,8 +200,12 @@ impl Tilepicker {
             )
         };
 
-        match y {
-            ..=0 => SelectedTile::Autotile(x),
+        match (y, self.view.atlas.layout) {
+            (_, luminol_graphics::AtlasLayout::Ace) => SelectedTile::from_id(
+                self.view.data[(x as usize, y as usize, 0)],
+                luminol_graphics::AtlasLayout::Ace,
+            ),
+            (..=0, _) => SelectedTile::Autotile(x),
             _ => SelectedTile::Tile(x + (y - 1) * 8 + 384),
         }
     }
@@ -154,7 +219,7 @@ impl Tilepicker {
         self.brush_random = update_state.toolbar.brush_random != ui.input(|i| i.modifiers.alt);
 
         let (canvas_rect, response) = ui.allocate_exact_size(
-            egui::vec2(256., self.view.atlas.tileset_height as f32 + 32.),
+            egui::vec2(256., self.view.data.ysize() as f32 * 32.),
             egui::Sense::click_and_drag(),
         );
 
@@ -212,7 +277,7 @@ impl Tilepicker {
                 pos
             };
             let rect = egui::Rect::from_two_pos(drag_origin, pos);
-            let bottom = self.view.atlas.tileset_height as i16 / 32;
+            let bottom = self.bottom();
             self.selected_tiles_left = (rect.left() as i16).clamp(0, 7);
             self.selected_tiles_right = (rect.right() as i16).clamp(0, 7);
             self.selected_tiles_top = (rect.top() as i16).clamp(0, bottom);
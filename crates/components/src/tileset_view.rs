@@ -0,0 +1,272 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_graphics::Renderable;
+
+const PASSAGE_DOWN: i16 = 0x01;
+const PASSAGE_LEFT: i16 = 0x02;
+const PASSAGE_RIGHT: i16 = 0x04;
+const PASSAGE_UP: i16 = 0x08;
+const PASSAGE_ALL: i16 = PASSAGE_DOWN | PASSAGE_LEFT | PASSAGE_RIGHT | PASSAGE_UP;
+const BUSH_FLAG: i16 = 0x40;
+const COUNTER_FLAG: i16 = 0x80;
+
+/// The per-tile flag that is edited by clicking on a `TilesetView`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[derive(strum::Display, strum::EnumIter)]
+pub enum TileFlag {
+    #[default]
+    Passage,
+    #[strum(to_string = "Passage (4 dir)")]
+    DirectionalPassage,
+    Priority,
+    #[strum(to_string = "Bush Flag")]
+    Bush,
+    #[strum(to_string = "Counter Flag")]
+    Counter,
+    #[strum(to_string = "Terrain Tag")]
+    Terrain,
+}
+
+/// The tileset graphic with one kind of per-tile flag drawn on top of it.
+pub struct TilesetView {
+    pub view: luminol_graphics::Tilepicker,
+    pub flag: TileFlag,
+}
+
+impl TilesetView {
+    pub fn new(
+        update_state: &luminol_core::UpdateState<'_>,
+        tileset: &luminol_data::rpg::Tileset,
+    ) -> color_eyre::Result<Self> {
+        let view = luminol_graphics::Tilepicker::new(
+            &update_state.graphics,
+            tileset,
+            update_state.filesystem,
+            false,
+        )?;
+
+        Ok(Self {
+            view,
+            flag: TileFlag::default(),
+        })
+    }
+
+    /// Returns the tile IDs for a position in the view. Every autotile in the first row stands for
+    /// all 48 of its tile IDs.
+    fn tile_ids(x: usize, y: usize) -> std::ops::Range<usize> {
+        if y == 0 {
+            x * 48..(x + 1) * 48
+        } else {
+            let id = 384 + (y - 1) * 8 + x;
+            id..id + 1
+        }
+    }
+
+    fn flag_text(&self, tileset: &luminol_data::rpg::Tileset, tile_id: usize) -> Option<String> {
+        let value = |table: &luminol_data::Table1| {
+            table.as_slice().get(tile_id).copied().unwrap_or_default()
+        };
+        let symbol = |set: bool| (if set { "○" } else { "×" }).to_string();
+
+        match self.flag {
+            // Passages are drawn by the collision layer instead
+            TileFlag::Passage | TileFlag::DirectionalPassage => None,
+            TileFlag::Priority => Some(value(&tileset.priorities).to_string()),
+            TileFlag::Bush => Some(symbol(value(&tileset.passages) & BUSH_FLAG != 0)),
+            TileFlag::Counter => Some(symbol(value(&tileset.passages) & COUNTER_FLAG != 0)),
+            TileFlag::Terrain => Some(value(&tileset.terrain_tags).to_string()),
+        }
+    }
+
+    /// Changes the selected flag of the tile at a position in the view. `offset` is the position of
+    /// the pointer inside of the tile, which is used to pick a direction when editing directional
+    /// passages. If `reverse` is true, priorities and terrain tags are decremented instead of
+    /// incremented.
+    fn edit_tile(
+        &mut self,
+        render_state: &luminol_egui_wgpu::RenderState,
+        tileset: &mut luminol_data::rpg::Tileset,
+        position: (usize, usize),
+        offset: egui::Vec2,
+        reverse: bool,
+    ) {
+        let ids = Self::tile_ids(position.0, position.1);
+        for table in [
+            &mut tileset.passages,
+            &mut tileset.priorities,
+            &mut tileset.terrain_tags,
+        ] {
+            if table.len() < ids.end {
+                table.resize(ids.end);
+            }
+        }
+
+        let cycle = |value: i16, count: i16| {
+            (value + if reverse { count - 1 } else { 1 }).rem_euclid(count)
+        };
+
+        let passage = tileset.passages[ids.start];
+        let (table, value) = match self.flag {
+            TileFlag::Passage => (
+                &mut tileset.passages,
+                if passage & PASSAGE_ALL != 0 {
+                    passage & !PASSAGE_ALL
+                } else {
+                    passage | PASSAGE_ALL
+                },
+            ),
+            TileFlag::DirectionalPassage => {
+                let offset = offset - egui::vec2(16., 16.);
+                let direction = if offset.x.abs() > offset.y.abs() {
+                    if offset.x < 0. {
+                        PASSAGE_LEFT
+                    } else {
+                        PASSAGE_RIGHT
+                    }
+                } else if offset.y < 0. {
+                    PASSAGE_UP
+                } else {
+                    PASSAGE_DOWN
+                };
+                (&mut tileset.passages, passage ^ direction)
+            }
+            TileFlag::Bush => (&mut tileset.passages, passage ^ BUSH_FLAG),
+            TileFlag::Counter => (&mut tileset.passages, passage ^ COUNTER_FLAG),
+            TileFlag::Priority => {
+                let priority = tileset.priorities[ids.start];
+                (&mut tileset.priorities, cycle(priority, 6))
+            }
+            TileFlag::Terrain => {
+                let terrain_tag = tileset.terrain_tags[ids.start];
+                (&mut tileset.terrain_tags, cycle(terrain_tag, 8))
+            }
+        };
+        table.as_mut_slice()[ids].fill(value);
+
+        if matches!(
+            self.flag,
+            TileFlag::Passage | TileFlag::DirectionalPassage | TileFlag::Bush | TileFlag::Counter
+        ) {
+            self.view
+                .collision
+                .set_passage(render_state, value, position);
+        }
+    }
+
+    /// Shows the view. The response is marked as changed if a flag was edited.
+    pub fn ui(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        ui: &mut egui::Ui,
+        scroll_rect: egui::Rect,
+        tileset: &mut luminol_data::rpg::Tileset,
+    ) -> egui::Response {
+        let rows = self.view.atlas.tileset_height as usize / 32 + 1;
+        let (canvas_rect, mut response) =
+            ui.allocate_exact_size(egui::vec2(256., rows as f32 * 32.), egui::Sense::click());
+
+        let absolute_scroll_rect = ui
+            .ctx()
+            .screen_rect()
+            .intersect(scroll_rect.translate(canvas_rect.min.to_vec2()));
+        let scroll_rect = absolute_scroll_rect.translate(-canvas_rect.min.to_vec2());
+
+        self.view.coll_enabled =
+            matches!(self.flag, TileFlag::Passage | TileFlag::DirectionalPassage);
+        self.view.grid.display.set_pixels_per_point(
+            &update_state.graphics.render_state,
+            ui.ctx().pixels_per_point(),
+        );
+
+        self.view.set_position(
+            &update_state.graphics.render_state,
+            glam::vec2(0.0, -scroll_rect.top()),
+        );
+        self.view.viewport.set(
+            &update_state.graphics.render_state,
+            glam::vec2(scroll_rect.width(), scroll_rect.height()),
+            glam::Vec2::ZERO,
+            glam::Vec2::ONE,
+        );
+        self.view
+            .update_animation(&update_state.graphics.render_state, ui.input(|i| i.time));
+
+        let painter = luminol_graphics::Painter::new(self.view.prepare(&update_state.graphics));
+        ui.painter()
+            .add(luminol_egui_wgpu::Callback::new_paint_callback(
+                absolute_scroll_rect,
+                painter,
+            ));
+
+        // Only draw the flags of the rows that are actually visible
+        let first_row = (scroll_rect.top() / 32.).floor().max(0.) as usize;
+        let last_row = ((scroll_rect.bottom() / 32.).ceil() as usize).min(rows);
+        let painter = ui.painter_at(absolute_scroll_rect);
+        for y in first_row..last_row {
+            for x in 0..8 {
+                let Some(text) = self.flag_text(tileset, Self::tile_ids(x, y).start) else {
+                    continue;
+                };
+                let center =
+                    canvas_rect.min + egui::vec2(x as f32 * 32. + 16., y as f32 * 32. + 16.);
+                let font_id = egui::FontId::proportional(16.);
+                painter.text(
+                    center + egui::vec2(1., 1.),
+                    egui::Align2::CENTER_CENTER,
+                    &text,
+                    font_id.clone(),
+                    egui::Color32::BLACK,
+                );
+                painter.text(
+                    center,
+                    egui::Align2::CENTER_CENTER,
+                    &text,
+                    font_id,
+                    egui::Color32::WHITE,
+                );
+            }
+        }
+
+        if response.clicked() || response.secondary_clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let pos = pos - canvas_rect.min;
+                let (x, y) = ((pos.x / 32.) as usize, (pos.y / 32.) as usize);
+                if x < 8 && y < rows {
+                    let offset = pos - egui::vec2(x as f32 * 32., y as f32 * 32.);
+                    self.edit_tile(
+                        &update_state.graphics.render_state,
+                        tileset,
+                        (x, y),
+                        offset,
+                        response.secondary_clicked(),
+                    );
+                    response.mark_changed();
+                }
+            }
+        }
+
+        response
+    }
+}
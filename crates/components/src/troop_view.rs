@@ -0,0 +1,341 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::WrapErr;
+use itertools::Itertools;
+use luminol_graphics::{Renderable, Sprite, Viewport};
+
+/// The size of the battle screen in RPG Maker XP.
+pub const BATTLE_SIZE: egui::Vec2 = egui::vec2(640., 320.);
+
+/// The y coordinate that auto-arranged battlers stand on.
+const ARRANGE_BASELINE: i32 = 304;
+
+/// Opacity of battlers that start the battle hidden.
+const HIDDEN_OPACITY: i32 = 128;
+
+/// Preview of a troop on top of the system battleback, where the members can be dragged around.
+pub struct TroopView {
+    /// The index of the selected member, if any.
+    pub selected_member: Option<usize>,
+
+    viewport: Viewport,
+    battleback: Option<Sprite>,
+    battleback_name: Option<camino::Utf8PathBuf>,
+    battlers: Vec<Battler>,
+
+    /// The enemy IDs the battlers were created for.
+    battler_enemies: Vec<usize>,
+    /// Offset of the pointer from the origin of the member being dragged.
+    drag_offset: Option<egui::Vec2>,
+}
+
+struct Battler {
+    sprite: Option<Sprite>,
+    size: egui::Vec2,
+}
+
+impl TroopView {
+    pub fn new(update_state: &luminol_core::UpdateState<'_>) -> Self {
+        Self {
+            selected_member: None,
+
+            viewport: Viewport::new(
+                &update_state.graphics,
+                glam::vec2(BATTLE_SIZE.x, BATTLE_SIZE.y),
+            ),
+            battleback: None,
+            battleback_name: None,
+            battlers: Vec::new(),
+
+            battler_enemies: Vec::new(),
+            drag_offset: None,
+        }
+    }
+
+    /// Spreads the members of a troop evenly across the battle screen.
+    pub fn auto_arrange(troop: &mut luminol_data::rpg::Troop) {
+        let count = troop.members.len() as i32;
+        for (i, member) in troop.members.iter_mut().enumerate() {
+            member.x = BATTLE_SIZE.x as i32 * (i as i32 + 1) / (count + 1);
+            member.y = ARRANGE_BASELINE;
+        }
+    }
+
+    /// Places a member that was just added to a troop in the middle of the battle screen.
+    pub fn place_new_member(member: &mut luminol_data::rpg::troop::Member) {
+        member.x = BATTLE_SIZE.x as i32 / 2;
+        member.y = ARRANGE_BASELINE;
+    }
+
+    /// Updates the enemy HP conditions of a troop's pages after the member at `index` was removed,
+    /// so that they keep referring to the same members and never to one past the end.
+    pub fn member_removed(troop: &mut luminol_data::rpg::Troop, index: usize) {
+        let last = troop.members.len().saturating_sub(1);
+        for page in troop.pages.iter_mut() {
+            let enemy_index = &mut page.condition.enemy_index;
+            if *enemy_index > index {
+                *enemy_index -= 1;
+            }
+            *enemy_index = (*enemy_index).min(last);
+        }
+    }
+
+    /// Forces the battler graphics to be reloaded the next time the view is shown.
+    pub fn clear(&mut self) {
+        self.selected_member = None;
+        self.battlers.clear();
+        self.battler_enemies.clear();
+        self.drag_offset = None;
+    }
+
+    fn load_texture(
+        update_state: &luminol_core::UpdateState<'_>,
+        directory: &str,
+        path: &camino::Utf8Path,
+    ) -> std::sync::Arc<luminol_graphics::Texture> {
+        update_state
+            .graphics
+            .texture_loader
+            .load_now_dir(update_state.filesystem, directory, path)
+            .wrap_err_with(|| format!("Error loading {directory} graphic {path:?}"))
+            .unwrap_or_else(|e| {
+                update_state.graphics.send_texture_error(e);
+                update_state.graphics.texture_loader.placeholder_texture()
+            })
+    }
+
+    fn update_sprites(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        troop: &luminol_data::rpg::Troop,
+    ) {
+        {
+            let system = update_state.data.system();
+            if system.battleback_name != self.battleback_name {
+                self.battleback = system.battleback_name.as_deref().map(|path| {
+                    let texture = Self::load_texture(update_state, "Graphics/Battlebacks", path);
+                    Sprite::basic(&update_state.graphics, &texture, &self.viewport)
+                });
+                self.battleback_name = system.battleback_name.clone();
+            }
+        }
+
+        if self
+            .battler_enemies
+            .iter()
+            .copied()
+            .eq(troop.members.iter().map(|m| m.enemy_id))
+        {
+            return;
+        }
+
+        let enemies = update_state.data.enemies();
+        self.battlers = troop
+            .members
+            .iter()
+            .map(|member| {
+                let Some(enemy) = enemies.data.get(member.enemy_id) else {
+                    return Battler::empty();
+                };
+                let Some(path) = enemy.battler_name.as_deref() else {
+                    return Battler::empty();
+                };
+
+                let texture = Self::load_texture(update_state, "Graphics/Battlers", path);
+                let sprite = Sprite::basic_hue(
+                    &update_state.graphics,
+                    enemy.battler_hue,
+                    &texture,
+                    &self.viewport,
+                );
+                Battler {
+                    sprite: Some(sprite),
+                    size: texture.size_vec2(),
+                }
+            })
+            .collect();
+        self.battler_enemies = troop.members.iter().map(|m| m.enemy_id).collect();
+        self.selected_member = self.selected_member.filter(|&i| i < self.battlers.len());
+    }
+
+    /// Shows the battle preview. The response is marked as changed if a member was moved.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        troop: &mut luminol_data::rpg::Troop,
+    ) -> egui::Response {
+        self.update_sprites(update_state, troop);
+
+        let width = ui.available_width().min(BATTLE_SIZE.x);
+        let scale = width / BATTLE_SIZE.x;
+        let (canvas_rect, mut response) =
+            ui.allocate_exact_size(BATTLE_SIZE * scale, egui::Sense::click());
+
+        ui.painter()
+            .rect_filled(canvas_rect, 0., egui::Color32::BLACK);
+
+        // Battlers further down the screen are drawn in front of the ones above them
+        let draw_order = troop
+            .members
+            .iter()
+            .enumerate()
+            .sorted_by_key(|(i, member)| (member.y, *i))
+            .map(|(i, _)| i)
+            .collect_vec();
+
+        let battler_rect = |battler: &Battler, member: &luminol_data::rpg::troop::Member| {
+            let size = battler.size;
+            let origin = egui::vec2(member.x as f32 - size.x / 2., member.y as f32 - size.y);
+            egui::Rect::from_min_size(canvas_rect.min + origin * scale, size * scale)
+        };
+
+        // Only the visible part of the canvas can be used as the viewport
+        let paint_rect = canvas_rect.intersect(ui.clip_rect());
+        if paint_rect.is_positive() {
+            let translation = canvas_rect.min - paint_rect.min;
+            self.viewport.set(
+                &update_state.graphics.render_state,
+                glam::vec2(paint_rect.width(), paint_rect.height()),
+                glam::vec2(translation.x, translation.y),
+                glam::Vec2::splat(scale),
+            );
+
+            if let Some(battleback) = &mut self.battleback {
+                ui.painter()
+                    .add(luminol_egui_wgpu::Callback::new_paint_callback(
+                        paint_rect,
+                        luminol_graphics::Painter::new(battleback.prepare(&update_state.graphics)),
+                    ));
+            }
+
+            for &i in &draw_order {
+                let member = &troop.members[i];
+                let battler = &mut self.battlers[i];
+                let Some(sprite) = &mut battler.sprite else {
+                    continue;
+                };
+
+                sprite.transform.set_position(
+                    &update_state.graphics.render_state,
+                    glam::vec2(
+                        member.x as f32 - battler.size.x / 2.,
+                        member.y as f32 - battler.size.y,
+                    ),
+                );
+                let opacity = if member.hidden { HIDDEN_OPACITY } else { 255 };
+                if sprite.graphic.opacity() != opacity {
+                    sprite
+                        .graphic
+                        .set_opacity(&update_state.graphics.render_state, opacity);
+                }
+
+                ui.painter()
+                    .add(luminol_egui_wgpu::Callback::new_paint_callback(
+                        paint_rect,
+                        luminol_graphics::Painter::new(sprite.prepare(&update_state.graphics)),
+                    ));
+            }
+        }
+
+        let mut modified = false;
+        let mut any_interacted = false;
+        for &i in &draw_order {
+            let rect = battler_rect(&self.battlers[i], &troop.members[i]);
+            let member_response =
+                ui.interact(rect, response.id.with(i), egui::Sense::click_and_drag());
+
+            if self.battlers[i].sprite.is_none() {
+                ui.painter()
+                    .rect_stroke(rect, 0., egui::Stroke::new(1., egui::Color32::GRAY));
+            }
+
+            if member_response.hovered() {
+                ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+            }
+
+            if member_response.clicked() || member_response.drag_started() {
+                any_interacted = true;
+                self.selected_member = Some(i);
+                self.drag_offset = member_response
+                    .interact_pointer_pos()
+                    .map(|pos| (pos - rect.min) / scale);
+            }
+
+            if member_response.dragged() {
+                any_interacted = true;
+                ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+
+                if let (Some(pos), Some(offset)) =
+                    (member_response.interact_pointer_pos(), self.drag_offset)
+                {
+                    let size = self.battlers[i].size;
+                    let origin = (pos - canvas_rect.min) / scale - offset;
+                    let x = (origin.x + size.x / 2.).round() as i32;
+                    let y = (origin.y + size.y).round() as i32;
+
+                    let member = &mut troop.members[i];
+                    let x = x.clamp(0, BATTLE_SIZE.x as i32);
+                    let y = y.clamp(0, BATTLE_SIZE.y as i32);
+                    if member.x != x || member.y != y {
+                        member.x = x;
+                        member.y = y;
+                        modified = true;
+                    }
+                }
+            }
+
+            if member_response.drag_stopped() {
+                self.drag_offset = None;
+            }
+        }
+
+        if response.clicked() && !any_interacted {
+            self.selected_member = None;
+        }
+
+        if let Some(i) = self.selected_member {
+            ui.painter().rect_stroke(
+                battler_rect(&self.battlers[i], &troop.members[i]),
+                2.,
+                egui::Stroke::new(2., ui.visuals().selection.stroke.color),
+            );
+        }
+
+        if modified {
+            response.mark_changed();
+        }
+        response
+    }
+}
+
+impl Battler {
+    fn empty() -> Self {
+        Self {
+            sprite: None,
+            size: egui::vec2(32., 32.),
+        }
+    }
+}
@@ -0,0 +1,237 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use itertools::Itertools;
+use luminol_config::command_db::CommandDB;
+use luminol_data::commands::{CommandKind, Parameter, ParameterKind};
+use luminol_data::rpg::EventCommand;
+use luminol_data::ParameterType;
+
+/// What kind of ID a [`Usage`] refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Switch,
+    Variable,
+}
+
+impl Kind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Switch => "Switch",
+            Self::Variable => "Variable",
+        }
+    }
+}
+
+/// Where a [`Usage`] was found. Common events and troops are stored by index, maps by their ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Source {
+    CommonEvent(usize),
+    Troop(usize),
+    Map(usize),
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::CommonEvent(id) => write!(f, "Common Event {:0>3}", id + 1),
+            Self::Troop(id) => write!(f, "Troop {:0>3}", id + 1),
+            Self::Map(id) => write!(f, "Map {id:0>3}"),
+        }
+    }
+}
+
+/// A place where a switch or variable is read or changed.
+#[derive(Clone, Debug)]
+pub struct Usage {
+    pub kind: Kind,
+    /// The index of the switch or variable in `System`, starting from 0.
+    pub id: usize,
+    pub source: Source,
+    /// Where exactly in the source it's used, like "Event 001 (EV001), page 2, command 5".
+    pub description: String,
+}
+
+struct Finder<'a> {
+    command_db: &'a CommandDB,
+    source: Source,
+    usages: Vec<Usage>,
+}
+
+impl Finder<'_> {
+    fn add(&mut self, kind: Kind, id: usize, description: impl Into<String>) {
+        self.usages.push(Usage {
+            kind,
+            id,
+            source: self.source,
+            description: description.into(),
+        });
+    }
+
+    fn commands(&mut self, list: &[EventCommand], what: &str) {
+        for (index, command) in list.iter().enumerate() {
+            let Some(description) = self.command_db.get(command.code) else {
+                continue;
+            };
+            let parameters = match &description.kind {
+                CommandKind::Single(parameters) | CommandKind::Branch { parameters, .. } => {
+                    parameters
+                }
+                CommandKind::Multi { .. } => continue,
+            };
+            let what = format!("{what}, command {} ({})", index + 1, description.name);
+            self.parameters(parameters, command, &what);
+        }
+    }
+
+    /// Only the chosen option of a selection is looked at, since the parameters of the other
+    /// options are unused.
+    fn parameters(&mut self, parameters: &[Parameter], command: &EventCommand, what: &str) {
+        for parameter in parameters {
+            match parameter {
+                Parameter::Single { index, kind, .. } => {
+                    let kind = match kind {
+                        ParameterKind::Switch => Kind::Switch,
+                        ParameterKind::Variable => Kind::Variable,
+                        _ => continue,
+                    };
+                    // Commands store IDs the way the game does, starting from 1
+                    if let Some(&id) = command
+                        .parameters
+                        .get(index.as_usize())
+                        .and_then(ParameterType::as_integer)
+                        .filter(|&&id| id > 0)
+                    {
+                        self.add(kind, id as usize - 1, what);
+                    }
+                }
+                Parameter::Selection {
+                    index, parameters, ..
+                } => {
+                    let selected = command
+                        .parameters
+                        .get(index.as_usize())
+                        .and_then(ParameterType::as_integer)
+                        .copied()
+                        .unwrap_or_default();
+                    if let Some((_, parameter)) = parameters
+                        .iter()
+                        .find(|(value, _)| *value as i32 == selected)
+                    {
+                        self.parameters(std::slice::from_ref(parameter), command, what);
+                    }
+                }
+                Parameter::Group { parameters, .. } => {
+                    self.parameters(parameters, command, what);
+                }
+                Parameter::Dummy | Parameter::Label(_) => {}
+            }
+        }
+    }
+}
+
+/// Finds every place a switch or variable is used in map events, common events and troops.
+/// Every map is loaded to do this. Maps that can't be loaded are skipped.
+///
+/// Only RPG Maker XP projects are supported.
+pub fn find(
+    data: &luminol_core::Data,
+    filesystem: &impl luminol_filesystem::FileSystem,
+    command_db: &CommandDB,
+) -> Vec<Usage> {
+    let mut f = Finder {
+        command_db,
+        source: Source::CommonEvent(0),
+        usages: Vec::new(),
+    };
+
+    for (id, common_event) in data.common_events().data.iter().enumerate() {
+        f.source = Source::CommonEvent(id);
+        // Unlike the other IDs in the data model, this one starts from 1
+        if common_event.trigger != 0 {
+            if let Some(id) = common_event.switch_id.checked_sub(1) {
+                f.add(Kind::Switch, id, "The condition switch");
+            }
+        }
+        f.commands(&common_event.list, "The event");
+    }
+
+    for (id, troop) in data.troops().data.iter().enumerate() {
+        f.source = Source::Troop(id);
+        for (index, page) in troop.pages.iter().enumerate() {
+            let what = format!("Page {}", index + 1);
+            if page.condition.switch_valid {
+                if let Some(switch_id) = page.condition.switch_id {
+                    f.add(Kind::Switch, switch_id, format!("{what}, the condition"));
+                }
+            }
+            f.commands(&page.list, &what);
+        }
+    }
+
+    let map_ids = data.map_infos().data.keys().copied().sorted().collect_vec();
+    for id in map_ids {
+        f.source = Source::Map(id);
+        let map = match data.try_get_or_load_map(id, filesystem) {
+            Ok(map) => map,
+            Err(e) => {
+                luminol_core::tracing::warn!(
+                    "Skipping map {id:0>3} while looking for switch and variable usages: {e}"
+                );
+                continue;
+            }
+        };
+
+        for (event_id, event) in map.events.iter() {
+            for (index, page) in event.pages.iter().enumerate() {
+                let what = format!("Event {event_id:0>3} ({}), page {}", event.name, index + 1);
+                let condition = &page.condition;
+                if condition.switch1_valid {
+                    f.add(
+                        Kind::Switch,
+                        condition.switch1_id,
+                        format!("{what}, the condition"),
+                    );
+                }
+                if condition.switch2_valid {
+                    f.add(
+                        Kind::Switch,
+                        condition.switch2_id,
+                        format!("{what}, the condition"),
+                    );
+                }
+                if condition.variable_valid {
+                    f.add(
+                        Kind::Variable,
+                        condition.variable_id,
+                        format!("{what}, the condition"),
+                    );
+                }
+                f.commands(&page.list, &what);
+            }
+        }
+    }
+
+    f.usages
+}
@@ -0,0 +1,60 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_components::TroopView;
+use luminol_data::rpg;
+
+#[test]
+fn enemy_conditions_follow_removed_members() {
+    let mut troop = rpg::Troop {
+        members: (0..2).map(|_| rpg::troop::Member::default()).collect(),
+        pages: [0, 1, 2]
+            .into_iter()
+            .map(|enemy_index| rpg::troop::Page {
+                condition: rpg::troop::Condition {
+                    enemy_index,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    // the troop had three members and the second one was removed
+    TroopView::member_removed(&mut troop, 1);
+    let indices = troop
+        .pages
+        .iter()
+        .map(|page| page.condition.enemy_index)
+        .collect::<Vec<_>>();
+    assert_eq!(indices, [0, 1, 1]);
+
+    troop.members.clear();
+    TroopView::member_removed(&mut troop, 0);
+    assert!(troop
+        .pages
+        .iter()
+        .all(|page| page.condition.enemy_index == 0));
+}
@@ -69,6 +69,15 @@ impl CommandDB {
         }
     }
 
+    /// Fills in the default commands if there are none.
+    ///
+    /// Projects created before the default commands existed saved an empty list, so we restore them here.
+    pub fn restore_missing_defaults(&mut self, ver: RMVer) {
+        if self.default.is_empty() {
+            self.default = Self::new(ver).default;
+        }
+    }
+
     pub fn get(&self, code: u16) -> Option<&CommandDescription> {
         self.user
             .iter()
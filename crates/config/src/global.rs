@@ -45,6 +45,8 @@ pub struct Config {
     /// The current code theme
     pub theme: CodeTheme,
     pub rtp_paths: HashMap<String, String>,
+    /// How many changes can be undone, both in the project-wide history and in each map.
+    pub history_depth: usize,
 }
 
 impl Default for Config {
@@ -59,6 +61,7 @@ impl Config {
             recent_projects: VecDeque::new(),
             theme: CodeTheme::dark(),
             rtp_paths: HashMap::new(),
+            history_depth: 50,
             #[cfg(not(target_arch = "wasm32"))]
             terminal: terminal::Config::default(),
         }
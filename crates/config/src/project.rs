@@ -46,6 +46,8 @@ pub struct Project {
     pub playtest_exe: String,
     pub prefer_rgssad: bool,
     pub persistence_id: u64,
+    /// How many backups of the data files to keep in `.luminol/backups` when saving.
+    pub backup_count: usize,
 }
 
 impl Default for Project {
@@ -59,6 +61,7 @@ impl Default for Project {
             playtest_exe: "game".to_string(),
             prefer_rgssad: false,
             persistence_id: 0,
+            backup_count: 5,
         }
     }
 }
@@ -0,0 +1,76 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_config::{command_db::CommandDB, RMVer};
+use luminol_data::rpg::EventCommand;
+
+/// Event commands as RPG Maker XP writes them into maps and common events, using every command
+/// the editor can create.
+const XP_CORPUS: &[(&str, &[u8])] = &[
+    (
+        "xp_sample_events.rxdata",
+        include_bytes!("fixtures/xp_sample_events.rxdata"),
+    ),
+    (
+        "xp_commands.rxdata",
+        include_bytes!("../../data/tests/fixtures/xp_commands.rxdata"),
+    ),
+    (
+        "unusual_parameters.rxdata",
+        include_bytes!("../../data/tests/fixtures/unusual_parameters.rxdata"),
+    ),
+];
+
+#[test]
+fn every_xp_command_is_known() {
+    let db = CommandDB::new(RMVer::XP);
+
+    for (name, data) in XP_CORPUS {
+        let mut deserializer = alox_48::Deserializer::new(data).expect("invalid marshal header");
+        let commands: Vec<EventCommand> =
+            alox_48::Deserialize::deserialize(&mut deserializer).expect("failed to load commands");
+
+        let mut missing: Vec<u16> = commands
+            .iter()
+            .map(|command| command.code)
+            .filter(|&code| db.get(code).is_none())
+            .collect();
+        missing.sort_unstable();
+        missing.dedup();
+        assert!(
+            missing.is_empty(),
+            "{name} uses unknown commands {missing:?}"
+        );
+    }
+}
+
+#[test]
+fn missing_defaults_are_restored() {
+    let mut db: CommandDB = ron::from_str("(default: [], user: [])").expect("invalid command db");
+    assert!(db.get(101).is_none());
+
+    db.restore_missing_defaults(RMVer::XP);
+    assert_eq!(db.len(), CommandDB::new(RMVer::XP).len());
+    assert!(db.get(101).is_some());
+}
@@ -23,10 +23,14 @@
 // Program grant you additional permission to convey the resulting work.
 
 use color_eyre::eyre::WrapErr;
-use luminol_data::rpg;
+use luminol_data::{rmvx, rmvxace, rpg};
 use std::{
     cell::{RefCell, RefMut},
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
+};
+
+use crate::data_format::{
+    read_data, read_nil_padded, write_data, write_nil_padded, write_ron, DataFormat,
 };
 
 // TODO convert this to an option like project config?
@@ -53,75 +57,60 @@ pub enum Data {
         weapons: RefCell<rpg::Weapons>,
 
         maps: RefCell<HashMap<usize, rpg::Map>>,
-    },
-}
-
-fn read_data<T>(
-    filesystem: &impl luminol_filesystem::FileSystem,
-    filename: impl AsRef<camino::Utf8Path>,
-) -> color_eyre::Result<T>
-where
-    T: for<'de> alox_48::Deserialize<'de>,
-{
-    let path = camino::Utf8PathBuf::from("Data").join(filename);
-    let data = filesystem.read(path)?;
-
-    let mut de = alox_48::Deserializer::new(&data)?;
-    let result = alox_48::path_to_error::deserialize(&mut de);
-
-    result.map_err(|(error, trace)| format_traced_error(error, trace))
-}
-
-fn write_data(
-    data: &impl alox_48::Serialize,
-    filesystem: &impl luminol_filesystem::FileSystem,
-    filename: impl AsRef<camino::Utf8Path>,
-) -> color_eyre::Result<()> {
-    let path = camino::Utf8PathBuf::from("Data").join(filename);
-
-    let mut serializer = alox_48::Serializer::new();
-    alox_48::path_to_error::serialize(data, &mut serializer)
-        .map_err(|(error, trace)| format_traced_error(error, trace))?;
+        /// Maps removed with [`Data::remove_map`]. Their files are deleted on the next save.
+        deleted_maps: RefCell<HashSet<usize>>,
 
-    filesystem
-        .write(path, serializer.output)
-        .map_err(color_eyre::Report::from)
-}
+        /// The format the data was loaded from and will be saved in.
+        format: DataFormat,
+    },
+    LoadedVX {
+        actors: RefCell<rmvx::Actors>,
+        animations: RefCell<rmvx::Animations>,
+        areas: RefCell<rmvx::Areas>,
+        armors: RefCell<rmvx::Armors>,
+        classes: RefCell<rmvx::Classes>,
+        common_events: RefCell<rpg::CommonEvents>,
+        enemies: RefCell<rmvx::Enemies>,
+        items: RefCell<rmvx::Items>,
+        map_infos: RefCell<rpg::MapInfos>,
+        scripts: RefCell<rpg::Scripts>,
+        skills: RefCell<rmvx::Skills>,
+        states: RefCell<rmvx::States>,
+        system: RefCell<rmvx::System>,
+        troops: RefCell<rmvx::Troops>,
+        weapons: RefCell<rmvx::Weapons>,
 
-fn read_nil_padded<T>(
-    filesystem: &impl luminol_filesystem::FileSystem,
-    filename: impl AsRef<camino::Utf8Path>,
-) -> color_eyre::Result<Vec<T>>
-where
-    T: for<'de> alox_48::Deserialize<'de>,
-{
-    let path = camino::Utf8PathBuf::from("Data").join(filename);
-    let data = filesystem.read(path)?;
+        maps: RefCell<HashMap<usize, rmvx::Map>>,
+        /// Maps removed with [`Data::remove_map`]. Their files are deleted on the next save.
+        deleted_maps: RefCell<HashSet<usize>>,
 
-    let mut trace = alox_48::path_to_error::Trace::new();
-    let mut de = alox_48::Deserializer::new(&data)?;
-    let de = alox_48::path_to_error::Deserializer::new(&mut de, &mut trace);
+        /// The format the data was loaded from and will be saved in.
+        format: DataFormat,
+    },
+    LoadedAce {
+        actors: RefCell<rmvxace::Actors>,
+        animations: RefCell<rmvxace::Animations>,
+        armors: RefCell<rmvxace::Armors>,
+        classes: RefCell<rmvxace::Classes>,
+        common_events: RefCell<rpg::CommonEvents>,
+        enemies: RefCell<rmvxace::Enemies>,
+        items: RefCell<rmvxace::Items>,
+        map_infos: RefCell<rpg::MapInfos>,
+        scripts: RefCell<rpg::Scripts>,
+        skills: RefCell<rmvxace::Skills>,
+        states: RefCell<rmvxace::States>,
+        system: RefCell<rmvxace::System>,
+        tilesets: RefCell<rmvxace::Tilesets>,
+        troops: RefCell<rmvxace::Troops>,
+        weapons: RefCell<rmvxace::Weapons>,
 
-    luminol_data::helpers::nil_padded_alox::deserialize_with(de)
-        .map_err(|error| format_traced_error(error, trace))
-}
+        maps: RefCell<HashMap<usize, rmvxace::Map>>,
+        /// Maps removed with [`Data::remove_map`]. Their files are deleted on the next save.
+        deleted_maps: RefCell<HashSet<usize>>,
 
-fn write_nil_padded(
-    data: &[impl alox_48::Serialize],
-    filesystem: &impl luminol_filesystem::FileSystem,
-    filename: impl AsRef<camino::Utf8Path>,
-) -> color_eyre::Result<()> {
-    let path = camino::Utf8PathBuf::from("Data").join(filename);
-
-    let mut trace = alox_48::path_to_error::Trace::new();
-    let mut ser = alox_48::Serializer::new();
-    let trace_ser = alox_48::path_to_error::Serializer::new(&mut ser, &mut trace);
-
-    luminol_data::helpers::nil_padded_alox::serialize_with(data, trace_ser)
-        .map_err(|error| format_traced_error(error, trace))?;
-    filesystem
-        .write(path, ser.output)
-        .map_err(color_eyre::Report::from)
+        /// The format the data was loaded from and will be saved in.
+        format: DataFormat,
+    },
 }
 
 pub fn format_traced_error(
@@ -135,11 +124,56 @@ pub fn format_traced_error(
     error
 }
 
+fn load_map_infos(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    format: DataFormat,
+) -> color_eyre::Result<RefCell<rpg::MapInfos>> {
+    Ok(RefCell::new(rpg::MapInfos {
+        data: read_data(filesystem, format, "MapInfos")
+            .wrap_err_with(|| format!("While reading {}", format.file_path("MapInfos")))?,
+        ..Default::default()
+    }))
+}
+
+fn load_scripts(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    config: &mut luminol_config::project::Config,
+    format: DataFormat,
+) -> color_eyre::Result<RefCell<rpg::Scripts>> {
+    let scripts_paths = [
+        std::mem::take(&mut config.project.scripts_path),
+        "xScripts".to_string(),
+        "Scripts".to_string(),
+    ];
+
+    for script_path in scripts_paths {
+        match read_data(filesystem, format, &script_path) {
+            Ok(s) => {
+                config.project.scripts_path = script_path;
+                return Ok(RefCell::new(rpg::Scripts {
+                    data: s,
+                    ..Default::default()
+                }));
+            }
+            Err(e) => eprintln!("error loading scripts from {script_path}: {e}"),
+        }
+    }
+
+    color_eyre::eyre::bail!(
+        "Unable to load scripts (tried {}, xScripts, and Scripts first)",
+        config.project.scripts_path
+    );
+}
+
 macro_rules! load {
-    ($fs:ident, $type:ident) => {
-        RefCell::new(rpg::$type {
-            data: read_nil_padded($fs, format!("{}.rxdata", stringify!($type)))
-                .wrap_err_with(|| format!("While reading {}.rxdata", stringify!($type)))?,
+    ($fs:ident, $format:ident, $module:ident::$type:ident) => {
+        RefCell::new($module::$type {
+            data: read_nil_padded($fs, $format, stringify!($type)).wrap_err_with(|| {
+                format!(
+                    "While reading {}",
+                    $format.container_path(stringify!($type))
+                )
+            })?,
             ..Default::default()
         })
     };
@@ -147,22 +181,122 @@ macro_rules! load {
 macro_rules! from_defaults {
     ($parent:ident, $child:ident) => {
         RefCell::new(rpg::$parent {
-            data: vec![rpg::$child::default()],
-            ..Default::default()
+            data: vec![<rpg::$child as rpg::DatabaseEntry>::default_with_id(1)],
+            modified: true,
         })
     };
 }
 
 macro_rules! save {
-    ($fs:ident, $type:ident, $field:ident) => {{
+    ($fs:ident, $format:ident, $type:ident, $field:ident) => {{
         let borrowed = $field.get_mut();
         if borrowed.modified {
-            write_nil_padded(&borrowed.data, $fs, format!("{}.rxdata", stringify!($type)))
-                .wrap_err_with(|| format!("While saving {}.rxdata", stringify!($type)))?;
+            write_nil_padded(&borrowed.data, $fs, $format, stringify!($type)).wrap_err_with(
+                || format!("While saving {}", $format.container_path(stringify!($type))),
+            )?;
         }
         borrowed.modified
     }};
 }
+
+/// Evaluates to the `modified` flags of the fields, followed by the ones of the cached maps.
+macro_rules! modified_flags {
+    ($maps:ident, $($field:ident),* $(,)?) => {
+        [$(&mut $field.get_mut().modified),*]
+            .into_iter()
+            .chain($maps.get_mut().values_mut().map(|map| &mut map.modified))
+            .collect()
+    };
+}
+
+/// Evaluates to whether the field holding the file called `$name` has unsaved changes, or `None`
+/// if none of the fields are stored in that file.
+macro_rules! file_modified {
+    ($name:ident, $($field:ident: $file:expr),* $(,)?) => {
+        $(
+            if $name == $file {
+                Some($field.borrow().modified)
+            } else
+        )* {
+            None
+        }
+    };
+}
+
+/// Replaces the fields stored in one of the files in `$names` with the ones in `$fresh`, unless
+/// they have unsaved changes. The names of the replaced files are pushed to `$reloaded`.
+macro_rules! reload_unmodified {
+    (
+        $variant:ident,
+        $fresh:ident,
+        $names:ident,
+        $reloaded:ident,
+        $($field:ident: $file:expr),* $(,)?
+    ) => {
+        $(
+            let file: &str = $file;
+            if $names.iter().any(|name| name == file) && !$field.get_mut().modified {
+                let Self::$variant { $field: fresh, .. } = &mut $fresh else {
+                    unreachable!("data was reloaded for a different version of RPG Maker");
+                };
+                *$field.get_mut() = std::mem::take(fresh.get_mut());
+                $reloaded.push(file.to_string());
+            }
+        )*
+    };
+}
+
+/// Parses the ID out of the name of a map file, like `Map001`.
+pub(crate) fn map_id(name: &str) -> Option<usize> {
+    name.strip_prefix("Map")?.parse().ok()
+}
+
+/// The names of the data files other than the scripts and maps, in every version of RPG Maker.
+const DATA_FILES: &[&str] = &[
+    "Actors",
+    "Animations",
+    "Areas",
+    "Armors",
+    "Classes",
+    "CommonEvents",
+    "Enemies",
+    "Items",
+    "MapInfos",
+    "Skills",
+    "States",
+    "System",
+    "Tilesets",
+    "Troops",
+    "Weapons",
+];
+
+fn modified_map_ids<M>(maps: &HashMap<usize, M>, modified: impl Fn(&M) -> bool) -> Vec<usize> {
+    maps.iter()
+        .filter(|(_, map)| modified(map))
+        .map(|(&id, _)| id)
+        .collect()
+}
+
+/// Reads a map again if it is cached and has no unsaved changes.
+fn reload_map<M>(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    format: DataFormat,
+    maps: &mut HashMap<usize, M>,
+    id: usize,
+    modified: impl Fn(&M) -> bool,
+) -> color_eyre::Result<bool>
+where
+    M: for<'de> alox_48::Deserialize<'de> + serde::de::DeserializeOwned,
+{
+    if !maps.get(&id).is_some_and(|map| !modified(map)) {
+        return Ok(false);
+    }
+    let map = read_data(filesystem, format, &format!("Map{id:0>3}"))
+        .wrap_err_with(|| format!("While reading map {id:0>3}"))?;
+    maps.insert(id, map);
+    Ok(true)
+}
+
 impl Data {
     /// Load all data required when opening a project.
     /// Does not load config. That is expected to have been loaded beforehand.
@@ -171,65 +305,152 @@ impl Data {
         filesystem: &impl luminol_filesystem::FileSystem,
         config: &mut luminol_config::project::Config,
     ) -> color_eyre::Result<()> {
-        let map_infos = RefCell::new(rpg::MapInfos {
-            data: read_data(filesystem, "MapInfos.rxdata")
-                .wrap_err("While reading MapInfos.rxdata")?,
-            ..Default::default()
-        });
+        let format = DataFormat::from_config(&config.project);
+        match config.project.editor_ver {
+            luminol_config::RMVer::XP => self.load_xp(filesystem, config, format),
+            luminol_config::RMVer::VX => self.load_vx(filesystem, config, format),
+            luminol_config::RMVer::Ace => self.load_ace(filesystem, config, format),
+        }
+    }
+
+    fn load_xp(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &mut luminol_config::project::Config,
+        format: DataFormat,
+    ) -> color_eyre::Result<()> {
+        let map_infos = load_map_infos(filesystem, format)?;
 
-        let mut system = read_data::<rpg::System>(filesystem, "System.rxdata")
-            .wrap_err("While reading System.rxdata")?;
+        let mut system = read_data::<rpg::System>(filesystem, format, "System")
+            .wrap_err_with(|| format!("While reading {}", format.file_path("System")))?;
         system.magic_number = rand::random();
 
         let system = RefCell::new(system);
 
-        let mut scripts = None;
-        let scripts_paths = [
-            std::mem::take(&mut config.project.scripts_path),
-            "xScripts".to_string(),
-            "Scripts".to_string(),
-        ];
-
-        for script_path in scripts_paths {
-            match read_data(filesystem, format!("{script_path}.rxdata")) {
-                Ok(s) => {
-                    config.project.scripts_path = script_path;
-                    scripts = Some(rpg::Scripts {
-                        data: s,
-                        ..Default::default()
-                    });
-                    break;
-                }
-                Err(e) => eprintln!("error loading scripts from {script_path}: {e}"),
+        let scripts = load_scripts(filesystem, config, format)?;
+
+        let maps = RefCell::new(std::collections::HashMap::with_capacity(32));
+
+        *self = Self::Loaded {
+            actors: load!(filesystem, format, rpg::Actors),
+            animations: load!(filesystem, format, rpg::Animations),
+            armors: load!(filesystem, format, rpg::Armors),
+            classes: load!(filesystem, format, rpg::Classes),
+            common_events: load!(filesystem, format, rpg::CommonEvents),
+            enemies: load!(filesystem, format, rpg::Enemies),
+            items: load!(filesystem, format, rpg::Items),
+            skills: load!(filesystem, format, rpg::Skills),
+            states: load!(filesystem, format, rpg::States),
+            tilesets: load!(filesystem, format, rpg::Tilesets),
+            troops: load!(filesystem, format, rpg::Troops),
+            weapons: load!(filesystem, format, rpg::Weapons),
+            map_infos,
+            system,
+            scripts,
+            maps,
+            deleted_maps: RefCell::default(),
+            format,
+        };
+
+        Ok(())
+    }
+
+    fn load_vx(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &mut luminol_config::project::Config,
+        format: DataFormat,
+    ) -> color_eyre::Result<()> {
+        let map_infos = load_map_infos(filesystem, format)?;
+
+        let mut system = read_data::<rmvx::System>(filesystem, format, "System")
+            .wrap_err_with(|| format!("While reading {}", format.file_path("System")))?;
+        system.version_id = rand::random();
+
+        let system = RefCell::new(system);
+
+        let scripts = load_scripts(filesystem, config, format)?;
+
+        // Areas are stored as a hash, but their IDs are sequential like everything else
+        let areas = match format {
+            DataFormat::Marshal(_) => {
+                let mut areas =
+                    read_data::<HashMap<usize, rmvx::Area>>(filesystem, format, "Areas")
+                        .wrap_err("While reading Areas.rvdata")?
+                        .into_values()
+                        .collect::<Vec<_>>();
+                areas.sort_by_key(|area| area.id);
+                RefCell::new(rmvx::Areas {
+                    data: areas,
+                    ..Default::default()
+                })
             }
-        }
-        let Some(scripts) = scripts else {
-            color_eyre::eyre::bail!(
-                "Unable to load scripts (tried {}, xScripts, and Scripts first)",
-                config.project.scripts_path
-            );
+            DataFormat::Ron => load!(filesystem, format, rmvx::Areas),
         };
-        let scripts = RefCell::new(scripts);
 
         let maps = RefCell::new(std::collections::HashMap::with_capacity(32));
 
-        *self = Self::Loaded {
-            actors: load!(filesystem, Actors),
-            animations: load!(filesystem, Animations),
-            armors: load!(filesystem, Armors),
-            classes: load!(filesystem, Classes),
-            common_events: load!(filesystem, CommonEvents),
-            enemies: load!(filesystem, Enemies),
-            items: load!(filesystem, Items),
-            skills: load!(filesystem, Skills),
-            states: load!(filesystem, States),
-            tilesets: load!(filesystem, Tilesets),
-            troops: load!(filesystem, Troops),
-            weapons: load!(filesystem, Weapons),
+        *self = Self::LoadedVX {
+            actors: load!(filesystem, format, rmvx::Actors),
+            animations: load!(filesystem, format, rmvx::Animations),
+            armors: load!(filesystem, format, rmvx::Armors),
+            classes: load!(filesystem, format, rmvx::Classes),
+            common_events: load!(filesystem, format, rpg::CommonEvents),
+            enemies: load!(filesystem, format, rmvx::Enemies),
+            items: load!(filesystem, format, rmvx::Items),
+            skills: load!(filesystem, format, rmvx::Skills),
+            states: load!(filesystem, format, rmvx::States),
+            troops: load!(filesystem, format, rmvx::Troops),
+            weapons: load!(filesystem, format, rmvx::Weapons),
+            areas,
             map_infos,
             system,
             scripts,
             maps,
+            deleted_maps: RefCell::default(),
+            format,
+        };
+
+        Ok(())
+    }
+
+    fn load_ace(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &mut luminol_config::project::Config,
+        format: DataFormat,
+    ) -> color_eyre::Result<()> {
+        let map_infos = load_map_infos(filesystem, format)?;
+
+        let mut system = read_data::<rmvxace::System>(filesystem, format, "System")
+            .wrap_err_with(|| format!("While reading {}", format.file_path("System")))?;
+        system.version_id = rand::random();
+
+        let system = RefCell::new(system);
+
+        let scripts = load_scripts(filesystem, config, format)?;
+
+        let maps = RefCell::new(std::collections::HashMap::with_capacity(32));
+
+        *self = Self::LoadedAce {
+            actors: load!(filesystem, format, rmvxace::Actors),
+            animations: load!(filesystem, format, rmvxace::Animations),
+            armors: load!(filesystem, format, rmvxace::Armors),
+            classes: load!(filesystem, format, rmvxace::Classes),
+            common_events: load!(filesystem, format, rpg::CommonEvents),
+            enemies: load!(filesystem, format, rmvxace::Enemies),
+            items: load!(filesystem, format, rmvxace::Items),
+            skills: load!(filesystem, format, rmvxace::Skills),
+            states: load!(filesystem, format, rmvxace::States),
+            tilesets: load!(filesystem, format, rmvxace::Tilesets),
+            troops: load!(filesystem, format, rmvxace::Troops),
+            weapons: load!(filesystem, format, rmvxace::Weapons),
+            map_infos,
+            system,
+            scripts,
+            maps,
+            deleted_maps: RefCell::default(),
+            format,
         };
 
         Ok(())
@@ -239,16 +460,19 @@ impl Data {
         *self = Self::Unloaded;
     }
 
+    /// A new XP project with one of everything. Everything is marked as modified, so saving it
+    /// writes out a complete set of data files.
     pub fn from_defaults() -> Self {
         let mut map_infos = std::collections::HashMap::with_capacity(16);
         map_infos.insert(1, rpg::MapInfo::default());
         let map_infos = RefCell::new(rpg::MapInfos {
             data: map_infos,
-            ..Default::default()
+            modified: true,
         });
 
         let system = rpg::System {
             magic_number: rand::random(),
+            modified: true,
             ..Default::default()
         };
         let system = RefCell::new(system);
@@ -256,11 +480,17 @@ impl Data {
         let scripts = vec![]; // FIXME legality of providing defualt scripts is unclear
         let scripts = RefCell::new(rpg::Scripts {
             data: scripts,
-            ..Default::default()
+            modified: true,
         });
 
         let mut maps = std::collections::HashMap::with_capacity(32);
-        maps.insert(1, rpg::Map::default());
+        maps.insert(
+            1,
+            rpg::Map {
+                modified: true,
+                ..Default::default()
+            },
+        );
         let maps = RefCell::new(maps);
 
         Self::Loaded {
@@ -280,19 +510,621 @@ impl Data {
             system,
             scripts,
             maps,
+            deleted_maps: RefCell::default(),
+            format: DataFormat::marshal(luminol_config::RMVer::XP),
         }
     }
 
-    pub fn rxdata_ext(&self) -> &'static str {
-        todo!()
+    /// The version of RPG Maker the loaded data is from.
+    pub fn editor_ver(&self) -> Option<luminol_config::RMVer> {
+        match self {
+            Self::Unloaded => None,
+            Self::Loaded { .. } => Some(luminol_config::RMVer::XP),
+            Self::LoadedVX { .. } => Some(luminol_config::RMVer::VX),
+            Self::LoadedAce { .. } => Some(luminol_config::RMVer::Ace),
+        }
+    }
+
+    /// The format the data of the loaded project is stored in.
+    pub fn format(&self) -> DataFormat {
+        match self {
+            Self::Unloaded => panic!("project not loaded"),
+            Self::Loaded { format, .. }
+            | Self::LoadedVX { format, .. }
+            | Self::LoadedAce { format, .. } => *format,
+        }
     }
 
     /// Save all cached data to disk.
+    ///
+    /// The files that are about to be overwritten are backed up to `.luminol/backups` first, and
+    /// the recovery copy of the unsaved changes is deleted once they're saved.
     // we take an &mut self to ensure no outsanding borrows of the cache exist.
     pub fn save(
         &mut self,
         filesystem: &impl luminol_filesystem::FileSystem,
         config: &luminol_config::project::Config,
+    ) -> color_eyre::Result<()> {
+        self.back_up(filesystem, config)
+            .wrap_err("While backing up the project data")?;
+        self.write_modified(filesystem, config)?;
+        self.delete_removed_maps(filesystem)?;
+        crate::recovery::discard(filesystem)?;
+        // Projects in zip files are only rewritten once all of the changes are in
+        filesystem
+            .commit()
+            .wrap_err("While writing the changes to the project")
+    }
+
+    /// Deletes the files of the maps removed since the last save.
+    fn delete_removed_maps(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+    ) -> color_eyre::Result<()> {
+        let format = self.format();
+        let deleted_maps = match self {
+            Self::Unloaded => panic!("project not loaded"),
+            Self::Loaded { deleted_maps, .. }
+            | Self::LoadedVX { deleted_maps, .. }
+            | Self::LoadedAce { deleted_maps, .. } => deleted_maps.get_mut(),
+        };
+        for id in deleted_maps.drain() {
+            let path = format.file_path(&format!("Map{id:0>3}"));
+            if filesystem.exists(&path)? {
+                filesystem
+                    .remove_file(&path)
+                    .wrap_err_with(|| format!("While deleting map {id:0>3}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the data with unsaved changes and marks it as saved.
+    pub(crate) fn write_modified(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+    ) -> color_eyre::Result<()> {
+        let format = self.format();
+        match self {
+            Self::Unloaded => panic!("project not loaded"),
+            Self::Loaded { .. } => self.save_xp(filesystem, config, format),
+            Self::LoadedVX { .. } => self.save_vx(filesystem, config, format),
+            Self::LoadedAce { .. } => self.save_ace(filesystem, config, format),
+        }
+    }
+
+    /// Saves the project, then writes all of its data again in `format`. Every map is loaded
+    /// first so that none of them are left out.
+    ///
+    /// The project keeps being saved in its own format afterwards, which makes this the way to
+    /// export a project stored as RON back to the Marshal files the game reads.
+    pub fn export(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+        format: DataFormat,
+    ) -> color_eyre::Result<()> {
+        self.save(filesystem, config)?;
+        self.load_all_maps(filesystem)?;
+        self.mark_all_modified();
+
+        let project_format = self.format();
+        self.set_format(format);
+        let result = self
+            .save(filesystem, config)
+            .wrap_err_with(|| format!("While exporting the project data to {format}"));
+        self.set_format(project_format);
+        result
+    }
+
+    /// Converts the project to store its data in `format` from now on and writes the change to
+    /// the project config. The files in the old format are left in place.
+    pub fn convert(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &mut luminol_config::project::Config,
+        format: DataFormat,
+    ) -> color_eyre::Result<()> {
+        self.export(filesystem, config, format)?;
+        self.set_format(format);
+
+        config.project.use_ron = format == DataFormat::Ron;
+        filesystem
+            .write(".luminol/config", ron::to_string(&config.project)?)
+            .wrap_err("While saving the project config")?;
+        filesystem
+            .commit()
+            .wrap_err("While writing the changes to the project")
+    }
+
+    pub(crate) fn set_format(&mut self, new_format: DataFormat) {
+        match self {
+            Self::Unloaded => panic!("project not loaded"),
+            Self::Loaded { format, .. }
+            | Self::LoadedVX { format, .. }
+            | Self::LoadedAce { format, .. } => *format = new_format,
+        }
+    }
+
+    /// Loads every map listed in the map infos that isn't cached yet.
+    pub fn load_all_maps(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+    ) -> color_eyre::Result<()> {
+        let format = self.format();
+        let ids = self.map_infos().data.keys().copied().collect::<Vec<_>>();
+        match self {
+            Self::Unloaded => panic!("project not loaded"),
+            Self::Loaded { maps, .. } => load_maps(filesystem, format, maps, &ids),
+            Self::LoadedVX { maps, .. } => load_maps(filesystem, format, maps, &ids),
+            Self::LoadedAce { maps, .. } => load_maps(filesystem, format, maps, &ids),
+        }
+    }
+
+    /// Whether the data stored in the file with the given name, like `Actors` or `Map001`, has
+    /// unsaved changes.
+    ///
+    /// Returns `None` if the file doesn't hold any data that is loaded.
+    pub fn is_file_modified(&self, name: &str, scripts_path: &str) -> Option<bool> {
+        if let Some(id) = map_id(name) {
+            return match self {
+                Self::Unloaded => None,
+                Self::Loaded { maps, .. } => maps.borrow().get(&id).map(|map| map.modified),
+                Self::LoadedVX { maps, .. } => maps.borrow().get(&id).map(|map| map.modified),
+                Self::LoadedAce { maps, .. } => maps.borrow().get(&id).map(|map| map.modified),
+            };
+        }
+
+        match self {
+            Self::Unloaded => None,
+            Self::Loaded {
+                actors,
+                animations,
+                armors,
+                classes,
+                common_events,
+                enemies,
+                items,
+                map_infos,
+                scripts,
+                skills,
+                states,
+                system,
+                tilesets,
+                troops,
+                weapons,
+                ..
+            } => file_modified!(
+                name,
+                actors: "Actors",
+                animations: "Animations",
+                armors: "Armors",
+                classes: "Classes",
+                common_events: "CommonEvents",
+                enemies: "Enemies",
+                items: "Items",
+                map_infos: "MapInfos",
+                scripts: scripts_path,
+                skills: "Skills",
+                states: "States",
+                system: "System",
+                tilesets: "Tilesets",
+                troops: "Troops",
+                weapons: "Weapons",
+            ),
+            Self::LoadedVX {
+                actors,
+                animations,
+                areas,
+                armors,
+                classes,
+                common_events,
+                enemies,
+                items,
+                map_infos,
+                scripts,
+                skills,
+                states,
+                system,
+                troops,
+                weapons,
+                ..
+            } => file_modified!(
+                name,
+                actors: "Actors",
+                animations: "Animations",
+                areas: "Areas",
+                armors: "Armors",
+                classes: "Classes",
+                common_events: "CommonEvents",
+                enemies: "Enemies",
+                items: "Items",
+                map_infos: "MapInfos",
+                scripts: scripts_path,
+                skills: "Skills",
+                states: "States",
+                system: "System",
+                troops: "Troops",
+                weapons: "Weapons",
+            ),
+            Self::LoadedAce {
+                actors,
+                animations,
+                armors,
+                classes,
+                common_events,
+                enemies,
+                items,
+                map_infos,
+                scripts,
+                skills,
+                states,
+                system,
+                tilesets,
+                troops,
+                weapons,
+                ..
+            } => file_modified!(
+                name,
+                actors: "Actors",
+                animations: "Animations",
+                armors: "Armors",
+                classes: "Classes",
+                common_events: "CommonEvents",
+                enemies: "Enemies",
+                items: "Items",
+                map_infos: "MapInfos",
+                scripts: scripts_path,
+                skills: "Skills",
+                states: "States",
+                system: "System",
+                tilesets: "Tilesets",
+                troops: "Troops",
+                weapons: "Weapons",
+            ),
+        }
+    }
+
+    /// The names of the files with unsaved changes, like `Actors` or `Map001`.
+    pub(crate) fn modified_files(&self, scripts_path: &str) -> Vec<String> {
+        let mut map_ids = match self {
+            Self::Unloaded => panic!("project not loaded"),
+            Self::Loaded { maps, .. } => modified_map_ids(&maps.borrow(), |map| map.modified),
+            Self::LoadedVX { maps, .. } => modified_map_ids(&maps.borrow(), |map| map.modified),
+            Self::LoadedAce { maps, .. } => modified_map_ids(&maps.borrow(), |map| map.modified),
+        };
+        // The files of deleted maps are about to be removed, which counts as a change too
+        match self {
+            Self::Unloaded => panic!("project not loaded"),
+            Self::Loaded { deleted_maps, .. }
+            | Self::LoadedVX { deleted_maps, .. }
+            | Self::LoadedAce { deleted_maps, .. } => {
+                map_ids.extend(deleted_maps.borrow().iter().copied());
+            }
+        }
+
+        DATA_FILES
+            .iter()
+            .copied()
+            .chain([scripts_path])
+            .filter(|name| self.is_file_modified(name, scripts_path) == Some(true))
+            .map(str::to_string)
+            .chain(map_ids.into_iter().map(|id| format!("Map{id:0>3}")))
+            .collect()
+    }
+
+    /// Reads the files with the given names, like `Actors` or `Map001`, from disk again,
+    /// replacing the cached data. Data with unsaved changes and maps that aren't cached are left
+    /// alone.
+    ///
+    /// Returns the names of the files that were reloaded.
+    pub fn reload_files(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+        names: &[String],
+    ) -> color_eyre::Result<Vec<String>> {
+        let format = self.format();
+        let scripts_path = config.project.scripts_path.as_str();
+        let mut reloaded = Vec::new();
+
+        if names.iter().any(|name| map_id(name).is_none()) {
+            // The database files refer to each other, so it's simplest to read them all at once
+            let mut fresh = Self::default();
+            fresh.load(filesystem, &mut config.clone())?;
+
+            match self {
+                Self::Unloaded => panic!("project not loaded"),
+                Self::Loaded {
+                    actors,
+                    animations,
+                    armors,
+                    classes,
+                    common_events,
+                    enemies,
+                    items,
+                    map_infos,
+                    scripts,
+                    skills,
+                    states,
+                    system,
+                    tilesets,
+                    troops,
+                    weapons,
+                    ..
+                } => {
+                    reload_unmodified!(
+                        Loaded,
+                        fresh,
+                        names,
+                        reloaded,
+                        actors: "Actors",
+                        animations: "Animations",
+                        armors: "Armors",
+                        classes: "Classes",
+                        common_events: "CommonEvents",
+                        enemies: "Enemies",
+                        items: "Items",
+                        map_infos: "MapInfos",
+                        scripts: scripts_path,
+                        skills: "Skills",
+                        states: "States",
+                        system: "System",
+                        tilesets: "Tilesets",
+                        troops: "Troops",
+                        weapons: "Weapons",
+                    );
+                }
+                Self::LoadedVX {
+                    actors,
+                    animations,
+                    areas,
+                    armors,
+                    classes,
+                    common_events,
+                    enemies,
+                    items,
+                    map_infos,
+                    scripts,
+                    skills,
+                    states,
+                    system,
+                    troops,
+                    weapons,
+                    ..
+                } => {
+                    reload_unmodified!(
+                        LoadedVX,
+                        fresh,
+                        names,
+                        reloaded,
+                        actors: "Actors",
+                        animations: "Animations",
+                        areas: "Areas",
+                        armors: "Armors",
+                        classes: "Classes",
+                        common_events: "CommonEvents",
+                        enemies: "Enemies",
+                        items: "Items",
+                        map_infos: "MapInfos",
+                        scripts: scripts_path,
+                        skills: "Skills",
+                        states: "States",
+                        system: "System",
+                        troops: "Troops",
+                        weapons: "Weapons",
+                    );
+                }
+                Self::LoadedAce {
+                    actors,
+                    animations,
+                    armors,
+                    classes,
+                    common_events,
+                    enemies,
+                    items,
+                    map_infos,
+                    scripts,
+                    skills,
+                    states,
+                    system,
+                    tilesets,
+                    troops,
+                    weapons,
+                    ..
+                } => {
+                    reload_unmodified!(
+                        LoadedAce,
+                        fresh,
+                        names,
+                        reloaded,
+                        actors: "Actors",
+                        animations: "Animations",
+                        armors: "Armors",
+                        classes: "Classes",
+                        common_events: "CommonEvents",
+                        enemies: "Enemies",
+                        items: "Items",
+                        map_infos: "MapInfos",
+                        scripts: scripts_path,
+                        skills: "Skills",
+                        states: "States",
+                        system: "System",
+                        tilesets: "Tilesets",
+                        troops: "Troops",
+                        weapons: "Weapons",
+                    );
+                }
+            }
+        }
+
+        for id in names.iter().filter_map(|name| map_id(name)) {
+            let was_reloaded = match self {
+                Self::Unloaded => panic!("project not loaded"),
+                Self::Loaded { maps, .. } => {
+                    reload_map(filesystem, format, maps.get_mut(), id, |map| map.modified)?
+                }
+                Self::LoadedVX { maps, .. } => {
+                    reload_map(filesystem, format, maps.get_mut(), id, |map| map.modified)?
+                }
+                Self::LoadedAce { maps, .. } => {
+                    reload_map(filesystem, format, maps.get_mut(), id, |map| map.modified)?
+                }
+            };
+            if was_reloaded {
+                reloaded.push(format!("Map{id:0>3}"));
+            }
+        }
+
+        Ok(reloaded)
+    }
+
+    /// Marks all cached data as modified, so that the next save writes every file.
+    pub(crate) fn mark_all_modified(&mut self) {
+        for flag in self.modified_flags() {
+            *flag = true;
+        }
+    }
+
+    /// The `modified` flags of all cached data, in an order that stays the same as long as no
+    /// maps are loaded or unloaded.
+    pub(crate) fn modified_flags(&mut self) -> Vec<&mut bool> {
+        match self {
+            Self::Unloaded => panic!("project not loaded"),
+            Self::Loaded {
+                actors,
+                animations,
+                armors,
+                classes,
+                common_events,
+                enemies,
+                items,
+                map_infos,
+                scripts,
+                skills,
+                states,
+                system,
+                tilesets,
+                troops,
+                weapons,
+                maps,
+                ..
+            } => {
+                modified_flags!(
+                    maps,
+                    actors,
+                    animations,
+                    armors,
+                    classes,
+                    common_events,
+                    enemies,
+                    items,
+                    map_infos,
+                    scripts,
+                    skills,
+                    states,
+                    system,
+                    tilesets,
+                    troops,
+                    weapons,
+                )
+            }
+            Self::LoadedVX {
+                actors,
+                animations,
+                areas,
+                armors,
+                classes,
+                common_events,
+                enemies,
+                items,
+                map_infos,
+                scripts,
+                skills,
+                states,
+                system,
+                troops,
+                weapons,
+                maps,
+                ..
+            } => {
+                modified_flags!(
+                    maps,
+                    actors,
+                    animations,
+                    areas,
+                    armors,
+                    classes,
+                    common_events,
+                    enemies,
+                    items,
+                    map_infos,
+                    scripts,
+                    skills,
+                    states,
+                    system,
+                    troops,
+                    weapons,
+                )
+            }
+            Self::LoadedAce {
+                actors,
+                animations,
+                armors,
+                classes,
+                common_events,
+                enemies,
+                items,
+                map_infos,
+                scripts,
+                skills,
+                states,
+                system,
+                tilesets,
+                troops,
+                weapons,
+                maps,
+                ..
+            } => {
+                modified_flags!(
+                    maps,
+                    actors,
+                    animations,
+                    armors,
+                    classes,
+                    common_events,
+                    enemies,
+                    items,
+                    map_infos,
+                    scripts,
+                    skills,
+                    states,
+                    system,
+                    tilesets,
+                    troops,
+                    weapons,
+                )
+            }
+        }
+    }
+
+    /// The number the system is stamped with on every save, so the game can tell that the data
+    /// changed. It's the `magic_number` in XP and the `version_id` in VX and VX Ace.
+    pub(crate) fn system_version_id(&mut self) -> &mut i32 {
+        match self {
+            Self::Unloaded => panic!("project not loaded"),
+            Self::Loaded { system, .. } => &mut system.get_mut().magic_number,
+            Self::LoadedVX { system, .. } => &mut system.get_mut().version_id,
+            Self::LoadedAce { system, .. } => &mut system.get_mut().version_id,
+        }
+    }
+
+    fn save_xp(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+        format: DataFormat,
     ) -> color_eyre::Result<()> {
         let Self::Loaded {
             actors,
@@ -311,6 +1143,7 @@ impl Data {
             weapons,
             system,
             maps,
+            ..
         } = self
         else {
             panic!("project not loaded")
@@ -318,59 +1151,196 @@ impl Data {
 
         let mut modified = false;
 
-        modified |= save!(filesystem, Actors, actors);
-        modified |= save!(filesystem, Animations, animations);
-        modified |= save!(filesystem, Armors, armors);
-        modified |= save!(filesystem, Classes, classes);
-        modified |= save!(filesystem, CommonEvents, common_events);
-        modified |= save!(filesystem, Enemies, enemies);
-        modified |= save!(filesystem, Items, items);
-        modified |= save!(filesystem, Skills, skills);
-        modified |= save!(filesystem, States, states);
-        modified |= save!(filesystem, Tilesets, tilesets);
-        modified |= save!(filesystem, Troops, troops);
-        modified |= save!(filesystem, Weapons, weapons);
+        modified |= save!(filesystem, format, Actors, actors);
+        modified |= save!(filesystem, format, Animations, animations);
+        modified |= save!(filesystem, format, Armors, armors);
+        modified |= save!(filesystem, format, Classes, classes);
+        modified |= save!(filesystem, format, CommonEvents, common_events);
+        modified |= save!(filesystem, format, Enemies, enemies);
+        modified |= save!(filesystem, format, Items, items);
+        modified |= save!(filesystem, format, Skills, skills);
+        modified |= save!(filesystem, format, States, states);
+        modified |= save!(filesystem, format, Tilesets, tilesets);
+        modified |= save!(filesystem, format, Troops, troops);
+        modified |= save!(filesystem, format, Weapons, weapons);
+
+        modified |= save_map_infos(filesystem, format, map_infos)?;
+        modified |= save_scripts(filesystem, config, format, scripts)?;
+        modified |= save_maps(filesystem, format, maps, |map| &mut map.modified)?;
 
         {
-            let map_infos = map_infos.get_mut();
-            if map_infos.modified {
-                modified = true;
-                write_data(&map_infos.data, filesystem, "MapInfos.rxdata")
-                    .wrap_err("While saving MapInfos.rxdata")?;
+            let system = system.get_mut();
+            if system.modified || modified {
+                system.magic_number = rand::random();
+                write_data(system, filesystem, format, "System")
+                    .wrap_err_with(|| format!("While saving {}", format.file_path("System")))?;
+                system.modified = false;
             }
         }
 
+        actors.borrow_mut().modified = false;
+        animations.borrow_mut().modified = false;
+        armors.borrow_mut().modified = false;
+        classes.borrow_mut().modified = false;
+        common_events.borrow_mut().modified = false;
+        enemies.borrow_mut().modified = false;
+        items.borrow_mut().modified = false;
+        skills.borrow_mut().modified = false;
+        states.borrow_mut().modified = false;
+        tilesets.borrow_mut().modified = false;
+        troops.borrow_mut().modified = false;
+        weapons.borrow_mut().modified = false;
+        Ok(())
+    }
+
+    fn save_vx(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+        format: DataFormat,
+    ) -> color_eyre::Result<()> {
+        let Self::LoadedVX {
+            actors,
+            animations,
+            areas,
+            armors,
+            classes,
+            common_events,
+            enemies,
+            items,
+            map_infos,
+            scripts,
+            skills,
+            states,
+            troops,
+            weapons,
+            system,
+            maps,
+            ..
+        } = self
+        else {
+            panic!("project not loaded")
+        };
+
+        let mut modified = false;
+
+        modified |= save!(filesystem, format, Actors, actors);
+        modified |= save!(filesystem, format, Animations, animations);
+        modified |= save!(filesystem, format, Armors, armors);
+        modified |= save!(filesystem, format, Classes, classes);
+        modified |= save!(filesystem, format, CommonEvents, common_events);
+        modified |= save!(filesystem, format, Enemies, enemies);
+        modified |= save!(filesystem, format, Items, items);
+        modified |= save!(filesystem, format, Skills, skills);
+        modified |= save!(filesystem, format, States, states);
+        modified |= save!(filesystem, format, Troops, troops);
+        modified |= save!(filesystem, format, Weapons, weapons);
+
         {
-            let scripts = scripts.get_mut();
-            if scripts.modified {
+            let areas = areas.get_mut();
+            if areas.modified {
                 modified = true;
-                write_data(
-                    &scripts.data,
-                    filesystem,
-                    format!("{}.rxdata", config.project.scripts_path),
-                )?;
+                match format {
+                    DataFormat::Marshal(_) => {
+                        let areas = areas
+                            .data
+                            .iter()
+                            .map(|area| (area.id + 1, area))
+                            .collect::<HashMap<_, _>>();
+                        write_data(&areas, filesystem, format, "Areas")
+                            .wrap_err("While saving Areas.rvdata")?;
+                    }
+                    DataFormat::Ron => {
+                        write_nil_padded(&areas.data, filesystem, format, "Areas")
+                            .wrap_err("While saving Data/Areas")?;
+                    }
+                }
             }
         }
 
+        modified |= save_map_infos(filesystem, format, map_infos)?;
+        modified |= save_scripts(filesystem, config, format, scripts)?;
+        modified |= save_maps(filesystem, format, maps, |map| &mut map.modified)?;
+
         {
-            let maps = maps.get_mut();
-            maps.iter().try_for_each(|(id, map)| {
-                if map.modified {
-                    modified = true;
-                    write_data(map, filesystem, format!("Map{id:0>3}.rxdata"))
-                        .wrap_err_with(|| format!("While saving map {id:0>3}"))
-                } else {
-                    Ok(())
-                }
-            })?
+            let system = system.get_mut();
+            if system.modified || modified {
+                system.version_id = rand::random();
+                write_data(system, filesystem, format, "System")
+                    .wrap_err_with(|| format!("While saving {}", format.file_path("System")))?;
+                system.modified = false;
+            }
         }
 
+        actors.borrow_mut().modified = false;
+        animations.borrow_mut().modified = false;
+        areas.borrow_mut().modified = false;
+        armors.borrow_mut().modified = false;
+        classes.borrow_mut().modified = false;
+        common_events.borrow_mut().modified = false;
+        enemies.borrow_mut().modified = false;
+        items.borrow_mut().modified = false;
+        skills.borrow_mut().modified = false;
+        states.borrow_mut().modified = false;
+        troops.borrow_mut().modified = false;
+        weapons.borrow_mut().modified = false;
+        Ok(())
+    }
+
+    fn save_ace(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+        format: DataFormat,
+    ) -> color_eyre::Result<()> {
+        let Self::LoadedAce {
+            actors,
+            animations,
+            armors,
+            classes,
+            common_events,
+            enemies,
+            items,
+            map_infos,
+            scripts,
+            skills,
+            states,
+            tilesets,
+            troops,
+            weapons,
+            system,
+            maps,
+            ..
+        } = self
+        else {
+            panic!("project not loaded")
+        };
+
+        let mut modified = false;
+
+        modified |= save!(filesystem, format, Actors, actors);
+        modified |= save!(filesystem, format, Animations, animations);
+        modified |= save!(filesystem, format, Armors, armors);
+        modified |= save!(filesystem, format, Classes, classes);
+        modified |= save!(filesystem, format, CommonEvents, common_events);
+        modified |= save!(filesystem, format, Enemies, enemies);
+        modified |= save!(filesystem, format, Items, items);
+        modified |= save!(filesystem, format, Skills, skills);
+        modified |= save!(filesystem, format, States, states);
+        modified |= save!(filesystem, format, Tilesets, tilesets);
+        modified |= save!(filesystem, format, Troops, troops);
+        modified |= save!(filesystem, format, Weapons, weapons);
+
+        modified |= save_map_infos(filesystem, format, map_infos)?;
+        modified |= save_scripts(filesystem, config, format, scripts)?;
+        modified |= save_maps(filesystem, format, maps, |map| &mut map.modified)?;
+
         {
             let system = system.get_mut();
             if system.modified || modified {
-                system.magic_number = rand::random();
-                write_data(system, filesystem, "System.rxdata")
-                    .wrap_err("While saving System.rxdata")?;
+                system.version_id = rand::random();
+                write_data(system, filesystem, format, "System")
+                    .wrap_err_with(|| format!("While saving {}", format.file_path("System")))?;
                 system.modified = false;
             }
         }
@@ -387,15 +1357,91 @@ impl Data {
         tilesets.borrow_mut().modified = false;
         troops.borrow_mut().modified = false;
         weapons.borrow_mut().modified = false;
-        map_infos.borrow_mut().modified = false;
-        scripts.borrow_mut().modified = false;
-        for (_, map) in maps.borrow_mut().iter_mut() {
-            map.modified = false;
-        }
         Ok(())
     }
 }
 
+fn save_map_infos(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    format: DataFormat,
+    map_infos: &mut RefCell<rpg::MapInfos>,
+) -> color_eyre::Result<bool> {
+    let map_infos = map_infos.get_mut();
+    if !map_infos.modified {
+        return Ok(false);
+    }
+    match format {
+        DataFormat::Marshal(_) => write_data(&map_infos.data, filesystem, format, "MapInfos"),
+        // Sort the map infos so they don't get shuffled around every time the project is saved
+        DataFormat::Ron => write_ron(
+            &map_infos.data.iter().collect::<BTreeMap<_, _>>(),
+            filesystem,
+            "MapInfos",
+        ),
+    }
+    .wrap_err_with(|| format!("While saving {}", format.file_path("MapInfos")))?;
+    map_infos.modified = false;
+    Ok(true)
+}
+
+fn save_scripts(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    config: &luminol_config::project::Config,
+    format: DataFormat,
+    scripts: &mut RefCell<rpg::Scripts>,
+) -> color_eyre::Result<bool> {
+    let scripts = scripts.get_mut();
+    if !scripts.modified {
+        return Ok(false);
+    }
+    write_data(
+        &scripts.data,
+        filesystem,
+        format,
+        &config.project.scripts_path,
+    )?;
+    scripts.modified = false;
+    Ok(true)
+}
+
+fn save_maps<M: alox_48::Serialize + serde::Serialize>(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    format: DataFormat,
+    maps: &mut RefCell<HashMap<usize, M>>,
+    modified: impl Fn(&mut M) -> &mut bool,
+) -> color_eyre::Result<bool> {
+    let mut any_modified = false;
+    for (id, map) in maps.get_mut().iter_mut() {
+        if *modified(map) {
+            any_modified = true;
+            write_data(map, filesystem, format, &format!("Map{id:0>3}"))
+                .wrap_err_with(|| format!("While saving map {id:0>3}"))?;
+            *modified(map) = false;
+        }
+    }
+    Ok(any_modified)
+}
+
+fn load_maps<M>(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    format: DataFormat,
+    maps: &mut RefCell<HashMap<usize, M>>,
+    ids: &[usize],
+) -> color_eyre::Result<()>
+where
+    M: for<'de> alox_48::Deserialize<'de> + serde::de::DeserializeOwned,
+{
+    let maps = maps.get_mut();
+    for &id in ids {
+        if let std::collections::hash_map::Entry::Vacant(entry) = maps.entry(id) {
+            let map = read_data(filesystem, format, &format!("Map{id:0>3}"))
+                .wrap_err_with(|| format!("While reading map {id:0>3}"))?;
+            entry.insert(map);
+        }
+    }
+    Ok(())
+}
+
 macro_rules! nested_ref_getter {
     ($($typ:ty, $name:ident),* $(,)?) => {
         $(
@@ -404,11 +1450,56 @@ macro_rules! nested_ref_getter {
                 match self {
                     Self::Unloaded => panic!("data cache unloaded"),
                     Self::Loaded { $name, ..} => $name.borrow_mut(),
+                    _ => panic!("not an RPG Maker XP project"),
+                }
+            }
+        )+
+    };
+
+}
+
+macro_rules! nested_ref_getter_vx {
+    ($($typ:ty, $name:ident, $getter:ident),* $(,)?) => {
+        $(
+            pub fn $getter(&self) -> RefMut<'_, $typ> {
+                match self {
+                    Self::Unloaded => panic!("data cache unloaded"),
+                    Self::LoadedVX { $name, ..} => $name.borrow_mut(),
+                    _ => panic!("not an RPG Maker VX project"),
                 }
             }
         )+
     };
+}
 
+macro_rules! nested_ref_getter_ace {
+    ($($typ:ty, $name:ident, $getter:ident),* $(,)?) => {
+        $(
+            pub fn $getter(&self) -> RefMut<'_, $typ> {
+                match self {
+                    Self::Unloaded => panic!("data cache unloaded"),
+                    Self::LoadedAce { $name, ..} => $name.borrow_mut(),
+                    _ => panic!("not an RPG Maker VX Ace project"),
+                }
+            }
+        )+
+    };
+}
+
+/// Getters for data that has the same layout in every version of RPG Maker.
+macro_rules! shared_ref_getter {
+    ($($typ:ty, $name:ident),* $(,)?) => {
+        $(
+            pub fn $name(&self) -> RefMut<'_, $typ> {
+                match self {
+                    Self::Unloaded => panic!("data cache unloaded"),
+                    Self::Loaded { $name, ..}
+                    | Self::LoadedVX { $name, .. }
+                    | Self::LoadedAce { $name, .. } => $name.borrow_mut(),
+                }
+            }
+        )+
+    };
 }
 
 impl Data {
@@ -417,11 +1508,8 @@ impl Data {
         rpg::Animations, animations,
         rpg::Armors, armors,
         rpg::Classes, classes,
-        rpg::CommonEvents, common_events,
         rpg::Enemies, enemies,
         rpg::Items, items,
-        rpg::MapInfos, map_infos,
-        rpg::Scripts, scripts,
         rpg::Skills, skills,
         rpg::States, states,
         rpg::System, system,
@@ -430,6 +1518,42 @@ impl Data {
         rpg::Weapons, weapons,
     }
 
+    nested_ref_getter_vx! {
+        rmvx::Actors, actors, vx_actors,
+        rmvx::Animations, animations, vx_animations,
+        rmvx::Areas, areas, vx_areas,
+        rmvx::Armors, armors, vx_armors,
+        rmvx::Classes, classes, vx_classes,
+        rmvx::Enemies, enemies, vx_enemies,
+        rmvx::Items, items, vx_items,
+        rmvx::Skills, skills, vx_skills,
+        rmvx::States, states, vx_states,
+        rmvx::System, system, vx_system,
+        rmvx::Troops, troops, vx_troops,
+        rmvx::Weapons, weapons, vx_weapons,
+    }
+
+    nested_ref_getter_ace! {
+        rmvxace::Actors, actors, ace_actors,
+        rmvxace::Animations, animations, ace_animations,
+        rmvxace::Armors, armors, ace_armors,
+        rmvxace::Classes, classes, ace_classes,
+        rmvxace::Enemies, enemies, ace_enemies,
+        rmvxace::Items, items, ace_items,
+        rmvxace::Skills, skills, ace_skills,
+        rmvxace::States, states, ace_states,
+        rmvxace::System, system, ace_system,
+        rmvxace::Tilesets, tilesets, ace_tilesets,
+        rmvxace::Troops, troops, ace_troops,
+        rmvxace::Weapons, weapons, ace_weapons,
+    }
+
+    shared_ref_getter! {
+        rpg::CommonEvents, common_events,
+        rpg::MapInfos, map_infos,
+        rpg::Scripts, scripts,
+    }
+
     /// Load a map.
     #[allow(clippy::panic)]
     pub fn get_or_load_map(
@@ -437,22 +1561,140 @@ impl Data {
         id: usize,
         filesystem: &impl luminol_filesystem::FileSystem,
     ) -> RefMut<'_, rpg::Map> {
-        let maps_ref = match self {
-            Self::Loaded { maps, .. } => maps.borrow_mut(),
+        // FIXME
+        self.try_get_or_load_map(id, filesystem)
+            .expect("failed to load map")
+    }
+
+    /// Load a map, returning an error instead of panicking if it can't be read.
+    pub fn try_get_or_load_map(
+        &self,
+        id: usize,
+        filesystem: &impl luminol_filesystem::FileSystem,
+    ) -> color_eyre::Result<RefMut<'_, rpg::Map>> {
+        let (mut maps_ref, format) = match self {
+            Self::Loaded { maps, format, .. } => (maps.borrow_mut(), *format),
             Self::Unloaded => panic!("project not loaded"),
+            _ => panic!("not an RPG Maker XP project"),
         };
-        RefMut::map(maps_ref, |maps| {
-            // FIXME
-            maps.entry(id).or_insert_with(|| {
-                read_data(filesystem, format!("Map{id:0>3}.rxdata")).expect("failed to load map")
-            })
-        })
+        if !maps_ref.contains_key(&id) {
+            let map = read_data(filesystem, format, &format!("Map{id:0>3}"))
+                .wrap_err_with(|| format!("While reading map {id:0>3}"))?;
+            maps_ref.insert(id, map);
+        }
+        Ok(RefMut::map(maps_ref, |maps| {
+            maps.get_mut(&id).expect("map was just loaded")
+        }))
     }
 
     pub fn get_map(&self, id: usize) -> RefMut<'_, rpg::Map> {
         let maps_ref = match self {
             Self::Loaded { maps, .. } => maps.borrow_mut(),
             Self::Unloaded => panic!("project not loaded"),
+            _ => panic!("not an RPG Maker XP project"),
+        };
+        RefMut::map(maps_ref, |maps| maps.get_mut(&id).expect("map not loaded"))
+    }
+
+    /// Adds a map to the cache, replacing any map that was loaded with the same ID.
+    /// The map is marked as modified so it is written out on the next save.
+    pub fn insert_map(&self, id: usize, mut map: rpg::Map) {
+        let (mut maps, mut deleted_maps) = match self {
+            Self::Loaded {
+                maps, deleted_maps, ..
+            } => (maps.borrow_mut(), deleted_maps.borrow_mut()),
+            Self::Unloaded => panic!("project not loaded"),
+            _ => panic!("not an RPG Maker XP project"),
+        };
+        deleted_maps.remove(&id);
+        map.modified = true;
+        maps.insert(id, map);
+    }
+
+    /// Removes a map from the cache. Its file is deleted from the project on the next save, so
+    /// that nothing is lost if the project is closed without saving.
+    /// This does not touch `MapInfos`.
+    pub fn remove_map(&self, id: usize) {
+        let deleted_maps = match self {
+            Self::Loaded {
+                maps, deleted_maps, ..
+            } => {
+                maps.borrow_mut().remove(&id);
+                deleted_maps
+            }
+            Self::LoadedVX {
+                maps, deleted_maps, ..
+            } => {
+                maps.borrow_mut().remove(&id);
+                deleted_maps
+            }
+            Self::LoadedAce {
+                maps, deleted_maps, ..
+            } => {
+                maps.borrow_mut().remove(&id);
+                deleted_maps
+            }
+            Self::Unloaded => panic!("project not loaded"),
+        };
+        deleted_maps.borrow_mut().insert(id);
+    }
+
+    /// Load an RPG Maker VX map.
+    pub fn get_or_load_vx_map(
+        &self,
+        id: usize,
+        filesystem: &impl luminol_filesystem::FileSystem,
+    ) -> color_eyre::Result<RefMut<'_, rmvx::Map>> {
+        let (mut maps_ref, format) = match self {
+            Self::LoadedVX { maps, format, .. } => (maps.borrow_mut(), *format),
+            Self::Unloaded => panic!("project not loaded"),
+            _ => panic!("not an RPG Maker VX project"),
+        };
+        if !maps_ref.contains_key(&id) {
+            let map = read_data(filesystem, format, &format!("Map{id:0>3}"))
+                .wrap_err_with(|| format!("While reading map {id:0>3}"))?;
+            maps_ref.insert(id, map);
+        }
+        Ok(RefMut::map(maps_ref, |maps| {
+            maps.get_mut(&id).expect("map was just loaded")
+        }))
+    }
+
+    pub fn get_vx_map(&self, id: usize) -> RefMut<'_, rmvx::Map> {
+        let maps_ref = match self {
+            Self::LoadedVX { maps, .. } => maps.borrow_mut(),
+            Self::Unloaded => panic!("project not loaded"),
+            _ => panic!("not an RPG Maker VX project"),
+        };
+        RefMut::map(maps_ref, |maps| maps.get_mut(&id).expect("map not loaded"))
+    }
+
+    /// Load an RPG Maker VX Ace map.
+    pub fn get_or_load_ace_map(
+        &self,
+        id: usize,
+        filesystem: &impl luminol_filesystem::FileSystem,
+    ) -> color_eyre::Result<RefMut<'_, rmvxace::Map>> {
+        let (mut maps_ref, format) = match self {
+            Self::LoadedAce { maps, format, .. } => (maps.borrow_mut(), *format),
+            Self::Unloaded => panic!("project not loaded"),
+            _ => panic!("not an RPG Maker VX Ace project"),
+        };
+        if !maps_ref.contains_key(&id) {
+            let map = read_data(filesystem, format, &format!("Map{id:0>3}"))
+                .wrap_err_with(|| format!("While reading map {id:0>3}"))?;
+            maps_ref.insert(id, map);
+        }
+        Ok(RefMut::map(maps_ref, |maps| {
+            maps.get_mut(&id).expect("map was just loaded")
+        }))
+    }
+
+    pub fn get_ace_map(&self, id: usize) -> RefMut<'_, rmvxace::Map> {
+        let maps_ref = match self {
+            Self::LoadedAce { maps, .. } => maps.borrow_mut(),
+            Self::Unloaded => panic!("project not loaded"),
+            _ => panic!("not an RPG Maker VX Ace project"),
         };
         RefMut::map(maps_ref, |maps| maps.get_mut(&id).expect("map not loaded"))
     }
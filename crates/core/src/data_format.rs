@@ -0,0 +1,256 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::WrapErr;
+use itertools::Itertools;
+
+use crate::data_cache::format_traced_error;
+
+/// The format the data files of a project are stored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    /// Ruby Marshal files with the given extension. This is the only format the game can read.
+    Marshal(&'static str),
+    /// Pretty printed RON files. Every database entry gets a file of its own, like
+    /// `Data/Actors/001.ron`, so changes to the project are easy to diff.
+    Ron,
+}
+
+impl DataFormat {
+    /// The format the project is configured to store its data in.
+    pub fn from_config(project: &luminol_config::project::Project) -> Self {
+        if project.use_ron {
+            Self::Ron
+        } else {
+            Self::marshal(project.editor_ver)
+        }
+    }
+
+    /// The Marshal format read by the given version of RPG Maker.
+    pub fn marshal(editor_ver: luminol_config::RMVer) -> Self {
+        Self::Marshal(match editor_ver {
+            luminol_config::RMVer::XP => "rxdata",
+            luminol_config::RMVer::VX => "rvdata",
+            luminol_config::RMVer::Ace => "rvdata2",
+        })
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Marshal(ext) => ext,
+            Self::Ron => "ron",
+        }
+    }
+
+    /// The path of a file holding a single object, like `System` or `Map001`.
+    pub fn file_path(self, name: &str) -> camino::Utf8PathBuf {
+        camino::Utf8PathBuf::from("Data").join(format!("{name}.{}", self.extension()))
+    }
+
+    /// The path holding a database container, like `Actors`. For RON this is a directory with a
+    /// file for every entry.
+    pub fn container_path(self, name: &str) -> camino::Utf8PathBuf {
+        match self {
+            Self::Marshal(_) => self.file_path(name),
+            Self::Ron => camino::Utf8PathBuf::from("Data").join(name),
+        }
+    }
+
+    /// The inverse of [`Self::file_path`] and [`Self::container_path`]: the name of the data file
+    /// a path belongs to, like `Actors` for `Data/Actors.rxdata`.
+    ///
+    /// Returns `None` if the path isn't a data file in this format.
+    pub fn name_of(self, path: &camino::Utf8Path) -> Option<String> {
+        let mut components = path.components().map(|c| c.as_str());
+        if !components.next()?.eq_ignore_ascii_case("Data") {
+            return None;
+        }
+
+        let file = camino::Utf8Path::new(components.next()?);
+        match (self, components.next()) {
+            (_, None) => file
+                .extension()?
+                .eq_ignore_ascii_case(self.extension())
+                .then(|| file.file_stem().map(str::to_string))?,
+            (Self::Ron, Some(entry)) if components.next().is_none() => {
+                entry_id(entry).map(|_| file.to_string())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DataFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Marshal(ext) => write!(f, "Marshal ({ext})"),
+            Self::Ron => f.write_str("RON"),
+        }
+    }
+}
+
+fn ron_config() -> ron::ser::PrettyConfig {
+    ron::ser::PrettyConfig::new().indentor("  ".into())
+}
+
+/// Parses the ID out of the name of a RON database entry, like `001.ron`.
+fn entry_id(file_name: &str) -> Option<usize> {
+    file_name.strip_suffix(".ron")?.parse().ok()
+}
+
+pub(crate) fn read_data<T>(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    format: DataFormat,
+    name: &str,
+) -> color_eyre::Result<T>
+where
+    T: for<'de> alox_48::Deserialize<'de> + serde::de::DeserializeOwned,
+{
+    let data = filesystem.read(format.file_path(name))?;
+
+    match format {
+        DataFormat::Marshal(_) => {
+            let mut de = alox_48::Deserializer::new(&data)?;
+            let result = alox_48::path_to_error::deserialize(&mut de);
+
+            result.map_err(|(error, trace)| format_traced_error(error, trace))
+        }
+        DataFormat::Ron => ron::de::from_bytes(&data).map_err(color_eyre::Report::from),
+    }
+}
+
+pub(crate) fn write_data(
+    data: &(impl alox_48::Serialize + serde::Serialize),
+    filesystem: &impl luminol_filesystem::FileSystem,
+    format: DataFormat,
+    name: &str,
+) -> color_eyre::Result<()> {
+    match format {
+        DataFormat::Marshal(_) => {
+            let mut serializer = alox_48::Serializer::new();
+            alox_48::path_to_error::serialize(data, &mut serializer)
+                .map_err(|(error, trace)| format_traced_error(error, trace))?;
+
+            filesystem
+                .write(format.file_path(name), serializer.output)
+                .map_err(color_eyre::Report::from)
+        }
+        DataFormat::Ron => write_ron(data, filesystem, name),
+    }
+}
+
+/// Writes `Data/{name}.ron`. Unlike [`write_data`] this doesn't need the data to be Marshal
+/// serializable, which allows sorting hashes before writing them out.
+pub(crate) fn write_ron(
+    data: &impl serde::Serialize,
+    filesystem: &impl luminol_filesystem::FileSystem,
+    name: &str,
+) -> color_eyre::Result<()> {
+    let text = ron::ser::to_string_pretty(data, ron_config())?;
+    filesystem
+        .write(DataFormat::Ron.file_path(name), text)
+        .map_err(color_eyre::Report::from)
+}
+
+pub(crate) fn read_nil_padded<T>(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    format: DataFormat,
+    name: &str,
+) -> color_eyre::Result<Vec<T>>
+where
+    T: for<'de> alox_48::Deserialize<'de> + serde::de::DeserializeOwned,
+{
+    let path = format.container_path(name);
+
+    if let DataFormat::Marshal(_) = format {
+        let data = filesystem.read(path)?;
+
+        let mut trace = alox_48::path_to_error::Trace::new();
+        let mut de = alox_48::Deserializer::new(&data)?;
+        let de = alox_48::path_to_error::Deserializer::new(&mut de, &mut trace);
+
+        return luminol_data::helpers::nil_padded_alox::deserialize_with(de)
+            .map_err(|error| format_traced_error(error, trace));
+    }
+
+    // Entries are numbered from 1 just like in the nil padded arrays, so every ID up to the last
+    // one needs a file
+    let ids = filesystem
+        .read_dir(&path)?
+        .iter()
+        .filter_map(|entry| entry_id(entry.file_name()))
+        .sorted_unstable()
+        .collect_vec();
+
+    ids.into_iter()
+        .enumerate()
+        .map(|(index, id)| {
+            let entry_path = path.join(format!("{:0>3}.ron", index + 1));
+            if id != index + 1 {
+                color_eyre::eyre::bail!("{entry_path} is missing");
+            }
+
+            let data = filesystem.read(&entry_path)?;
+            ron::de::from_bytes(&data).wrap_err_with(|| format!("While reading {entry_path}"))
+        })
+        .collect()
+}
+
+pub(crate) fn write_nil_padded(
+    data: &[impl alox_48::Serialize + serde::Serialize],
+    filesystem: &impl luminol_filesystem::FileSystem,
+    format: DataFormat,
+    name: &str,
+) -> color_eyre::Result<()> {
+    let path = format.container_path(name);
+
+    if let DataFormat::Marshal(_) = format {
+        let mut trace = alox_48::path_to_error::Trace::new();
+        let mut ser = alox_48::Serializer::new();
+        let trace_ser = alox_48::path_to_error::Serializer::new(&mut ser, &mut trace);
+
+        luminol_data::helpers::nil_padded_alox::serialize_with(data, trace_ser)
+            .map_err(|error| format_traced_error(error, trace))?;
+        return filesystem
+            .write(path, ser.output)
+            .map_err(color_eyre::Report::from);
+    }
+
+    filesystem.create_dir(&path)?;
+    for (index, entry) in data.iter().enumerate() {
+        let entry_path = path.join(format!("{:0>3}.ron", index + 1));
+        let text = ron::ser::to_string_pretty(entry, ron_config())
+            .wrap_err_with(|| format!("While writing {entry_path}"))?;
+        filesystem.write(entry_path, text)?;
+    }
+
+    // Remove the files of entries that were deleted since the last save
+    for entry in filesystem.read_dir(&path)? {
+        if entry_id(entry.file_name()).is_some_and(|id| id > data.len()) {
+            filesystem.remove_file(path.join(entry.file_name()))?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Projects for tests and tools that need one without going through the UI.
+
+use color_eyre::eyre::WrapErr;
+
+use crate::{Data, DataFormat};
+
+/// A project made from [`Data::from_defaults`] that lives in a
+/// [`luminol_filesystem::memory::FileSystem`], so it can be loaded and saved without touching
+/// the disk.
+pub struct Fixture {
+    pub filesystem: luminol_filesystem::memory::FileSystem,
+    pub config: luminol_config::project::Config,
+    pub data: Data,
+}
+
+impl Fixture {
+    /// An XP project storing its data in Marshal files.
+    pub fn new() -> color_eyre::Result<Self> {
+        Self::with_project(luminol_config::project::Project {
+            project_name: "Fixture".to_string(),
+            persistence_id: 1,
+            ..Default::default()
+        })
+    }
+
+    /// A project with the given config. `use_ron` is respected, but the project must be an XP
+    /// project since that's the only kind [`Data::from_defaults`] can make.
+    pub fn with_project(project: luminol_config::project::Project) -> color_eyre::Result<Self> {
+        let filesystem = luminol_filesystem::memory::FileSystem::new();
+        let (data, config) = create_project(&filesystem, project)?;
+        Ok(Self {
+            filesystem,
+            config,
+            data,
+        })
+    }
+
+    /// Loads the project from the filesystem again, like opening it in a fresh editor would.
+    pub fn load(&self) -> color_eyre::Result<Data> {
+        let mut config = self.config.clone();
+        let mut data = Data::Unloaded;
+        data.load(&self.filesystem, &mut config)?;
+        Ok(data)
+    }
+
+    /// Saves the cached data to the filesystem.
+    pub fn save(&mut self) -> color_eyre::Result<()> {
+        self.data.save(&self.filesystem, &self.config)
+    }
+}
+
+/// Writes a new project into `filesystem`, laid out the same way as projects created from the
+/// new project window, and returns its data and config.
+pub fn create_project(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    project: luminol_config::project::Project,
+) -> color_eyre::Result<(Data, luminol_config::project::Config)> {
+    let c = "While creating a fixture project";
+    if project.editor_ver != luminol_config::RMVer::XP {
+        color_eyre::eyre::bail!("Fixture projects can only be made for RPG Maker XP");
+    }
+
+    for dir in ["Audio", "Data", "Graphics", ".luminol"] {
+        filesystem.create_dir(dir).wrap_err(c)?;
+    }
+    filesystem
+        .create_file(format!("{}.lumproj", project.project_name))
+        .wrap_err(c)?;
+
+    let config = luminol_config::project::Config::from_project(project);
+    filesystem
+        .write(".luminol/config", ron::to_string(&config.project)?)
+        .wrap_err(c)?;
+    filesystem
+        .write(".luminol/commands", ron::to_string(&config.command_db)?)
+        .wrap_err(c)?;
+    let mut game_ini = Vec::new();
+    config.game_ini.write_to(&mut game_ini).wrap_err(c)?;
+    filesystem.write("Game.ini", game_ini).wrap_err(c)?;
+
+    let mut data = Data::from_defaults();
+    data.set_format(DataFormat::from_config(&config.project));
+    data.save(filesystem, &config).wrap_err(c)?;
+
+    Ok((data, config))
+}
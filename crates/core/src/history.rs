@@ -0,0 +1,432 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::WrapErr;
+use std::collections::VecDeque;
+
+use luminol_data::rpg;
+
+use crate::{data_cache::format_traced_error, Data, UpdateState};
+
+/// Changes to the same targets made less than this many seconds apart are merged into one entry,
+/// so that typing a name or dragging a slider can be undone in one step.
+const MERGE_TIME: f64 = 1.;
+
+macro_rules! containers {
+    ($($variant:ident => $typ:ty, $getter:ident),* $(,)?) => {
+        /// A database container whose entries can be restored by the history.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[allow(missing_docs)]
+        pub enum Container {
+            $($variant),*
+        }
+
+        $(
+            impl Tracked for $typ {
+                const CONTAINER: Container = Container::$variant;
+            }
+        )*
+
+        impl Container {
+            fn restore_entry(self, data: &Data, index: usize, bytes: &[u8]) -> color_eyre::Result<()> {
+                match self {
+                    $(
+                        Self::$variant => {
+                            let entry: $typ = from_bytes(bytes)?;
+                            let mut container = data.$getter();
+                            if let Some(slot) = container.data.get_mut(index) {
+                                *slot = entry;
+                                container.modified = true;
+                            }
+                        }
+                    )*
+                }
+                Ok(())
+            }
+
+            fn restore_entries(self, data: &Data, bytes: &[u8]) -> color_eyre::Result<()> {
+                match self {
+                    $(
+                        Self::$variant => {
+                            let entries: Vec<$typ> = from_bytes(bytes)?;
+                            let mut container = data.$getter();
+                            container.data = entries;
+                            container.modified = true;
+                        }
+                    )*
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+/// Database entries that can be recorded in the history.
+pub trait Tracked: alox_48::Serialize + for<'de> alox_48::Deserialize<'de> {
+    /// The container the entries are stored in.
+    const CONTAINER: Container;
+}
+
+containers! {
+    Actors => rpg::Actor, actors,
+    Animations => rpg::Animation, animations,
+    Armors => rpg::Armor, armors,
+    Classes => rpg::Class, classes,
+    CommonEvents => rpg::CommonEvent, common_events,
+    Enemies => rpg::Enemy, enemies,
+    Items => rpg::Item, items,
+    Skills => rpg::Skill, skills,
+    States => rpg::State, states,
+    Tilesets => rpg::Tileset, tilesets,
+    Troops => rpg::Troop, troops,
+    Weapons => rpg::Weapon, weapons,
+}
+
+/// Something in the project that the history can restore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// The entry with the given index in a database container.
+    Entry(Container, usize),
+    /// Every entry of a database container, for changes to the number of entries.
+    Entries(Container),
+    /// An event on an RPG Maker XP map.
+    Event { map_id: usize, event_id: usize },
+    /// A whole RPG Maker XP map, for changes like resizing that touch all of it.
+    Map(usize),
+    /// Everything about an RPG Maker XP map except its tiles and events. Snapshots of this are
+    /// taken with [`snapshot_map_properties`].
+    MapProperties(usize),
+    /// The `MapInfos` entry of a map.
+    MapInfo(usize),
+}
+
+impl Target {
+    /// Whether restoring this can change the tiles or tilesets that maps are drawn with.
+    fn affects_atlases(self) -> bool {
+        matches!(
+            self,
+            Self::Map(_)
+                | Self::MapProperties(_)
+                | Self::Entry(Container::Tilesets, _)
+                | Self::Entries(Container::Tilesets)
+        )
+    }
+
+    fn restore(
+        self,
+        data: &Data,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        bytes: Option<&[u8]>,
+    ) -> color_eyre::Result<()> {
+        match (self, bytes) {
+            (Self::Entry(container, index), Some(bytes)) => {
+                container.restore_entry(data, index, bytes)?
+            }
+            (Self::Entries(container), Some(bytes)) => container.restore_entries(data, bytes)?,
+            (Self::Entry(..) | Self::Entries(_), None) => {}
+
+            (Self::Event { map_id, event_id }, bytes) => {
+                let mut map = data.try_get_or_load_map(map_id, filesystem)?;
+                match bytes {
+                    Some(bytes) => {
+                        let event: rpg::Event = from_bytes(bytes)?;
+                        event.extra_data.graphic_modified.set(true);
+                        map.events.insert(event_id, event);
+                    }
+                    None => drop(map.events.option_remove(event_id)),
+                }
+                map.modified = true;
+            }
+
+            (Self::Map(id), Some(bytes)) => data.insert_map(id, from_bytes(bytes)?),
+            (Self::Map(id), None) => data.remove_map(id),
+            (Self::MapProperties(id), Some(bytes)) => {
+                let mut properties: rpg::Map = from_bytes(bytes)?;
+                let mut map = data.try_get_or_load_map(id, filesystem)?;
+                properties.data = std::mem::take(&mut map.data);
+                properties.events = std::mem::take(&mut map.events);
+                properties.modified = true;
+                *map = properties;
+            }
+            (Self::MapProperties(_), None) => {}
+
+            (Self::MapInfo(id), bytes) => {
+                let mut map_infos = data.map_infos();
+                match bytes {
+                    Some(bytes) => drop(map_infos.data.insert(id, from_bytes(bytes)?)),
+                    None => drop(map_infos.data.remove(&id)),
+                }
+                map_infos.modified = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The state of a target before and after a change. `None` means the target didn't exist.
+#[derive(Debug)]
+pub struct Diff {
+    target: Target,
+    before: Option<Vec<u8>>,
+    after: Option<Vec<u8>>,
+}
+
+impl Diff {
+    /// Create a diff from two snapshots taken with [`snapshot`].
+    pub fn new(target: Target, before: Option<Vec<u8>>, after: Option<Vec<u8>>) -> Self {
+        Self {
+            target,
+            before,
+            after,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.before == self.after
+    }
+}
+
+/// One undoable change, made up of the diffs of everything it touched.
+#[derive(Debug)]
+pub struct Entry {
+    description: String,
+    diffs: Vec<Diff>,
+    time: f64,
+}
+
+impl Entry {
+    /// A short description of the change, like "Edit actor 001: Aluxes".
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn has_targets_of(&self, other: &Self) -> bool {
+        self.diffs.len() == other.diffs.len()
+            && std::iter::zip(&self.diffs, &other.diffs).all(|(a, b)| a.target == b.target)
+    }
+}
+
+/// The project-wide undo history.
+///
+/// Entries store Marshal snapshots of whatever they changed, so undoing one puts back the
+/// snapshots taken before the change and redoing it puts back the ones taken after.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: VecDeque<Entry>,
+    redo: Vec<Entry>,
+    revision: u64,
+}
+
+impl History {
+    /// The entries that can be undone, oldest first.
+    pub fn undo_entries(&self) -> impl DoubleEndedIterator<Item = &Entry> {
+        self.undo.iter()
+    }
+
+    /// The entries that can be redone, next one first.
+    pub fn redo_entries(&self) -> impl DoubleEndedIterator<Item = &Entry> {
+        self.redo.iter().rev()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Goes up whenever a change is recorded, undone or redone, or the history is cleared. Copies
+    /// of the project data taken at an older revision may be out of date.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.revision += 1;
+    }
+}
+
+/// Takes a snapshot of something for a [`Diff`].
+pub fn snapshot(value: &impl alox_48::Serialize) -> color_eyre::Result<Vec<u8>> {
+    let mut serializer = alox_48::Serializer::new();
+    alox_48::path_to_error::serialize(value, &mut serializer)
+        .map_err(|(error, trace)| format_traced_error(error, trace))?;
+    Ok(serializer.output)
+}
+
+/// Takes a snapshot of a map without its tiles and events, which is a lot cheaper than
+/// snapshotting all of it.
+pub fn snapshot_map_properties(map: &mut rpg::Map) -> color_eyre::Result<Vec<u8>> {
+    let data = std::mem::take(&mut map.data);
+    let events = std::mem::take(&mut map.events);
+    let result = snapshot(map);
+    map.data = data;
+    map.events = events;
+    result
+}
+
+fn from_bytes<T>(bytes: &[u8]) -> color_eyre::Result<T>
+where
+    T: for<'de> alox_48::Deserialize<'de>,
+{
+    let mut de = alox_48::Deserializer::new(bytes)?;
+    alox_48::path_to_error::deserialize(&mut de)
+        .map_err(|(error, trace)| format_traced_error(error, trace))
+}
+
+/// Returns whether undo and redo were pressed this frame. Redo is either Ctrl+Y or Ctrl+Shift+Z.
+pub fn undo_redo_pressed(input: &egui::InputState) -> (bool, bool) {
+    let is_undo_pressed =
+        input.modifiers.command && !input.modifiers.shift && input.key_pressed(egui::Key::Z);
+    let is_redo_pressed = input.modifiers.command
+        && (input.modifiers.shift || input.key_pressed(egui::Key::Y))
+        && (!input.modifiers.shift || input.key_pressed(egui::Key::Z));
+    (is_undo_pressed, is_redo_pressed)
+}
+
+/// Removes this frame's undo and redo keypresses, for when something other than the project-wide
+/// history (like the tile history of a map) already handled them.
+pub fn consume_undo_redo(input: &mut egui::InputState) {
+    input.events.retain(|event| {
+        !matches!(
+            event,
+            egui::Event::Key {
+                key: egui::Key::Z | egui::Key::Y,
+                pressed: true,
+                modifiers,
+                ..
+            } if modifiers.command
+        )
+    });
+}
+
+impl<'res> UpdateState<'res> {
+    /// Records a change in the history. Diffs that didn't change anything are left out, and
+    /// nothing is recorded if that leaves no diffs.
+    pub fn record_change(&mut self, description: impl Into<String>, diffs: Vec<Diff>) {
+        let diffs = diffs
+            .into_iter()
+            .filter(|d| !d.is_empty())
+            .collect::<Vec<_>>();
+        if diffs.is_empty() {
+            return;
+        }
+
+        let entry = Entry {
+            description: description.into(),
+            diffs,
+            time: self.ctx.input(|i| i.time),
+        };
+
+        let history = &mut *self.history;
+        history.revision += 1;
+        let merge = history.redo.is_empty()
+            && history.undo.back().is_some_and(|last| {
+                last.has_targets_of(&entry) && entry.time - last.time < MERGE_TIME
+            });
+        if merge {
+            let last = history.undo.back_mut().expect("history is empty");
+            for (diff, new) in std::iter::zip(&mut last.diffs, entry.diffs) {
+                diff.after = new.after;
+            }
+            last.description = entry.description;
+            last.time = entry.time;
+            if last.diffs.iter().all(Diff::is_empty) {
+                history.undo.pop_back();
+            }
+            return;
+        }
+
+        history.redo.clear();
+        history.undo.push_back(entry);
+        while history.undo.len() > self.global_config.history_depth.max(1) {
+            history.undo.pop_front();
+        }
+    }
+
+    /// Undoes the last change in the history, if there is one.
+    pub fn undo(&mut self) {
+        if let Some(entry) = self.history.undo.pop_back() {
+            if let Some(entry) = self.apply_entry(entry, true) {
+                self.history.redo.push(entry);
+            }
+        }
+    }
+
+    /// Redoes the last undone change, if there is one.
+    pub fn redo(&mut self) {
+        if let Some(entry) = self.history.redo.pop() {
+            if let Some(entry) = self.apply_entry(entry, false) {
+                self.history.undo.push_back(entry);
+            }
+        }
+    }
+
+    /// Puts back the snapshots from before (or after) an entry's change. Returns `None` if
+    /// restoring them failed, in which case the entry can't be trusted anymore and is dropped.
+    fn apply_entry(&mut self, mut entry: Entry, undo: bool) -> Option<Entry> {
+        self.history.revision += 1;
+        let mut atlases_stale = false;
+        let result = if undo {
+            entry.diffs.iter().rev().try_for_each(|diff| {
+                atlases_stale |= diff.target.affects_atlases();
+                diff.target
+                    .restore(&*self.data, &*self.filesystem, diff.before.as_deref())
+            })
+        } else {
+            entry.diffs.iter().try_for_each(|diff| {
+                atlases_stale |= diff.target.affects_atlases();
+                diff.target
+                    .restore(&*self.data, &*self.filesystem, diff.after.as_deref())
+            })
+        };
+
+        if atlases_stale {
+            // Open maps are redrawn from the restored data and tilesets
+            self.graphics.atlas_loader.clear();
+        }
+        self.modified.set(true);
+
+        match result.wrap_err_with(|| {
+            format!(
+                "While {} \"{}\"",
+                if undo { "undoing" } else { "redoing" },
+                entry.description
+            )
+        }) {
+            Ok(()) => {
+                // Merging into an entry that was undone and redone would be surprising
+                entry.time = f64::NEG_INFINITY;
+                Some(entry)
+            }
+            Err(e) => {
+                crate::error!(self.toasts, e);
+                None
+            }
+        }
+    }
+}
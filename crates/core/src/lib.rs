@@ -37,6 +37,16 @@ pub use modal::Modal;
 mod data_cache;
 pub use data_cache::Data;
 
+mod data_format;
+pub use data_format::DataFormat;
+
+pub mod fixtures;
+
+pub mod history;
+pub use history::History;
+
+pub mod recovery;
+
 /// Toasts to be displayed for errors, information, etc.
 mod toasts;
 pub use toasts::Toasts;
@@ -81,6 +91,7 @@ pub struct UpdateState<'res> {
     pub edit_windows: &'res mut EditWindows,
     pub edit_tabs: &'res mut EditTabs,
     pub toasts: &'res mut Toasts,
+    pub history: &'res mut History,
 
     pub project_config: &'res mut Option<luminol_config::project::Config>,
     pub global_config: &'res mut luminol_config::global::Config,
@@ -176,6 +187,8 @@ pub enum Pencil {
     Circle,
     Rectangle,
     Fill,
+    /// Selects a rectangle of tiles to copy and paste.
+    Select,
 }
 
 impl Default for ToolbarState {
@@ -203,6 +216,7 @@ impl<'res> UpdateState<'res> {
             edit_tabs: self.edit_tabs,
             edit_windows,
             toasts: self.toasts,
+            history: self.history,
             project_config: self.project_config,
             global_config: self.global_config,
             toolbar: self.toolbar,
@@ -227,6 +241,7 @@ impl<'res> UpdateState<'res> {
             edit_tabs,
             edit_windows: self.edit_windows,
             toasts: self.toasts,
+            history: self.history,
             project_config: self.project_config,
             global_config: self.global_config,
             toolbar: self.toolbar,
@@ -344,6 +359,31 @@ impl<'res> UpdateState<'res> {
             }
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(p) = self.project_manager.load_zip_promise.take() {
+            match p.try_take() {
+                Ok(Ok(path)) => {
+                    self.close_project();
+
+                    filesystem_open_result = Some(self.filesystem.load_project_from_zip(
+                        self.project_config,
+                        self.global_config,
+                        path,
+                    ));
+                }
+                Ok(Err(error))
+                    if !matches!(
+                        error.root_cause().downcast_ref(),
+                        Some(luminol_filesystem::Error::CancelledLoading)
+                    ) =>
+                {
+                    error!(self.toasts, error.wrap_err("Error locating project files"));
+                }
+                Ok(Err(_)) => {}
+                Err(p) => self.project_manager.load_zip_promise = Some(p),
+            }
+        }
+
         if let Some(r) = self.project_manager.filesystem_open_result.take() {
             filesystem_open_result = Some(r);
         }
@@ -375,6 +415,7 @@ impl<'res> UpdateState<'res> {
                     );
                     self.close_project();
                 } else {
+                    self.history.clear();
                     info!(
                         self.toasts,
                         format!(
@@ -430,9 +471,15 @@ impl<'res> UpdateState<'res> {
         self.audio.clear_sinks(); // audio loads files borrows from the filesystem. unloading while they are playing is a crash
         self.graphics.atlas_loader.clear();
         self.graphics.texture_loader.clear();
+        // closing the project means the unsaved changes were either saved or thrown away. if the
+        // data isn't loaded the project failed to open, so the changes are kept for next time
+        if self.filesystem.project_loaded() && !matches!(*self.data, Data::Unloaded) {
+            let _ = recovery::discard(&*self.filesystem);
+        }
         self.filesystem.unload_project();
         *self.project_config = None;
         self.data.unload();
+        self.history.clear();
         self.modified.set(false);
     }
 }
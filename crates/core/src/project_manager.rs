@@ -28,6 +28,8 @@ pub struct ProjectManager {
 
     pub create_project_promise: Option<poll_promise::Promise<CreateProjectPromiseResult>>,
     pub load_filesystem_promise: Option<poll_promise::Promise<FileSystemPromiseResult>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub load_zip_promise: Option<poll_promise::Promise<ZipPromiseResult>>,
     pub filesystem_open_result: Option<FileSystemOpenResult>,
 }
 
@@ -40,6 +42,8 @@ pub struct CreateProjectResult {
 type ProjectManagerClosure = dyn FnOnce(&mut crate::UpdateState<'_>);
 pub type CreateProjectPromiseResult = color_eyre::Result<CreateProjectResult>;
 pub type FileSystemPromiseResult = luminol_filesystem::Result<luminol_filesystem::host::FileSystem>;
+#[cfg(not(target_arch = "wasm32"))]
+pub type ZipPromiseResult = luminol_filesystem::Result<camino::Utf8PathBuf>;
 pub type FileSystemOpenResult = luminol_filesystem::Result<luminol_filesystem::project::LoadResult>;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -67,6 +71,8 @@ impl ProjectManager {
             closure: None,
             create_project_promise: None,
             load_filesystem_promise: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            load_zip_promise: None,
             filesystem_open_result: None,
         }
     }
@@ -78,6 +84,10 @@ impl ProjectManager {
 
     /// Returns whether or not a file or filder picker is currently open.
     pub fn is_picker_open(&self) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.load_zip_promise.is_some() {
+            return true;
+        }
         self.filesystem_open_result.is_some()
             || self.create_project_promise.is_some()
             || self.load_filesystem_promise.is_some()
@@ -113,9 +123,20 @@ impl ProjectManager {
         });
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Opens a picker for a project stored in a zip file after asking the user to save unsaved
+    /// changes.
+    pub fn open_zip_project_picker(&mut self) {
+        self.run_custom(|update_state| {
+            update_state.project_manager.load_zip_promise = Some(spawn_future(
+                luminol_filesystem::project::FileSystem::pick_zip_project(),
+            ));
+        });
+    }
+
     /// Opens a recent project after asking the user to save unsaved changes.
     ///
-    /// On native, `key` should be the absolute path to the project folder.
+    /// On native, `key` should be the absolute path to the project folder or zip file.
     /// On web, `key` should be the IndexedDB key of the project folder.
     pub fn load_recent_project(&mut self, key: String) {
         self.run_custom(|update_state| {
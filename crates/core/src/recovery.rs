@@ -0,0 +1,217 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Copies of unsaved changes that survive a crash, and backups of the data files that saving
+//! overwrites.
+
+use color_eyre::eyre::WrapErr;
+use luminol_filesystem::FileSystem;
+
+use crate::{data_cache::map_id, Data};
+
+/// The directory the unsaved changes are autosaved to. It mirrors the layout of the project, so
+/// the data files end up in `.luminol/recovery/Data`.
+pub const RECOVERY_DIR: &str = ".luminol/recovery";
+/// The directory holding a timestamped directory with the old data files for every save.
+pub const BACKUP_DIR: &str = ".luminol/backups";
+
+fn recovery_data_dir() -> camino::Utf8PathBuf {
+    camino::Utf8Path::new(RECOVERY_DIR).join("Data")
+}
+
+/// Whether the project has unsaved changes left over from a previous session.
+pub fn exists(filesystem: &impl FileSystem) -> bool {
+    let dir = recovery_data_dir();
+    filesystem.exists(&dir).unwrap_or(false)
+        && filesystem
+            .read_dir(&dir)
+            .is_ok_and(|entries| !entries.is_empty())
+}
+
+/// Deletes the autosaved changes, if there are any.
+pub fn discard(filesystem: &impl FileSystem) -> color_eyre::Result<()> {
+    if filesystem.exists(RECOVERY_DIR)? {
+        filesystem
+            .remove_dir(RECOVERY_DIR)
+            .wrap_err("While deleting the autosaved changes")?;
+    }
+    Ok(())
+}
+
+impl Data {
+    /// Writes the data with unsaved changes to [`RECOVERY_DIR`]. Unlike [`Data::save`] this
+    /// leaves the project untouched, and the data is still marked as modified afterwards.
+    pub fn write_recovery(
+        &mut self,
+        filesystem: &impl FileSystem,
+        config: &luminol_config::project::Config,
+    ) -> color_eyre::Result<()> {
+        let redirect = luminol_filesystem::redirect::FileSystem::new(
+            filesystem,
+            camino::Utf8Path::new(RECOVERY_DIR),
+        );
+        redirect.create_dir("Data")?;
+
+        let modified: Vec<bool> = self
+            .modified_flags()
+            .into_iter()
+            .map(|flag| *flag)
+            .collect();
+        // Writing the system gives it a new version ID, which should only happen on a real save
+        let version_id = *self.system_version_id();
+        let result = self.write_modified(&redirect, config);
+        for (flag, was_modified) in self.modified_flags().into_iter().zip(modified) {
+            *flag = was_modified;
+        }
+        *self.system_version_id() = version_id;
+
+        result.wrap_err("While autosaving the unsaved changes")
+    }
+
+    /// Replaces the data with the copies in [`RECOVERY_DIR`] and marks everything as modified,
+    /// so that the next save writes the recovered changes to the project.
+    ///
+    /// Returns the names of the files that were recovered.
+    pub fn restore_recovery(
+        &mut self,
+        filesystem: &impl FileSystem,
+        config: &luminol_config::project::Config,
+    ) -> color_eyre::Result<Vec<String>> {
+        let format = self.format();
+        let names: Vec<String> = filesystem
+            .read_dir(recovery_data_dir())
+            .wrap_err("While listing the autosaved changes")?
+            .into_iter()
+            .filter_map(|entry| {
+                if entry.metadata.is_file {
+                    format.name_of(entry.path.strip_prefix(RECOVERY_DIR).ok()?)
+                } else {
+                    Some(entry.file_name().to_string())
+                }
+            })
+            .collect();
+
+        let redirect = luminol_filesystem::redirect::FileSystem::new(
+            filesystem,
+            camino::Utf8Path::new(RECOVERY_DIR),
+        );
+        let mut recovered = self
+            .reload_files(&redirect, config, &names)
+            .wrap_err("While reading the autosaved changes")?;
+
+        // Maps that weren't open yet aren't reloaded, so they have to be loaded here.
+        let unopened_maps: Vec<usize> = names
+            .iter()
+            .filter(|name| !recovered.contains(name))
+            .filter_map(|name| map_id(name))
+            .collect();
+        for id in unopened_maps {
+            match self {
+                Self::Unloaded => panic!("project not loaded"),
+                Self::Loaded { .. } => drop(self.try_get_or_load_map(id, &redirect)?),
+                Self::LoadedVX { .. } => drop(self.get_or_load_vx_map(id, &redirect)?),
+                Self::LoadedAce { .. } => drop(self.get_or_load_ace_map(id, &redirect)?),
+            }
+            recovered.push(format!("Map{id:0>3}"));
+        }
+
+        self.mark_all_modified();
+        Ok(recovered)
+    }
+
+    /// Copies the data files that the next save will overwrite into a new directory in
+    /// [`BACKUP_DIR`], then deletes the oldest backups until only
+    /// [`luminol_config::project::Project::backup_count`] are left.
+    pub(crate) fn back_up(
+        &self,
+        filesystem: &impl FileSystem,
+        config: &luminol_config::project::Config,
+    ) -> color_eyre::Result<()> {
+        let count = config.project.backup_count;
+        let mut names = self.modified_files(&config.project.scripts_path);
+        if count == 0 || names.is_empty() {
+            return Ok(());
+        }
+        // the system is written on every save
+        if !names.iter().any(|name| name == "System") {
+            names.push("System".to_string());
+        }
+
+        let format = self.format();
+        let paths: Vec<_> = names
+            .iter()
+            .filter_map(|name| {
+                [format.container_path(name), format.file_path(name)]
+                    .into_iter()
+                    .find(|path| filesystem.exists(path).unwrap_or(false))
+            })
+            .collect();
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let timestamp = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let dir = camino::Utf8Path::new(BACKUP_DIR).join(timestamp.to_string());
+        filesystem.create_dir(dir.join("Data"))?;
+        for path in paths {
+            copy(filesystem, &path, &dir.join(&path))
+                .wrap_err_with(|| format!("While backing up {path}"))?;
+        }
+
+        let mut backups: Vec<(u128, camino::Utf8PathBuf)> = filesystem
+            .read_dir(BACKUP_DIR)?
+            .into_iter()
+            .filter(|entry| !entry.metadata.is_file)
+            .filter_map(|entry| Some((entry.file_name().parse().ok()?, entry.path)))
+            .collect();
+        backups.sort_unstable_by_key(|(timestamp, _)| *timestamp);
+        for (_, path) in backups.iter().rev().skip(count) {
+            filesystem
+                .remove_dir(path)
+                .wrap_err_with(|| format!("While deleting the old backup {path}"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Copies a file, or a directory and everything in it.
+fn copy(
+    filesystem: &impl FileSystem,
+    from: &camino::Utf8Path,
+    to: &camino::Utf8Path,
+) -> color_eyre::Result<()> {
+    if filesystem.metadata(from)?.is_file {
+        filesystem.write(to, filesystem.read(from)?)?;
+    } else {
+        filesystem.create_dir(to)?;
+        for entry in filesystem.read_dir(from)? {
+            copy(filesystem, &entry.path, &to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
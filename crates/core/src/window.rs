@@ -71,6 +71,8 @@ impl Windows {
     }
 
     pub fn process_edit_windows(&mut self, mut edit_windows: EditWindows) {
+        self.windows
+            .retain(|w| !edit_windows.removed.contains(&w.id()));
         for window in edit_windows.added.drain(..) {
             self.add_boxed_window(window)
         }
@@ -110,6 +112,10 @@ impl Windows {
             open
         });
 
+        // Remove windows before adding new ones so that a window can be replaced by a new one
+        // with the same ID
+        self.windows
+            .retain(|w| !edit_windows.removed.contains(&w.id()));
         for window in edit_windows.added {
             if self.windows.iter().any(|w| w.id() == window.id()) {
                 return;
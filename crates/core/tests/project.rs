@@ -0,0 +1,168 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_core::{fixtures::Fixture, Data, DataFormat};
+use luminol_filesystem::{path_cache, FileSystem};
+
+#[test]
+fn fixture_has_one_of_everything() -> color_eyre::Result<()> {
+    let fixture = Fixture::new()?;
+    assert!(fixture.filesystem.exists("Data/Actors.rxdata")?);
+    assert!(fixture.filesystem.exists("Data/Map001.rxdata")?);
+    assert!(fixture.filesystem.exists("Game.ini")?);
+
+    let data = fixture.load()?;
+    assert_eq!(data.format(), DataFormat::Marshal("rxdata"));
+    assert_eq!(data.actors().data.len(), 1);
+    assert_eq!(data.actors().data[0].id, 1);
+    assert_eq!(data.map_infos().data.len(), 1);
+    data.try_get_or_load_map(1, &fixture.filesystem)?;
+    Ok(())
+}
+
+#[test]
+fn ron_fixture() -> color_eyre::Result<()> {
+    let fixture = Fixture::with_project(luminol_config::project::Project {
+        use_ron: true,
+        ..Default::default()
+    })?;
+    assert!(fixture.filesystem.exists("Data/Actors/001.ron")?);
+    assert!(!fixture.filesystem.exists("Data/Actors.rxdata")?);
+
+    let data = fixture.load()?;
+    assert_eq!(data.format(), DataFormat::Ron);
+    assert_eq!(data.actors().data.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn save_and_reload() -> color_eyre::Result<()> {
+    for use_ron in [false, true] {
+        let mut fixture = Fixture::with_project(luminol_config::project::Project {
+            use_ron,
+            ..Default::default()
+        })?;
+        {
+            let mut actors = fixture.data.actors();
+            actors.data[0].name = "Aluxes".to_string();
+            actors.data.push(luminol_data::rpg::Actor {
+                id: 2,
+                name: "Basil".to_string(),
+                ..Default::default()
+            });
+            actors.modified = true;
+        }
+        fixture.save()?;
+
+        let data = fixture.load()?;
+        let actors = data.actors();
+        assert_eq!(actors.data.len(), 2);
+        assert_eq!(actors.data[0].name, "Aluxes");
+        assert_eq!(actors.data[1].name, "Basil");
+    }
+    Ok(())
+}
+
+#[test]
+fn unmodified_data_is_not_saved() -> color_eyre::Result<()> {
+    let mut fixture = Fixture::new()?;
+    fixture.filesystem.remove_file("Data/Actors.rxdata")?;
+    fixture.save()?;
+    assert!(!fixture.filesystem.exists("Data/Actors.rxdata")?);
+    Ok(())
+}
+
+#[test]
+fn convert_to_ron() -> color_eyre::Result<()> {
+    let mut fixture = Fixture::new()?;
+    fixture.data.actors().data[0].name = "Aluxes".to_string();
+    fixture
+        .data
+        .convert(&fixture.filesystem, &mut fixture.config, DataFormat::Ron)?;
+    assert!(fixture.config.project.use_ron);
+    assert!(fixture.filesystem.exists("Data/Actors/001.ron")?);
+
+    let data = fixture.load()?;
+    assert_eq!(data.format(), DataFormat::Ron);
+    assert_eq!(data.actors().data[0].name, "Aluxes");
+    Ok(())
+}
+
+#[test]
+fn load_ignores_case() -> color_eyre::Result<()> {
+    let fixture = Fixture::new()?;
+    fixture
+        .filesystem
+        .rename("Data/Actors.rxdata", "Data/actors.RXDATA")?;
+    fixture.filesystem.rename("Data", "data")?;
+
+    let filesystem = path_cache::FileSystem::new(fixture.filesystem.clone())?;
+    let mut config = fixture.config.clone();
+    let mut data = Data::Unloaded;
+    data.load(&filesystem, &mut config)?;
+    assert_eq!(data.actors().data.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn removed_maps_are_deleted_on_save() -> color_eyre::Result<()> {
+    let mut fixture = Fixture::new()?;
+    fixture.data.remove_map(1);
+    assert!(fixture.filesystem.exists("Data/Map001.rxdata")?);
+
+    fixture.save()?;
+    assert!(!fixture.filesystem.exists("Data/Map001.rxdata")?);
+    Ok(())
+}
+
+#[test]
+fn reinserted_maps_are_not_deleted() -> color_eyre::Result<()> {
+    let mut fixture = Fixture::new()?;
+    let map = fixture
+        .data
+        .try_get_or_load_map(1, &fixture.filesystem)?
+        .clone();
+    fixture.data.remove_map(1);
+    fixture.data.insert_map(1, map);
+
+    fixture.save()?;
+    assert!(fixture.filesystem.exists("Data/Map001.rxdata")?);
+    Ok(())
+}
+
+#[test]
+fn recovery_leaves_data_untouched() -> color_eyre::Result<()> {
+    let mut fixture = Fixture::new()?;
+    fixture.data.actors().data[0].name = "Aluxes".to_string();
+    fixture.data.actors().modified = true;
+    let magic_number = fixture.data.system().magic_number;
+
+    fixture
+        .data
+        .write_recovery(&fixture.filesystem, &fixture.config)?;
+    assert!(luminol_core::recovery::exists(&fixture.filesystem));
+    assert!(fixture.data.actors().modified);
+    assert_eq!(fixture.data.system().magic_number, magic_number);
+    Ok(())
+}
@@ -224,6 +224,48 @@ pub enum ParameterKind {
     Int,
     /// Parameter is a bool stored as an integer
     IntBool,
+    /// Parameter is a bool
+    Bool,
+
+    /// Parameter is an actor id
+    Actor,
+    /// Parameter is a class id
+    Class,
+    /// Parameter is a skill id
+    Skill,
+    /// Parameter is an item id
+    Item,
+    /// Parameter is a weapon id
+    Weapon,
+    /// Parameter is an armor id
+    Armor,
+    /// Parameter is an enemy id
+    Enemy,
+    /// Parameter is a troop id
+    Troop,
+    /// Parameter is a state id
+    State,
+    /// Parameter is an animation id
+    Animation,
+    /// Parameter is a common event id
+    CommonEvent,
+    /// Parameter is a map id
+    Map,
+    /// Parameter is a character on the current map
+    ///
+    /// -1 is the player, 0 is the event running the command, and anything else is an event id
+    Character,
+
+    /// Parameter is an audio file
+    AudioFile,
+    /// Parameter is a color
+    Color,
+    /// Parameter is a tone
+    Tone,
+    /// Parameter is a move route
+    MoveRoute,
+    /// Parameter is a list of choices, stored as an array of strings
+    Choices,
 
     /// Parameter is a choice between a set of enums
     ///
@@ -0,0 +1,42 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+/// RPG Maker VX Ace stores some numbers as integers until they're edited, after which they become
+/// floats (e.g. feature values). This reads either and always writes a float.
+pub fn deserialize_with<'de, D>(deserializer: D) -> Result<f64, alox_48::DeError>
+where
+    D: alox_48::DeserializerTrait<'de>,
+{
+    use alox_48::Deserialize;
+
+    match alox_48::Value::deserialize(deserializer)? {
+        alox_48::Value::Integer(v) => Ok(v as f64),
+        alox_48::Value::Float(v) => Ok(v),
+        value => Err(alox_48::DeError::custom(format!(
+            "expected a number, got {value:?}"
+        ))),
+    }
+}
+
+pub fn serialize_with<S>(value: &f64, serializer: S) -> Result<S::Ok, alox_48::SerError>
+where
+    S: alox_48::SerializerTrait,
+{
+    use alox_48::Serialize;
+
+    value.serialize(serializer)
+}
@@ -21,6 +21,7 @@ pub mod nil_padded_serde;
 pub mod optional_id_serde;
 pub mod optional_path_serde;
 
+pub mod float_alox;
 pub mod id_alox;
 pub mod id_vec_alox;
 pub mod nil_padded_alox;
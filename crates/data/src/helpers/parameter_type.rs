@@ -22,13 +22,13 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use std::borrow::Cow;
+
 use crate::rgss_structs::{Color, Tone};
 use crate::shared::{AudioFile, MoveCommand, MoveRoute};
 
 #[derive(Debug, Clone, PartialEq, Default)]
 #[derive(serde::Deserialize, serde::Serialize)]
-#[derive(alox_48::Deserialize, alox_48::Serialize)]
-#[marshal(from = "alox_48::Value", into = "alox_48::Value")] // TODO make this serde compatible
 #[allow(missing_docs)]
 pub enum ParameterType {
     Integer(i32),
@@ -36,27 +36,166 @@ pub enum ParameterType {
     Color(Color),
     Tone(Tone),
     AudioFile(AudioFile),
-    Float(f32),
+    Float(f64),
     MoveRoute(MoveRoute),
     MoveCommand(MoveCommand),
     Array(Vec<ParameterType>),
     Bool(bool),
+    /// A value that none of the other variants can hold without changing how it's saved, like a
+    /// string that isn't UTF-8, a hash or an instance of a class defined by a script.
+    /// It is kept exactly as it was loaded.
+    Raw(#[serde(with = "raw_serde")] alox_48::Value),
 
     #[default]
     None,
 }
 
-// FIXME this really should be try_from and try_into
 impl From<alox_48::Value> for ParameterType {
-    // This is a massive sore spot right now, so I'm not even bothering...
-    fn from(_value: alox_48::Value) -> Self {
-        ParameterType::None
+    fn from(value: alox_48::Value) -> Self {
+        use alox_48::Value;
+
+        match value {
+            Value::Nil => Self::None,
+            Value::Bool(v) => Self::Bool(v),
+            Value::Integer(v) => Self::Integer(v),
+            Value::Array(v) => Self::Array(v.into_iter().map(Self::from).collect()),
+            value => {
+                let converted = match &value {
+                    Value::String(_) | Value::Instance(_) => raw_string(&value)
+                        .and_then(|data| String::from_utf8(data.to_vec()).ok())
+                        .map(Self::String),
+                    Value::Float(v) => Some(Self::Float(*v)),
+                    Value::Userdata(userdata) if userdata.class.as_str() == "Color" => {
+                        Some(Self::Color(userdata.clone().into()))
+                    }
+                    Value::Userdata(userdata) if userdata.class.as_str() == "Tone" => {
+                        Some(Self::Tone(userdata.clone().into()))
+                    }
+                    Value::Object(object) if object.class.as_str() == "RPG::AudioFile" => {
+                        deserialize_value(&value).ok().map(Self::AudioFile)
+                    }
+                    Value::Object(object) if object.class.as_str() == "RPG::MoveRoute" => {
+                        deserialize_value(&value).ok().map(Self::MoveRoute)
+                    }
+                    Value::Object(object) if object.class.as_str() == "RPG::MoveCommand" => {
+                        deserialize_value(&value).ok().map(Self::MoveCommand)
+                    }
+                    _ => None,
+                };
+
+                // Strings are written back as UTF-8 and objects only with the fields we know of,
+                // so anything that would come out different is kept as it is instead.
+                match converted {
+                    Some(converted) if marshals_the_same(&converted, &value) => converted,
+                    _ => Self::Raw(value),
+                }
+            }
+        }
+    }
+}
+
+/// The bytes of a string value, including strings wrapped in an instance to store their
+/// encoding like the ones written by newer versions of Ruby.
+fn raw_string(value: &alox_48::Value) -> Option<&[u8]> {
+    match value {
+        alox_48::Value::String(string) => Some(&string.data),
+        alox_48::Value::Instance(instance) => match &*instance.value {
+            alox_48::Value::String(string) => Some(&string.data),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn marshal(value: &impl alox_48::Serialize) -> Result<Vec<u8>, alox_48::SerError> {
+    let mut serializer = alox_48::Serializer::new();
+    value.serialize(&mut serializer)?;
+    Ok(serializer.output)
+}
+
+/// Whether two values are written as the exact same bytes.
+fn marshals_the_same(a: &impl alox_48::Serialize, b: &impl alox_48::Serialize) -> bool {
+    matches!((marshal(a), marshal(b)), (Ok(a), Ok(b)) if a == b)
+}
+
+/// Converts a generic marshal value into a concrete type by round-tripping it through the marshal format.
+///
+/// This is only used for the handful of RPG classes that can appear in event command parameters,
+/// so we don't need to care too much about it being fast.
+fn deserialize_value<T>(value: &alox_48::Value) -> Result<T, alox_48::DeError>
+where
+    T: for<'de> alox_48::Deserialize<'de>,
+{
+    let output = marshal(value).map_err(|e| alox_48::DeError::custom(e.to_string()))?;
+
+    let mut deserializer = alox_48::Deserializer::new(&output)?;
+    T::deserialize(&mut deserializer)
+}
+
+/// Raw values are stored in RON as base64 encoded Marshal data, as RON has no way to represent
+/// things like symbols or the encoding of a string.
+mod raw_serde {
+    use base64::Engine;
+
+    pub fn serialize<S>(value: &alox_48::Value, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+
+        let data = super::marshal(value).map_err(|e| S::Error::custom(e.to_string()))?;
+        serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<alox_48::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let data = <String as serde::Deserialize>::deserialize(deserializer)?;
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(D::Error::custom)?;
+        let mut deserializer =
+            alox_48::Deserializer::new(&data).map_err(|e| D::Error::custom(e.to_string()))?;
+        alox_48::Deserialize::deserialize(&mut deserializer)
+            .map_err(|e: alox_48::DeError| D::Error::custom(e.to_string()))
+    }
+}
+
+impl<'de> alox_48::Deserialize<'de> for ParameterType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, alox_48::DeError>
+    where
+        D: alox_48::DeserializerTrait<'de>,
+    {
+        use alox_48::Deserialize;
+
+        alox_48::Value::deserialize(deserializer).map(Self::from)
     }
 }
 
-impl From<ParameterType> for alox_48::Value {
-    fn from(_value: ParameterType) -> Self {
-        alox_48::Value::Nil
+impl alox_48::Serialize for ParameterType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, alox_48::SerError>
+    where
+        S: alox_48::SerializerTrait,
+    {
+        use alox_48::Serialize;
+
+        match self {
+            Self::Integer(v) => v.serialize(serializer),
+            Self::String(v) => serializer.serialize_rust_string(v),
+            Self::Color(v) => v.serialize(serializer),
+            Self::Tone(v) => v.serialize(serializer),
+            Self::AudioFile(v) => v.serialize(serializer),
+            Self::Float(v) => v.serialize(serializer),
+            Self::MoveRoute(v) => v.serialize(serializer),
+            Self::MoveCommand(v) => v.serialize(serializer),
+            Self::Array(v) => v.serialize(serializer),
+            Self::Bool(v) => v.serialize(serializer),
+            Self::Raw(v) => v.serialize(serializer),
+            Self::None => serializer.serialize_nil(),
+        }
     }
 }
 
@@ -153,7 +292,7 @@ variant_impl! {
     Color, Color,
     Tone, Tone,
     AudioFile, AudioFile,
-    Float, f32,
+    Float, f64,
     MoveRoute, MoveRoute,
     MoveCommand, MoveCommand,
     Array, Vec<ParameterType>,
@@ -176,6 +315,35 @@ impl ParameterType {
     pub fn new_none() -> Self {
         Self::None
     }
+
+    pub fn is_raw(&self) -> bool {
+        matches!(self, Self::Raw(_))
+    }
+
+    /// The text of a string parameter. Strings that aren't UTF-8 are decoded lossily, so this is
+    /// only meant for displaying them.
+    pub fn text(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Self::String(v) => Some(Cow::Borrowed(v)),
+            Self::Raw(v) => raw_string(v).map(String::from_utf8_lossy),
+            _ => None,
+        }
+    }
+
+    /// Replaces the text of a string parameter. Strings wrapped with their encoding, like the ones
+    /// saved by RPG Maker VX Ace, stay wrapped and are marked as UTF-8.
+    pub fn set_text(&mut self, text: String) {
+        if let Self::Raw(alox_48::Value::Instance(instance)) = self {
+            let is_utf8 = instance.fields.iter().all(|(name, value)| {
+                name.as_str() == "E" && matches!(value, alox_48::Value::Bool(true))
+            });
+            if let (true, alox_48::Value::String(string)) = (is_utf8, &mut *instance.value) {
+                string.data = text.into_bytes();
+                return;
+            }
+        }
+        *self = Self::String(text);
+    }
 }
 
 impl From<()> for ParameterType {
@@ -1,6 +1,32 @@
 #![allow(non_upper_case_globals)]
 
+macro_rules! basic_container {
+    ($($parent:ident, $child:ident),* $(,)?) => {
+        $(
+            #[derive(Debug, Default)]
+            pub struct $parent {
+                pub data: Vec<$child>,
+                pub modified: bool,
+            }
+        )*
+    };
+}
+
+macro_rules! database_entry {
+    ($($type:ident),* $(,)?) => {
+        $(
+            impl $crate::rpg::DatabaseEntry for $type {
+                fn default_with_id(id: usize) -> Self {
+                    Self { id, ..Default::default() }
+                }
+            }
+        )*
+    };
+}
+
 // Editor specific types
+pub mod rmvx;
+pub mod rmvxace;
 pub mod rmxp;
 
 // Shared structs with the same layout
@@ -16,7 +42,7 @@ pub mod commands;
 
 pub use helpers::*;
 pub use option_vec::OptionVec;
-pub use rgss_structs::{Color, Table1, Table2, Table3, Tone};
+pub use rgss_structs::{Color, Rect, Table1, Table2, Table3, Tone};
 
 pub mod rpg {
     pub use crate::rmxp::*;
@@ -29,30 +55,6 @@ pub mod rpg {
         fn default_with_id(id: usize) -> Self;
     }
 
-    macro_rules! basic_container {
-        ($($parent:ident, $child:ident),* $(,)?) => {
-            $(
-                #[derive(Debug, Default)]
-                pub struct $parent {
-                    pub data: Vec<$child>,
-                    pub modified: bool,
-                }
-            )*
-        };
-    }
-
-    macro_rules! database_entry {
-        ($($type:ident),* $(,)?) => {
-            $(
-                impl DatabaseEntry for $type {
-                    fn default_with_id(id: usize) -> Self {
-                        Self { id, ..Default::default() }
-                    }
-                }
-            )*
-        };
-    }
-
     basic_container! {
         Actors, Actor,
         Animations, Animation,
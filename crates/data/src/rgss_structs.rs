@@ -86,6 +86,43 @@ impl From<Tone> for alox_48::Value {
     }
 }
 
+/// **A struct representing a rectangle.**
+///
+/// Used by RPG Maker VX for areas.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(from = "alox_48::Userdata", into = "alox_48::Userdata")]
+#[derive(bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl From<alox_48::Userdata> for Rect {
+    fn from(value: alox_48::Userdata) -> Self {
+        bytemuck::pod_read_unaligned(&value.data)
+    }
+}
+
+impl From<Rect> for alox_48::Userdata {
+    fn from(value: Rect) -> Self {
+        alox_48::Userdata {
+            class: "Rect".into(),
+            data: bytemuck::bytes_of(&value).to_vec(),
+        }
+    }
+}
+
+impl From<Rect> for alox_48::Value {
+    fn from(value: Rect) -> Self {
+        Self::Userdata(value.into())
+    }
+}
+
 use std::ops::{Index, IndexMut};
 
 /// Normal RGSS has dynamically dimensioned arrays, but in practice that does not map well to Rust.
@@ -0,0 +1,62 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{id_alox, id_serde, optional_path_alox, optional_path_serde, Color, Path, Table2};
+
+use super::SE;
+
+/// An animation. RPG Maker VX animations can use two cell sheets at once, with patterns 100 and
+/// up taken from the second one.
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Animation")]
+pub struct Animation {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub animation1_name: Path,
+    pub animation1_hue: i32,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub animation2_name: Path,
+    pub animation2_hue: i32,
+    pub position: crate::rmxp::animation::Position,
+    pub frame_max: i32,
+    pub frames: Vec<Frame>,
+    pub timings: Vec<Timing>,
+}
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Animation::Frame")]
+pub struct Frame {
+    pub cell_max: i32,
+    pub cell_data: Table2,
+}
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Animation::Timing")]
+pub struct Timing {
+    pub frame: i32,
+    pub se: SE,
+    pub flash_scope: i32,
+    pub flash_color: Color,
+    pub flash_duration: i32,
+}
@@ -0,0 +1,33 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{id_alox, id_serde, Rect};
+
+/// A rectangular part of a map with its own encounters.
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Area")]
+pub struct Area {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    /// The ID of the map as used by `MapInfos`.
+    pub map_id: usize,
+    pub rect: Rect,
+    pub encounter_list: Vec<i32>,
+    pub order: i32,
+}
@@ -0,0 +1,49 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{id_alox, id_serde, id_vec_alox, id_vec_serde};
+
+/// A piece of armor. `RPG::Armor` inherits from `RPG::BaseItem`, whose fields come first.
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Armor")]
+pub struct Armor {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    pub icon_index: i32,
+    pub description: String,
+    pub note: String,
+
+    pub kind: crate::rmxp::armor::Kind,
+    pub price: i32,
+    pub eva: i32,
+    pub atk: i32,
+    pub def: i32,
+    pub spi: i32,
+    pub agi: i32,
+    pub prevent_critical: bool,
+    pub half_mp_cost: bool,
+    pub double_exp_gain: bool,
+    pub auto_hp_recover: bool,
+    #[serde(with = "id_vec_serde")]
+    #[marshal(with = "id_vec_alox")]
+    pub element_set: Vec<usize>,
+    #[serde(with = "id_vec_serde")]
+    #[marshal(with = "id_vec_alox")]
+    pub state_set: Vec<usize>,
+}
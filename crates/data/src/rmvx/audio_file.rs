@@ -0,0 +1,74 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{optional_path_alox, optional_path_serde, rpg::AudioFile, Path};
+
+macro_rules! audio_file {
+    ($($name:ident, $class:literal),* $(,)?) => {
+        $(
+            /// RPG Maker VX stores audio in subclasses of `RPG::AudioFile`, which need to keep
+            /// their class name when saved.
+            #[derive(Debug, Clone, PartialEq)]
+            #[derive(serde::Deserialize, serde::Serialize)]
+            #[derive(alox_48::Deserialize, alox_48::Serialize)]
+            #[marshal(class = $class)]
+            pub struct $name {
+                #[serde(with = "optional_path_serde")]
+                #[marshal(with = "optional_path_alox")]
+                pub name: Path,
+                pub volume: u8,
+                pub pitch: u8,
+            }
+
+            impl Default for $name {
+                fn default() -> Self {
+                    Self {
+                        name: None,
+                        volume: 100,
+                        pitch: 100,
+                    }
+                }
+            }
+
+            impl From<AudioFile> for $name {
+                fn from(value: AudioFile) -> Self {
+                    Self {
+                        name: value.name,
+                        volume: value.volume,
+                        pitch: value.pitch,
+                    }
+                }
+            }
+
+            impl From<$name> for AudioFile {
+                fn from(value: $name) -> Self {
+                    Self {
+                        name: value.name,
+                        volume: value.volume,
+                        pitch: value.pitch,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+audio_file! {
+    BGM, "RPG::BGM",
+    BGS, "RPG::BGS",
+    ME, "RPG::ME",
+    SE, "RPG::SE",
+}
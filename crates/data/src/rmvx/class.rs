@@ -0,0 +1,49 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{id_alox, id_serde, id_vec_alox, id_vec_serde, Table1};
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Class")]
+pub struct Class {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    pub position: crate::rmxp::class::Position,
+    #[serde(with = "id_vec_serde")]
+    #[marshal(with = "id_vec_alox")]
+    pub weapon_set: Vec<usize>,
+    #[serde(with = "id_vec_serde")]
+    #[marshal(with = "id_vec_alox")]
+    pub armor_set: Vec<usize>,
+    pub element_ranks: Table1,
+    pub state_ranks: Table1,
+    pub learnings: Vec<Learning>,
+    pub skill_name_valid: bool,
+    pub skill_name: String,
+}
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Class::Learning")]
+pub struct Learning {
+    pub level: i32,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub skill_id: usize,
+}
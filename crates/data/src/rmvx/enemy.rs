@@ -0,0 +1,99 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{
+    id_alox, id_serde, optional_id_alox, optional_id_serde, optional_path_alox,
+    optional_path_serde, Path, Table1,
+};
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Enemy")]
+pub struct Enemy {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub battler_name: Path,
+    pub battler_hue: i32,
+    pub maxhp: i32,
+    pub maxmp: i32,
+    pub atk: i32,
+    pub def: i32,
+    pub spi: i32,
+    pub agi: i32,
+    pub hit: i32,
+    pub eva: i32,
+    pub exp: i32,
+    pub gold: i32,
+    pub drop_item1: DropItem,
+    pub drop_item2: DropItem,
+    pub levitate: bool,
+    pub has_critical: bool,
+    pub element_ranks: Table1,
+    pub state_ranks: Table1,
+    pub actions: Vec<Action>,
+    pub note: String,
+}
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Enemy::DropItem")]
+pub struct DropItem {
+    /// 0 for no drop, 1 for an item, 2 for a weapon and 3 for armor.
+    pub kind: i32,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub item_id: Option<usize>,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub weapon_id: Option<usize>,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub armor_id: Option<usize>,
+    pub denominator: i32,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Enemy::Action")]
+pub struct Action {
+    pub kind: crate::rmxp::enemy::Kind,
+    pub basic: i32,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub skill_id: usize,
+    pub condition_type: i32,
+    pub condition_param1: i32,
+    pub condition_param2: i32,
+    pub rating: i32,
+}
+
+impl Default for Action {
+    fn default() -> Self {
+        Self {
+            kind: crate::rmxp::enemy::Kind::default(),
+            basic: 0,
+            skill_id: 0,
+            condition_type: 0,
+            condition_param1: 0,
+            condition_param2: 0,
+            rating: 5,
+        }
+    }
+}
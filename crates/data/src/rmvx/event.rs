@@ -0,0 +1,147 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{
+    id_alox, id_serde, optional_path_alox, optional_path_serde,
+    rpg::{EventCommand, EventTrigger, MoveFreq, MoveRoute, MoveSpeed, MoveType, SelfSwitch},
+    Path,
+};
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Event")]
+pub struct Event {
+    pub id: usize,
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub pages: Vec<EventPage>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Event::Page")]
+pub struct EventPage {
+    pub condition: EventCondition,
+    pub graphic: Graphic,
+    pub move_type: MoveType,
+    pub move_speed: MoveSpeed,
+    pub move_frequency: MoveFreq,
+    pub move_route: MoveRoute,
+    pub walk_anime: bool,
+    pub step_anime: bool,
+    pub direction_fix: bool,
+    pub through: bool,
+    /// 0 below characters, 1 same as characters and 2 above characters.
+    pub priority_type: i32,
+    pub trigger: EventTrigger,
+    pub list: Vec<EventCommand>,
+}
+
+impl Default for EventPage {
+    fn default() -> Self {
+        Self {
+            condition: EventCondition::default(),
+            graphic: Graphic::default(),
+            move_type: MoveType::Fixed,
+            move_speed: MoveSpeed::Slow,
+            move_frequency: MoveFreq::Low,
+            move_route: MoveRoute::default(),
+            walk_anime: true,
+            step_anime: false,
+            direction_fix: false,
+            through: false,
+            priority_type: 0,
+            trigger: EventTrigger::ActionButton,
+            list: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Event::Page::Condition")]
+pub struct EventCondition {
+    pub switch1_valid: bool,
+    pub switch2_valid: bool,
+    pub variable_valid: bool,
+    pub self_switch_valid: bool,
+    pub item_valid: bool,
+    pub actor_valid: bool,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub switch1_id: usize,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub switch2_id: usize,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub variable_id: usize,
+    pub variable_value: i32,
+    pub self_switch_ch: SelfSwitch,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub item_id: usize,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub actor_id: usize,
+}
+
+impl Default for EventCondition {
+    fn default() -> Self {
+        Self {
+            switch1_valid: false,
+            switch2_valid: false,
+            variable_valid: false,
+            self_switch_valid: false,
+            item_valid: false,
+            actor_valid: false,
+            switch1_id: 0,
+            switch2_id: 0,
+            variable_id: 0,
+            variable_value: 0,
+            self_switch_ch: SelfSwitch::A,
+            item_id: 0,
+            actor_id: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Event::Page::Graphic")]
+pub struct Graphic {
+    /// Tile graphics use the raw tile ID, where 0 means there is no tile.
+    pub tile_id: i32,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub character_name: Path,
+    pub character_index: i32,
+    pub direction: i32,
+    pub pattern: i32,
+}
+
+impl Default for Graphic {
+    fn default() -> Self {
+        Self {
+            tile_id: 0,
+            character_name: None,
+            character_index: 0,
+            direction: 2,
+            pattern: 0,
+        }
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use super::{Event, BGM, BGS};
+use crate::{option_vec, optional_path_alox, optional_path_serde, Path, Table3};
+
+/// A map. Unlike RPG Maker XP, every map uses the same tileset graphics, and the passability of
+/// tiles is stored in `System::passages`.
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Map")]
+pub struct Map {
+    pub width: usize,
+    pub height: usize,
+    pub scroll_type: i32,
+    pub autoplay_bgm: bool,
+    pub bgm: BGM,
+    pub autoplay_bgs: bool,
+    pub bgs: BGS,
+    pub disable_dashing: bool,
+    pub encounter_list: Vec<i32>,
+    pub encounter_step: i32,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub parallax_name: Path,
+    pub parallax_loop_x: bool,
+    pub parallax_loop_y: bool,
+    pub parallax_sx: i32,
+    pub parallax_sy: i32,
+    pub parallax_show: bool,
+    pub data: Table3,
+    pub events: option_vec::OptionVec<Event>,
+
+    #[marshal(skip)]
+    #[serde(skip)]
+    pub modified: bool,
+}
@@ -0,0 +1,81 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+pub mod actor;
+pub mod animation;
+pub mod area;
+pub mod armor;
+pub mod audio_file;
+pub mod class;
+pub mod enemy;
+pub mod event;
+pub mod item;
+pub mod map;
+pub mod skill;
+pub mod state;
+pub mod system;
+pub mod troop;
+pub mod weapon;
+
+pub use actor::Actor;
+pub use animation::Animation;
+pub use area::Area;
+pub use armor::Armor;
+pub use audio_file::{BGM, BGS, ME, SE};
+pub use class::Class;
+pub use enemy::Enemy;
+pub use event::{Event, EventPage};
+pub use item::Item;
+pub use map::Map;
+pub use skill::Skill;
+pub use state::State;
+pub use system::System;
+pub use troop::Troop;
+pub use weapon::Weapon;
+
+// RPG Maker VX shares these classes with RPG Maker XP.
+pub use crate::rpg::{
+    CommonEvent, CommonEvents, EventCommand, MapInfo, MapInfos, MoveCommand, MoveRoute, Script,
+    Scripts,
+};
+
+basic_container! {
+    Actors, Actor,
+    Animations, Animation,
+    Areas, Area,
+    Armors, Armor,
+    Classes, Class,
+    Enemies, Enemy,
+    Items, Item,
+    Skills, Skill,
+    States, State,
+    Troops, Troop,
+    Weapons, Weapon,
+}
+
+database_entry! {
+    Actor,
+    Animation,
+    Area,
+    Armor,
+    Class,
+    Enemy,
+    Item,
+    Skill,
+    State,
+    Troop,
+    Weapon,
+}
@@ -0,0 +1,156 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use super::{BGM, ME, SE};
+use crate::{
+    id_alox, id_serde, id_vec_alox, id_vec_serde, nil_padded_alox, nil_padded_serde,
+    optional_id_alox, optional_id_serde, optional_path_alox, optional_path_serde, Path, Table1,
+};
+
+#[derive(Default, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[serde(default)]
+#[marshal(default)]
+#[marshal(class = "RPG::System")]
+pub struct System {
+    pub game_title: String,
+    /// Changed every time the project is saved so the game can tell old save files apart.
+    pub version_id: i32,
+    #[serde(with = "id_vec_serde")]
+    #[marshal(with = "id_vec_alox")]
+    pub party_members: Vec<usize>,
+    pub elements: Vec<String>,
+    #[marshal(with = "nil_padded_alox")]
+    #[serde(with = "nil_padded_serde")]
+    pub switches: Vec<String>,
+    #[marshal(with = "nil_padded_alox")]
+    #[serde(with = "nil_padded_serde")]
+    pub variables: Vec<String>,
+    /// The passability of every tile, since RPG Maker VX has a single tileset.
+    pub passages: Table1,
+    pub boat: Vehicle,
+    pub ship: Vehicle,
+    pub airship: Vehicle,
+    pub title_bgm: BGM,
+    pub battle_bgm: BGM,
+    pub battle_end_me: ME,
+    pub gameover_me: ME,
+    /// The 20 system sound effects, in the order they are listed in the editor.
+    pub sounds: Vec<SE>,
+    pub test_battlers: Vec<TestBattler>,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub test_troop_id: Option<usize>,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub start_map_id: usize,
+    pub start_x: i32,
+    pub start_y: i32,
+    pub terms: Terms,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub battler_name: Path,
+    pub battler_hue: i32,
+    pub edit_map_id: usize,
+
+    #[marshal(skip)]
+    #[serde(skip)]
+    pub modified: bool,
+}
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::System::Vehicle")]
+pub struct Vehicle {
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub character_name: Path,
+    pub character_index: i32,
+    pub bgm: BGM,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub start_map_id: usize,
+    pub start_x: i32,
+    pub start_y: i32,
+}
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::System::Terms")]
+#[serde(default)]
+pub struct Terms {
+    pub level: String,
+    pub level_a: String,
+    pub hp: String,
+    pub hp_a: String,
+    pub mp: String,
+    pub mp_a: String,
+    pub atk: String,
+    pub def: String,
+    pub spi: String,
+    pub agi: String,
+    pub weapon: String,
+    pub armor1: String,
+    pub armor2: String,
+    pub armor3: String,
+    pub armor4: String,
+    pub weapon1: String,
+    pub weapon2: String,
+    pub attack: String,
+    pub skill: String,
+    pub guard: String,
+    pub item: String,
+    pub equip: String,
+    pub status: String,
+    pub save: String,
+    pub game_end: String,
+    pub fight: String,
+    pub escape: String,
+    pub new_game: String,
+    #[serde(rename = "continue")]
+    #[marshal(rename = "continue")]
+    pub continue_: String,
+    pub shutdown: String,
+    pub to_title: String,
+    pub cancel: String,
+    pub gold: String,
+}
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::System::TestBattler")]
+pub struct TestBattler {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub actor_id: usize,
+    pub level: i32,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub weapon_id: Option<usize>,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub armor1_id: Option<usize>,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub armor2_id: Option<usize>,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub armor3_id: Option<usize>,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub armor4_id: Option<usize>,
+}
@@ -0,0 +1,51 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use super::Feature;
+use crate::{id_alox, id_serde, optional_path_alox, optional_path_serde, Path};
+
+/// An actor. Like every RPG Maker VX Ace database entry, the fields up to `note` are inherited
+/// from `RPG::BaseItem`.
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Actor")]
+pub struct Actor {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    pub icon_index: i32,
+    pub description: String,
+    pub features: Vec<Feature>,
+    pub note: String,
+
+    pub nickname: String,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub class_id: usize,
+    pub initial_level: i32,
+    pub max_level: i32,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub character_name: Path,
+    pub character_index: i32,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub face_name: Path,
+    pub face_index: i32,
+    /// The initial weapon or armor ID of each equipment slot, or 0 if the slot is empty.
+    pub equips: Vec<i32>,
+}
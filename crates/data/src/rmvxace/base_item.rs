@@ -0,0 +1,122 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::float_alox;
+
+/// A trait of a database entry, e.g. an element rate or an added skill type. What `data_id` and
+/// `value` mean depends on `code`.
+#[derive(Default, Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::BaseItem::Feature")]
+pub struct Feature {
+    pub code: i32,
+    pub data_id: i32,
+    #[marshal(with = "float_alox")]
+    pub value: f64,
+}
+
+impl Feature {
+    pub const ELEMENT_RATE: i32 = 11;
+    pub const DEBUFF_RATE: i32 = 12;
+    pub const STATE_RATE: i32 = 13;
+    pub const STATE_RESIST: i32 = 14;
+    pub const PARAM: i32 = 21;
+    pub const XPARAM: i32 = 22;
+    pub const SPARAM: i32 = 23;
+    pub const ATK_ELEMENT: i32 = 31;
+    pub const ATK_STATE: i32 = 32;
+    pub const ATK_SPEED: i32 = 33;
+    pub const ATK_TIMES: i32 = 34;
+    pub const STYPE_ADD: i32 = 41;
+    pub const STYPE_SEAL: i32 = 42;
+    pub const SKILL_ADD: i32 = 43;
+    pub const SKILL_SEAL: i32 = 44;
+    pub const EQUIP_WTYPE: i32 = 51;
+    pub const EQUIP_ATYPE: i32 = 52;
+    pub const EQUIP_FIX: i32 = 53;
+    pub const EQUIP_SEAL: i32 = 54;
+    pub const SLOT_TYPE: i32 = 55;
+    pub const ACTION_PLUS: i32 = 61;
+    pub const SPECIAL_FLAG: i32 = 62;
+    pub const COLLAPSE_TYPE: i32 = 63;
+    pub const PARTY_ABILITY: i32 = 64;
+}
+
+/// The fields every RPG Maker VX Ace database entry inherits from `RPG::BaseItem`.
+///
+/// Marshal stores inherited instance variables alongside the subclass's own, so each type repeats
+/// these as its first fields and implements this trait to make them accessible generically.
+pub trait BaseItem {
+    fn id(&self) -> usize;
+    fn name(&self) -> &str;
+    fn icon_index(&self) -> i32;
+    fn description(&self) -> &str;
+    fn features(&self) -> &[Feature];
+    fn features_mut(&mut self) -> &mut Vec<Feature>;
+    fn note(&self) -> &str;
+    fn note_mut(&mut self) -> &mut String;
+}
+
+macro_rules! base_item {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl BaseItem for $type {
+                fn id(&self) -> usize {
+                    self.id
+                }
+
+                fn name(&self) -> &str {
+                    &self.name
+                }
+
+                fn icon_index(&self) -> i32 {
+                    self.icon_index
+                }
+
+                fn description(&self) -> &str {
+                    &self.description
+                }
+
+                fn features(&self) -> &[Feature] {
+                    &self.features
+                }
+
+                fn features_mut(&mut self) -> &mut Vec<Feature> {
+                    &mut self.features
+                }
+
+                fn note(&self) -> &str {
+                    &self.note
+                }
+
+                fn note_mut(&mut self) -> &mut String {
+                    &mut self.note
+                }
+            }
+        )*
+    };
+}
+
+base_item! {
+    super::Actor,
+    super::Armor,
+    super::Class,
+    super::Enemy,
+    super::Item,
+    super::Skill,
+    super::State,
+    super::Weapon,
+}
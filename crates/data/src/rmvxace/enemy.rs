@@ -0,0 +1,83 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use super::Feature;
+use crate::{float_alox, id_alox, id_serde, optional_path_alox, optional_path_serde, Path};
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Enemy")]
+pub struct Enemy {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    pub icon_index: i32,
+    pub description: String,
+    pub features: Vec<Feature>,
+    pub note: String,
+
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub battler_name: Path,
+    pub battler_hue: i32,
+    /// The 8 basic parameters.
+    pub params: Vec<i32>,
+    pub exp: i32,
+    pub gold: i32,
+    pub drop_items: Vec<DropItem>,
+    pub actions: Vec<Action>,
+}
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Enemy::DropItem")]
+pub struct DropItem {
+    /// 0 for no drop, 1 for an item, 2 for a weapon and 3 for armor.
+    pub kind: i32,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub data_id: usize,
+    pub denominator: i32,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Enemy::Action")]
+pub struct Action {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub skill_id: usize,
+    pub condition_type: i32,
+    /// HP and MP conditions use rates between 0 and 1, so these aren't always integers.
+    #[marshal(with = "float_alox")]
+    pub condition_param1: f64,
+    #[marshal(with = "float_alox")]
+    pub condition_param2: f64,
+    pub rating: i32,
+}
+
+impl Default for Action {
+    fn default() -> Self {
+        Self {
+            skill_id: 0,
+            condition_type: 0,
+            condition_param1: 0.,
+            condition_param2: 0.,
+            rating: 5,
+        }
+    }
+}
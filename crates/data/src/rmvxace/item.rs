@@ -0,0 +1,52 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use super::{Damage, Effect, Feature};
+use crate::{id_alox, id_serde};
+
+/// An item. `RPG::Item` inherits from `RPG::UsableItem` and `RPG::BaseItem`, whose fields come
+/// first.
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Item")]
+pub struct Item {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    pub icon_index: i32,
+    pub description: String,
+    pub features: Vec<Feature>,
+    pub note: String,
+
+    pub scope: i32,
+    pub occasion: crate::rpg::Occasion,
+    pub speed: i32,
+    pub success_rate: i32,
+    pub repeats: i32,
+    pub tp_gain: i32,
+    /// 0 for certain hit, 1 for physical and 2 for magical attacks.
+    pub hit_type: i32,
+    /// -1 to use the user's attack animation, 0 for none.
+    pub animation_id: i32,
+    pub damage: Damage,
+    pub effects: Vec<Effect>,
+
+    /// 1 for regular items and 2 for key items.
+    pub itype_id: i32,
+    pub price: i32,
+    pub consumable: bool,
+}
@@ -0,0 +1,73 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+pub mod actor;
+pub mod armor;
+pub mod base_item;
+pub mod class;
+pub mod enemy;
+pub mod item;
+pub mod map;
+pub mod skill;
+pub mod state;
+pub mod system;
+pub mod tileset;
+pub mod usable_item;
+pub mod weapon;
+
+pub use actor::Actor;
+pub use armor::Armor;
+pub use base_item::{BaseItem, Feature};
+pub use class::Class;
+pub use enemy::Enemy;
+pub use item::Item;
+pub use map::Map;
+pub use skill::Skill;
+pub use state::State;
+pub use system::System;
+pub use tileset::{AutotileKind, Tileset};
+pub use usable_item::{Damage, Effect};
+pub use weapon::Weapon;
+
+// RPG Maker VX Ace didn't change these classes from RPG Maker VX.
+pub use crate::rmvx::{
+    Animation, Animations, CommonEvent, CommonEvents, Event, EventCommand, EventPage, MapInfo,
+    MapInfos, MoveCommand, MoveRoute, Script, Scripts, Troop, Troops, BGM, BGS, ME, SE,
+};
+
+basic_container! {
+    Actors, Actor,
+    Armors, Armor,
+    Classes, Class,
+    Enemies, Enemy,
+    Items, Item,
+    Skills, Skill,
+    States, State,
+    Tilesets, Tileset,
+    Weapons, Weapon,
+}
+
+database_entry! {
+    Actor,
+    Armor,
+    Class,
+    Enemy,
+    Item,
+    Skill,
+    State,
+    Tileset,
+    Weapon,
+}
@@ -0,0 +1,49 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use super::Feature;
+use crate::{id_alox, id_serde};
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::State")]
+pub struct State {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    pub icon_index: i32,
+    pub description: String,
+    pub features: Vec<Feature>,
+    pub note: String,
+
+    pub restriction: i32,
+    pub priority: i32,
+    pub remove_at_battle_end: bool,
+    pub remove_by_restriction: bool,
+    /// 0 for never, 1 at the end of an action and 2 at the end of a turn.
+    pub auto_removal_timing: i32,
+    pub min_turns: i32,
+    pub max_turns: i32,
+    pub remove_by_damage: bool,
+    pub chance_by_damage: i32,
+    pub remove_by_walking: bool,
+    pub steps_to_remove: i32,
+    pub message1: String,
+    pub message2: String,
+    pub message3: String,
+    pub message4: String,
+}
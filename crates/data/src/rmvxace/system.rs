@@ -0,0 +1,135 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::rmvx::system::Vehicle;
+use crate::rmvx::{BGM, ME, SE};
+use crate::{
+    id_alox, id_serde, id_vec_alox, id_vec_serde, nil_padded_alox, nil_padded_serde,
+    optional_id_alox, optional_id_serde, optional_path_alox, optional_path_serde, Path, Tone,
+};
+
+#[derive(Default, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[serde(default)]
+#[marshal(default)]
+#[marshal(class = "RPG::System")]
+pub struct System {
+    pub game_title: String,
+    /// Changed every time the project is saved so the game can tell old save files apart.
+    pub version_id: i32,
+    pub japanese: bool,
+    #[serde(with = "id_vec_serde")]
+    #[marshal(with = "id_vec_alox")]
+    pub party_members: Vec<usize>,
+    pub currency_unit: String,
+    #[marshal(with = "nil_padded_alox")]
+    #[serde(with = "nil_padded_serde")]
+    pub elements: Vec<String>,
+    #[marshal(with = "nil_padded_alox")]
+    #[serde(with = "nil_padded_serde")]
+    pub skill_types: Vec<String>,
+    #[marshal(with = "nil_padded_alox")]
+    #[serde(with = "nil_padded_serde")]
+    pub weapon_types: Vec<String>,
+    #[marshal(with = "nil_padded_alox")]
+    #[serde(with = "nil_padded_serde")]
+    pub armor_types: Vec<String>,
+    #[marshal(with = "nil_padded_alox")]
+    #[serde(with = "nil_padded_serde")]
+    pub switches: Vec<String>,
+    #[marshal(with = "nil_padded_alox")]
+    #[serde(with = "nil_padded_serde")]
+    pub variables: Vec<String>,
+    pub boat: Vehicle,
+    pub ship: Vehicle,
+    pub airship: Vehicle,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub title1_name: Path,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub title2_name: Path,
+    pub opt_draw_title: bool,
+    pub opt_use_midi: bool,
+    pub opt_transparent: bool,
+    pub opt_followers: bool,
+    pub opt_slip_death: bool,
+    pub opt_floor_death: bool,
+    pub opt_display_tp: bool,
+    pub opt_extra_exp: bool,
+    pub window_tone: Tone,
+    pub title_bgm: BGM,
+    pub battle_bgm: BGM,
+    pub battle_end_me: ME,
+    pub gameover_me: ME,
+    /// The 24 system sound effects, in the order they are listed in the editor.
+    pub sounds: Vec<SE>,
+    pub test_battlers: Vec<TestBattler>,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub test_troop_id: Option<usize>,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub start_map_id: usize,
+    pub start_x: i32,
+    pub start_y: i32,
+    pub terms: Terms,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub battleback1_name: Path,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub battleback2_name: Path,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub battler_name: Path,
+    pub battler_hue: i32,
+    pub edit_map_id: usize,
+
+    #[marshal(skip)]
+    #[serde(skip)]
+    pub modified: bool,
+}
+
+/// The in-game vocabulary. Unlike RPG Maker VX, terms are grouped into arrays instead of having a
+/// field each.
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::System::Terms")]
+#[serde(default)]
+pub struct Terms {
+    /// Level, HP, MP and TP, each followed by its abbreviation.
+    pub basic: Vec<String>,
+    /// The names of the 8 basic parameters.
+    pub params: Vec<String>,
+    /// The names of the 5 equipment slot types.
+    pub etypes: Vec<String>,
+    /// The 23 menu and battle commands.
+    pub commands: Vec<String>,
+}
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::System::TestBattler")]
+pub struct TestBattler {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub actor_id: usize,
+    pub level: i32,
+    /// The weapon or armor ID of each equipment slot, or 0 if the slot is empty.
+    pub equips: Vec<i32>,
+}
@@ -0,0 +1,124 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{id_alox, id_serde, Table1};
+
+/// A tileset. Unlike RPG Maker XP, tilesets are made up of up to 9 graphics from
+/// `Graphics/Tilesets`: the autotile sheets A1 to A4, the A5 sheet and the upper layer sheets B to
+/// E.
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Tileset")]
+pub struct Tileset {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    /// 0 for field, 1 for area and 2 for VX compatible tilesets.
+    pub mode: i32,
+    pub name: String,
+    /// The names of the A1, A2, A3, A4, A5, B, C, D and E graphics, empty if unused.
+    pub tileset_names: Vec<String>,
+    /// The flags of every tile ID. See the associated constants.
+    pub flags: Table1,
+    pub note: String,
+}
+
+impl Tileset {
+    /// Impassable when moving down.
+    pub const FLAG_DOWN: i16 = 0x0001;
+    /// Impassable when moving left.
+    pub const FLAG_LEFT: i16 = 0x0002;
+    /// Impassable when moving right.
+    pub const FLAG_RIGHT: i16 = 0x0004;
+    /// Impassable when moving up.
+    pub const FLAG_UP: i16 = 0x0008;
+    /// Drawn above characters and ignored for passability.
+    pub const FLAG_STAR: i16 = 0x0010;
+    pub const FLAG_LADDER: i16 = 0x0020;
+    pub const FLAG_BUSH: i16 = 0x0040;
+    pub const FLAG_COUNTER: i16 = 0x0080;
+    pub const FLAG_DAMAGE_FLOOR: i16 = 0x0100;
+    pub const FLAG_BOAT_IMPASSABLE: i16 = 0x0200;
+    pub const FLAG_SHIP_IMPASSABLE: i16 = 0x0400;
+    pub const FLAG_AIRSHIP_CANNOT_LAND: i16 = 0x0800;
+    /// The terrain tag is stored in the top 4 bits.
+    pub const TERRAIN_TAG_SHIFT: u32 = 12;
+}
+
+/// How the 48 tile IDs of an autotile are drawn, which depends on the sheet it comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutotileKind {
+    /// Ground autotiles with 48 shapes, like RPG Maker XP autotiles.
+    Floor,
+    /// Walls and roofs, which only connect orthogonally and have 16 shapes.
+    Wall,
+    /// The waterfalls on A1, which only connect horizontally and have 4 shapes.
+    Waterfall,
+}
+
+impl Tileset {
+    /// The first tile ID of the B sheet.
+    pub const TILE_ID_B: i16 = 0;
+    /// The first tile ID of the C sheet.
+    pub const TILE_ID_C: i16 = 256;
+    /// The first tile ID of the D sheet.
+    pub const TILE_ID_D: i16 = 512;
+    /// The first tile ID of the E sheet.
+    pub const TILE_ID_E: i16 = 768;
+    /// The first tile ID of the A5 sheet.
+    pub const TILE_ID_A5: i16 = 1536;
+    /// The first tile ID of the A1 sheet. Every tile ID from here on is an autotile.
+    pub const TILE_ID_A1: i16 = 2048;
+    /// The first tile ID of the A2 sheet.
+    pub const TILE_ID_A2: i16 = 2816;
+    /// The first tile ID of the A3 sheet.
+    pub const TILE_ID_A3: i16 = 4352;
+    /// The first tile ID of the A4 sheet.
+    pub const TILE_ID_A4: i16 = 5888;
+    /// One past the last valid tile ID.
+    pub const TILE_ID_MAX: i16 = 8192;
+
+    /// Returns the index of the autotile (counting from the first A1 autotile) a tile ID belongs
+    /// to and what kind of autotile it is, or `None` if the tile ID isn't an autotile.
+    pub fn autotile_kind(tile_id: i16) -> Option<(usize, AutotileKind)> {
+        if !(Self::TILE_ID_A1..Self::TILE_ID_MAX).contains(&tile_id) {
+            return None;
+        }
+
+        let index = (tile_id - Self::TILE_ID_A1) as usize / 48;
+        let kind = match index {
+            // The odd A1 autotiles in the right half of the sheet are waterfalls
+            5 | 7 | 9 | 11 | 13 | 15 => AutotileKind::Waterfall,
+            0..=47 => AutotileKind::Floor,
+            48..=79 => AutotileKind::Wall,
+            // A4 alternates between rows of roofs (floors) and rows of walls
+            _ if (index - 80) / 8 % 2 == 0 => AutotileKind::Floor,
+            _ => AutotileKind::Wall,
+        };
+        Some((index, kind))
+    }
+}
+
+impl AutotileKind {
+    /// The number of distinct shapes this kind of autotile has.
+    pub const fn shape_count(self) -> i16 {
+        match self {
+            Self::Floor => 48,
+            Self::Wall => 16,
+            Self::Waterfall => 4,
+        }
+    }
+}
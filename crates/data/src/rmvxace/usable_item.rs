@@ -0,0 +1,60 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::float_alox;
+
+/// How a skill or item deals damage. `RPG::Skill` and `RPG::Item` both inherit this from
+/// `RPG::UsableItem`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::UsableItem::Damage")]
+pub struct Damage {
+    /// 0 for none, 1 to 4 for HP and MP damage or recovery, and 5 and 6 for HP and MP drain.
+    #[serde(rename = "type")]
+    #[marshal(rename = "type")]
+    pub type_: i32,
+    /// -1 to use the attacker's attack element, 0 for no element.
+    pub element_id: i32,
+    /// A Ruby expression evaluated with `a` as the user and `b` as the target.
+    pub formula: String,
+    pub variance: i32,
+    pub critical: bool,
+}
+
+impl Default for Damage {
+    fn default() -> Self {
+        Self {
+            type_: 0,
+            element_id: 0,
+            formula: "0".to_string(),
+            variance: 20,
+            critical: false,
+        }
+    }
+}
+
+/// An effect applied by a skill or item besides its damage, e.g. adding a state.
+#[derive(Default, Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::UsableItem::Effect")]
+pub struct Effect {
+    pub code: i32,
+    pub data_id: i32,
+    #[marshal(with = "float_alox")]
+    pub value1: f64,
+    #[marshal(with = "float_alox")]
+    pub value2: f64,
+}
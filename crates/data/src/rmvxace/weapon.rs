@@ -0,0 +1,46 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use super::Feature;
+use crate::{id_alox, id_serde, optional_id_alox, optional_id_serde};
+
+/// A weapon. `RPG::Weapon` inherits from `RPG::EquipItem` and `RPG::BaseItem`, whose fields come
+/// first.
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Weapon")]
+pub struct Weapon {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    pub icon_index: i32,
+    pub description: String,
+    pub features: Vec<Feature>,
+    pub note: String,
+
+    pub price: i32,
+    /// The equipment slot type, which is always 0 for weapons.
+    pub etype_id: i32,
+    /// The bonus to each of the 8 basic parameters.
+    pub params: Vec<i32>,
+
+    /// Index into `System::weapon_types`, or 0 for none.
+    pub wtype_id: usize,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub animation_id: Option<usize>,
+}
@@ -17,7 +17,7 @@
 use crate::rpg::{AudioFile, Event};
 use crate::{id_alox, id_serde, option_vec, Table3};
 
-#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::Map")]
 pub struct Map {
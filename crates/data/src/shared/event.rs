@@ -197,6 +197,17 @@ pub struct Graphic {
     pub blend_type: BlendMode,
 }
 
+impl EventPage {
+    /// Clones this page, giving every command a new guid so the copy can be edited alongside the original.
+    pub fn duplicate(&self) -> Self {
+        Self {
+            move_route: self.move_route.duplicate(),
+            list: self.list.iter().map(EventCommand::duplicate).collect(),
+            ..self.clone()
+        }
+    }
+}
+
 impl Default for Graphic {
     fn default() -> Self {
         Self {
@@ -299,3 +310,31 @@ pub struct EventCommand {
     #[serde(skip)]
     pub guid: u16,
 }
+
+impl EventCommand {
+    /// Creates a new `EventCommand` with a random guid.
+    pub fn new(code: u16, indent: usize, parameters: Vec<ParameterType>) -> Self {
+        Self {
+            code,
+            indent,
+            parameters,
+            guid: rand::random(),
+        }
+    }
+
+    /// Clones this command, giving it and any move commands in its parameters a new guid.
+    pub fn duplicate(&self) -> Self {
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|parameter| match parameter {
+                ParameterType::MoveRoute(route) => ParameterType::MoveRoute(route.duplicate()),
+                ParameterType::MoveCommand(command) => {
+                    ParameterType::MoveCommand(command.duplicate())
+                }
+                parameter => parameter.clone(),
+            })
+            .collect();
+        Self::new(self.code, self.indent, parameters)
+    }
+}
@@ -39,3 +39,82 @@ pub struct MoveCommand {
     #[serde(skip)]
     pub guid: u16,
 }
+
+impl MoveRoute {
+    /// Clones this route, giving every move command a new guid.
+    pub fn duplicate(&self) -> Self {
+        Self {
+            list: self.list.iter().map(MoveCommand::duplicate).collect(),
+            ..self.clone()
+        }
+    }
+}
+
+impl MoveCommand {
+    /// Creates a new `MoveCommand` with a random guid.
+    pub fn new(code: u16, parameters: Vec<ParameterType>) -> Self {
+        Self {
+            code,
+            parameters,
+            guid: rand::random(),
+        }
+    }
+
+    /// Clones this command with a new guid.
+    pub fn duplicate(&self) -> Self {
+        Self::new(self.code, self.parameters.clone())
+    }
+
+    /// The human readable name of this move command, as shown by RPG Maker XP.
+    pub fn name(&self) -> &'static str {
+        match self.code {
+            0 => "",
+            1 => "Move Down",
+            2 => "Move Left",
+            3 => "Move Right",
+            4 => "Move Up",
+            5 => "Move Lower Left",
+            6 => "Move Lower Right",
+            7 => "Move Upper Left",
+            8 => "Move Upper Right",
+            9 => "Move at Random",
+            10 => "Move toward Player",
+            11 => "Move away from Player",
+            12 => "1 Step Forward",
+            13 => "1 Step Backward",
+            14 => "Jump",
+            15 => "Wait",
+            16 => "Turn Down",
+            17 => "Turn Left",
+            18 => "Turn Right",
+            19 => "Turn Up",
+            20 => "Turn 90° Right",
+            21 => "Turn 90° Left",
+            22 => "Turn 180°",
+            23 => "Turn 90° Right or Left",
+            24 => "Turn at Random",
+            25 => "Turn toward Player",
+            26 => "Turn away from Player",
+            27 => "Switch ON",
+            28 => "Switch OFF",
+            29 => "Change Speed",
+            30 => "Change Frequency",
+            31 => "Move Animation ON",
+            32 => "Move Animation OFF",
+            33 => "Stop Animation ON",
+            34 => "Stop Animation OFF",
+            35 => "Direction Fix ON",
+            36 => "Direction Fix OFF",
+            37 => "Through ON",
+            38 => "Through OFF",
+            39 => "Always on Top ON",
+            40 => "Always on Top OFF",
+            41 => "Change Graphic",
+            42 => "Change Opacity",
+            43 => "Change Blending",
+            44 => "Play SE",
+            45 => "Script",
+            _ => "Unknown",
+        }
+    }
+}
@@ -0,0 +1,338 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Event commands have to come out of a load and save exactly as they went in, whatever their
+//! parameters hold.
+//!
+//! The files in `fixtures/` are lists of `RPG::EventCommand` laid out byte-for-byte the way
+//! `Marshal.dump` writes them: `xp_commands.rxdata` with the plain strings of Ruby 1.8 (RPG Maker
+//! XP and VX), `ace_commands.rvdata2` with the encoded strings of Ruby 1.9 (RPG Maker VX Ace) and
+//! `unusual_parameters.rxdata` with things scripts and other editors put in parameters.
+
+use alox_48::Serialize;
+use luminol_data::{rpg::EventCommand, ParameterType};
+use rand::{Rng, SeedableRng};
+
+fn load(data: &[u8]) -> Vec<EventCommand> {
+    let mut deserializer = alox_48::Deserializer::new(data).expect("invalid marshal header");
+    alox_48::Deserialize::deserialize(&mut deserializer).expect("failed to load commands")
+}
+
+fn save(commands: &Vec<EventCommand>) -> Vec<u8> {
+    let mut serializer = alox_48::Serializer::new();
+    commands
+        .serialize(&mut serializer)
+        .expect("failed to save commands");
+    serializer.output
+}
+
+fn assert_round_trip(name: &str, data: &[u8]) {
+    let saved = save(&load(data));
+    assert!(
+        saved == data,
+        "{name} changed when it was loaded and saved again\nbefore: {data:02x?}\nafter:  {saved:02x?}"
+    );
+}
+
+#[test]
+fn fixtures_round_trip() {
+    for (name, data) in [
+        (
+            "xp_commands.rxdata",
+            &include_bytes!("fixtures/xp_commands.rxdata")[..],
+        ),
+        (
+            "ace_commands.rvdata2",
+            &include_bytes!("fixtures/ace_commands.rvdata2")[..],
+        ),
+        (
+            "unusual_parameters.rxdata",
+            &include_bytes!("fixtures/unusual_parameters.rxdata")[..],
+        ),
+    ] {
+        assert_round_trip(name, data);
+    }
+}
+
+/// Whether strings are saved wrapped in an instance with their encoding, like Ruby 1.9 does.
+fn saves_encoded_strings() -> bool {
+    let mut serializer = alox_48::Serializer::new();
+    ParameterType::from("a")
+        .serialize(&mut serializer)
+        .expect("failed to save a string");
+    serializer.output[2] == b'I'
+}
+
+#[test]
+fn known_parameters_are_converted() {
+    for (data, encoded_strings) in [
+        (&include_bytes!("fixtures/xp_commands.rxdata")[..], false),
+        (&include_bytes!("fixtures/ace_commands.rvdata2")[..], true),
+    ] {
+        let commands = load(data);
+        assert_eq!(
+            commands[0].parameters[0].text().as_deref(),
+            Some("Hello there!")
+        );
+        assert_eq!(
+            commands[1].parameters[0].text().as_deref(),
+            Some("こんにちは、世界")
+        );
+        assert_eq!(commands[2].parameters[1], ParameterType::Integer(2));
+
+        // audio files hold their name as a plain string, so they can only be converted when
+        // strings are saved the same way as in the file
+        if encoded_strings == saves_encoded_strings() {
+            let Some(bgm) = commands[8].parameters[0].as_audiofile() else {
+                panic!(
+                    "expected an audio file, got {:?}",
+                    commands[8].parameters[0]
+                );
+            };
+            assert_eq!(
+                bgm.name.as_deref().map(camino::Utf8Path::as_str),
+                Some("015-Theme04")
+            );
+        }
+
+        let Some(route) = commands[10].parameters[1].as_moveroute() else {
+            panic!(
+                "expected a move route, got {:?}",
+                commands[10].parameters[1]
+            );
+        };
+        assert!(route.skippable && !route.repeat);
+        assert_eq!(route.list.len(), 4);
+        assert!(commands[11].parameters[0].is_movecommand());
+        assert!(commands[13].parameters[0].is_tone());
+        assert!(commands[14].parameters[0].is_color());
+        assert_eq!(commands[15].parameters[4], ParameterType::Integer(-5000));
+    }
+}
+
+#[test]
+fn edited_ace_strings_keep_their_encoding() {
+    let mut commands = load(include_bytes!("fixtures/ace_commands.rvdata2"));
+    let parameter = &mut commands[0].parameters[0];
+    parameter.set_text("Goodbye".to_string());
+
+    let mut serializer = alox_48::Serializer::new();
+    parameter
+        .serialize(&mut serializer)
+        .expect("failed to save the string");
+    assert_eq!(serializer.output, b"\x04\x08I\"\x0cGoodbye\x06:\x06ET");
+
+    // the rest of the file is untouched
+    let saved = load(&save(&commands));
+    assert_eq!(saved[0].parameters[0].text().as_deref(), Some("Goodbye"));
+    assert_eq!(
+        saved[1].parameters[0].text().as_deref(),
+        Some("こんにちは、世界")
+    );
+}
+
+#[test]
+fn unusual_parameters_are_kept_raw() {
+    let commands = load(include_bytes!("fixtures/unusual_parameters.rxdata"));
+
+    // Shift-JIS, with and without an encoding
+    for command in &commands[0..2] {
+        let parameter = &command.parameters[0];
+        assert!(parameter.is_raw(), "{parameter:?}");
+        assert!(parameter
+            .text()
+            .is_some_and(|text| text.contains('\u{FFFD}')));
+    }
+    assert_eq!(
+        commands[2].parameters[0].text().as_deref(),
+        Some("ascii only")
+    );
+    // a symbol, a hash, an object of a class defined by a script and an audio file with a field
+    // RPG Maker doesn't have
+    for command in &commands[4..8] {
+        let parameter = &command.parameters[0];
+        assert!(parameter.is_raw(), "{parameter:?}");
+        assert!(parameter.text().is_none());
+    }
+}
+
+/// Writes Marshal data the same way `Marshal.dump` does, for generating parameters.
+struct Writer {
+    output: Vec<u8>,
+    symbols: Vec<String>,
+}
+
+impl Writer {
+    fn new() -> Self {
+        Self {
+            output: vec![4, 8],
+            symbols: Vec::new(),
+        }
+    }
+
+    fn long(&mut self, value: i32) {
+        match value {
+            0 => self.output.push(0),
+            1..=122 => self.output.push(value as u8 + 5),
+            -123..=-1 => self.output.push((value - 5) as u8),
+            _ => {
+                let bytes = value.to_le_bytes();
+                let padding = if value > 0 { 0 } else { 0xff };
+                let len = bytes
+                    .iter()
+                    .rposition(|&b| b != padding)
+                    .map_or(1, |i| i + 1);
+                self.output.push(if value > 0 {
+                    len as u8
+                } else {
+                    (len as i8).wrapping_neg() as u8
+                });
+                self.output.extend_from_slice(&bytes[..len]);
+            }
+        }
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.long(bytes.len() as i32);
+        self.output.extend_from_slice(bytes);
+    }
+
+    fn symbol(&mut self, name: &str) {
+        if let Some(index) = self.symbols.iter().position(|s| s == name) {
+            self.output.push(b';');
+            self.long(index as i32);
+        } else {
+            self.output.push(b':');
+            self.bytes(name.as_bytes());
+            self.symbols.push(name.to_string());
+        }
+    }
+
+    fn event_command(&mut self, code: i32, write_parameters: impl FnOnce(&mut Self)) {
+        self.output.push(b'o');
+        self.symbol("RPG::EventCommand");
+        self.long(3);
+        self.symbol("@code");
+        self.integer(code);
+        self.symbol("@indent");
+        self.integer(0);
+        self.symbol("@parameters");
+        write_parameters(self);
+    }
+
+    fn integer(&mut self, value: i32) {
+        self.output.push(b'i');
+        self.long(value);
+    }
+}
+
+/// A random value like the ones found in event command parameters.
+fn write_parameter(writer: &mut Writer, rng: &mut rand::rngs::StdRng, depth: u32) {
+    let string = |rng: &mut rand::rngs::StdRng| -> Vec<u8> {
+        let len = rng.gen_range(0..12);
+        match rng.gen_range(0..3) {
+            0 => (0..len).map(|_| rng.gen_range(b' '..=b'~')).collect(),
+            1 => "あいうえお漢字".repeat(len % 3).into_bytes(),
+            _ => (0..len).map(|_| rng.gen()).collect(),
+        }
+    };
+
+    let kinds = if depth == 0 { 9 } else { 7 };
+    match rng.gen_range(0..kinds) {
+        0 => writer.output.push(b'0'),
+        1 => writer.output.push(if rng.gen() { b'T' } else { b'F' }),
+        2 => writer.integer(rng.gen_range(-(1 << 30)..(1 << 30))),
+        3 => {
+            // quarters, so that every float is written the same way by Ruby and Rust
+            let value = f64::from(rng.gen_range(-4000..4000)) / 4.;
+            writer.output.push(b'f');
+            writer.bytes(value.to_string().as_bytes());
+        }
+        4 => {
+            let data = string(rng);
+            writer.output.push(b'"');
+            writer.bytes(&data);
+        }
+        5 => {
+            let data = string(rng);
+            writer.output.extend_from_slice(b"I\"");
+            writer.bytes(&data);
+            writer.long(1);
+            if rng.gen() {
+                writer.symbol("E");
+                writer.output.push(if rng.gen() { b'T' } else { b'F' });
+            } else {
+                writer.symbol("encoding");
+                writer.output.push(b'"');
+                writer.bytes(b"Shift_JIS");
+            }
+        }
+        6 => {
+            let name = if rng.gen() { "foo" } else { "bar" };
+            writer.symbol(name);
+        }
+        7 => {
+            let len = rng.gen_range(0..4);
+            writer.output.push(b'[');
+            writer.long(len);
+            for _ in 0..len {
+                write_parameter(writer, rng, depth + 1);
+            }
+        }
+        _ => {
+            let class = ["Color", "Tone"][rng.gen_range(0..2)];
+            writer.output.push(b'u');
+            writer.symbol(class);
+            writer.long(32);
+            for _ in 0..4 {
+                let channel = f64::from(rng.gen_range(-255..=255));
+                writer.output.extend_from_slice(&channel.to_le_bytes());
+            }
+        }
+    }
+}
+
+#[test]
+fn random_parameters_round_trip() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0x1d_ca7);
+    for case in 0..512 {
+        let mut writer = Writer::new();
+        let len = rng.gen_range(1..6);
+        writer.output.push(b'[');
+        writer.long(len);
+        for _ in 0..len {
+            let code = rng.gen_range(0..700);
+            writer.event_command(code, |writer| {
+                let len = rng.gen_range(0..5);
+                writer.output.push(b'[');
+                writer.long(len);
+                for _ in 0..len {
+                    write_parameter(writer, &mut rng, 0);
+                }
+            });
+        }
+
+        assert_round_trip(&format!("case {case}"), &writer.output);
+    }
+}
@@ -179,6 +179,51 @@ impl RenderState {
             renderer: Arc::new(RwLock::new(renderer)),
         })
     }
+
+    /// Creates a new `RenderState` that isn't tied to any surface, for rendering offscreen to
+    /// textures with the given format.
+    ///
+    /// # Errors
+    /// Wgpu initialization may fail due to incompatible hardware or driver for a given config.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn create_headless(
+        config: &WgpuConfiguration,
+        instance: &wgpu::Instance,
+        target_format: wgpu::TextureFormat,
+    ) -> Result<Self, WgpuError> {
+        crate::profile_scope!("RenderState::create_headless");
+
+        let available_adapters = instance.enumerate_adapters(wgpu::Backends::all());
+
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: config.power_preference,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await
+            .ok_or(WgpuError::NoSuitableAdapterFound)?;
+
+        log::debug!(
+            "Picked wgpu adapter: {}",
+            adapter_info_summary(&adapter.get_info())
+        );
+
+        let (device, queue) = adapter
+            .request_device(&(*config.device_descriptor)(&adapter), None)
+            .await?;
+
+        let renderer = Renderer::new(&device, target_format, None, 1);
+
+        Ok(Self {
+            adapter: Arc::new(adapter),
+            available_adapters: available_adapters.into(),
+            device: Arc::new(device),
+            queue: Arc::new(queue),
+            target_format,
+            renderer: Arc::new(RwLock::new(renderer)),
+        })
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -36,6 +36,8 @@ pub trait ErasedFilesystem: Send + Sync {
 
     fn read_dir(&self, path: &camino::Utf8Path) -> Result<Vec<DirEntry>>;
 
+    fn commit(&self) -> Result<()>;
+
     fn read(&self, path: &camino::Utf8Path) -> Result<Vec<u8>>;
 
     fn read_to_string(&self, path: &camino::Utf8Path) -> Result<String>;
@@ -85,6 +87,10 @@ where
         self.read_dir(path)
     }
 
+    fn commit(&self) -> Result<()> {
+        self.commit()
+    }
+
     fn read(&self, path: &camino::Utf8Path) -> Result<Vec<u8>> {
         self.read(path)
     }
@@ -151,6 +157,10 @@ impl crate::FileSystem for dyn ErasedFilesystem {
         self.read_dir(path.as_ref())
     }
 
+    fn commit(&self) -> Result<()> {
+        ErasedFilesystem::commit(self)
+    }
+
     fn remove(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
         self.remove(path.as_ref())
     }
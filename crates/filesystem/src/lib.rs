@@ -19,14 +19,19 @@ pub mod archiver;
 pub mod egui_bytes_loader;
 pub mod erased;
 pub mod list;
+pub mod memory;
 pub mod path_cache;
 pub mod project;
+pub mod redirect;
+pub mod zip;
 
 mod trie;
 pub use trie::*;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod native;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod watcher;
 #[cfg(target_arch = "wasm32")]
 pub mod web;
 
@@ -227,6 +232,12 @@ pub trait FileSystem: Send + Sync {
 
     fn read_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Vec<DirEntry>>;
 
+    /// Writes out the changes the filesystem is holding back. Only zip files do that, since every
+    /// change to one rewrites the whole zip file; other filesystems write every change right away.
+    fn commit(&self) -> Result<()> {
+        Ok(())
+    }
+
     /// Corresponds to [`std::fs::read()`].
     /// Will open a file at the path and read the entire file into a buffer.
     fn read(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Vec<u8>> {
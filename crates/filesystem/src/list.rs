@@ -155,6 +155,14 @@ impl crate::FileSystem for FileSystem {
         Ok(entries)
     }
 
+    fn commit(&self) -> Result<()> {
+        let c = "While committing the changes in a list filesystem";
+        for fs in self.filesystems.iter() {
+            fs.commit().wrap_err(c)?;
+        }
+        Ok(())
+    }
+
     fn read(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Vec<u8>> {
         let path = path.as_ref();
         let c = format!("While reading from the file {path:?} in a list filesystem");
@@ -0,0 +1,356 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+use color_eyre::eyre::WrapErr;
+use itertools::Itertools;
+use std::io::{
+    prelude::*,
+    ErrorKind::{InvalidInput, PermissionDenied},
+    SeekFrom,
+};
+use std::sync::Arc;
+
+use crate::{DirEntry, Error, Metadata, OpenFlags, Result, StdIoErrorExt};
+
+type Contents = Arc<parking_lot::RwLock<Vec<u8>>>;
+
+/// A filesystem that only exists in memory. Nothing written to it ever touches the disk, which
+/// makes it useful for testing code that works with projects.
+///
+/// Cloning it gives another handle to the same files.
+#[derive(Debug, Default, Clone)]
+pub struct FileSystem {
+    trie: Arc<parking_lot::RwLock<crate::FileSystemTrie<Contents>>>,
+}
+
+/// A file in a [`FileSystem`]. Writes are visible to the filesystem and every other open handle
+/// to the same file straight away; there's nothing to flush.
+#[derive(Debug)]
+pub struct File {
+    contents: Contents,
+    path: camino::Utf8PathBuf,
+    position: u64,
+    read_allowed: bool,
+    write_allowed: bool,
+}
+
+impl FileSystem {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns whether or not the parent directory of the given path exists.
+    fn parent_exists(trie: &crate::FileSystemTrie<Contents>, path: &camino::Utf8Path) -> bool {
+        trie.contains_dir(path.parent().unwrap_or(camino::Utf8Path::new("")))
+    }
+}
+
+impl crate::FileSystem for FileSystem {
+    type File = File;
+
+    fn open_file(
+        &self,
+        path: impl AsRef<camino::Utf8Path>,
+        flags: OpenFlags,
+    ) -> Result<Self::File> {
+        let path = path.as_ref();
+        let c = format!("While opening file {path:?} in a memory filesystem");
+
+        let existing = self.trie.read().get_file(path).cloned();
+        let contents = if let Some(contents) = existing {
+            contents
+        } else if flags.contains(OpenFlags::Create) {
+            let mut trie = self.trie.write();
+            if trie.contains_dir(path) || !Self::parent_exists(&trie, path) {
+                return Err(Error::NotExist).wrap_err_with(|| c.clone());
+            }
+            trie.get_or_create_file(path, Default::default()).clone()
+        } else {
+            return Err(Error::NotExist).wrap_err_with(|| c.clone());
+        };
+
+        if flags.contains(OpenFlags::Truncate) {
+            contents.write().clear();
+        }
+
+        Ok(File {
+            contents,
+            path: path.to_owned(),
+            position: 0,
+            read_allowed: flags.contains(OpenFlags::Read),
+            write_allowed: flags.contains(OpenFlags::Write),
+        })
+    }
+
+    fn metadata(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Metadata> {
+        let path = path.as_ref();
+        let trie = self.trie.read();
+        if let Some(contents) = trie.get_file(path) {
+            Ok(Metadata {
+                is_file: true,
+                size: contents.read().len() as u64,
+            })
+        } else if let Some(size) = trie.get_dir_size(path) {
+            Ok(Metadata {
+                is_file: false,
+                size: size as u64,
+            })
+        } else {
+            Err(Error::NotExist.into())
+        }
+    }
+
+    fn rename(
+        &self,
+        from: impl AsRef<camino::Utf8Path>,
+        to: impl AsRef<camino::Utf8Path>,
+    ) -> Result<()> {
+        let from = from.as_ref();
+        let to = to.as_ref();
+        let c = format!("While renaming {from:?} to {to:?} in a memory filesystem");
+        let mut trie = self.trie.write();
+
+        if trie.contains(to) {
+            return Err(Error::IoError(std::io::ErrorKind::AlreadyExists.into()))
+                .wrap_err_with(|| c.clone());
+        }
+        if !Self::parent_exists(&trie, to) {
+            return Err(Error::NotExist).wrap_err_with(|| c.clone());
+        }
+
+        if let Some(contents) = trie.remove_file(from) {
+            trie.create_file(to, contents);
+        } else if trie.contains_dir(from) {
+            let files = trie
+                .iter_prefix(from)
+                .into_iter()
+                .flatten()
+                .map(|(path, contents)| {
+                    let path = path.strip_prefix(from).unwrap_or(&path).to_owned();
+                    (path, contents.clone())
+                })
+                .collect_vec();
+            trie.remove_dir(from);
+            trie.create_dir(to);
+            for (path, contents) in files {
+                trie.create_file(to.join(path), contents);
+            }
+        } else {
+            return Err(Error::NotExist).wrap_err_with(|| c.clone());
+        }
+
+        Ok(())
+    }
+
+    fn exists(&self, path: impl AsRef<camino::Utf8Path>) -> Result<bool> {
+        Ok(self.trie.read().contains(path))
+    }
+
+    fn create_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut trie = self.trie.write();
+        if trie.contains_file(path) {
+            return Err(Error::IoError(std::io::ErrorKind::AlreadyExists.into())).wrap_err_with(
+                || format!("While creating directory {path:?} in a memory filesystem"),
+            );
+        }
+        trie.create_dir(path);
+        Ok(())
+    }
+
+    fn remove_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        let path = path.as_ref();
+        if !self.trie.write().remove_dir(path) {
+            return Err(Error::NotExist).wrap_err_with(|| {
+                format!("While removing directory {path:?} in a memory filesystem")
+            });
+        }
+        Ok(())
+    }
+
+    fn remove_file(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        let path = path.as_ref();
+        if self.trie.write().remove_file(path).is_none() {
+            return Err(Error::NotExist)
+                .wrap_err_with(|| format!("While removing file {path:?} in a memory filesystem"));
+        }
+        Ok(())
+    }
+
+    fn read_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Vec<DirEntry>> {
+        let path = path.as_ref();
+        let trie = self.trie.read();
+
+        let Some(iter) = trie.iter_dir(path) else {
+            // The root directory always exists, even when there's nothing in it
+            if path.as_str().is_empty() {
+                return Ok(Vec::new());
+            }
+            return Err(Error::NotExist).wrap_err_with(|| {
+                format!(
+                    "While reading the contents of the directory {path:?} in a memory filesystem"
+                )
+            });
+        };
+        Ok(iter
+            .map(|(name, contents)| {
+                let path = path.join(name);
+                let metadata = match contents {
+                    Some(contents) => Metadata {
+                        is_file: true,
+                        size: contents.read().len() as u64,
+                    },
+                    None => Metadata {
+                        is_file: false,
+                        size: trie.get_dir_size(&path).unwrap_or_default() as u64,
+                    },
+                };
+                DirEntry::new(path, metadata)
+            })
+            .collect())
+    }
+}
+
+impl File {
+    fn no_permission<R>(&self, action: &str) -> std::io::Result<R> {
+        Err(std::io::Error::new(
+            PermissionDenied,
+            format!("Attempted to {action} file with no {action} permissions"),
+        ))
+        .wrap_io_err_with(|| {
+            format!(
+                "While accessing file {:?} in a memory filesystem",
+                self.path
+            )
+        })
+    }
+}
+
+impl std::io::Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if !self.read_allowed {
+            return self.no_permission("read");
+        }
+        let contents = self.contents.read();
+        let start = (self.position as usize).min(contents.len());
+        let len = buf.len().min(contents.len() - start);
+        buf[..len].copy_from_slice(&contents[start..start + len]);
+        drop(contents);
+        self.position += len as u64;
+        Ok(len)
+    }
+}
+
+impl std::io::Write for File {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if !self.write_allowed {
+            return self.no_permission("write");
+        }
+        let mut contents = self.contents.write();
+        let start = self.position as usize;
+        let end = start + buf.len();
+        if contents.len() < end {
+            contents.resize(end, 0);
+        }
+        contents[start..end].copy_from_slice(buf);
+        drop(contents);
+        self.position = end as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl std::io::Seek for File {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let new_position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => (self.contents.read().len() as u64).checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        self.position = new_position.ok_or_else(|| {
+            std::io::Error::new(
+                InvalidInput,
+                "Attempted to seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
+
+impl futures_lite::AsyncRead for File {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+        buf: &mut [u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        std::task::Poll::Ready(self.get_mut().read(buf))
+    }
+}
+
+impl futures_lite::AsyncWrite for File {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        std::task::Poll::Ready(self.get_mut().write(buf))
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+impl futures_lite::AsyncSeek for File {
+    fn poll_seek(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+        pos: SeekFrom,
+    ) -> std::task::Poll<std::io::Result<u64>> {
+        std::task::Poll::Ready(self.get_mut().seek(pos))
+    }
+}
+
+impl crate::File for File {
+    fn metadata(&self) -> std::io::Result<Metadata> {
+        Ok(Metadata {
+            is_file: true,
+            size: self.contents.read().len() as u64,
+        })
+    }
+
+    fn set_len(&self, new_size: u64) -> std::io::Result<()> {
+        if !self.write_allowed {
+            return self.no_permission("write");
+        }
+        self.contents.write().resize(new_size as usize, 0);
+        Ok(())
+    }
+}
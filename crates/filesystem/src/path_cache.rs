@@ -178,6 +178,23 @@ impl Cache {
         Ok(())
     }
 
+    /// Removes a path and everything inside of it from the cache. The path can be given in any
+    /// letter casing.
+    fn remove(&mut self, path: impl AsRef<camino::Utf8Path>) {
+        let mut path = to_lowercase(path);
+        path.set_extension("");
+
+        let Some(iter) = self.trie.iter_prefix(&path) else {
+            return;
+        };
+        for extension_trie in iter.map(|(_, t)| t) {
+            for index in extension_trie.values().copied() {
+                self.cactus.remove(index);
+            }
+        }
+        self.trie.remove_dir(&path);
+    }
+
     /// Gets the case-sensitive version of the given case-insensitive path from the cache.
     /// The path has to already exist in the cache; you need to use `.regen` to insert paths into
     /// the cache before this can get them.
@@ -213,6 +230,12 @@ where
         Ok(this)
     }
 
+    /// Removes a path from the cache so that it's looked up again the next time it's used. This
+    /// is needed when the path was created, changed or removed by another program.
+    pub fn invalidate(&self, path: impl AsRef<camino::Utf8Path>) {
+        self.cache.write().remove(path);
+    }
+
     pub fn fs(&self) -> &F {
         &self.fs
     }
@@ -298,14 +321,14 @@ where
         cache.regen(&self.fs, path).wrap_err_with(|| c.clone())?;
 
         if flags.contains(OpenFlags::Create) && cache.desensitize(path).is_none() {
+            let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) else {
+                return Err(Error::NotExist).wrap_err_with(|| c.clone());
+            };
             let path = cache
-                .desensitize(
-                    path.parent()
-                        .ok_or(Error::NotExist)
-                        .wrap_err_with(|| c.clone())?,
-                )
+                .desensitize(parent)
                 .ok_or(Error::NotExist)
-                .wrap_err_with(|| c.clone())?;
+                .wrap_err_with(|| c.clone())?
+                .join(file_name);
             let file = self
                 .fs
                 .open_file(&path, flags)
@@ -359,15 +382,7 @@ where
 
         self.fs.rename(&from, to).wrap_err_with(|| c.clone())?;
 
-        {
-            let cache = &mut *cache;
-            for extension_trie in cache.trie.iter_prefix(&from).unwrap().map(|(_, t)| t) {
-                for index in extension_trie.values().copied() {
-                    cache.cactus.remove(index);
-                }
-            }
-            cache.trie.remove_dir(&from);
-        }
+        cache.remove(&from);
 
         Ok(())
     }
@@ -428,15 +443,7 @@ where
 
         self.fs.remove_dir(&path).wrap_err_with(|| c.clone())?;
 
-        {
-            let cache = &mut *cache;
-            for extension_trie in cache.trie.iter_prefix(&path).unwrap().map(|(_, t)| t) {
-                for index in extension_trie.values().copied() {
-                    cache.cactus.remove(index);
-                }
-            }
-            cache.trie.remove_dir(&path);
-        }
+        cache.remove(&path);
 
         Ok(())
     }
@@ -453,15 +460,7 @@ where
 
         self.fs.remove_file(&path).wrap_err_with(|| c.clone())?;
 
-        {
-            let cache = &mut *cache;
-            for extension_trie in cache.trie.iter_prefix(&path).unwrap().map(|(_, t)| t) {
-                for index in extension_trie.values().copied() {
-                    cache.cactus.remove(index);
-                }
-            }
-            cache.trie.remove_dir(&path);
-        }
+        cache.remove(&path);
 
         Ok(())
     }
@@ -477,4 +476,8 @@ where
             .wrap_err_with(|| c.clone())?;
         self.fs.read_dir(path).wrap_err_with(|| c.clone())
     }
+
+    fn commit(&self) -> Result<()> {
+        self.fs.commit()
+    }
 }
@@ -23,6 +23,10 @@ use crate::FileSystem as _;
 use crate::{archiver, host, list, path_cache};
 use crate::{DirEntry, Error, Metadata, OpenFlags, Result};
 
+/// The directories of a project with its backups and autosaved changes. These only matter on the
+/// computer the project is on, so they are left out of zip files.
+pub const LOCAL_DIRS: [&str; 2] = [".luminol/backups", ".luminol/recovery"];
+
 #[derive(Default)]
 pub enum FileSystem {
     #[default]
@@ -30,8 +34,15 @@ pub enum FileSystem {
     HostLoaded(host::FileSystem),
     Loaded {
         filesystem: path_cache::FileSystem<list::FileSystem>,
-        host_filesystem: host::FileSystem,
+        /// The project folder, or `None` if the project is in a zip file.
+        host_filesystem: Option<host::FileSystem>,
+        /// For projects in zip files, the folder next to the zip file that [`LOCAL_DIRS`] are
+        /// kept in instead.
+        local_filesystem: Option<host::FileSystem>,
         project_path: camino::Utf8PathBuf,
+        /// Watches the project and RTP folders for changes made by other programs.
+        #[cfg(not(target_arch = "wasm32"))]
+        watcher: Option<crate::watcher::Watcher>,
     },
 }
 
@@ -62,27 +73,52 @@ impl FileSystem {
         !matches!(self, FileSystem::Unloaded)
     }
 
+    /// Whether the loaded project is stored in a zip file instead of a folder.
+    pub fn is_zip_project(&self) -> bool {
+        matches!(self, FileSystem::Loaded { project_path, .. } if is_zip_file(project_path))
+    }
+
     pub fn unload_project(&mut self) {
         *self = FileSystem::Unloaded;
     }
+
+    /// Returns the local folder of a zip project and the path in it, if `path` is in one of the
+    /// [`LOCAL_DIRS`]. Every change to a zip file rewrites all of it, which would be a lot of work
+    /// for backups and autosaves.
+    fn local_path<'a>(
+        &self,
+        path: &'a camino::Utf8Path,
+    ) -> Option<(&host::FileSystem, &'a camino::Utf8Path)> {
+        let FileSystem::Loaded {
+            local_filesystem: Some(local),
+            ..
+        } = self
+        else {
+            return None;
+        };
+        if !LOCAL_DIRS.iter().any(|dir| path.starts_with(dir)) {
+            return None;
+        }
+        Some((local, path.strip_prefix(".luminol").ok()?))
+    }
 }
 
 // Not platform specific
 impl FileSystem {
-    fn detect_rm_ver(&self) -> Option<luminol_config::RMVer> {
-        if self.exists("Data/Actors.rxdata").ok()? {
+    fn detect_rm_ver(filesystem: &impl crate::FileSystem) -> Option<luminol_config::RMVer> {
+        if filesystem.exists("Data/Actors.rxdata").ok()? {
             return Some(luminol_config::RMVer::XP);
         }
 
-        if self.exists("Data/Actors.rvdata").ok()? {
+        if filesystem.exists("Data/Actors.rvdata").ok()? {
             return Some(luminol_config::RMVer::VX);
         }
 
-        if self.exists("Data/Actors.rvdata2").ok()? {
+        if filesystem.exists("Data/Actors.rvdata2").ok()? {
             return Some(luminol_config::RMVer::Ace);
         }
 
-        for path in self.read_dir("").ok()? {
+        for path in filesystem.read_dir("").ok()? {
             let path = path.path();
             if path.extension() == Some("rgssad") {
                 return Some(luminol_config::RMVer::XP);
@@ -100,11 +136,13 @@ impl FileSystem {
         None
     }
 
-    fn load_project_config(&self) -> Result<luminol_config::project::Config> {
+    fn load_project_config(
+        filesystem: &impl crate::FileSystem,
+    ) -> Result<luminol_config::project::Config> {
         let c = "While loading project configuration";
-        self.create_dir(".luminol").wrap_err(c)?;
+        filesystem.create_dir(".luminol").wrap_err(c)?;
 
-        let project = match self
+        let project = match filesystem
             .read_to_string(".luminol/config")
             .ok()
             .and_then(|s| ron::from_str::<luminol_config::project::Project>(&s).ok())
@@ -114,42 +152,48 @@ impl FileSystem {
                 while config.persistence_id == 0 {
                     config.persistence_id = rand::random();
                 }
-                self.write(".luminol/config", ron::to_string(&config).wrap_err(c)?)
+                filesystem
+                    .write(".luminol/config", ron::to_string(&config).wrap_err(c)?)
                     .wrap_err(c)?;
                 config
             }
             None => {
-                let Some(editor_ver) = self.detect_rm_ver() else {
+                let Some(editor_ver) = Self::detect_rm_ver(filesystem) else {
                     return Err(Error::UnableToDetectRMVer).wrap_err(c);
                 };
                 let config = luminol_config::project::Project {
                     editor_ver,
                     ..Default::default()
                 };
-                self.write(".luminol/config", ron::to_string(&config).wrap_err(c)?)
+                filesystem
+                    .write(".luminol/config", ron::to_string(&config).wrap_err(c)?)
                     .wrap_err(c)?;
                 config
             }
         };
 
-        let command_db = match self
+        let command_db = match filesystem
             .read_to_string(".luminol/commands")
             .ok()
-            .and_then(|s| ron::from_str(&s).ok())
+            .and_then(|s| ron::from_str::<luminol_config::command_db::CommandDB>(&s).ok())
         {
-            Some(c) => c,
+            Some(mut command_db) => {
+                command_db.restore_missing_defaults(project.editor_ver);
+                command_db
+            }
             None => {
                 let command_db = luminol_config::command_db::CommandDB::new(project.editor_ver);
-                self.write(
-                    ".luminol/commands",
-                    ron::to_string(&command_db).wrap_err(c)?,
-                )
-                .wrap_err(c)?;
+                filesystem
+                    .write(
+                        ".luminol/commands",
+                        ron::to_string(&command_db).wrap_err(c)?,
+                    )
+                    .wrap_err(c)?;
                 command_db
             }
         };
 
-        let game_ini = match self
+        let game_ini = match filesystem
             .read_to_string("Game.ini")
             .ok()
             .and_then(|i| ini::Ini::load_from_str_noescape(&i).ok())
@@ -206,7 +250,7 @@ impl FileSystem {
         let c = "While loading project data";
 
         *self = FileSystem::HostLoaded(host);
-        let config = self.load_project_config().wrap_err(c)?;
+        let config = Self::load_project_config(&*self).wrap_err(c)?;
 
         let Self::HostLoaded(host) = std::mem::take(self) else {
             return Err(std::io::Error::new(
@@ -225,13 +269,29 @@ impl FileSystem {
         Ok(result)
     }
 
+    /// Tells the watcher that Luminol itself is about to change a file, so that it isn't reported
+    /// as changed by another program.
+    fn ignore_own_change(&self, path: &camino::Utf8Path) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let FileSystem::Loaded {
+            watcher: Some(watcher),
+            ..
+        } = self
+        {
+            watcher.ignore(path);
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = path;
+    }
+
+    /// The folder the project is in on the host filesystem. Projects in zip files don't have one.
     pub fn host(&self) -> Option<host::FileSystem> {
         match self {
             FileSystem::Unloaded => None,
             FileSystem::HostLoaded(host) => Some(host.clone()),
             FileSystem::Loaded {
                 host_filesystem, ..
-            } => Some(host_filesystem.clone()),
+            } => host_filesystem.clone(),
         }
     }
 }
@@ -302,6 +362,15 @@ impl FileSystem {
                     }
                 }
 
+                let path = filesystem
+                    .root_path()
+                    .join("RTP")
+                    .join(format!("{rtp}.zip"));
+                if path.is_file() {
+                    paths.push(path);
+                    continue;
+                }
+
                 if let Some(path) = global_config.rtp_paths.get(rtp) {
                     let path = camino::Utf8PathBuf::from(path);
                     if path.exists() {
@@ -355,6 +424,15 @@ impl FileSystem {
                     }
                 }
 
+                let path = filesystem
+                    .root_path()
+                    .join("RTP")
+                    .join(format!("{rtp}.zip"));
+                if path.is_file() {
+                    paths.push(path);
+                    continue;
+                }
+
                 missing_rtps.push(rtp.to_string());
             }
         }
@@ -365,16 +443,102 @@ impl FileSystem {
 // Specific to native
 #[cfg(not(target_arch = "wasm32"))]
 impl FileSystem {
+    /// Returns the paths that were changed by other programs since the last call, relative to the
+    /// project or RTP folder they are in, and makes sure the path cache doesn't return stale
+    /// results for them.
+    pub fn take_changed_paths(&self, ctx: &egui::Context) -> Vec<camino::Utf8PathBuf> {
+        let FileSystem::Loaded {
+            filesystem,
+            watcher: Some(watcher),
+            ..
+        } = self
+        else {
+            return Vec::new();
+        };
+
+        let paths = watcher.take_changed_paths(ctx);
+        for path in paths.iter() {
+            filesystem.invalidate(path);
+        }
+        paths
+    }
+
     pub fn load_project_from_path(
         &mut self,
         project_config: &mut Option<luminol_config::project::Config>,
         global_config: &mut luminol_config::global::Config,
         project_path: impl AsRef<camino::Utf8Path>,
     ) -> Result<LoadResult> {
+        let project_path = project_path.as_ref();
+        if is_zip_file(project_path) {
+            return self.load_project_from_zip(project_config, global_config, project_path);
+        }
         let host = host::FileSystem::new(project_path);
         self.load_project(host, project_config, global_config)
     }
 
+    /// Opens a project stored in a zip file without extracting it. Changes to the project are
+    /// written back to the zip file, except for backups and autosaved changes, which go in a
+    /// folder next to it named after the zip file (`Project.luminol` for `Project.zip`).
+    pub fn load_project_from_zip(
+        &mut self,
+        project_config: &mut Option<luminol_config::project::Config>,
+        global_config: &mut luminol_config::global::Config,
+        zip_path: impl AsRef<camino::Utf8Path>,
+    ) -> Result<LoadResult> {
+        let zip_path = zip_path.as_ref();
+        let c = format!("While loading project data from the zip file {zip_path:?}");
+
+        let zip =
+            open_zip(zip_path, OpenFlags::Read | OpenFlags::Write).wrap_err_with(|| c.clone())?;
+        let config = Self::load_project_config(&zip).wrap_err_with(|| c.clone())?;
+        // The config might have just been created
+        zip.commit().wrap_err_with(|| c.clone())?;
+
+        // RTPs are looked for next to the zip file
+        let parent = zip_path.parent().unwrap_or(camino::Utf8Path::new(""));
+        let host = host::FileSystem::new(parent);
+        let local = host::FileSystem::new(parent.join(format!(
+            "{}.luminol",
+            zip_path.file_stem().unwrap_or_default()
+        )));
+        let (found_rtps, missing_rtps) = Self::find_rtp_paths(&host, &config, global_config);
+
+        let mut list = list::FileSystem::new();
+        list.push(zip);
+        Self::push_rtps(&mut list, &found_rtps).wrap_err_with(|| c.clone())?;
+
+        let path_cache = path_cache::FileSystem::new(list).wrap_err_with(|| c.clone())?;
+
+        *self = FileSystem::Loaded {
+            filesystem: path_cache,
+            host_filesystem: None,
+            local_filesystem: Some(local),
+            project_path: zip_path.to_path_buf(),
+            watcher: Self::watch(found_rtps),
+        };
+
+        Self::add_recent_project(global_config, zip_path);
+        *project_config = Some(config);
+
+        Ok(LoadResult { missing_rtps })
+    }
+
+    /// Asks the user to pick a zip file containing a project, and returns its path.
+    pub async fn pick_zip_project() -> Result<camino::Utf8PathBuf> {
+        let c = "While picking a zip file from the host filesystem";
+        let Some(path) = rfd::AsyncFileDialog::default()
+            .add_filter("zipped project", &["zip"])
+            .pick_file()
+            .await
+        else {
+            return Err(Error::CancelledLoading).wrap_err(c);
+        };
+        camino::Utf8PathBuf::from_path_buf(path.path().to_path_buf())
+            .map_err(|_| Error::PathUtf8Error)
+            .wrap_err(c)
+    }
+
     pub fn load_partially_loaded_project(
         &mut self,
         host: host::FileSystem,
@@ -403,9 +567,7 @@ impl FileSystem {
 
         list.push(host);
 
-        for path in found_rtps {
-            list.push(host::FileSystem::new(path))
-        }
+        Self::push_rtps(&mut list, &found_rtps)?;
         if let Some(archive) = archive {
             list.push(archive);
         }
@@ -414,8 +576,10 @@ impl FileSystem {
 
         *self = FileSystem::Loaded {
             filesystem: path_cache,
-            host_filesystem: host_clone,
+            host_filesystem: Some(host_clone),
+            local_filesystem: None,
             project_path: project_path.to_path_buf(),
+            watcher: Self::watch(std::iter::once(project_path.clone()).chain(found_rtps)),
         };
 
         // FIXME: handle
@@ -424,19 +588,66 @@ impl FileSystem {
         //     return Err(e);
         // }
 
+        Self::add_recent_project(global_config, &project_path);
+
+        Ok(LoadResult { missing_rtps })
+    }
+
+    /// Adds the RTPs to a list filesystem. RTPs can be folders or zip files.
+    fn push_rtps(list: &mut list::FileSystem, rtp_paths: &[camino::Utf8PathBuf]) -> Result<()> {
+        for path in rtp_paths {
+            if is_zip_file(path) {
+                list.push(open_zip(path, OpenFlags::Read)?);
+            } else {
+                list.push(host::FileSystem::new(path));
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts watching the given folders for changes made by other programs. Zip files aren't
+    /// watched.
+    fn watch(
+        paths: impl IntoIterator<Item = camino::Utf8PathBuf>,
+    ) -> Option<crate::watcher::Watcher> {
+        // Not being able to watch the project isn't fatal, it only means that changes made by
+        // other programs aren't picked up until the project is reopened
+        crate::watcher::Watcher::new(paths.into_iter().filter(|path| !is_zip_file(path)))
+            .map_err(|e| tracing::warn!("Unable to watch the project for changes: {e:?}"))
+            .ok()
+    }
+
+    fn add_recent_project(
+        global_config: &mut luminol_config::global::Config,
+        project_path: &camino::Utf8Path,
+    ) {
         let mut projects: std::collections::VecDeque<_> = global_config
             .recent_projects
             .iter()
             .filter(|p| p.as_str() != project_path)
             .cloned()
             .collect();
-        projects.push_front(project_path.into_string());
+        projects.push_front(project_path.to_string());
         global_config.recent_projects = projects;
-
-        Ok(LoadResult { missing_rtps })
     }
 }
 
+fn is_zip_file(path: &camino::Utf8Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("zip"))
+        && path.is_file()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn open_zip(
+    path: &camino::Utf8Path,
+    flags: OpenFlags,
+) -> Result<crate::zip::FileSystem<host::File>> {
+    let host = host::FileSystem::new(path.parent().unwrap_or(camino::Utf8Path::new("")));
+    let file = host.open_file(path.file_name().ok_or(Error::NotExist)?, flags)?;
+    crate::zip::FileSystem::new(file)
+}
+
 // Specific to web
 #[cfg(target_arch = "wasm32")]
 impl FileSystem {
@@ -528,7 +739,8 @@ impl FileSystem {
 
         *self = Self::Loaded {
             filesystem: path_cache,
-            host_filesystem: host.clone(),
+            host_filesystem: Some(host.clone()),
+            local_filesystem: None,
             project_path: root_path.clone(),
         };
 
@@ -633,6 +845,12 @@ impl crate::FileSystem for FileSystem {
         path: impl AsRef<camino::Utf8Path>,
         flags: OpenFlags,
     ) -> Result<Self::File> {
+        if flags.intersects(OpenFlags::Write | OpenFlags::Create | OpenFlags::Truncate) {
+            self.ignore_own_change(path.as_ref());
+        }
+        if let Some((local, path)) = self.local_path(path.as_ref()) {
+            return local.open_file(path, flags).map(File::Host);
+        }
         match self {
             FileSystem::Unloaded => Err(Error::NotLoaded.into()),
             FileSystem::HostLoaded(f) => f.open_file(path, flags).map(File::Host),
@@ -641,6 +859,9 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn metadata(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Metadata> {
+        if let Some((local, path)) = self.local_path(path.as_ref()) {
+            return local.metadata(path);
+        }
         match self {
             FileSystem::Unloaded => Err(Error::NotLoaded.into()),
             FileSystem::HostLoaded(f) => f.metadata(path),
@@ -653,6 +874,13 @@ impl crate::FileSystem for FileSystem {
         from: impl AsRef<camino::Utf8Path>,
         to: impl AsRef<camino::Utf8Path>,
     ) -> Result<()> {
+        self.ignore_own_change(from.as_ref());
+        self.ignore_own_change(to.as_ref());
+        if let (Some((local, from)), Some((_, to))) =
+            (self.local_path(from.as_ref()), self.local_path(to.as_ref()))
+        {
+            return local.rename(from, to);
+        }
         match self {
             FileSystem::Unloaded => Err(Error::NotLoaded.into()),
             FileSystem::HostLoaded(f) => f.rename(from, to),
@@ -661,6 +889,9 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn exists(&self, path: impl AsRef<camino::Utf8Path>) -> Result<bool> {
+        if let Some((local, path)) = self.local_path(path.as_ref()) {
+            return local.exists(path);
+        }
         match self {
             FileSystem::Unloaded => Err(Error::NotLoaded.into()),
             FileSystem::HostLoaded(f) => f.exists(path),
@@ -669,6 +900,9 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn create_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        if let Some((local, path)) = self.local_path(path.as_ref()) {
+            return local.create_dir(path);
+        }
         match self {
             FileSystem::Unloaded => Err(Error::NotLoaded.into()),
             FileSystem::HostLoaded(f) => f.create_dir(path),
@@ -677,6 +911,10 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn remove_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        self.ignore_own_change(path.as_ref());
+        if let Some((local, path)) = self.local_path(path.as_ref()) {
+            return local.remove_dir(path);
+        }
         match self {
             FileSystem::Unloaded => Err(Error::NotLoaded.into()),
             FileSystem::HostLoaded(f) => f.remove_dir(path),
@@ -685,6 +923,10 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn remove_file(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        self.ignore_own_change(path.as_ref());
+        if let Some((local, path)) = self.local_path(path.as_ref()) {
+            return local.remove_file(path);
+        }
         match self {
             FileSystem::Unloaded => Err(Error::NotLoaded.into()),
             FileSystem::HostLoaded(f) => f.remove_file(path),
@@ -693,10 +935,31 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn read_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Vec<DirEntry>> {
+        if let Some((local, path)) = self.local_path(path.as_ref()) {
+            // The entries have to have paths in the project, not in the local folder
+            return Ok(local
+                .read_dir(path)?
+                .into_iter()
+                .map(|entry| {
+                    DirEntry::new(
+                        camino::Utf8Path::new(".luminol").join(entry.path),
+                        entry.metadata,
+                    )
+                })
+                .collect());
+        }
         match self {
             FileSystem::Unloaded => Err(Error::NotLoaded.into()),
             FileSystem::HostLoaded(f) => f.read_dir(path),
             FileSystem::Loaded { filesystem, .. } => filesystem.read_dir(path),
         }
     }
+
+    fn commit(&self) -> Result<()> {
+        match self {
+            FileSystem::Unloaded => Err(Error::NotLoaded.into()),
+            FileSystem::HostLoaded(f) => f.commit(),
+            FileSystem::Loaded { filesystem, .. } => filesystem.commit(),
+        }
+    }
 }
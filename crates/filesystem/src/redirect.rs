@@ -0,0 +1,129 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{DirEntry, Metadata, OpenFlags, Result};
+
+/// A filesystem that sends everything written to it into a directory of another filesystem.
+/// Reads prefer the files in that directory and fall back to the other filesystem, so it looks
+/// like the other filesystem with the writes applied on top.
+///
+/// This is used to keep copies of project data somewhere else, like in `.luminol/recovery`,
+/// with the same code that writes it to the project.
+#[derive(Debug, Clone, Copy)]
+pub struct FileSystem<'fs, F> {
+    fs: &'fs F,
+    dir: &'fs camino::Utf8Path,
+}
+
+impl<'fs, F> FileSystem<'fs, F> {
+    pub fn new(fs: &'fs F, dir: &'fs camino::Utf8Path) -> Self {
+        Self { fs, dir }
+    }
+
+    fn redirected(&self, path: impl AsRef<camino::Utf8Path>) -> camino::Utf8PathBuf {
+        self.dir.join(path)
+    }
+}
+
+impl<F> crate::FileSystem for FileSystem<'_, F>
+where
+    F: crate::FileSystem,
+{
+    type File = F::File;
+
+    fn open_file(
+        &self,
+        path: impl AsRef<camino::Utf8Path>,
+        flags: OpenFlags,
+    ) -> Result<Self::File> {
+        let redirected = self.redirected(&path);
+        if flags.intersects(OpenFlags::Write | OpenFlags::Create | OpenFlags::Truncate)
+            || self.fs.exists(&redirected)?
+        {
+            self.fs.open_file(redirected, flags)
+        } else {
+            self.fs.open_file(path, flags)
+        }
+    }
+
+    fn metadata(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Metadata> {
+        let redirected = self.redirected(&path);
+        if self.fs.exists(&redirected)? {
+            self.fs.metadata(redirected)
+        } else {
+            self.fs.metadata(path)
+        }
+    }
+
+    fn rename(
+        &self,
+        from: impl AsRef<camino::Utf8Path>,
+        to: impl AsRef<camino::Utf8Path>,
+    ) -> Result<()> {
+        self.fs.rename(self.redirected(from), self.redirected(to))
+    }
+
+    fn exists(&self, path: impl AsRef<camino::Utf8Path>) -> Result<bool> {
+        Ok(self.fs.exists(self.redirected(&path))? || self.fs.exists(path)?)
+    }
+
+    fn create_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        self.fs.create_dir(self.redirected(path))
+    }
+
+    fn remove_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        let redirected = self.redirected(path);
+        if self.fs.exists(&redirected)? {
+            self.fs.remove_dir(redirected)?;
+        }
+        Ok(())
+    }
+
+    fn remove_file(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        let redirected = self.redirected(path);
+        if self.fs.exists(&redirected)? {
+            self.fs.remove_file(redirected)?;
+        }
+        Ok(())
+    }
+
+    /// Lists the redirected directory if it exists, otherwise the one in the other filesystem.
+    /// The contents of the two are never merged.
+    fn read_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Vec<DirEntry>> {
+        let redirected = self.redirected(&path);
+        if !self.fs.exists(&redirected)? {
+            return self.fs.read_dir(path);
+        }
+
+        Ok(self
+            .fs
+            .read_dir(redirected)?
+            .into_iter()
+            .map(|entry| {
+                let path = match entry.path.strip_prefix(self.dir) {
+                    Ok(path) => path.to_owned(),
+                    Err(_) => entry.path,
+                };
+                DirEntry::new(path, entry.metadata)
+            })
+            .collect())
+    }
+
+    fn commit(&self) -> Result<()> {
+        self.fs.commit()
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::time::{Duration, Instant};
+
+use crate::path_cache::to_lowercase;
+
+/// How long after Luminol writes to a file changes to it are assumed to be our own.
+const OWN_CHANGE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Watches the folders of a project for changes made by other programs, like an image editor
+/// saving a graphic that the project uses.
+pub struct Watcher {
+    _watcher: notify::RecommendedWatcher,
+    shared: std::sync::Arc<parking_lot::Mutex<Shared>>,
+}
+
+#[derive(Default)]
+struct Shared {
+    /// Changed paths that haven't been taken yet, relative to the root they are in.
+    changed: Vec<camino::Utf8PathBuf>,
+    /// Lowercased paths that were recently written to by Luminol, and when.
+    own_changes: std::collections::HashMap<camino::Utf8PathBuf, Instant>,
+    /// The context to wake up when something changes while the editor is idle.
+    ctx: Option<egui::Context>,
+}
+
+impl Watcher {
+    /// Starts watching the given folders and everything in them.
+    pub fn new(roots: impl IntoIterator<Item = camino::Utf8PathBuf>) -> crate::Result<Self> {
+        // The paths in events start with the canonical path of the folder being watched
+        let roots = roots
+            .into_iter()
+            .map(|root| root.canonicalize_utf8())
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        let shared = std::sync::Arc::new(parking_lot::Mutex::new(Shared::default()));
+        let handler_shared = shared.clone();
+        let handler_roots = roots.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(e) => {
+                        tracing::warn!("Error watching project files: {e}");
+                        return;
+                    }
+                };
+                if matches!(event.kind, notify::EventKind::Access(_)) {
+                    return;
+                }
+
+                let mut shared = handler_shared.lock();
+                let now = Instant::now();
+                shared
+                    .own_changes
+                    .retain(|_, time| now.duration_since(*time) < OWN_CHANGE_TIMEOUT);
+
+                let mut any_changed = false;
+                for path in event.paths {
+                    let Ok(path) = camino::Utf8PathBuf::try_from(path) else {
+                        continue;
+                    };
+                    let Some(path) = handler_roots
+                        .iter()
+                        .find_map(|root| path.strip_prefix(root).ok())
+                    else {
+                        continue;
+                    };
+                    // Luminol's own files are never edited by other programs
+                    if path.starts_with(".luminol")
+                        || shared.own_changes.contains_key(&to_lowercase(path))
+                        || shared.changed.iter().any(|changed| changed == path)
+                    {
+                        continue;
+                    }
+                    shared.changed.push(path.to_path_buf());
+                    any_changed = true;
+                }
+
+                if any_changed {
+                    if let Some(ctx) = &shared.ctx {
+                        ctx.request_repaint();
+                    }
+                }
+            })?;
+
+        for root in roots.iter() {
+            notify::Watcher::watch(
+                &mut watcher,
+                root.as_std_path(),
+                notify::RecursiveMode::Recursive,
+            )?;
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            shared,
+        })
+    }
+
+    /// Marks a path as about to be changed by Luminol itself, so that it isn't reported as changed.
+    pub fn ignore(&self, path: impl AsRef<camino::Utf8Path>) {
+        self.shared
+            .lock()
+            .own_changes
+            .insert(to_lowercase(path), Instant::now());
+    }
+
+    /// Takes the paths that were changed since the last call, relative to the folder they are in.
+    ///
+    /// `ctx` is remembered so that changes made while the editor is idle wake it up.
+    pub fn take_changed_paths(&self, ctx: &egui::Context) -> Vec<camino::Utf8PathBuf> {
+        let mut shared = self.shared.lock();
+        if shared.ctx.is_none() {
+            shared.ctx = Some(ctx.clone());
+        }
+        std::mem::take(&mut shared.changed)
+    }
+}
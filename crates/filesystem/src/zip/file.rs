@@ -0,0 +1,119 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::io::{prelude::*, ErrorKind::PermissionDenied};
+
+use super::{Entry, FileSystem};
+use crate::{Metadata, StdIoErrorExt};
+
+/// A file in a zip filesystem. Its decompressed contents are kept in memory, and handed back to
+/// the filesystem when it's flushed, to be written to the zip file on the next commit.
+pub struct File<T> {
+    pub(super) filesystem: Option<FileSystem<T>>,
+    pub(super) path: camino::Utf8PathBuf,
+    pub(super) read_allowed: bool,
+    pub(super) contents: parking_lot::Mutex<std::io::Cursor<Vec<u8>>>,
+    pub(super) modified: parking_lot::Mutex<bool>,
+}
+
+fn no_write_permission<R>(c: String) -> std::io::Result<R> {
+    Err(std::io::Error::new(
+        PermissionDenied,
+        "Attempted to write to file with no write permissions",
+    ))
+    .wrap_io_err(c)
+}
+
+impl<T> std::io::Write for File<T>
+where
+    T: crate::File,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let c = format!("While writing to file {:?} within a zip file", self.path);
+        if self.filesystem.is_none() {
+            return no_write_permission(c);
+        }
+        *self.modified.get_mut() = true;
+        self.contents.get_mut().write(buf).wrap_io_err(c)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        let modified = self.modified.get_mut();
+        if !*modified {
+            return Ok(());
+        }
+        let c = format!("While flushing file {:?} within a zip file", self.path);
+        let Some(filesystem) = &self.filesystem else {
+            return no_write_permission(c);
+        };
+
+        let data = self.contents.get_mut().get_ref().as_slice().into();
+        let mut inner = filesystem.inner.lock();
+        inner.trie.create_file(&self.path, Entry::Modified(data));
+        inner.dirty = true;
+
+        *modified = false;
+        Ok(())
+    }
+}
+
+impl<T> std::io::Read for File<T> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let c = format!("While reading from file {:?} within a zip file", self.path);
+        if self.read_allowed {
+            self.contents.get_mut().read(buf).wrap_io_err(c)
+        } else {
+            Err(std::io::Error::new(
+                PermissionDenied,
+                "Attempted to read from file with no read permissions",
+            ))
+            .wrap_io_err(c)
+        }
+    }
+}
+
+impl<T> std::io::Seek for File<T> {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        let c = format!("While seeking file {:?} within a zip file", self.path);
+        self.contents.get_mut().seek(pos).wrap_io_err(c)
+    }
+}
+
+impl<T> crate::File for File<T>
+where
+    T: crate::File,
+{
+    fn metadata(&self) -> std::io::Result<Metadata> {
+        Ok(Metadata {
+            is_file: true,
+            size: self.contents.lock().get_ref().len() as u64,
+        })
+    }
+
+    fn set_len(&self, new_size: u64) -> std::io::Result<()> {
+        let c = format!(
+            "While setting length for file {:?} within a zip file",
+            self.path
+        );
+        if self.filesystem.is_none() {
+            return no_write_permission(c);
+        }
+        *self.modified.lock() = true;
+        self.contents.lock().get_mut().resize(new_size as usize, 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,448 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+use color_eyre::eyre::WrapErr;
+use itertools::Itertools;
+use std::io::{prelude::*, SeekFrom};
+
+use crate::{DirEntry, Error, Metadata, OpenFlags, Result};
+
+mod file;
+pub use file::File;
+
+type Trie = crate::FileSystemTrie<Entry>;
+
+#[derive(Debug, Clone)]
+enum Entry {
+    /// A file stored in the zip file at the given index.
+    Archived { index: usize, size: u64 },
+    /// A file that was changed and hasn't been written to the zip file yet.
+    Modified(std::sync::Arc<[u8]>),
+}
+
+impl Entry {
+    fn size(&self) -> u64 {
+        match self {
+            Entry::Archived { size, .. } => *size,
+            Entry::Modified(data) => data.len() as u64,
+        }
+    }
+}
+
+/// A filesystem stored in a zip file.
+///
+/// If everything in the zip file is inside of one folder, like in most zip files people share,
+/// that folder is used as the root of the filesystem.
+///
+/// Zip files can't be changed in place, so changes are kept in memory until
+/// [`crate::FileSystem::commit`] is called, which rewrites the whole zip file once. Empty
+/// directories aren't kept.
+pub struct FileSystem<T> {
+    inner: std::sync::Arc<parking_lot::Mutex<Inner<T>>>,
+}
+
+struct Inner<T> {
+    /// This is only `None` if rewriting the zip file failed.
+    archive: Option<::zip::ZipArchive<T>>,
+    trie: Trie,
+    /// The folder everything in the zip file is inside of.
+    prefix: camino::Utf8PathBuf,
+    /// Whether the trie has changes that haven't been written to the zip file yet.
+    dirty: bool,
+}
+
+impl<T> Clone for FileSystem<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> FileSystem<T>
+where
+    T: crate::File,
+{
+    /// Creates a new zip filesystem from a file containing an existing zip file.
+    pub fn new(file: T) -> Result<Self> {
+        Ok(Self {
+            inner: std::sync::Arc::new(parking_lot::Mutex::new(Inner::new(file)?)),
+        })
+    }
+
+    /// Creates a zip file containing every file in `source`, inside of a folder called `folder`,
+    /// and writes it to `file`. Git repositories, backups and autosaved changes are left out.
+    pub fn from_filesystem(
+        file: T,
+        source: &impl crate::FileSystem,
+        folder: impl AsRef<camino::Utf8Path>,
+    ) -> Result<Self> {
+        let folder = folder.as_ref();
+        let c = "While creating a zip file";
+
+        let mut paths = Vec::new();
+        find_files(source, "".into(), &mut paths).wrap_err(c)?;
+
+        let mut writer = ::zip::ZipWriter::new(file);
+        for path in paths {
+            writer
+                .start_file(entry_name(folder, &path), file_options())
+                .wrap_err_with(|| format!("While adding {path:?} to the zip file"))
+                .wrap_err(c)?;
+            let mut source_file = source
+                .open_file(&path, OpenFlags::Read)
+                .wrap_err_with(|| format!("While adding {path:?} to the zip file"))
+                .wrap_err(c)?;
+            std::io::copy(&mut source_file, &mut writer)
+                .wrap_err_with(|| format!("While adding {path:?} to the zip file"))
+                .wrap_err(c)?;
+        }
+        let file = writer.finish().wrap_err(c)?;
+
+        Self::new(file)
+    }
+}
+
+fn file_options() -> ::zip::write::FileOptions {
+    ::zip::write::FileOptions::default().compression_method(::zip::CompressionMethod::Deflated)
+}
+
+/// The name of the entry in a zip file for a path. These always use forward slashes.
+fn entry_name(prefix: &camino::Utf8Path, path: &camino::Utf8Path) -> String {
+    prefix.iter().chain(path.iter()).join("/")
+}
+
+/// Recursively finds the paths of every file in a directory of `source`.
+fn find_files(
+    source: &impl crate::FileSystem,
+    dir: camino::Utf8PathBuf,
+    paths: &mut Vec<camino::Utf8PathBuf>,
+) -> Result<()> {
+    for entry in source.read_dir(&dir)? {
+        let path = dir.join(entry.file_name());
+        if entry.file_name() == ".git"
+            || crate::project::LOCAL_DIRS
+                .iter()
+                .any(|local| path == camino::Utf8Path::new(local))
+        {
+            continue;
+        }
+        if entry.metadata.is_file {
+            paths.push(path);
+        } else {
+            find_files(source, path, paths)?;
+        }
+    }
+    Ok(())
+}
+
+impl<T> Inner<T>
+where
+    T: crate::File,
+{
+    fn new(file: T) -> Result<Self> {
+        let c = "While reading the contents of a zip file";
+        let mut archive = ::zip::ZipArchive::new(file).wrap_err(c)?;
+
+        let entries: Vec<_> = (0..archive.len())
+            .map(|index| -> Result<_> {
+                let file = archive.by_index_raw(index)?;
+                Ok((
+                    index,
+                    file.name().replace('\\', "/"),
+                    file.is_dir(),
+                    file.size(),
+                ))
+            })
+            .collect::<Result<_>>()
+            .wrap_err(c)?;
+
+        // Use the only top-level folder as the root, if there is one
+        let mut top_levels =
+            entries
+                .iter()
+                .map(|(_, name, is_dir, _)| match name.split_once('/') {
+                    Some((folder, _)) => Some(folder),
+                    None if *is_dir => Some(name.as_str()),
+                    None => None,
+                });
+        let prefix = match top_levels.next() {
+            Some(Some(folder)) if top_levels.all(|other| other == Some(folder)) => {
+                camino::Utf8PathBuf::from(folder)
+            }
+            _ => camino::Utf8PathBuf::new(),
+        };
+
+        let mut trie = Trie::new();
+        for (index, name, is_dir, size) in entries {
+            let Ok(path) = camino::Utf8Path::new(&name).strip_prefix(&prefix) else {
+                continue;
+            };
+            if path.as_str().is_empty() {
+                continue;
+            }
+            if is_dir {
+                trie.create_dir(path);
+            } else {
+                trie.create_file(path, Entry::Archived { index, size });
+            }
+        }
+
+        Ok(Self {
+            archive: Some(archive),
+            trie,
+            prefix,
+            dirty: false,
+        })
+    }
+
+    fn archive(&mut self) -> Result<&mut ::zip::ZipArchive<T>> {
+        self.archive
+            .as_mut()
+            .ok_or(Error::InvalidHeader)
+            .wrap_err("The zip file could not be read after it was last changed")
+    }
+
+    /// Reads the decompressed contents of a file.
+    fn read(&mut self, path: &camino::Utf8Path) -> Result<Vec<u8>> {
+        match self.trie.get_file(path).cloned() {
+            Some(Entry::Archived { index, size }) => {
+                let mut file = self.archive()?.by_index(index)?;
+                let mut buf = Vec::with_capacity(size as usize);
+                file.read_to_end(&mut buf)?;
+                Ok(buf)
+            }
+            Some(Entry::Modified(data)) => Ok(data.to_vec()),
+            None => Err(Error::NotExist.into()),
+        }
+    }
+
+    /// Writes the contents of the trie to the zip file, replacing everything that was in it.
+    fn commit(&mut self) -> Result<()> {
+        let c = "While writing changes to a zip file";
+
+        let files = self
+            .trie
+            .iter_prefix("")
+            .into_iter()
+            .flatten()
+            .map(|(path, entry)| {
+                let path = camino::Utf8Path::new(path.as_str().trim_start_matches('/'));
+                (entry_name(&self.prefix, path), entry.clone())
+            })
+            .collect_vec();
+
+        // Compressed files that didn't change are copied over as they are
+        let archive = self.archive().wrap_err(c)?;
+        let mut writer = ::zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, entry) in files {
+            match entry {
+                Entry::Archived { index, .. } => {
+                    let file = archive.by_index_raw(index).wrap_err(c)?;
+                    writer.raw_copy_file_rename(file, name).wrap_err(c)?;
+                }
+                Entry::Modified(data) => {
+                    writer.start_file(name, file_options()).wrap_err(c)?;
+                    writer.write_all(&data).wrap_err(c)?;
+                }
+            }
+        }
+        let buf = writer.finish().wrap_err(c)?.into_inner();
+
+        let mut file = self
+            .archive
+            .take()
+            .expect("archive was checked above")
+            .into_inner();
+        file.seek(SeekFrom::Start(0)).wrap_err(c)?;
+        file.write_all(&buf).wrap_err(c)?;
+        file.set_len(buf.len() as u64).wrap_err(c)?;
+        file.flush().wrap_err(c)?;
+
+        *self = Self::new(file).wrap_err(c)?;
+        Ok(())
+    }
+}
+
+impl<T> crate::FileSystem for FileSystem<T>
+where
+    T: crate::File,
+{
+    type File = File<T>;
+
+    fn open_file(
+        &self,
+        path: impl AsRef<camino::Utf8Path>,
+        flags: OpenFlags,
+    ) -> Result<Self::File> {
+        let path = path.as_ref();
+        let c = format!("While opening file {path:?} in a zip file");
+        let mut inner = self.inner.lock();
+
+        let mut created = false;
+        let contents = if flags.contains(OpenFlags::Create) && !inner.trie.contains_file(path) {
+            created = true;
+            inner
+                .trie
+                .create_file(path, Entry::Modified(Vec::new().into()));
+            Vec::new()
+        } else if !flags.contains(OpenFlags::Truncate) {
+            inner.read(path).wrap_err_with(|| c.clone())?
+        } else if !inner.trie.contains_file(path) {
+            return Err(Error::NotExist).wrap_err_with(|| c.clone());
+        } else {
+            Vec::new()
+        };
+
+        Ok(File {
+            filesystem: flags.contains(OpenFlags::Write).then(|| self.clone()),
+            path: path.to_owned(),
+            read_allowed: flags.contains(OpenFlags::Read),
+            contents: parking_lot::Mutex::new(std::io::Cursor::new(contents)),
+            modified: parking_lot::Mutex::new(
+                flags.contains(OpenFlags::Write)
+                    && (created || flags.contains(OpenFlags::Truncate)),
+            ),
+        })
+    }
+
+    fn metadata(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Metadata> {
+        let path = path.as_ref();
+        let inner = self.inner.lock();
+        if let Some(entry) = inner.trie.get_file(path) {
+            Ok(Metadata {
+                is_file: true,
+                size: entry.size(),
+            })
+        } else if let Some(size) = inner.trie.get_dir_size(path) {
+            Ok(Metadata {
+                is_file: false,
+                size: size as u64,
+            })
+        } else {
+            Err(Error::NotExist.into())
+        }
+    }
+
+    fn rename(
+        &self,
+        from: impl AsRef<camino::Utf8Path>,
+        to: impl AsRef<camino::Utf8Path>,
+    ) -> Result<()> {
+        let from = from.as_ref();
+        let to = to.as_ref();
+        let c = format!("While renaming {from:?} to {to:?} in a zip file");
+        let mut inner = self.inner.lock();
+
+        if inner.trie.contains(to) {
+            return Err(Error::IoError(std::io::ErrorKind::AlreadyExists.into()))
+                .wrap_err_with(|| c.clone());
+        }
+
+        if let Some(entry) = inner.trie.remove_file(from) {
+            inner.trie.create_file(to, entry);
+        } else if inner.trie.contains_dir(from) {
+            let entries = inner
+                .trie
+                .iter_prefix(from)
+                .into_iter()
+                .flatten()
+                .map(|(path, entry)| {
+                    let path = path.strip_prefix(from).unwrap_or(&path).to_owned();
+                    (path, entry.clone())
+                })
+                .collect_vec();
+            inner.trie.remove_dir(from);
+            inner.trie.create_dir(to);
+            for (path, entry) in entries {
+                inner.trie.create_file(to.join(path), entry);
+            }
+        } else {
+            return Err(Error::NotExist).wrap_err_with(|| c.clone());
+        }
+
+        inner.dirty = true;
+        Ok(())
+    }
+
+    fn exists(&self, path: impl AsRef<camino::Utf8Path>) -> Result<bool> {
+        Ok(self.inner.lock().trie.contains(path))
+    }
+
+    fn create_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        self.inner.lock().trie.create_dir(path);
+        Ok(())
+    }
+
+    fn remove_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        let path = path.as_ref();
+        let c = format!("While removing directory {path:?} in a zip file");
+        let mut inner = self.inner.lock();
+
+        if !inner.trie.remove_dir(path) {
+            return Err(Error::NotExist).wrap_err_with(|| c.clone());
+        }
+        inner.dirty = true;
+        Ok(())
+    }
+
+    fn remove_file(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        let path = path.as_ref();
+        let c = format!("While removing file {path:?} in a zip file");
+        let mut inner = self.inner.lock();
+
+        if inner.trie.remove_file(path).is_none() {
+            return Err(Error::NotExist).wrap_err_with(|| c.clone());
+        }
+        inner.dirty = true;
+        Ok(())
+    }
+
+    fn read_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Vec<DirEntry>> {
+        let path = path.as_ref();
+        let c = format!("While reading the contents of the directory {path:?} in a zip file");
+        let inner = self.inner.lock();
+
+        let Some(iter) = inner.trie.iter_dir(path) else {
+            return Err(Error::NotExist).wrap_err_with(|| c.clone());
+        };
+        Ok(iter
+            .map(|(name, entry)| {
+                let path = path.join(name);
+                let metadata = match entry {
+                    Some(entry) => Metadata {
+                        is_file: true,
+                        size: entry.size(),
+                    },
+                    None => Metadata {
+                        is_file: false,
+                        size: inner.trie.get_dir_size(&path).unwrap_or_default() as u64,
+                    },
+                };
+                DirEntry { path, metadata }
+            })
+            .collect())
+    }
+
+    fn commit(&self) -> Result<()> {
+        let mut inner = self.inner.lock();
+        if inner.dirty {
+            inner.commit()?;
+        }
+        Ok(())
+    }
+}
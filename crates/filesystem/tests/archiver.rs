@@ -0,0 +1,83 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+use luminol_filesystem::{archiver, memory, File, FileSystem, OpenFlags};
+
+const FILES: [(&str, &str); 3] = [
+    ("Data/Actors.rxdata", "actors"),
+    ("Data/MapInfos.rxdata", "map infos"),
+    ("Graphics/Characters/001-Fighter01.png", "fighter"),
+];
+
+fn create_archive(
+    dest: &memory::FileSystem,
+    version: u8,
+) -> color_eyre::Result<archiver::FileSystem<memory::File>> {
+    let source = memory::FileSystem::new();
+    source.create_dir("Data")?;
+    source.create_dir("Graphics/Characters")?;
+    for (path, contents) in FILES {
+        source.write(path, contents)?;
+    }
+
+    let buffer = dest.open_file(
+        "Game.rgssad",
+        OpenFlags::Read | OpenFlags::Write | OpenFlags::Create,
+    )?;
+    let paths = FILES.map(|(path, _)| path);
+    futures_lite::future::block_on(archiver::FileSystem::from_buffer_and_files(
+        buffer,
+        version,
+        paths.iter().map(|path| {
+            let file = source.open_file(path, OpenFlags::Read)?;
+            let size = file.metadata()?.size as u32;
+            Ok((path, size, file))
+        }),
+    ))
+}
+
+fn check_archive(archive: &impl FileSystem) -> color_eyre::Result<()> {
+    for (path, contents) in FILES {
+        assert_eq!(archive.read_to_string(path)?, contents);
+    }
+    assert_eq!(archive.read_dir("Data")?.len(), 2);
+    assert!(!archive.exists("Data/Skills.rxdata")?);
+    Ok(())
+}
+
+#[test]
+fn create_and_read_archives() -> color_eyre::Result<()> {
+    for version in [1, 3] {
+        let dest = memory::FileSystem::new();
+        let archive = create_archive(&dest, version)?;
+        check_archive(&archive)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn reopen_archives() -> color_eyre::Result<()> {
+    for version in [1, 3] {
+        let dest = memory::FileSystem::new();
+        create_archive(&dest, version)?;
+
+        let buffer = dest.open_file("Game.rgssad", OpenFlags::Read)?;
+        let archive = archiver::FileSystem::new(buffer)?;
+        check_archive(&archive)?;
+    }
+    Ok(())
+}
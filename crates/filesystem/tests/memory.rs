@@ -0,0 +1,140 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use luminol_filesystem::{memory, File, FileSystem, OpenFlags};
+
+#[test]
+fn write_then_read() -> color_eyre::Result<()> {
+    let fs = memory::FileSystem::new();
+    fs.create_dir("Data")?;
+    fs.write("Data/Test.txt", "Hello, world!")?;
+
+    assert_eq!(fs.read_to_string("Data/Test.txt")?, "Hello, world!");
+    let metadata = fs.metadata("Data/Test.txt")?;
+    assert!(metadata.is_file);
+    assert_eq!(metadata.size, 13);
+    assert!(!fs.metadata("Data")?.is_file);
+    Ok(())
+}
+
+#[test]
+fn create_needs_parent_directory() {
+    let fs = memory::FileSystem::new();
+    assert!(fs.write("Data/Test.txt", "").is_err());
+    assert!(fs.read("Test.txt").is_err());
+}
+
+#[test]
+fn seek_and_overwrite() -> color_eyre::Result<()> {
+    let fs = memory::FileSystem::new();
+    let mut file = fs.open_file(
+        "test",
+        OpenFlags::Read | OpenFlags::Write | OpenFlags::Create,
+    )?;
+    file.write_all(b"abcdef")?;
+    file.seek(SeekFrom::Start(2))?;
+    file.write_all(b"XY")?;
+    file.seek(SeekFrom::End(2))?;
+    file.write_all(b"!")?;
+    assert_eq!(fs.read("test")?, b"abXYef\0\0!");
+
+    file.set_len(3)?;
+    file.seek(SeekFrom::Start(0))?;
+    let mut buf = String::new();
+    file.read_to_string(&mut buf)?;
+    assert_eq!(buf, "abX");
+    assert!(file.seek(SeekFrom::Current(-4)).is_err());
+    Ok(())
+}
+
+#[test]
+fn permissions() -> color_eyre::Result<()> {
+    let fs = memory::FileSystem::new();
+    fs.write("test", "abc")?;
+
+    let mut file = fs.open_file("test", OpenFlags::Read)?;
+    assert!(file.write_all(b"def").is_err());
+    let mut file = fs.open_file("test", OpenFlags::Write)?;
+    assert!(file.read(&mut [0; 3]).is_err());
+
+    fs.open_file("test", OpenFlags::Write | OpenFlags::Truncate)?;
+    assert!(fs.read("test")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn rename_files_and_directories() -> color_eyre::Result<()> {
+    let fs = memory::FileSystem::new();
+    fs.create_dir("Graphics/Characters")?;
+    fs.write("Graphics/Characters/001-Fighter01.png", "fighter")?;
+    fs.write("Graphics/Characters/002-Fighter02.png", "fighter 2")?;
+
+    fs.rename(
+        "Graphics/Characters/002-Fighter02.png",
+        "Graphics/Characters/003-Fighter03.png",
+    )?;
+    assert!(!fs.exists("Graphics/Characters/002-Fighter02.png")?);
+    assert_eq!(
+        fs.read_to_string("Graphics/Characters/003-Fighter03.png")?,
+        "fighter 2"
+    );
+
+    fs.rename("Graphics", "Images")?;
+    assert!(!fs.exists("Graphics")?);
+    assert_eq!(
+        fs.read_to_string("Images/Characters/001-Fighter01.png")?,
+        "fighter"
+    );
+    assert_eq!(fs.read_dir("Images/Characters")?.len(), 2);
+
+    fs.write("Other", "")?;
+    assert!(fs.rename("Other", "Images").is_err());
+    Ok(())
+}
+
+#[test]
+fn remove_files_and_directories() -> color_eyre::Result<()> {
+    let fs = memory::FileSystem::new();
+    fs.create_dir("Audio/BGM")?;
+    fs.write("Audio/BGM/001-Battle01.mid", "")?;
+    fs.write("Game.ini", "")?;
+
+    fs.remove_file("Game.ini")?;
+    assert!(!fs.exists("Game.ini")?);
+    assert!(fs.remove_file("Game.ini").is_err());
+
+    fs.remove("Audio")?;
+    assert!(!fs.exists("Audio/BGM/001-Battle01.mid")?);
+    assert!(fs.read_dir("")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn open_handles_share_contents() -> color_eyre::Result<()> {
+    let fs = memory::FileSystem::new();
+    let mut writer = fs.create_file("test")?;
+    let mut reader = fs.open_file("test", OpenFlags::Read)?;
+
+    writer.write_all(b"abc")?;
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf)?;
+    assert_eq!(buf, b"abc");
+    assert_eq!(reader.metadata()?.size, 3);
+    Ok(())
+}
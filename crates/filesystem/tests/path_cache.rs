@@ -0,0 +1,94 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+use luminol_filesystem::{memory, path_cache, FileSystem};
+
+fn filesystem() -> color_eyre::Result<path_cache::FileSystem<memory::FileSystem>> {
+    let fs = memory::FileSystem::new();
+    fs.create_dir("Data")?;
+    fs.create_dir("Graphics/Characters")?;
+    fs.write("Data/MapInfos.rxdata", "map infos")?;
+    fs.write("Graphics/Characters/001-Fighter01.png", "fighter")?;
+    path_cache::FileSystem::new(fs)
+}
+
+#[test]
+fn lookup_ignores_case() -> color_eyre::Result<()> {
+    let fs = filesystem()?;
+    assert_eq!(fs.read_to_string("data/mapinfos.rxdata")?, "map infos");
+    assert_eq!(fs.read_to_string("DATA/MAPINFOS.RXDATA")?, "map infos");
+    assert_eq!(
+        fs.read_to_string("graphics/characters/001-fighter01.PNG")?,
+        "fighter"
+    );
+    assert!(fs.exists("graphics/CHARACTERS")?);
+    assert!(!fs.exists("data/actors.rxdata")?);
+    Ok(())
+}
+
+#[test]
+fn lookup_ignores_extension() -> color_eyre::Result<()> {
+    let fs = filesystem()?;
+    // Graphics are referred to without their extension in the data files
+    assert_eq!(
+        fs.read_to_string("Graphics/Characters/001-Fighter01")?,
+        "fighter"
+    );
+    Ok(())
+}
+
+#[test]
+fn created_files_keep_their_parents_case() -> color_eyre::Result<()> {
+    let fs = filesystem()?;
+    fs.write("data/Actors.rxdata", "actors")?;
+    assert!(fs.fs().exists("Data/Actors.rxdata")?);
+    assert_eq!(fs.read_to_string("DATA/ACTORS.RXDATA")?, "actors");
+
+    fs.create_dir("graphics/tilesets")?;
+    assert!(fs.fs().exists("Graphics/tilesets")?);
+    Ok(())
+}
+
+#[test]
+fn removed_and_renamed_files_are_forgotten() -> color_eyre::Result<()> {
+    let fs = filesystem()?;
+    assert!(fs.exists("data/mapinfos.rxdata")?);
+
+    fs.rename("data/mapinfos.rxdata", "Data/MapInfos2.rxdata")?;
+    assert!(!fs.exists("data/mapinfos.rxdata")?);
+    assert_eq!(fs.read_to_string("data/mapinfos2.rxdata")?, "map infos");
+
+    fs.remove_file("DATA/MAPINFOS2.RXDATA")?;
+    assert!(!fs.exists("data/mapinfos2.rxdata")?);
+
+    fs.remove_dir("graphics")?;
+    assert!(!fs.exists("graphics/characters/001-fighter01.png")?);
+    Ok(())
+}
+
+#[test]
+fn invalidate_picks_up_external_changes() -> color_eyre::Result<()> {
+    let fs = filesystem()?;
+    assert!(fs.exists("data/mapinfos.rxdata")?);
+
+    // Change the case of a file behind the cache's back
+    fs.fs()
+        .rename("Data/MapInfos.rxdata", "Data/mapinfos.rxdata")?;
+    fs.invalidate("Data/MapInfos.rxdata");
+    assert_eq!(fs.read_to_string("Data/MapInfos.rxdata")?, "map infos");
+    Ok(())
+}
@@ -0,0 +1,84 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_filesystem::{memory, zip, FileSystem, OpenFlags};
+
+fn create_zip(dest: &memory::FileSystem) -> color_eyre::Result<zip::FileSystem<memory::File>> {
+    let source = memory::FileSystem::new();
+    source.create_dir("Data")?;
+    source.write("Data/Scripts.rxdata", "scripts")?;
+    source.write("Game.ini", "ini")?;
+
+    let file = dest.open_file(
+        "Project.zip",
+        OpenFlags::Read | OpenFlags::Write | OpenFlags::Create,
+    )?;
+    zip::FileSystem::from_filesystem(file, &source, "Project")
+}
+
+fn reopen(dest: &memory::FileSystem) -> color_eyre::Result<zip::FileSystem<memory::File>> {
+    zip::FileSystem::new(dest.open_file("Project.zip", OpenFlags::Read)?)
+}
+
+#[test]
+fn changes_are_written_on_commit() -> color_eyre::Result<()> {
+    let dest = memory::FileSystem::new();
+    let zip = create_zip(&dest)?;
+
+    zip.write("Game.ini", "changed")?;
+    zip.remove_file("Data/Scripts.rxdata")?;
+    assert_eq!(zip.read_to_string("Game.ini")?, "changed");
+
+    let unchanged = reopen(&dest)?;
+    assert_eq!(unchanged.read_to_string("Game.ini")?, "ini");
+    assert!(unchanged.exists("Data/Scripts.rxdata")?);
+
+    zip.commit()?;
+    let changed = reopen(&dest)?;
+    assert_eq!(changed.read_to_string("Game.ini")?, "changed");
+    assert!(!changed.exists("Data/Scripts.rxdata")?);
+    Ok(())
+}
+
+#[test]
+fn backups_and_recovery_are_left_out() -> color_eyre::Result<()> {
+    let source = memory::FileSystem::new();
+    source.create_dir(".luminol/backups/1700000000000/Data")?;
+    source.create_dir(".luminol/recovery/Data")?;
+    source.write(".luminol/config", "config")?;
+    source.write(".luminol/backups/1700000000000/Data/System.rxdata", "old")?;
+    source.write(".luminol/recovery/Data/System.rxdata", "unsaved")?;
+
+    let dest = memory::FileSystem::new();
+    let file = dest.open_file(
+        "Project.zip",
+        OpenFlags::Read | OpenFlags::Write | OpenFlags::Create,
+    )?;
+    let zip = zip::FileSystem::from_filesystem(file, &source, "Project")?;
+
+    assert_eq!(zip.read_to_string(".luminol/config")?, "config");
+    assert!(!zip.exists(".luminol/backups")?);
+    assert!(!zip.exists(".luminol/recovery")?);
+    Ok(())
+}
@@ -25,7 +25,8 @@ pub use loaders::texture::Texture;
 // Building blocks that make up more complex parts (i.e. the map view, or events)
 pub mod primitives;
 pub use primitives::{
-    collision::Collision, grid::Grid, sprite::Sprite, tiles::Atlas, tiles::Tiles,
+    collision::Collision, grid::Grid, sprite::Sprite, tiles::Atlas, tiles::AtlasLayout,
+    tiles::Tiles,
 };
 
 pub mod data;
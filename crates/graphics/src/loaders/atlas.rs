@@ -16,6 +16,10 @@
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 use crate::{Atlas, GraphicsState};
 
+/// The ID the atlas of RPG Maker VX's tileset is stored with. RPG Maker VX projects don't have a
+/// tilesets file, so this can't clash with the atlas of another tileset.
+pub const VX_ATLAS_ID: usize = 0;
+
 #[derive(Default)]
 pub struct Loader {
     atlases: dashmap::DashMap<usize, Atlas>,
@@ -48,6 +52,31 @@ impl Loader {
             .clone())
     }
 
+    pub fn load_ace_atlas(
+        &self,
+        graphics_state: &GraphicsState,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        tileset: &luminol_data::rmvxace::Tileset,
+    ) -> color_eyre::Result<Atlas> {
+        Ok(self
+            .atlases
+            .entry(tileset.id)
+            .or_insert_with(|| Atlas::new_ace(graphics_state, filesystem, tileset))
+            .clone())
+    }
+
+    pub fn load_vx_atlas(
+        &self,
+        graphics_state: &GraphicsState,
+        filesystem: &impl luminol_filesystem::FileSystem,
+    ) -> color_eyre::Result<Atlas> {
+        Ok(self
+            .atlases
+            .entry(VX_ATLAS_ID)
+            .or_insert_with(|| Atlas::new_vx(graphics_state, filesystem))
+            .clone())
+    }
+
     pub fn get_atlas(&self, id: usize) -> Option<Atlas> {
         self.atlases.get(&id).map(|atlas| atlas.clone())
     }
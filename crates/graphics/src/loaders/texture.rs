@@ -253,6 +253,14 @@ impl Loader {
             .map(|(_, value)| value)
     }
 
+    /// Removes every texture loaded from the given path, ignoring case and file extension.
+    pub fn evict(&self, path: impl AsRef<camino::Utf8Path>) {
+        let path = luminol_filesystem::path_cache::to_lowercase(path.as_ref().with_extension(""));
+        self.loaded_textures.retain(|loaded_path, _| {
+            luminol_filesystem::path_cache::to_lowercase(loaded_path.with_extension("")) != path
+        });
+    }
+
     pub fn clear(&self) {
         self.loaded_textures.clear();
     }
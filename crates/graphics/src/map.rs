@@ -161,6 +161,98 @@ impl Map {
         })
     }
 
+    /// Creates the graphics of an RPG Maker VX Ace map. Only the tiles are drawn for now, so
+    /// events, parallaxes, shadows and regions are not shown.
+    pub fn new_ace(
+        graphics_state: &GraphicsState,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        map: &luminol_data::rmvxace::Map,
+        tileset: &luminol_data::rmvxace::Tileset,
+        passages: &luminol_data::Table2,
+    ) -> color_eyre::Result<Self> {
+        let atlas =
+            graphics_state
+                .atlas_loader
+                .load_ace_atlas(graphics_state, filesystem, tileset)?;
+        Ok(Self::from_ace_atlas(
+            graphics_state,
+            atlas,
+            &map.data,
+            passages,
+        ))
+    }
+
+    /// Creates the graphics of an RPG Maker VX map. Like RPG Maker VX Ace maps, only the tiles are
+    /// drawn for now.
+    pub fn new_vx(
+        graphics_state: &GraphicsState,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        map: &luminol_data::rmvx::Map,
+        passages: &luminol_data::Table2,
+    ) -> color_eyre::Result<Self> {
+        let atlas = graphics_state
+            .atlas_loader
+            .load_vx_atlas(graphics_state, filesystem)?;
+        Ok(Self::from_ace_atlas(
+            graphics_state,
+            atlas,
+            &map.data,
+            passages,
+        ))
+    }
+
+    fn from_ace_atlas(
+        graphics_state: &GraphicsState,
+        atlas: Atlas,
+        data: &luminol_data::Table3,
+        passages: &luminol_data::Table2,
+    ) -> Self {
+        let viewport = Viewport::new(
+            graphics_state,
+            glam::vec2(data.xsize() as f32 * 32., data.ysize() as f32 * 32.),
+        );
+
+        let tiles = Tiles::new(
+            graphics_state,
+            data,
+            &atlas,
+            &viewport,
+            Transform::unit(graphics_state),
+        );
+        let grid = Grid::new(
+            graphics_state,
+            &viewport,
+            Transform::unit(graphics_state),
+            data.xsize() as u32,
+            data.ysize() as u32,
+        );
+        let collision = Collision::new(
+            graphics_state,
+            &viewport,
+            Transform::unit(graphics_state),
+            passages,
+        );
+
+        Self {
+            tiles,
+            panorama: None,
+            fog: None,
+            collision,
+            grid,
+            events: Default::default(),
+            viewport,
+            atlas,
+
+            ani_time: None,
+
+            fog_enabled: false,
+            pano_enabled: false,
+            coll_enabled: false,
+            grid_enabled: true,
+            event_enabled: false,
+        }
+    }
+
     pub fn set_tile(
         &self,
         render_state: &luminol_egui_wgpu::RenderState,
@@ -179,6 +271,192 @@ impl Map {
         self.collision.set_passage(render_state, passage, position);
     }
 
+    /// Renders the whole map into an image. Maps bigger than the device's texture size limits are
+    /// rendered in several passes. All rendering commands are submitted right away; the returned
+    /// future only waits for the results to be copied back.
+    pub fn render_to_image(
+        &mut self,
+        graphics_state: &std::sync::Arc<GraphicsState>,
+        map_size: (usize, usize),
+    ) -> impl std::future::Future<Output = color_eyre::Result<image::RgbaImage>> {
+        let c = "While rendering the map to an image";
+
+        let max_texture_dimension_2d = graphics_state
+            .render_state
+            .device
+            .limits()
+            .max_texture_dimension_2d
+            / wgpu::COPY_BYTES_PER_ROW_ALIGNMENT
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let max_buffer_size = graphics_state.render_state.device.limits().max_buffer_size as u32
+            / wgpu::COPY_BYTES_PER_ROW_ALIGNMENT
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let screenshot_width = map_size.0 as u32 * 32;
+        let screenshot_height = map_size.1 as u32 * 32;
+
+        let max_texture_width = screenshot_width
+            .min(max_texture_dimension_2d)
+            .min(max_buffer_size);
+        let max_texture_height = screenshot_height
+            .min(max_texture_dimension_2d)
+            .min(max_buffer_size / (max_texture_width * 4));
+
+        let mut command_encoder = graphics_state
+            .render_state
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+
+        let buffers = (0..screenshot_height)
+            .step_by(max_texture_height as usize)
+            .cartesian_product((0..screenshot_width).step_by(max_texture_width as usize))
+            .map(|(y_offset, x_offset)| {
+                let width = max_texture_width.min(screenshot_width - x_offset);
+                let height = max_texture_height.min(screenshot_height - y_offset);
+                let width_padded = width.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT / 4);
+
+                let texture =
+                    graphics_state
+                        .render_state
+                        .device
+                        .create_texture(&wgpu::TextureDescriptor {
+                            label: Some("map screenshot texture"),
+                            size: wgpu::Extent3d {
+                                width,
+                                height,
+                                depth_or_array_layers: 1,
+                            },
+                            mip_level_count: 1,
+                            sample_count: 1,
+                            dimension: wgpu::TextureDimension::D2,
+                            format: graphics_state.render_state.target_format,
+                            usage: wgpu::TextureUsages::COPY_SRC
+                                | wgpu::TextureUsages::RENDER_ATTACHMENT,
+                            view_formats: &[],
+                        });
+                let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+                let buffer =
+                    graphics_state
+                        .render_state
+                        .device
+                        .create_buffer(&wgpu::BufferDescriptor {
+                            label: Some("map screenshot buffer"),
+                            size: width_padded as u64 * height as u64 * 4,
+                            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                            mapped_at_creation: false,
+                        });
+
+                self.viewport.set(
+                    &graphics_state.render_state,
+                    glam::vec2(width as f32, height as f32),
+                    glam::vec2(x_offset as f32, y_offset as f32),
+                    glam::Vec2::ONE,
+                );
+
+                // we probably don't need to prepare the map every time, but it's not that expensive
+                let prepared = self.prepare(graphics_state);
+
+                let mut render_pass =
+                    command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                        label: Some("map screenshot render pass"),
+                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                            view: &view,
+                            resolve_target: None,
+                            ops: wgpu::Operations::default(),
+                        })],
+                        depth_stencil_attachment: None,
+                        timestamp_writes: None,
+                        occlusion_query_set: None,
+                    });
+
+                prepared.draw(&mut render_pass);
+
+                drop(render_pass);
+
+                command_encoder.copy_texture_to_buffer(
+                    wgpu::ImageCopyTexture {
+                        texture: &texture,
+                        mip_level: 0,
+                        origin: wgpu::Origin3d::ZERO,
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    wgpu::ImageCopyBuffer {
+                        buffer: &buffer,
+                        layout: wgpu::ImageDataLayout {
+                            offset: 0,
+                            bytes_per_row: Some(width_padded * 4),
+                            rows_per_image: Some(height),
+                        },
+                    },
+                    wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                );
+
+                buffer
+            })
+            .collect_vec();
+
+        graphics_state
+            .render_state
+            .queue
+            .submit(std::iter::once(command_encoder.finish()));
+
+        let graphics_state = graphics_state.clone();
+        let mut vec = vec![0; screenshot_width as usize * screenshot_height as usize * 4];
+        async move {
+            for ((y_offset, x_offset), buffer) in (0..screenshot_height)
+                .step_by(max_texture_height as usize)
+                .cartesian_product((0..screenshot_width).step_by(max_texture_width as usize))
+                .zip(buffers)
+            {
+                let width = max_texture_width.min(screenshot_width - x_offset);
+                let width_padded = width.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT / 4);
+
+                let (tx, rx) = oneshot::channel();
+                buffer
+                    .slice(..)
+                    .map_async(wgpu::MapMode::Read, move |result| {
+                        let _ = tx.send(result);
+                    });
+                if !graphics_state
+                    .render_state
+                    .device
+                    .poll(wgpu::Maintain::Wait)
+                    .is_queue_empty()
+                {
+                    return Err(color_eyre::eyre::eyre!("wgpu::Device::poll timed out").wrap_err(c));
+                }
+                rx.await.unwrap().wrap_err(c)?;
+
+                for (i, row) in buffer
+                    .slice(..)
+                    .get_mapped_range()
+                    .chunks_exact(width_padded as usize * 4)
+                    .enumerate()
+                {
+                    let offset = ((y_offset as usize + i) * screenshot_width as usize
+                        + x_offset as usize)
+                        * 4;
+                    vec[offset..offset + width as usize * 4]
+                        .copy_from_slice(&row[..width as usize * 4]);
+                }
+            }
+
+            if graphics_state.render_state.target_format == wgpu::TextureFormat::Bgra8Unorm {
+                for (b, _g, r, _a) in vec.iter_mut().tuples() {
+                    std::mem::swap(b, r);
+                }
+            }
+
+            image::RgbaImage::from_raw(screenshot_width, screenshot_height, vec)
+                .ok_or_else(|| color_eyre::eyre::eyre!("image buffer has the wrong size"))
+                .wrap_err(c)
+        }
+    }
+
     pub fn update_animation(&mut self, render_state: &luminol_egui_wgpu::RenderState, time: f64) {
         if let Some(ani_time) = self.ani_time {
             if time - ani_time >= 16. / 60. {
@@ -143,6 +143,45 @@ impl Collision {
         }
     }
 
+    /// Like `calculate_passages`, but for RPG Maker VX Ace maps, which store the passability of
+    /// every tile in the flags of their tileset. Event collision isn't shown for these maps.
+    pub fn calculate_passages_ace(
+        flags: &luminol_data::Table1,
+        tiles: &luminol_data::Table3,
+        layers: impl Iterator<Item = usize> + Clone,
+        mut f: impl FnMut(usize, usize, i16),
+    ) {
+        use luminol_data::rmvxace::Tileset;
+        const PASSAGE_MASK: i16 =
+            Tileset::FLAG_DOWN | Tileset::FLAG_LEFT | Tileset::FLAG_RIGHT | Tileset::FLAG_UP;
+
+        for (y, x) in (0..tiles.ysize()).cartesian_product(0..tiles.xsize()) {
+            f(
+                x,
+                y,
+                Self::calculate_passage(layers.clone().map(|z| {
+                    let tile_id = tiles[(x, y, z)];
+                    // Tile 0 is the empty tile
+                    let collision_type = if tile_id == 0 {
+                        CollisionType::BlankTile
+                    } else {
+                        CollisionType::Tile
+                    };
+                    let flag = usize::try_from(tile_id)
+                        .ok()
+                        .filter(|&tile_id| tile_id < flags.len())
+                        .map_or(0, |tile_id| flags[tile_id]);
+                    // Star tiles are drawn above characters, like tiles with a priority in XP
+                    (
+                        flag & PASSAGE_MASK,
+                        (flag & Tileset::FLAG_STAR != 0) as i16,
+                        collision_type,
+                    )
+                })),
+            );
+        }
+    }
+
     /// Determines the passage value for a position on the map given an iterator over the
     /// `(passage, priority, collision_type)` values for the tiles in each layer on that position.
     /// The iterator should iterate over the layers from top to bottom.
@@ -17,6 +17,7 @@ struct Autotiles {
     frame_counts: array<vec4<u32>, 2>,
     animation_index: u32,
     max_frame_count: u32,
+    ace_layout: u32,
 }
 
 @group(0) @binding(0)
@@ -59,11 +60,34 @@ const TEX_COORDS = array<vec2f, 6>(
     vec2f(31.99, 31.99),
 );
 
+// RPG Maker VX Ace atlases store every tile ID at its own position, followed by the extra frames of A1
+fn ace_atlas_tile_position(tile_id: u32) -> vec2<f32> {
+    var index = tile_id;
+    if tile_id >= #ACE_TILE_ID_A1 && tile_id < #ACE_TILE_ID_A2 {
+        // A1 autotiles cycle through their 3 frames back and forth
+        var frames = array<u32, 4>(0u, 1u, 2u, 1u);
+        let frame = frames[autotiles.animation_index % 4u];
+        if frame != 0u {
+            index = #ACE_FRAME_OFFSET + (frame - 1u) * #ACE_A1_FRAME_SIZE + (tile_id - #ACE_TILE_ID_A1);
+        }
+    }
+
+    return vec2<f32>(
+        f32(index % #ACE_ATLAS_COLUMNS * #TILE_SIZE),
+        f32(index / #ACE_ATLAS_COLUMNS * #TILE_SIZE)
+    );
+}
+
 @vertex
 fn vs_main(@builtin(vertex_index) vertex_index: u32, instance: InstanceInput) -> VertexOutput {
     var out: VertexOutput;
 
-    if instance.tile_id < #AUTOTILE_ID_AMOUNT {
+    if autotiles.ace_layout != 0u {
+        // Tile 0 is always empty and anything past A4 is invalid
+        if instance.tile_id == 0u || instance.tile_id >= #ACE_FRAME_OFFSET {
+            return out;
+        }
+    } else if instance.tile_id < #AUTOTILE_ID_AMOUNT {
         return out;
     }
 
@@ -85,7 +109,9 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32, instance: InstanceInput) ->
     let is_under_autotiles = !is_autotile && instance.tile_id - #TOTAL_AUTOTILE_ID_AMOUNT < max_tiles_under_autotiles;
 
     var atlas_tile_position: vec2<f32>;
-    if is_autotile {
+    if autotiles.ace_layout != 0u {
+        atlas_tile_position = ace_atlas_tile_position(instance.tile_id);
+    } else if is_autotile {
         atlas_tile_position = vec2<f32>(
             // If the tile is an autotile
             f32((instance.tile_id - #AUTOTILE_ID_AMOUNT) % #AUTOTILE_FRAME_COLS * #TILE_SIZE),
@@ -107,7 +133,7 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32, instance: InstanceInput) ->
         }
     }
 
-    if is_autotile {
+    if autotiles.ace_layout == 0u && is_autotile {
         let autotile_type = instance.tile_id / #AUTOTILE_ID_AMOUNT - 1;
         let frame_count = autotiles.frame_counts[autotile_type / 4][autotile_type % 4];
 
@@ -0,0 +1,379 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+use color_eyre::eyre::WrapErr;
+use image::RgbaImage;
+use luminol_data::rmvxace::{AutotileKind, Tileset};
+use wgpu::util::DeviceExt;
+
+use super::atlas::{Atlas, AtlasLayout, AUTOTILE_AMOUNT, TILE_SIZE};
+use super::autotile_ids::AUTOTILES;
+use crate::GraphicsState;
+
+pub const ACE_ATLAS_COLUMNS: u32 = 64; // The atlas is 64 tiles across
+pub const ACE_ATLAS_WIDTH: u32 = ACE_ATLAS_COLUMNS * TILE_SIZE; // self explanatory
+pub const ACE_A1_FRAME_SIZE: u32 = 768; // there are 768 tile ids on A1 (16 autotiles of 48 ids each)
+pub const ACE_A1_FRAMES: u32 = 3; // every animated A1 autotile has 3 frames
+pub const ACE_FRAME_OFFSET: u32 = Tileset::TILE_ID_MAX as u32; // the 2nd and 3rd frames of A1 are stored after every other tile id
+pub const ACE_ATLAS_ROWS: u32 =
+    (ACE_FRAME_OFFSET + ACE_A1_FRAME_SIZE * (ACE_A1_FRAMES - 1)) / ACE_ATLAS_COLUMNS; // total number of rows in the atlas
+pub const ACE_ATLAS_HEIGHT: u32 = ACE_ATLAS_ROWS * TILE_SIZE; // self explanatory
+
+/// The sizes of the A1, A2, A3, A4, A5, B, C, D and E graphics in pixels.
+const SHEET_SIZES: [(u32, u32); 9] = [
+    (512, 384),
+    (512, 384),
+    (512, 256),
+    (512, 480),
+    (256, 512),
+    (512, 512),
+    (512, 512),
+    (512, 512),
+    (512, 512),
+];
+
+/// The names of the A1, A2, A3, A4, A5, B, C, D and E graphics of RPG Maker VX.
+const VX_SHEET_NAMES: [&str; 9] = [
+    "TileA1", "TileA2", "TileA3", "TileA4", "TileA5", "TileB", "TileC", "TileD", "TileE",
+];
+
+/// The positions of the 16 autotiles of A1 on the sheet, in tiles.
+const A1_POSITIONS: [(u32, u32); 16] = [
+    (0, 0),
+    (0, 3),
+    (6, 0),
+    (6, 3),
+    (8, 0),
+    (14, 0),
+    (8, 3),
+    (14, 3),
+    (0, 6),
+    (6, 6),
+    (0, 9),
+    (6, 9),
+    (8, 6),
+    (14, 6),
+    (8, 9),
+    (14, 9),
+];
+
+impl Atlas {
+    /// Creates the atlas of an RPG Maker VX Ace tileset.
+    ///
+    /// Unlike RPG Maker XP, every autotile shape is prebuilt on the atlas at the position of its tile
+    /// id, so tile ids map directly onto the atlas. The 2nd and 3rd frame of the A1 autotiles are
+    /// stored after the last tile id.
+    pub fn new_ace(
+        graphics_state: &GraphicsState,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        tileset: &Tileset,
+    ) -> Atlas {
+        let sheets = std::array::from_fn(|i| {
+            let name = tileset.tileset_names.get(i).map_or("", String::as_str);
+            load_sheet(
+                graphics_state,
+                filesystem,
+                "Graphics/Tilesets",
+                name,
+                SHEET_SIZES[i],
+            )
+        });
+        Self::from_ace_sheets(graphics_state, &sheets, tileset.id)
+    }
+
+    /// Creates the atlas of the tileset every RPG Maker VX map uses. It's made up of the same
+    /// sheets as an RPG Maker VX Ace tileset, which are always `TileA1` to `TileE` from
+    /// `Graphics/System`, so tile ids are laid out the same way.
+    pub fn new_vx(
+        graphics_state: &GraphicsState,
+        filesystem: &impl luminol_filesystem::FileSystem,
+    ) -> Atlas {
+        let sheets = std::array::from_fn(|i| {
+            load_sheet(
+                graphics_state,
+                filesystem,
+                "Graphics/System",
+                VX_SHEET_NAMES[i],
+                SHEET_SIZES[i],
+            )
+        });
+        Self::from_ace_sheets(graphics_state, &sheets, crate::loaders::atlas::VX_ATLAS_ID)
+    }
+
+    fn from_ace_sheets(
+        graphics_state: &GraphicsState,
+        sheets: &[Option<RgbaImage>; 9],
+        id: usize,
+    ) -> Atlas {
+        let [a1, a2, a3, a4, a5, upper_sheets @ ..] = sheets;
+
+        let mut atlas = RgbaImage::new(ACE_ATLAS_WIDTH, ACE_ATLAS_HEIGHT);
+
+        // B to E
+        for (sheet_index, sheet) in upper_sheets.iter().enumerate() {
+            let Some(sheet) = sheet else { continue };
+            for n in 0..256 {
+                let tile_id = Tileset::TILE_ID_B as u32 + sheet_index as u32 * 256 + n;
+                let source = (n % 8 + n / 128 * 8, n / 8 % 16);
+                blit_tile(&mut atlas, sheet, source, tile_id);
+            }
+        }
+
+        // A5
+        if let Some(sheet) = a5 {
+            for n in 0..128 {
+                blit_tile(
+                    &mut atlas,
+                    sheet,
+                    (n % 8, n / 8),
+                    Tileset::TILE_ID_A5 as u32 + n,
+                );
+            }
+        }
+
+        // A1
+        if let Some(sheet) = a1 {
+            for (index, &(x, y)) in A1_POSITIONS.iter().enumerate() {
+                let base_id = Tileset::TILE_ID_A1 as u32 + index as u32 * 48;
+                let (_, kind) = Tileset::autotile_kind(base_id as i16).unwrap();
+                for frame in 0..ACE_A1_FRAMES {
+                    // Autotiles 2 and 3 are not animated, but they still need to be present on
+                    // every frame
+                    let block = match (index, kind) {
+                        (2 | 3, _) => (x, y),
+                        (_, AutotileKind::Waterfall) => (x, y + frame),
+                        _ => (x + frame * 2, y),
+                    };
+                    compose_autotile(&mut atlas, sheet, block, kind, base_id, frame);
+                }
+            }
+        }
+
+        // A2 to A4
+        for (sheet, first_id, count) in [
+            (a2, Tileset::TILE_ID_A2, 32),
+            (a3, Tileset::TILE_ID_A3, 32),
+            (a4, Tileset::TILE_ID_A4, 48),
+        ] {
+            let Some(sheet) = sheet else { continue };
+            for k in 0..count {
+                let base_id = first_id as u32 + k * 48;
+                let Some((_, kind)) = Tileset::autotile_kind(base_id as i16) else {
+                    continue;
+                };
+                let block = if first_id == Tileset::TILE_ID_A2 {
+                    (k % 8 * 2, k / 8 * 3)
+                } else if first_id == Tileset::TILE_ID_A3 {
+                    (k % 8 * 2, k / 8 * 2)
+                } else {
+                    // A4 alternates between 3 tile tall rows of roofs and 2 tile tall rows of walls
+                    let row = k / 8;
+                    (k % 8 * 2, row / 2 * 5 + row % 2 * 3)
+                };
+                compose_autotile(&mut atlas, sheet, block, kind, base_id, 0);
+            }
+        }
+
+        let atlas_texture = graphics_state.render_state.device.create_texture_with_data(
+            &graphics_state.render_state.queue,
+            &wgpu::TextureDescriptor {
+                label: Some("tileset_atlas"),
+                size: wgpu::Extent3d {
+                    width: ACE_ATLAS_WIDTH,
+                    height: ACE_ATLAS_HEIGHT,
+                    depth_or_array_layers: 1,
+                },
+                dimension: wgpu::TextureDimension::D2,
+                mip_level_count: 1,
+                sample_count: 1,
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                usage: wgpu::TextureUsages::COPY_SRC
+                    | wgpu::TextureUsages::COPY_DST
+                    | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            atlas.as_raw(),
+        );
+
+        let atlas_texture = graphics_state
+            .texture_loader
+            .register_texture(format!("tileset_atlases/{id}"), atlas_texture);
+
+        Atlas {
+            atlas_texture,
+            autotile_width: 0,
+            tileset_height: 0,
+            autotile_frames: [ACE_A1_FRAMES; AUTOTILE_AMOUNT as usize],
+            layout: AtlasLayout::Ace,
+        }
+    }
+}
+
+/// Returns the position of a tile id on a VX Ace atlas, in tiles. `frame` is only meaningful for
+/// A1 autotiles.
+pub(super) fn ace_atlas_position(tile_id: u32, frame: u32) -> (u32, u32) {
+    let index = if frame == 0
+        || !(Tileset::TILE_ID_A1 as u32..Tileset::TILE_ID_A2 as u32).contains(&tile_id)
+    {
+        tile_id
+    } else {
+        ACE_FRAME_OFFSET + (frame - 1) * ACE_A1_FRAME_SIZE + (tile_id - Tileset::TILE_ID_A1 as u32)
+    };
+    (index % ACE_ATLAS_COLUMNS, index / ACE_ATLAS_COLUMNS)
+}
+
+fn load_sheet(
+    graphics_state: &GraphicsState,
+    filesystem: &impl luminol_filesystem::FileSystem,
+    directory: &str,
+    name: &str,
+    (width, height): (u32, u32),
+) -> Option<RgbaImage> {
+    if name.is_empty() {
+        return None;
+    }
+
+    let result = filesystem
+        .read(camino::Utf8Path::new(directory).join(name))
+        .and_then(|file| image::load_from_memory(&file).map_err(|e| e.into()))
+        .wrap_err_with(|| format!("Error loading atlas tileset {name:?}"));
+    match result {
+        Ok(img) => Some(img.into_rgba8()),
+        Err(e) => {
+            graphics_state.send_texture_error(e);
+
+            // Tile the placeholder image over the area the sheet would have covered
+            let placeholder_img = graphics_state.texture_loader.placeholder_image();
+            Some(RgbaImage::from_fn(width, height, |x, y| {
+                *placeholder_img
+                    .get_pixel(x % placeholder_img.width(), y % placeholder_img.height())
+            }))
+        }
+    }
+}
+
+/// Copies a `size` pixel region, skipping it if it's not entirely inside of both images (sheets
+/// can be smaller than RPG Maker expects them to be).
+fn blit(
+    atlas: &mut RgbaImage,
+    sheet: &RgbaImage,
+    (source_x, source_y): (u32, u32),
+    (dest_x, dest_y): (u32, u32),
+    (width, height): (u32, u32),
+) {
+    if source_x + width > sheet.width()
+        || source_y + height > sheet.height()
+        || dest_x + width > atlas.width()
+        || dest_y + height > atlas.height()
+    {
+        return;
+    }
+
+    for y in 0..height {
+        for x in 0..width {
+            atlas.put_pixel(
+                dest_x + x,
+                dest_y + y,
+                *sheet.get_pixel(source_x + x, source_y + y),
+            );
+        }
+    }
+}
+
+fn blit_tile(atlas: &mut RgbaImage, sheet: &RgbaImage, (x, y): (u32, u32), tile_id: u32) {
+    let (dest_x, dest_y) = ace_atlas_position(tile_id, 0);
+    blit(
+        atlas,
+        sheet,
+        (x * TILE_SIZE, y * TILE_SIZE),
+        (dest_x * TILE_SIZE, dest_y * TILE_SIZE),
+        (TILE_SIZE, TILE_SIZE),
+    );
+}
+
+/// Builds every shape of an autotile out of the block of the sheet at `block` (in tiles).
+fn compose_autotile(
+    atlas: &mut RgbaImage,
+    sheet: &RgbaImage,
+    block: (u32, u32),
+    kind: AutotileKind,
+    base_id: u32,
+    frame: u32,
+) {
+    for shape in 0..kind.shape_count() as u32 {
+        let (dest_x, dest_y) = ace_atlas_position(base_id + shape, frame);
+        for quarter in 0..4 {
+            let (half_x, half_y) = autotile_quarter(kind, shape, quarter);
+            blit(
+                atlas,
+                sheet,
+                (
+                    block.0 * TILE_SIZE + half_x * 16,
+                    block.1 * TILE_SIZE + half_y * 16,
+                ),
+                (
+                    dest_x * TILE_SIZE + quarter % 2 * 16,
+                    dest_y * TILE_SIZE + quarter / 2 * 16,
+                ),
+                (16, 16),
+            );
+        }
+    }
+}
+
+/// Returns where the top left, top right, bottom left or bottom right (`quarter` 0 to 3) 16x16
+/// quarter of an autotile shape comes from, in half tiles relative to the autotile's block.
+fn autotile_quarter(kind: AutotileKind, shape: u32, quarter: u32) -> (u32, u32) {
+    let left = quarter % 2 == 0;
+    let top = quarter / 2 == 0;
+    let side = |edge: bool, first: bool, outer: (u32, u32), inner: (u32, u32)| match (edge, first) {
+        (true, true) => outer.0,
+        (true, false) => outer.1,
+        (false, true) => inner.0,
+        (false, false) => inner.1,
+    };
+
+    match kind {
+        // Floors use the same 48 shapes as RPG Maker XP autotiles, but the block is 2 tiles wide
+        // instead of 3, so the XP sub-tiles are mapped onto the smaller block
+        AutotileKind::Floor => {
+            let sub_tile = AUTOTILES[shape as usize][quarter as usize];
+            let (x, y) = (sub_tile % 6, sub_tile / 6);
+            if y < 2 {
+                // The preview tile and the inner corners
+                (if x < 2 { x } else { x - 2 }, y)
+            } else {
+                let y = y - 2;
+                (
+                    side(x == 0 || x == 5, left, (0, 3), (2, 1)),
+                    side(y == 0 || y == 5, top, (2, 5), (4, 3)),
+                )
+            }
+        }
+        // Bit 0 is the left edge, bit 1 the top edge, bit 2 the right edge and bit 3 the bottom edge
+        AutotileKind::Wall => (
+            side(shape & if left { 1 } else { 4 } != 0, left, (0, 3), (2, 1)),
+            side(shape & if top { 2 } else { 8 } != 0, top, (0, 3), (2, 1)),
+        ),
+        // Bit 0 is the left edge and bit 1 the right edge
+        AutotileKind::Waterfall => (
+            side(shape & if left { 1 } else { 2 } != 0, left, (0, 3), (2, 1)),
+            if top { 0 } else { 1 },
+        ),
+    }
+}
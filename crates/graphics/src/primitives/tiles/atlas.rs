@@ -46,12 +46,23 @@ pub const AUTOTILE_FRAME_WIDTH: u32 = AUTOTILE_FRAME_COLS * TILE_SIZE; // This i
 use image::GenericImageView;
 use std::sync::Arc;
 
+/// How tile ids are laid out on an atlas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AtlasLayout {
+    /// RPG Maker XP: 7 autotiles followed by a single tileset graphic.
+    XP,
+    /// RPG Maker VX Ace: the A1 to A5 and B to E graphics, with tile ids mapping directly onto the
+    /// atlas.
+    Ace,
+}
+
 #[derive(Clone)]
 pub struct Atlas {
     pub atlas_texture: Arc<Texture>,
     pub autotile_width: u32,
     pub tileset_height: u32,
     pub autotile_frames: [u32; AUTOTILE_AMOUNT as usize],
+    pub layout: AtlasLayout,
 }
 
 impl Atlas {
@@ -287,12 +298,18 @@ impl Atlas {
             autotile_width,
             tileset_height,
             autotile_frames,
+            layout: AtlasLayout::XP,
         }
     }
 
     pub fn calc_quad(&self, tile: i16) -> Quad {
         let tile_u32 = if tile < 0 { 0 } else { tile as u32 };
 
+        if self.layout == AtlasLayout::Ace {
+            let (x, y) = super::ace::ace_atlas_position(tile_u32, 0);
+            return Self::tile_quad(egui::pos2((x * TILE_SIZE) as f32, (y * TILE_SIZE) as f32));
+        }
+
         let is_autotile = tile_u32 < TOTAL_AUTOTILE_ID_AMOUNT;
         let max_frame_count = self.autotile_width / AUTOTILE_FRAME_WIDTH;
         let max_tiles_under_autotiles = max_frame_count * ROWS_UNDER_AUTOTILES_TIMES_COLUMNS;
@@ -331,6 +348,10 @@ impl Atlas {
             )
         };
 
+        Self::tile_quad(atlas_tile_position)
+    }
+
+    fn tile_quad(atlas_tile_position: egui::Pos2) -> Quad {
         Quad::new(
             egui::Rect::from_min_size(
                 egui::pos2(0., 0.),
@@ -32,7 +32,8 @@ struct Data {
     _array_padding: u32,
     ani_index: u32,
     max_frame_count: u32,
-    _end_padding: u64,
+    ace_layout: u32,
+    _end_padding: u32,
 }
 
 impl Autotiles {
@@ -41,6 +42,7 @@ impl Autotiles {
             autotile_frames: atlas.autotile_frames,
             max_frame_count: atlas.autotile_width / super::atlas::AUTOTILE_FRAME_WIDTH,
             ani_index: 0,
+            ace_layout: (atlas.layout == super::AtlasLayout::Ace) as u32,
             _array_padding: 0,
             _end_padding: 0,
         };
@@ -24,6 +24,7 @@ pub struct Instances {
 
     map_width: usize,
     map_height: usize,
+    layers: usize,
 }
 
 #[repr(C)]
@@ -36,8 +37,9 @@ impl Instances {
     pub fn new(
         render_state: &luminol_egui_wgpu::RenderState,
         map_data: &luminol_data::Table3,
+        layers: usize,
     ) -> Self {
-        let instances = Self::calculate_instances(map_data);
+        let instances = Self::calculate_instances(map_data, layers);
         let instance_buffer =
             render_state
                 .device
@@ -52,6 +54,7 @@ impl Instances {
 
             map_width: map_data.xsize(),
             map_height: map_data.ysize(),
+            layers,
         }
     }
 
@@ -62,6 +65,10 @@ impl Instances {
         tile_id: i16,
         position: (usize, usize, usize),
     ) {
+        if position.2 >= self.layers {
+            return;
+        }
+
         let offset = position.0
             + (position.1 * self.map_width)
             + (position.2 * self.map_width * self.map_height);
@@ -75,10 +82,11 @@ impl Instances {
         )
     }
 
-    fn calculate_instances(map_data: &luminol_data::Table3) -> Vec<Instance> {
+    fn calculate_instances(map_data: &luminol_data::Table3, layers: usize) -> Vec<Instance> {
         map_data
             .iter()
             .copied()
+            .take(map_data.xsize() * map_data.ysize() * layers)
             // Previously we'd filter out tiles that would not display (anything < 48).
             // However, storing the entire map like this makes it easier to edit tiles without remaking the entire buffer.
             // It's a memory tradeoff for a lot of performance.
@@ -28,6 +28,7 @@ use autotiles::Autotiles;
 use display::Display;
 use instance::Instances;
 
+mod ace;
 mod atlas;
 mod autotile_ids;
 pub(crate) mod autotiles;
@@ -56,13 +57,19 @@ impl Tiles {
         viewport: &Viewport,
         transform: Transform,
     ) -> Self {
+        // The 4th layer of RPG Maker VX Ace maps holds shadows and regions, which aren't tiles
+        let layers = match atlas.layout {
+            AtlasLayout::XP => tiles.zsize(),
+            AtlasLayout::Ace => tiles.zsize().min(3),
+        };
+
         let autotiles = Autotiles::new(graphics_state, atlas);
-        let instances = Instances::new(&graphics_state.render_state, tiles);
+        let instances = Instances::new(&graphics_state.render_state, tiles, layers);
         let display = Display::new(
             graphics_state,
             tiles.xsize() as u32,
             tiles.ysize() as u32,
-            tiles.zsize(),
+            layers,
         );
 
         let mut bind_group_builder = BindGroupBuilder::new();
@@ -84,7 +91,7 @@ impl Tiles {
             autotiles,
             display,
             transform,
-            enabled_layers: vec![true; tiles.zsize()],
+            enabled_layers: vec![true; layers],
             selected_layer: None,
             auto_opacity: true,
 
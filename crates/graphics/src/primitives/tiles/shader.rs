@@ -88,6 +88,30 @@ pub fn create_render_pipeline(
                 "AUTOTILE_FRAME_WIDTH".to_string(),
                 naga_oil::compose::ShaderDefValue::UInt(super::atlas::AUTOTILE_FRAME_WIDTH),
             ),
+            (
+                "ACE_ATLAS_COLUMNS".to_string(),
+                naga_oil::compose::ShaderDefValue::UInt(super::ace::ACE_ATLAS_COLUMNS),
+            ),
+            (
+                "ACE_A1_FRAME_SIZE".to_string(),
+                naga_oil::compose::ShaderDefValue::UInt(super::ace::ACE_A1_FRAME_SIZE),
+            ),
+            (
+                "ACE_FRAME_OFFSET".to_string(),
+                naga_oil::compose::ShaderDefValue::UInt(super::ace::ACE_FRAME_OFFSET),
+            ),
+            (
+                "ACE_TILE_ID_A1".to_string(),
+                naga_oil::compose::ShaderDefValue::UInt(
+                    luminol_data::rmvxace::Tileset::TILE_ID_A1 as u32,
+                ),
+            ),
+            (
+                "ACE_TILE_ID_A2".to_string(),
+                naga_oil::compose::ShaderDefValue::UInt(
+                    luminol_data::rmvxace::Tileset::TILE_ID_A2 as u32,
+                ),
+            ),
         ]),
         additional_imports: &[],
     })?;
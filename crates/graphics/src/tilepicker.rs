@@ -38,6 +38,8 @@ pub struct Tilepicker {
     pub collision: Collision,
     pub grid: Grid,
     pub atlas: Atlas,
+    /// The tile ID shown at every position of the tilepicker.
+    pub data: luminol_data::Table3,
 
     pub viewport: Viewport,
     ani_time: Option<f64>,
@@ -118,6 +120,7 @@ impl Tilepicker {
             collision,
             grid,
             atlas,
+            data: tilepicker_data,
 
             viewport,
 
@@ -127,6 +130,126 @@ impl Tilepicker {
         })
     }
 
+    /// Creates a tilepicker for an RPG Maker VX Ace tileset. The first rows hold a preview of
+    /// every autotile, followed by the tiles of A5 and B to E. Graphics the tileset doesn't use are
+    /// left out.
+    pub fn new_ace(
+        graphics_state: &GraphicsState,
+        tileset: &luminol_data::rmvxace::Tileset,
+        filesystem: &impl luminol_filesystem::FileSystem,
+    ) -> color_eyre::Result<Self> {
+        let atlas =
+            graphics_state
+                .atlas_loader
+                .load_ace_atlas(graphics_state, filesystem, tileset)?;
+        let has_sheet = |i: usize| tileset.tileset_names.get(i).is_some_and(|n| !n.is_empty());
+        Ok(Self::from_ace_atlas(
+            graphics_state,
+            atlas,
+            has_sheet,
+            &tileset.flags,
+        ))
+    }
+
+    /// Creates a tilepicker for the tileset of RPG Maker VX, laid out like the one of an RPG Maker
+    /// VX Ace tileset. `passages` are the passages of the system.
+    pub fn new_vx(
+        graphics_state: &GraphicsState,
+        passages: &luminol_data::Table1,
+        filesystem: &impl luminol_filesystem::FileSystem,
+    ) -> color_eyre::Result<Self> {
+        let atlas = graphics_state
+            .atlas_loader
+            .load_vx_atlas(graphics_state, filesystem)?;
+        // Every sheet is used, since all maps share them
+        Ok(Self::from_ace_atlas(
+            graphics_state,
+            atlas,
+            |_| true,
+            passages,
+        ))
+    }
+
+    fn from_ace_atlas(
+        graphics_state: &GraphicsState,
+        atlas: Atlas,
+        has_sheet: impl Fn(usize) -> bool,
+        flags: &luminol_data::Table1,
+    ) -> Self {
+        use luminol_data::rmvxace::Tileset;
+
+        let autotile_preview = |index: i16| {
+            let base_id = Tileset::TILE_ID_A1 + index * 48;
+            Tileset::autotile_kind(base_id)
+                .map_or(base_id, |(_, kind)| base_id + kind.shape_count() - 1)
+        };
+        let mut tilepicker_data = [(0, 0..16), (1, 16..48), (2, 48..80), (3, 80..128)]
+            .into_iter()
+            .filter(|(sheet, _)| has_sheet(*sheet))
+            .flat_map(|(_, autotiles)| autotiles.map(autotile_preview))
+            .collect_vec();
+        if has_sheet(4) {
+            tilepicker_data.extend(Tileset::TILE_ID_A5..Tileset::TILE_ID_A5 + 128);
+        }
+        for sheet in 0..4 {
+            if has_sheet(5 + sheet) {
+                let first_id = Tileset::TILE_ID_B + sheet as i16 * 256;
+                tilepicker_data.extend(first_id..first_id + 256);
+            }
+        }
+        if tilepicker_data.is_empty() {
+            tilepicker_data = vec![0; 8];
+        }
+        let rows = tilepicker_data.len() / 8;
+        let tilepicker_data = luminol_data::Table3::new_data(8, rows, 1, tilepicker_data);
+
+        let viewport = Viewport::new(graphics_state, glam::vec2(256., rows as f32 * 32.));
+
+        let tiles = Tiles::new(
+            graphics_state,
+            &tilepicker_data,
+            &atlas,
+            &viewport,
+            Transform::unit(graphics_state),
+        );
+
+        let grid = Grid::new(
+            graphics_state,
+            &viewport,
+            Transform::unit(graphics_state),
+            tilepicker_data.xsize() as u32,
+            tilepicker_data.ysize() as u32,
+        );
+
+        let mut passages = luminol_data::Table2::new(8, rows);
+        Collision::calculate_passages_ace(
+            flags,
+            &tilepicker_data,
+            std::iter::once(0),
+            |x, y, passage| passages[(x, y)] = passage,
+        );
+        let collision = Collision::new(
+            graphics_state,
+            &viewport,
+            Transform::unit(graphics_state),
+            &passages,
+        );
+
+        Self {
+            tiles,
+            collision,
+            grid,
+            atlas,
+            data: tilepicker_data,
+
+            viewport,
+
+            coll_enabled: false,
+            grid_enabled: true,
+            ani_time: None,
+        }
+    }
+
     pub fn update_animation(&mut self, render_state: &luminol_egui_wgpu::RenderState, time: f64) {
         if let Some(ani_time) = self.ani_time {
             if time - ani_time >= 16. / 60. {
@@ -22,8 +22,10 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use std::collections::HashMap;
 use std::marker::PhantomData;
 
+use luminol_components::usages::{self, Usage};
 use luminol_components::UiExt;
 
 mod variable;
@@ -46,6 +48,9 @@ enum State {
         search_text: String,
         selected_id: usize,
         new_size: Option<usize>,
+        /// Where each entry is used, once it's been looked up.
+        usages: Option<HashMap<usize, Vec<Usage>>>,
+        unused_only: bool,
     },
 }
 
@@ -64,6 +69,35 @@ pub trait DatabaseModalHandler {
         None
     }
     fn resize(update_state: &mut luminol_core::UpdateState<'_>, new_size: usize) {}
+
+    /// The kind of ID the entries are, if [`luminol_components::usages`] can find where they're
+    /// used.
+    fn usage_kind() -> Option<usages::Kind> {
+        None
+    }
+}
+
+/// Finds where every entry of the given kind is used, keyed by entry.
+///
+/// Anything taken out of the data cache for editing while this runs isn't searched. That's the
+/// event being edited in the event editor, for example.
+fn find_usages(
+    kind: usages::Kind,
+    update_state: &luminol_core::UpdateState<'_>,
+) -> HashMap<usize, Vec<Usage>> {
+    let command_db = &update_state
+        .project_config
+        .as_ref()
+        .expect("project not loaded")
+        .command_db;
+
+    let mut map: HashMap<usize, Vec<Usage>> = HashMap::new();
+    for usage in usages::find(&*update_state.data, &*update_state.filesystem, command_db) {
+        if usage.kind == kind {
+            map.entry(usage.id).or_default().push(usage);
+        }
+    }
+    map
 }
 
 impl<M> Modal<M>
@@ -103,6 +137,8 @@ where
                     search_text: String::new(),
                     selected_id: *data,
                     new_size: M::current_size(update_state),
+                    usages: None,
+                    unused_only: false,
                 };
             }
             if ui.is_enabled() {
@@ -137,6 +173,8 @@ where
             search_text,
             selected_id,
             new_size,
+            usages,
+            unused_only,
         } = &mut self.state
         else {
             return;
@@ -160,12 +198,25 @@ where
                                     if matcher.fuzzy(&text, search_text, false).is_none() {
                                         continue;
                                     }
+                                    let use_count = usages
+                                        .as_ref()
+                                        .map(|usages| usages.get(&id).map_or(0, Vec::len));
+                                    if *unused_only && use_count.is_some_and(|count| count > 0) {
+                                        continue;
+                                    }
                                     is_faint = !is_faint;
 
                                     ui.with_stripe(is_faint, |ui| {
                                         ui.horizontal(|ui| {
                                             let response =
                                                 ui.selectable_value(selected_id, id, text);
+                                            if let Some(count) = use_count {
+                                                ui.weak(match count {
+                                                    0 => "unused".to_string(),
+                                                    1 => "1 use".to_string(),
+                                                    _ => format!("{count} uses"),
+                                                });
+                                            }
                                             ui.add_space(ui.available_width());
                                             if response.double_clicked() {
                                                 keep_open = false;
@@ -178,6 +229,44 @@ where
                         })
                 });
 
+                if let Some(kind) = M::usage_kind() {
+                    ui.label("Used by");
+                    ui.group(|ui| {
+                        ui.set_width(ui.available_width());
+                        match usages.as_ref() {
+                            None => {
+                                ui.horizontal(|ui| {
+                                    if ui.button("Find usages").clicked() {
+                                        *usages = Some(find_usages(kind, update_state));
+                                    }
+                                    ui.weak("Loads every map, which may take a while");
+                                });
+                            }
+                            Some(usages) => match usages.get(selected_id) {
+                                Some(list) if !list.is_empty() => {
+                                    egui::ScrollArea::vertical()
+                                        .id_source("used_by")
+                                        .max_height(128.)
+                                        .show(ui, |ui| {
+                                            for usage in list {
+                                                ui.add(
+                                                    egui::Label::new(format!(
+                                                        "{}: {}",
+                                                        usage.source, usage.description
+                                                    ))
+                                                    .truncate(true),
+                                                );
+                                            }
+                                        });
+                                }
+                                _ => {
+                                    ui.weak("Not used by any events");
+                                }
+                            },
+                        }
+                    });
+                }
+
                 if M::current_size(update_state).is_some_and(|size| size <= 999) && new_size.is_some_and(|size| size > 999) {
                     egui::Frame::none().show(ui, |ui| {
                         ui.style_mut()
@@ -210,6 +299,11 @@ where
                     egui::TextEdit::singleline(search_text)
                         .hint_text("Search 🔎")
                         .show(ui);
+
+                    if M::usage_kind().is_some() {
+                        ui.add_enabled(usages.is_some(), egui::Checkbox::new(unused_only, "Unused only"))
+                            .on_disabled_hover_text("Find usages first");
+                    }
                 });
             });
 
@@ -56,4 +56,8 @@ impl super::DatabaseModalHandler for Variable {
         let system = &mut update_state.data.system();
         system.variables.resize_with(new_size, String::new);
     }
+
+    fn usage_kind() -> Option<luminol_components::usages::Kind> {
+        Some(luminol_components::usages::Kind::Variable)
+    }
 }
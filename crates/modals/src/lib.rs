@@ -27,3 +27,7 @@ pub mod sound_picker;
 pub mod graphic_picker;
 
 pub mod database_modal;
+
+pub mod map_position_picker;
+
+pub mod move_route;
@@ -0,0 +1,209 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_components::UiExt;
+
+/// A modal for picking a map and a tile on it.
+pub struct Modal {
+    state: State,
+    id_source: egui::Id,
+}
+
+enum State {
+    Closed,
+    Open {
+        map_id: usize,
+        x: i32,
+        y: i32,
+        view: Option<luminol_components::MapPositionView>,
+    },
+}
+
+impl Modal {
+    pub fn new(id_source: impl Into<egui::Id>) -> Self {
+        Self {
+            state: State::Closed,
+            id_source: id_source.into(),
+        }
+    }
+
+    fn load_view(
+        update_state: &mut luminol_core::UpdateState<'_>,
+        map_id: usize,
+    ) -> Option<luminol_components::MapPositionView> {
+        if !update_state.data.map_infos().data.contains_key(&map_id) {
+            return None;
+        }
+
+        match luminol_components::MapPositionView::new(update_state, map_id) {
+            Ok(view) => Some(view),
+            Err(e) => {
+                luminol_core::error!(
+                    update_state.toasts,
+                    e.wrap_err(format!("Error loading map {map_id}"))
+                );
+                None
+            }
+        }
+    }
+}
+
+impl luminol_core::Modal for Modal {
+    /// The map ID (as used by `MapInfos`) and the x and y coordinates of the tile.
+    type Data<'m> = (&'m mut usize, &'m mut i32, &'m mut i32);
+
+    fn button<'m>(
+        &'m mut self,
+        data: Self::Data<'m>,
+        update_state: &'m mut luminol_core::UpdateState<'_>,
+    ) -> impl egui::Widget + 'm {
+        move |ui: &mut egui::Ui| {
+            let (map_id, x, y) = data;
+
+            let button_text = {
+                let map_infos = update_state.data.map_infos();
+                let name = map_infos
+                    .data
+                    .get(&*map_id)
+                    .map_or("(Missing map)", |info| info.name.as_str());
+                format!("{:0>3}: {name} ({x}, {y})", *map_id)
+            };
+            let mut button_response = ui.button(button_text);
+
+            if button_response.clicked() {
+                let view = Self::load_view(update_state, *map_id);
+                self.state = State::Open {
+                    map_id: *map_id,
+                    x: *x,
+                    y: *y,
+                    view,
+                };
+            }
+            if self.show_window(update_state, ui.ctx(), (map_id, x, y)) {
+                button_response.mark_changed();
+            }
+
+            button_response
+        }
+    }
+
+    fn reset(&mut self, _: &mut luminol_core::UpdateState<'_>, _data: Self::Data<'_>) {
+        self.state = State::Closed;
+    }
+}
+
+impl Modal {
+    fn show_window(
+        &mut self,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        ctx: &egui::Context,
+        data: (&mut usize, &mut i32, &mut i32),
+    ) -> bool {
+        let mut win_open = true;
+        let mut keep_open = true;
+        let mut needs_save = false;
+
+        let State::Open { map_id, x, y, view } = &mut self.state else {
+            return false;
+        };
+
+        egui::Window::new("Map Position Picker")
+            .resizable(true)
+            .open(&mut win_open)
+            .id(self.id_source.with("window"))
+            .show(ctx, |ui| {
+                egui::TopBottomPanel::bottom(self.id_source.with("bottom_panel")).show_inside(
+                    ui,
+                    |ui| {
+                        ui.add_space(1.0);
+                        ui.horizontal(|ui| {
+                            luminol_components::close_options_ui(
+                                ui,
+                                &mut keep_open,
+                                &mut needs_save,
+                            );
+                            ui.label(format!("Position: ({x}, {y})"));
+                        });
+                    },
+                );
+
+                egui::SidePanel::left(self.id_source.with("map_list"))
+                    .resizable(false)
+                    .show_inside(ui, |ui| {
+                        let mut entries = {
+                            let map_infos = update_state.data.map_infos();
+                            map_infos
+                                .data
+                                .iter()
+                                .map(|(&id, info)| (id, format!("{id:0>3}: {}", info.name)))
+                                .collect::<Vec<_>>()
+                        };
+                        entries.sort_unstable_by_key(|(id, _)| *id);
+
+                        let mut new_map_id = None;
+                        egui::ScrollArea::vertical()
+                            .auto_shrink([false, false])
+                            .show(ui, |ui| {
+                                for (i, (id, text)) in entries.into_iter().enumerate() {
+                                    ui.with_stripe(i % 2 != 0, |ui| {
+                                        if ui.selectable_label(*map_id == id, text).clicked() {
+                                            new_map_id = Some(id);
+                                        }
+                                    });
+                                }
+                            });
+
+                        if let Some(id) = new_map_id.filter(|&id| id != *map_id) {
+                            *map_id = id;
+                            *x = 0;
+                            *y = 0;
+                            *view = Self::load_view(update_state, id);
+                        }
+                    });
+
+                egui::ScrollArea::both()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| match view {
+                        Some(view) => {
+                            view.ui(ui, update_state, (x, y));
+                        }
+                        None => {
+                            ui.label("Select a map to pick a position on it.");
+                        }
+                    });
+            });
+
+        if needs_save {
+            let (data_map_id, data_x, data_y) = data;
+            *data_map_id = *map_id;
+            *data_x = *x;
+            *data_y = *y;
+        }
+
+        if !(win_open && keep_open) {
+            self.state = State::Closed;
+        }
+        needs_save
+    }
+}
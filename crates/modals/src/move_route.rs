@@ -21,3 +21,98 @@
 // it with Steamworks API by Valve Corporation, containing parts covered by
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
+
+pub struct Modal {
+    state: State,
+    id_source: egui::Id,
+}
+
+enum State {
+    Closed,
+    Open {
+        route: luminol_data::rpg::MoveRoute,
+        view: luminol_components::MoveRouteView,
+    },
+}
+
+impl Modal {
+    pub fn new(id_source: impl Into<egui::Id>) -> Self {
+        Self {
+            state: State::Closed,
+            id_source: id_source.into(),
+        }
+    }
+}
+
+impl luminol_core::Modal for Modal {
+    type Data<'m> = &'m mut luminol_data::rpg::MoveRoute;
+
+    fn button<'m>(
+        &'m mut self,
+        data: Self::Data<'m>,
+        update_state: &'m mut luminol_core::UpdateState<'_>,
+    ) -> impl egui::Widget + 'm {
+        |ui: &mut egui::Ui| {
+            let mut button_response = ui.button("Move Route...");
+
+            if button_response.clicked() {
+                self.state = State::Open {
+                    route: data.clone(),
+                    view: luminol_components::MoveRouteView::new(self.id_source.with("view")),
+                };
+            }
+            if self.show_window(update_state, ui.ctx(), data) {
+                button_response.mark_changed()
+            }
+
+            button_response
+        }
+    }
+
+    fn reset(&mut self, _: &mut luminol_core::UpdateState<'_>, _data: Self::Data<'_>) {
+        // the route is copied when the window is opened, so there's nothing to keep in sync
+        self.state = State::Closed;
+    }
+}
+
+impl Modal {
+    pub fn show_window(
+        &mut self,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        ctx: &egui::Context,
+        data: &mut luminol_data::rpg::MoveRoute,
+    ) -> bool {
+        let mut win_open = true;
+        let mut keep_open = true;
+        let mut needs_save = false;
+
+        let State::Open { route, view } = &mut self.state else {
+            return false;
+        };
+
+        egui::Window::new("Move Route")
+            .open(&mut win_open)
+            .id(self.id_source.with("window"))
+            .show(ctx, |ui| {
+                egui::TopBottomPanel::bottom(self.id_source.with("bottom_panel")).show_inside(
+                    ui,
+                    |ui| {
+                        ui.add_space(1.0);
+                        luminol_components::close_options_ui(ui, &mut keep_open, &mut needs_save);
+                    },
+                );
+
+                view.ui(ui, update_state, route);
+            });
+
+        let changed = needs_save && *data != *route;
+        if changed {
+            *data = route.clone();
+        }
+
+        if !(win_open && keep_open) {
+            self.state = State::Closed;
+        }
+        changed
+    }
+}
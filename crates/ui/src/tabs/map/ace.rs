@@ -0,0 +1,162 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+impl super::Tab {
+    /// Shows the tilemap of an RPG Maker VX or VX Ace map. Only the tiles can be edited for now.
+    pub(super) fn show_ace_map(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        is_focused: bool,
+    ) {
+        if update_state.data.editor_ver() == Some(luminol_config::RMVer::VX) {
+            let mut map = update_state.data.get_vx_map(self.id);
+            let map = &mut *map;
+            let system = update_state.data.vx_system();
+            self.show_ace_tiles(
+                ui,
+                update_state,
+                &mut map.data,
+                &mut map.modified,
+                &system.passages,
+                is_focused,
+            );
+        } else {
+            let mut map = update_state.data.get_ace_map(self.id);
+            let map = &mut *map;
+            let tilesets = update_state.data.ace_tilesets();
+            let tileset = &tilesets.data[map.tileset_id];
+            self.show_ace_tiles(
+                ui,
+                update_state,
+                &mut map.data,
+                &mut map.modified,
+                &tileset.flags,
+                is_focused,
+            );
+        }
+    }
+
+    /// Edits the tiles of a map drawn with an [`luminol_graphics::AtlasLayout::Ace`] atlas.
+    /// `flags` are the passages of every tile id.
+    fn show_ace_tiles(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        data: &mut luminol_data::Table3,
+        modified: &mut bool,
+        flags: &luminol_data::Table1,
+        is_focused: bool,
+    ) {
+        // Save the state of the selected layer into the cache
+        if let luminol_components::SelectedLayer::Tiles(tile_layer) = self.view.selected_layer {
+            self.layer_cache
+                .copy_from_slice(data.layer_as_slice(tile_layer));
+        }
+
+        let response = self.view.ui(
+            ui,
+            update_state,
+            data,
+            None,
+            &self.tilepicker,
+            false,
+            self.drawing_shape,
+            self.drawing_shape_pos,
+            matches!(update_state.toolbar.pencil, luminol_core::Pencil::Pen),
+            is_focused,
+        );
+
+        if !response.is_pointer_button_down_on()
+            || ui.input(|i| !i.pointer.button_down(egui::PointerButton::Primary))
+        {
+            self.drawing_shape = false;
+            self.end_drawing(update_state, data, modified);
+        }
+
+        if let luminol_components::SelectedLayer::Tiles(tile_layer) = self.view.selected_layer {
+            // Tile drawing
+            if response.is_pointer_button_down_on()
+                && ui.input(|i| {
+                    i.pointer.button_down(egui::PointerButton::Primary) && !i.modifiers.command
+                })
+            {
+                if self.drawing_shape_pos.is_none() {
+                    // Before drawing tiles, save the state of the current layer so we can
+                    // undo it later if we need to
+                    self.tilemap_undo_cache_layer = tile_layer;
+                    self.tilemap_undo_cache.copy_from_slice(&self.layer_cache);
+                }
+
+                self.handle_brush(
+                    self.view.cursor_pos.x as usize,
+                    self.view.cursor_pos.y as usize,
+                    tile_layer,
+                    update_state.toolbar.pencil,
+                    data,
+                );
+            }
+        }
+
+        // Handle undo/redo keypresses. Only tile changes can end up in the history of these maps.
+        let (is_undo_pressed, is_redo_pressed) = Self::undo_redo_pressed(ui, &response, is_focused);
+        if is_undo_pressed || is_redo_pressed {
+            let entry = if is_undo_pressed {
+                self.history.pop_back()
+            } else {
+                self.redo_history.pop()
+            };
+
+            if let Some(super::HistoryEntry::Tiles { layer, delta }) = entry {
+                let new_entry = self.swap_tiles(update_state, data, layer, delta);
+                update_state.modified.set(true);
+                *modified = true;
+                if is_undo_pressed {
+                    self.redo_history.push(new_entry);
+                } else {
+                    self.history.push_back(new_entry);
+                }
+            }
+        }
+
+        self.write_tile_changes(update_state, data);
+
+        // Update the collision preview
+        luminol_graphics::Collision::calculate_passages_ace(
+            flags,
+            data,
+            (0..self.view.map.tiles.enabled_layers.len())
+                .filter(|&i| self.view.map.tiles.enabled_layers[i])
+                .rev(),
+            |x, y, passage| {
+                if self.passages[(x, y)] != passage {
+                    self.view
+                        .map
+                        .set_passage(&update_state.graphics.render_state, passage, (x, y));
+                    self.passages[(x, y)] = passage;
+                }
+            },
+        );
+    }
+}
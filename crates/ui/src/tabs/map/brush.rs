@@ -32,11 +32,12 @@ impl super::Tab {
         map_y: usize,
         tile_layer: usize,
         pencil: luminol_core::Pencil,
-        map: &mut luminol_data::rpg::Map,
+        map_data: &mut luminol_data::Table3,
     ) {
+        let layout = self.view.map.atlas.layout;
         let map_pos = egui::pos2(map_x as f32, map_y as f32);
         let initial_tile =
-            luminol_components::SelectedTile::from_id(map.data[(map_x, map_y, tile_layer)]);
+            luminol_components::SelectedTile::from_id(map_data[(map_x, map_y, tile_layer)], layout);
         let left = self.tilepicker.selected_tiles_left;
         let right = self.tilepicker.selected_tiles_right;
         let top = self.tilepicker.selected_tiles_top;
@@ -63,12 +64,12 @@ impl super::Tab {
                     let absolute_y = map_y + y as usize;
 
                     // Skip out-of-bounds tiles
-                    if absolute_x >= map.data.xsize() || absolute_y >= map.data.ysize() {
+                    if absolute_x >= map_data.xsize() || absolute_y >= map_data.ysize() {
                         continue;
                     }
 
                     self.set_tile(
-                        map,
+                        map_data,
                         self.tilepicker.get_tile_from_offset(
                             absolute_x as i16,
                             absolute_y as i16,
@@ -94,7 +95,7 @@ impl super::Tab {
                 let mut stack = vec![(map_x, map_y, tile_layer); 1];
                 while let Some(position) = stack.pop() {
                     self.set_tile(
-                        map,
+                        map_data,
                         self.tilepicker.get_tile_from_offset(
                             position.0 as i16,
                             position.1 as i16,
@@ -104,16 +105,16 @@ impl super::Tab {
                         ),
                         position,
                     );
-                    self.dfs_cache[position.0 + position.1 * map.data.xsize()] = true;
+                    self.dfs_cache[position.0 + position.1 * map_data.xsize()] = true;
 
                     let x_array: [isize; 4] = [-1, 1, 0, 0];
                     let y_array: [isize; 4] = [0, 0, -1, 1];
                     for (x, y) in x_array.into_iter().zip(y_array.into_iter()) {
                         // Don't search tiles that are out of bounds
                         if (x == -1 && position.0 == 0)
-                            || (x == 1 && position.0 + 1 == map.data.xsize())
+                            || (x == 1 && position.0 + 1 == map_data.xsize())
                             || (y == -1 && position.1 == 0)
-                            || (y == 1 && position.1 + 1 == map.data.ysize())
+                            || (y == 1 && position.1 + 1 == map_data.ysize())
                         {
                             continue;
                         }
@@ -126,11 +127,11 @@ impl super::Tab {
 
                         // Don't search tiles that we've already searched before
                         // because that would cause an infinite loop
-                        if self.dfs_cache[position.0 + position.1 * map.data.xsize()] {
+                        if self.dfs_cache[position.0 + position.1 * map_data.xsize()] {
                             continue;
                         }
 
-                        if luminol_components::SelectedTile::from_id(map.data[position])
+                        if luminol_components::SelectedTile::from_id(map_data[position], layout)
                             == initial_tile
                         {
                             stack.push(position);
@@ -146,19 +147,19 @@ impl super::Tab {
             luminol_core::Pencil::Rectangle => {
                 if !self.drawing_shape {
                     // Save the current layer
-                    for x in 0..map.data.xsize() {
-                        for y in 0..map.data.ysize() {
-                            self.brush_layer_cache[x + y * map.data.xsize()] =
-                                map.data[(x, y, tile_layer)];
+                    for x in 0..map_data.xsize() {
+                        for y in 0..map_data.ysize() {
+                            self.brush_layer_cache[x + y * map_data.xsize()] =
+                                map_data[(x, y, tile_layer)];
                         }
                     }
                     self.drawing_shape = true;
                 } else {
                     // Restore the previously stored state of the current layer
-                    for y in 0..map.data.ysize() {
-                        for x in 0..map.data.xsize() {
-                            map.data[(x, y, tile_layer)] =
-                                self.brush_layer_cache[x + y * map.data.xsize()];
+                    for y in 0..map_data.ysize() {
+                        for x in 0..map_data.xsize() {
+                            map_data[(x, y, tile_layer)] =
+                                self.brush_layer_cache[x + y * map_data.xsize()];
                         }
                     }
                 }
@@ -169,7 +170,7 @@ impl super::Tab {
                         for x in (bounding_rect.min.x as usize)..=(bounding_rect.max.x) as usize {
                             let position = (x, y, tile_layer);
                             self.set_tile(
-                                map,
+                                map_data,
                                 self.tilepicker.get_tile_from_offset(
                                     x as i16,
                                     y as i16,
@@ -186,22 +187,25 @@ impl super::Tab {
                 }
             }
 
+            // Selections are handled by the map tab, since they don't draw anything
+            luminol_core::Pencil::Select => {}
+
             luminol_core::Pencil::Circle => {
                 if !self.drawing_shape {
                     // Save the current layer
-                    for x in 0..map.data.xsize() {
-                        for y in 0..map.data.ysize() {
-                            self.brush_layer_cache[x + y * map.data.xsize()] =
-                                map.data[(x, y, tile_layer)];
+                    for x in 0..map_data.xsize() {
+                        for y in 0..map_data.ysize() {
+                            self.brush_layer_cache[x + y * map_data.xsize()] =
+                                map_data[(x, y, tile_layer)];
                         }
                     }
                     self.drawing_shape = true;
                 } else {
                     // Restore the previously stored state of the current layer
-                    for y in 0..map.data.ysize() {
-                        for x in 0..map.data.xsize() {
-                            map.data[(x, y, tile_layer)] =
-                                self.brush_layer_cache[x + y * map.data.xsize()];
+                    for y in 0..map_data.ysize() {
+                        for x in 0..map_data.xsize() {
+                            map_data[(x, y, tile_layer)] =
+                                self.brush_layer_cache[x + y * map_data.xsize()];
                         }
                     }
                 }
@@ -215,7 +219,7 @@ impl super::Tab {
                     // 1x1 ellipse.
                     if drawing_shape_pos == map_pos {
                         self.set_tile(
-                            map,
+                            map_data,
                             self.tilepicker.get_tile_from_offset(
                                 map_x as i16,
                                 map_y as i16,
@@ -266,7 +270,7 @@ impl super::Tab {
                                     let x = (x0 + j).floor();
                                     let y = (y0 + i).floor();
                                     self.set_tile(
-                                        map,
+                                        map_data,
                                         self.tilepicker.get_tile_from_offset(
                                             x as i16,
                                             y as i16,
@@ -311,7 +315,7 @@ impl super::Tab {
                                     let x = (x0 + i).floor();
                                     let y = (y0 + j).floor();
                                     self.set_tile(
-                                        map,
+                                        map_data,
                                         self.tilepicker.get_tile_from_offset(
                                             x as i16,
                                             y as i16,
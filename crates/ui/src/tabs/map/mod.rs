@@ -26,8 +26,6 @@
 use egui::Pos2;
 use std::{cell::RefMut, collections::HashMap, collections::VecDeque};
 
-const HISTORY_SIZE: usize = 50;
-
 struct EventDragInfo {
     /// ID of the event being dragged
     id: usize,
@@ -44,8 +42,10 @@ use crate::windows::event_edit;
 
 use itertools::Itertools;
 
+mod ace;
 mod brush;
 mod history;
+mod selection;
 mod util;
 
 pub struct Tab {
@@ -94,6 +94,18 @@ pub struct Tab {
 
     /// Asynchronous task used to save the map as an image file
     save_as_image_promise: Option<poll_promise::Promise<color_eyre::Result<()>>>,
+
+    /// The tiles selected with the selection tool, in map coordinates
+    selection: Option<egui::Rect>,
+    /// The tile the user started dragging the selection from
+    selection_start: Option<egui::Pos2>,
+    /// Whether the clipboard follows the cursor, waiting to be pasted
+    pasting: bool,
+    /// Where the paste preview was drawn and the tiles it covers, which need to be put back
+    /// when it moves
+    paste_preview: Option<(egui::Pos2, Vec<(usize, usize, usize)>)>,
+    /// Whether copying tiles also copies the events on them
+    copy_events: bool,
 }
 
 // TODO: If we add support for changing event IDs, these need to be added as history entries
@@ -113,9 +125,22 @@ enum HistoryEntry {
         event: luminol_data::rpg::Event,
         sprite: Option<luminol_graphics::Event>,
     },
+    /// Contains the (x, y, z, tile_id) delta for tiles changed on any layer, along with the IDs of
+    /// the events that were created and the events that were deleted at the same time. Pasting
+    /// creates these.
+    Region {
+        delta: Vec<(usize, usize, usize, i16)>,
+        created: Vec<usize>,
+        deleted: Vec<(luminol_data::rpg::Event, Option<luminol_graphics::Event>)>,
+    },
 }
 
 impl Tab {
+    /// The ID of the tab editing the map with this ID.
+    pub fn tab_id(map_id: usize) -> egui::Id {
+        egui::Id::new("luminol_map").with(map_id)
+    }
+
     /// Create a new map editor.
     pub fn new(
         id: usize,
@@ -123,24 +148,15 @@ impl Tab {
     ) -> color_eyre::Result<Self> {
         // *sigh*
         // borrow checker.
-        let view = luminol_components::MapView::new(update_state, id)?;
+        let mut view = luminol_components::MapView::new(update_state, id)?;
+        if view.map.atlas.layout == luminol_graphics::AtlasLayout::Ace {
+            // There is no event layer to select on these maps yet
+            view.selected_layer = luminol_components::SelectedLayer::Tiles(0);
+        }
         let tilepicker = luminol_components::Tilepicker::new(update_state, id)?;
 
-        let map = update_state
-            .data
-            .get_or_load_map(id, update_state.filesystem);
-        let tilesets = update_state.data.tilesets();
-        let tileset = &tilesets.data[map.tileset_id];
-
-        let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
-        luminol_graphics::Collision::calculate_passages(
-            &tileset.passages,
-            &tileset.priorities,
-            &map.data,
-            Some(&map.events),
-            (0..map.data.zsize()).rev(),
-            |x, y, passage| passages[(x, y)] = passage,
-        );
+        let passages = Self::calculate_passages(update_state, id);
+        let (width, height) = (passages.xsize(), passages.ysize());
 
         let mut brush_seed = [0u8; 16];
         brush_seed[0..8].copy_from_slice(
@@ -166,15 +182,15 @@ impl Tab {
 
             event_drag_info: None,
 
-            layer_cache: vec![0; map.data.xsize() * map.data.ysize()],
+            layer_cache: vec![0; width * height],
 
-            dfs_cache: vec![false; map.data.xsize() * map.data.ysize()],
-            brush_layer_cache: vec![0; map.data.xsize() * map.data.ysize()],
+            dfs_cache: vec![false; width * height],
+            brush_layer_cache: vec![0; width * height],
             drawing_shape_pos: None,
 
-            history: VecDeque::with_capacity(HISTORY_SIZE),
-            redo_history: Vec::with_capacity(HISTORY_SIZE),
-            tilemap_undo_cache: vec![0; map.data.xsize() * map.data.ysize()],
+            history: VecDeque::new(),
+            redo_history: Vec::new(),
+            tilemap_undo_cache: vec![0; width * height],
             tilemap_undo_cache_layer: 0,
 
             passages,
@@ -183,27 +199,157 @@ impl Tab {
             brush_seed,
 
             save_as_image_promise: None,
+
+            selection: None,
+            selection_start: None,
+            pasting: false,
+            paste_preview: None,
+            copy_events: true,
         })
     }
 }
 
+impl Tab {
+    fn is_ace(update_state: &luminol_core::UpdateState<'_>) -> bool {
+        update_state.data.editor_ver() == Some(luminol_config::RMVer::Ace)
+    }
+
+    fn is_vx(update_state: &luminol_core::UpdateState<'_>) -> bool {
+        update_state.data.editor_ver() == Some(luminol_config::RMVer::VX)
+    }
+
+    /// Determines the passage values of the whole map, as shown by the collision preview. The map
+    /// must already be loaded.
+    fn calculate_passages(
+        update_state: &luminol_core::UpdateState<'_>,
+        id: usize,
+    ) -> luminol_data::Table2 {
+        if Self::is_ace(update_state) {
+            let map = update_state.data.get_ace_map(id);
+            let tilesets = update_state.data.ace_tilesets();
+            let tileset = &tilesets.data[map.tileset_id];
+
+            let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
+            luminol_graphics::Collision::calculate_passages_ace(
+                &tileset.flags,
+                &map.data,
+                (0..map.data.zsize().min(3)).rev(),
+                |x, y, passage| passages[(x, y)] = passage,
+            );
+            return passages;
+        }
+        if Self::is_vx(update_state) {
+            let map = update_state.data.get_vx_map(id);
+            let system = update_state.data.vx_system();
+
+            let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
+            luminol_graphics::Collision::calculate_passages_ace(
+                &system.passages,
+                &map.data,
+                (0..map.data.zsize()).rev(),
+                |x, y, passage| passages[(x, y)] = passage,
+            );
+            return passages;
+        }
+
+        let map = update_state.data.get_map(id);
+        let tilesets = update_state.data.tilesets();
+        let tileset = &tilesets.data[map.tileset_id];
+
+        let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
+        luminol_graphics::Collision::calculate_passages(
+            &tileset.passages,
+            &tileset.priorities,
+            &map.data,
+            Some(&map.events),
+            (0..map.data.zsize()).rev(),
+            |x, y, passage| passages[(x, y)] = passage,
+        );
+        passages
+    }
+
+    /// Rebuilds the map and tilepicker graphics if the atlas for the map's tileset was replaced,
+    /// which happens whenever the tileset is edited, or if the map's tileset or size was changed
+    /// from the map properties window.
+    fn reload_if_changed(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+    ) -> color_eyre::Result<()> {
+        let (tileset_id, size) = if Self::is_ace(update_state) {
+            let map = update_state.data.get_ace_map(self.id);
+            (map.tileset_id, (map.data.xsize(), map.data.ysize()))
+        } else if Self::is_vx(update_state) {
+            let map = update_state.data.get_vx_map(self.id);
+            (
+                luminol_graphics::loaders::atlas::VX_ATLAS_ID,
+                (map.data.xsize(), map.data.ysize()),
+            )
+        } else {
+            let map = update_state.data.get_map(self.id);
+            (map.tileset_id, (map.data.xsize(), map.data.ysize()))
+        };
+        let resized = size != (self.passages.xsize(), self.passages.ysize());
+        let atlas_changed = update_state
+            .graphics
+            .atlas_loader
+            .get_atlas(tileset_id)
+            .map_or(true, |atlas| {
+                !std::sync::Arc::ptr_eq(&atlas.atlas_texture, &self.view.map.atlas.atlas_texture)
+            });
+        if !resized && !atlas_changed {
+            return Ok(());
+        }
+
+        if resized {
+            // The history refers to positions on the old map, so it can't be kept around
+            let (width, height) = size;
+            self.layer_cache = vec![0; width * height];
+            self.dfs_cache = vec![false; width * height];
+            self.brush_layer_cache = vec![0; width * height];
+            self.tilemap_undo_cache = vec![0; width * height];
+            self.history.clear();
+            self.redo_history.clear();
+            self.drawing_shape_pos = None;
+            self.event_drag_info = None;
+            self.view.selected_event_id = None;
+            self.selection = None;
+            self.pasting = false;
+            self.paste_preview = None;
+            self.view.paste_rect = None;
+        }
+
+        self.view.reload(update_state, self.id)?;
+        self.tilepicker.reload(update_state, self.id)?;
+        self.passages = Self::calculate_passages(update_state, self.id);
+
+        Ok(())
+    }
+}
+
 impl luminol_core::Tab for Tab {
     fn name(&self, update_state: &luminol_core::UpdateState<'_>) -> String {
         let map_infos = update_state.data.map_infos();
+        // Undoing the creation of a map removes it while its tab is still open
+        if !map_infos.data.contains_key(&self.id) {
+            return format!("Map {}", self.id);
+        }
+        let modified = if Self::is_vx(update_state) {
+            update_state.data.get_vx_map(self.id).modified
+        } else if self.view.map.atlas.layout == luminol_graphics::AtlasLayout::Ace {
+            update_state.data.get_ace_map(self.id).modified
+        } else {
+            update_state.data.get_map(self.id).modified
+        };
         format!(
             "{}Map {}: {}",
-            if update_state.data.get_map(self.id).modified {
-                "*"
-            } else {
-                ""
-            },
+            if modified { "*" } else { "" },
             self.id,
             map_infos.data[&self.id].name,
         )
     }
 
     fn id(&self) -> egui::Id {
-        egui::Id::new("luminol_map").with(self.id)
+        Self::tab_id(self.id)
     }
 
     fn force_close(&mut self) -> bool {
@@ -216,8 +362,23 @@ impl luminol_core::Tab for Tab {
         update_state: &mut luminol_core::UpdateState<'_>,
         is_focused: bool,
     ) {
+        if !update_state.data.map_infos().data.contains_key(&self.id) {
+            self.force_close = true;
+            return;
+        }
+
         self.brush_density = update_state.toolbar.brush_density;
 
+        if let Err(error) = self.reload_if_changed(update_state) {
+            luminol_core::error!(
+                update_state.toasts,
+                error.wrap_err("Error reloading the map graphics after its tileset changed")
+            );
+        }
+
+        let mut copy_clicked = false;
+        let mut paste_clicked = false;
+
         // Display the toolbar.
         // FIXME: find a proper place for this toolbar! it looks very out of place right now.
         egui::TopBottomPanel::top(format!("map_{}_toolbar", self.id)).show_inside(ui, |ui| {
@@ -245,9 +406,16 @@ impl luminol_core::Tab for Tab {
                         egui::Grid::new(self.id().with("layer_select"))
                             .striped(true)
                             .show(ui, |ui| {
-                                ui.label(egui::RichText::new("Panorama").underline());
-                                ui.checkbox(&mut self.view.map.pano_enabled, "👁");
-                                ui.end_row();
+                                // RPG Maker VX and VX Ace maps don't show panoramas, events or
+                                // fogs yet
+                                let is_ace = self.view.map.atlas.layout
+                                    == luminol_graphics::AtlasLayout::Ace;
+
+                                if !is_ace {
+                                    ui.label(egui::RichText::new("Panorama").underline());
+                                    ui.checkbox(&mut self.view.map.pano_enabled, "👁");
+                                    ui.end_row();
+                                }
 
                                 for (index, layer) in
                                     self.view.map.tiles.enabled_layers.iter_mut().enumerate()
@@ -263,20 +431,22 @@ impl luminol_core::Tab for Tab {
                                     ui.end_row();
                                 }
 
-                                // Display event layer.
-                                ui.columns(1, |columns| {
-                                    columns[0].selectable_value(
-                                        &mut self.view.selected_layer,
-                                        luminol_components::SelectedLayer::Events,
-                                        egui::RichText::new("Events").italics(),
-                                    );
-                                });
-                                ui.checkbox(&mut self.view.map.event_enabled, "👁");
-                                ui.end_row();
+                                if !is_ace {
+                                    // Display event layer.
+                                    ui.columns(1, |columns| {
+                                        columns[0].selectable_value(
+                                            &mut self.view.selected_layer,
+                                            luminol_components::SelectedLayer::Events,
+                                            egui::RichText::new("Events").italics(),
+                                        );
+                                    });
+                                    ui.checkbox(&mut self.view.map.event_enabled, "👁");
+                                    ui.end_row();
 
-                                ui.label(egui::RichText::new("Fog").underline());
-                                ui.checkbox(&mut self.view.map.fog_enabled, "👁");
-                                ui.end_row();
+                                    ui.label(egui::RichText::new("Fog").underline());
+                                    ui.checkbox(&mut self.view.map.fog_enabled, "👁");
+                                    ui.end_row();
+                                }
 
                                 ui.label(egui::RichText::new("Collision").underline());
                                 ui.checkbox(&mut self.view.map.coll_enabled, "👁");
@@ -311,11 +481,30 @@ impl luminol_core::Tab for Tab {
 
                 ui.separator();
 
+                if update_state.toolbar.pencil == luminol_core::Pencil::Select
+                    && self.view.map.atlas.layout != luminol_graphics::AtlasLayout::Ace
+                {
+                    copy_clicked = ui
+                        .add_enabled(self.selection.is_some(), egui::Button::new("Copy"))
+                        .on_hover_text("Copy the selected tiles on every layer (Ctrl+C)")
+                        .clicked();
+                    paste_clicked = ui
+                        .add_enabled(
+                            !selection::Clipboard::is_empty(ui.ctx()),
+                            egui::Button::new("Paste"),
+                        )
+                        .on_hover_text("Click on the map to place the copied tiles (Ctrl+V)")
+                        .clicked();
+                    ui.checkbox(&mut self.copy_events, "Copy events");
+
+                    ui.separator();
+                }
+
                 if ui.button("Save map preview").clicked() && self.save_as_image_promise.is_none() {
                     self.save_as_image_promise =
                         Some(luminol_core::spawn_future(self.view.save_as_image(
                             &update_state.graphics,
-                            &update_state.data.get_map(self.id),
+                            (self.passages.xsize(), self.passages.ysize()),
                         )))
                 }
 
@@ -355,6 +544,11 @@ impl luminol_core::Tab for Tab {
 
         egui::CentralPanel::default().show_inside(ui, |ui| {
             egui::Frame::canvas(ui.style()).show(ui, |ui| {
+                if self.view.map.atlas.layout == luminol_graphics::AtlasLayout::Ace {
+                    self.show_ace_map(ui, update_state, is_focused);
+                    return;
+                }
+
                 // Get the map.
                 let mut map = update_state.data.get_map(self.id);
                 let tilesets = update_state.data.tilesets();
@@ -371,7 +565,8 @@ impl luminol_core::Tab for Tab {
                 let response = self.view.ui(
                     ui,
                     update_state,
-                    &map,
+                    &map.data,
+                    Some(&map.events),
                     &self.tilepicker,
                     self.event_drag_info.is_some(),
                     self.drawing_shape,
@@ -408,7 +603,7 @@ impl luminol_core::Tab for Tab {
                     {
                         self.push_to_history(
                             update_state,
-                            &mut map,
+                            &mut map.modified,
                             HistoryEntry::EventMoved {
                                 id: info.id,
                                 x: info.x,
@@ -425,32 +620,35 @@ impl luminol_core::Tab for Tab {
                         self.drawing_shape = false;
                     }
 
-                    if self.drawing_shape_pos.is_some() {
-                        self.drawing_shape_pos = None;
-                        let delta = (0..map.data.ysize())
-                            .cartesian_product(0..map.data.xsize())
-                            .filter_map(|(y, x)| {
-                                let old_id = self.tilemap_undo_cache[x + y * map.data.xsize()];
-                                (map.data[(x, y, self.tilemap_undo_cache_layer)] != old_id)
-                                    .then_some((x, y, old_id))
-                            })
-                            .collect();
-                        self.push_to_history(
-                            update_state,
-                            &mut map,
-                            HistoryEntry::Tiles {
-                                layer: self.tilemap_undo_cache_layer,
-                                delta,
-                            },
-                        );
-                    }
+                    let map = &mut *map;
+                    self.end_drawing(update_state, &map.data, &mut map.modified);
+                }
+
+                let is_selecting = update_state.toolbar.pencil == luminol_core::Pencil::Select
+                    && matches!(
+                        self.view.selected_layer,
+                        luminol_components::SelectedLayer::Tiles(_)
+                    );
+                if !is_selecting {
+                    self.selection = None;
+                    self.view.selection_rect = None;
+                    self.clear_paste_preview(update_state, &map.data);
                 }
 
                 if let luminol_components::SelectedLayer::Tiles(tile_layer) =
                     self.view.selected_layer
                 {
-                    // Tile drawing
-                    if response.is_pointer_button_down_on()
+                    // Tile selection and drawing
+                    if is_selecting {
+                        self.handle_selection(
+                            ui,
+                            update_state,
+                            &response,
+                            is_focused,
+                            &mut map,
+                            (copy_clicked, paste_clicked),
+                        );
+                    } else if response.is_pointer_button_down_on()
                         && ui.input(|i| {
                             i.pointer.button_down(egui::PointerButton::Primary)
                                 && !i.modifiers.command
@@ -468,7 +666,7 @@ impl luminol_core::Tab for Tab {
                             map_y as usize,
                             tile_layer,
                             update_state.toolbar.pencil,
-                            &mut map,
+                            &mut map.data,
                         );
                     }
                 } else if let Some(selected_event_id) = self.view.selected_event_id {
@@ -493,7 +691,7 @@ impl luminol_core::Tab for Tab {
                         let sprite = self.view.map.events.try_remove(selected_event_id).ok();
                         self.push_to_history(
                             update_state,
-                            &mut map,
+                            &mut map.modified,
                             HistoryEntry::EventDeleted { event, sprite },
                         );
                     }
@@ -558,7 +756,7 @@ impl luminol_core::Tab for Tab {
                         if let Some(id) = self.add_event(update_state, &mut map) {
                             self.push_to_history(
                                 update_state,
-                                &mut map,
+                                &mut map.modified,
                                 HistoryEntry::EventCreated(id),
                             );
                         }
@@ -566,18 +764,9 @@ impl luminol_core::Tab for Tab {
                 }
 
                 // Handle undo/redo keypresses
-                let is_dragged_by_primary = response.dragged_by(egui::PointerButton::Primary);
-                let is_undo_pressed = is_focused
-                    && ui.input(|i| {
-                        i.modifiers.command && !i.modifiers.shift && i.key_pressed(egui::Key::Z)
-                    });
-                let is_redo_pressed = is_focused
-                    && ui.input(|i| {
-                        i.modifiers.command
-                            && (i.modifiers.shift || i.key_pressed(egui::Key::Y))
-                            && (!i.modifiers.shift || i.key_pressed(egui::Key::Z))
-                    });
-                if !is_dragged_by_primary && (is_undo_pressed || is_redo_pressed) {
+                let (is_undo_pressed, is_redo_pressed) =
+                    Self::undo_redo_pressed(ui, &response, is_focused);
+                if is_undo_pressed || is_redo_pressed {
                     let new_entry = match if is_undo_pressed {
                         self.history.pop_back()
                     } else {
@@ -585,19 +774,8 @@ impl luminol_core::Tab for Tab {
                     } {
                         None => None,
 
-                        Some(HistoryEntry::Tiles { layer, mut delta }) => {
-                            for d in delta.iter_mut() {
-                                let position = (d.0, d.1, layer);
-                                let new_id = d.2;
-                                *d = (d.0, d.1, map.data[position]);
-                                map.data[position] = new_id;
-                                self.view.map.set_tile(
-                                    &update_state.graphics.render_state,
-                                    new_id,
-                                    position,
-                                );
-                            }
-                            Some(HistoryEntry::Tiles { layer, delta })
+                        Some(HistoryEntry::Tiles { layer, delta }) => {
+                            Some(self.swap_tiles(update_state, &mut map.data, layer, delta))
                         }
 
                         Some(HistoryEntry::EventMoved { id, x, y }) => {
@@ -626,9 +804,19 @@ impl luminol_core::Tab for Tab {
                             }
                             Some(HistoryEntry::EventCreated(id))
                         }
+
+                        Some(HistoryEntry::Region {
+                            delta,
+                            created,
+                            deleted,
+                        }) => {
+                            Some(self.swap_region(update_state, &mut map, delta, created, deleted))
+                        }
                     };
 
                     if let Some(new_entry) = new_entry {
+                        // The map's own history handled this, so the project-wide one shouldn't
+                        ui.input_mut(luminol_core::history::consume_undo_redo);
                         update_state.modified.set(true);
                         map.modified = true;
                         if is_undo_pressed {
@@ -643,24 +831,7 @@ impl luminol_core::Tab for Tab {
                     event.extra_data.is_editor_open = false;
                 }
 
-                if let luminol_components::SelectedLayer::Tiles(tile_layer) =
-                    self.view.selected_layer
-                {
-                    // Write the buffered tile changes to the tilemap
-                    for y in 0..map.data.ysize() {
-                        for x in 0..map.data.xsize() {
-                            let position = (x, y, tile_layer);
-                            let new_tile_id = map.data[position];
-                            if new_tile_id != self.layer_cache[x + y * map.data.xsize()] {
-                                self.view.map.set_tile(
-                                    &update_state.graphics.render_state,
-                                    new_tile_id,
-                                    position,
-                                );
-                            }
-                        }
-                    }
-                }
+                self.write_tile_changes(update_state, &map.data);
 
                 // Update the collision preview
                 luminol_graphics::Collision::calculate_passages(
@@ -0,0 +1,378 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use itertools::Itertools;
+use luminol_data::rpg;
+
+/// Where copied tiles are kept in egui memory, so they can be pasted into any map.
+const CLIPBOARD_ID: &str = "luminol_map_clipboard";
+
+/// A rectangle of tiles copied from a map, along with the events that were inside of it.
+#[derive(Clone)]
+pub(super) struct Clipboard {
+    /// Every layer of the copied tiles
+    tiles: luminol_data::Table3,
+    /// The copied events, positioned relative to the top left corner of the copied tiles
+    events: Vec<ClipboardEvent>,
+}
+
+/// An event in the clipboard. Events aren't stored whole since their editor state isn't `Sync`.
+#[derive(Clone)]
+struct ClipboardEvent {
+    x: i32,
+    y: i32,
+    name: String,
+    pages: Vec<rpg::EventPage>,
+}
+
+impl Clipboard {
+    fn get(ctx: &egui::Context) -> Option<Self> {
+        ctx.data(|d| d.get_temp(egui::Id::new(CLIPBOARD_ID)))
+    }
+
+    pub(super) fn is_empty(ctx: &egui::Context) -> bool {
+        ctx.data(|d| d.get_temp::<Self>(egui::Id::new(CLIPBOARD_ID)).is_none())
+    }
+
+    fn size(&self) -> egui::Vec2 {
+        egui::vec2(self.tiles.xsize() as f32, self.tiles.ysize() as f32)
+    }
+}
+
+impl super::Tab {
+    /// Handles the selection tool for this frame: dragging out a selection, copying it and pasting
+    /// the clipboard with a preview of where it will go.
+    pub(super) fn handle_selection(
+        &mut self,
+        ui: &egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        response: &egui::Response,
+        is_focused: bool,
+        map: &mut rpg::Map,
+        (copy_clicked, paste_clicked): (bool, bool),
+    ) {
+        let (copy_pressed, paste_pressed, escape_pressed) = if is_focused {
+            ui.input(|i| {
+                (
+                    i.events.iter().any(|e| matches!(e, egui::Event::Copy))
+                        || (i.modifiers.command && i.key_pressed(egui::Key::C)),
+                    i.events.iter().any(|e| matches!(e, egui::Event::Paste(_)))
+                        || (i.modifiers.command && i.key_pressed(egui::Key::V)),
+                    i.key_pressed(egui::Key::Escape),
+                )
+            })
+        } else {
+            (false, false, false)
+        };
+
+        if copy_clicked || copy_pressed {
+            self.copy_selection(ui.ctx(), map);
+        }
+        if (paste_clicked || paste_pressed) && !Clipboard::is_empty(ui.ctx()) {
+            self.pasting = true;
+            self.selection = None;
+        }
+        if escape_pressed {
+            self.selection = None;
+            self.clear_paste_preview(update_state, &map.data);
+        }
+
+        if self.pasting {
+            let Some(clipboard) = Clipboard::get(ui.ctx()) else {
+                self.clear_paste_preview(update_state, &map.data);
+                return;
+            };
+            let origin = self.view.cursor_pos;
+            self.view.paste_rect = Some(egui::Rect::from_min_size(origin, clipboard.size()));
+
+            if response.clicked_by(egui::PointerButton::Primary) {
+                self.paste(update_state, map, &clipboard, origin);
+                self.clear_paste_preview(update_state, &map.data);
+            } else if self.paste_preview.as_ref().map(|(pos, _)| *pos) != Some(origin) {
+                self.restore_paste_preview(update_state, &map.data);
+                let layout = self.view.map.atlas.layout;
+                let changes = paste_changes(&map.data, &clipboard, origin, layout);
+                for &(x, y, z, tile_id) in changes.iter() {
+                    self.view
+                        .map
+                        .set_tile(&update_state.graphics.render_state, tile_id, (x, y, z));
+                }
+                let positions = changes.into_iter().map(|(x, y, z, _)| (x, y, z)).collect();
+                self.paste_preview = Some((origin, positions));
+            }
+        } else if response.is_pointer_button_down_on()
+            && ui.input(|i| {
+                i.pointer.button_down(egui::PointerButton::Primary) && !i.modifiers.command
+            })
+        {
+            let start = *self.selection_start.get_or_insert(self.view.cursor_pos);
+            let rect = egui::Rect::from_two_pos(start, self.view.cursor_pos);
+            self.selection = Some(egui::Rect::from_min_max(
+                rect.min,
+                rect.max + egui::Vec2::splat(1.),
+            ));
+        } else {
+            self.selection_start = None;
+        }
+
+        self.view.selection_rect = self.selection;
+    }
+
+    /// Stops pasting, putting back the tiles that were covered by the paste preview.
+    pub(super) fn clear_paste_preview(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        data: &luminol_data::Table3,
+    ) {
+        self.restore_paste_preview(update_state, data);
+        self.pasting = false;
+        self.view.paste_rect = None;
+    }
+
+    fn restore_paste_preview(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        data: &luminol_data::Table3,
+    ) {
+        let Some((_, positions)) = self.paste_preview.take() else {
+            return;
+        };
+        for position in positions {
+            self.view.map.set_tile(
+                &update_state.graphics.render_state,
+                data[position],
+                position,
+            );
+        }
+    }
+
+    fn copy_selection(&self, ctx: &egui::Context, map: &rpg::Map) {
+        let Some(selection) = self.selection else {
+            return;
+        };
+        let (x0, y0) = (selection.min.x as usize, selection.min.y as usize);
+        let (width, height) = (selection.width() as usize, selection.height() as usize);
+
+        let mut tiles = luminol_data::Table3::new(width, height, map.data.zsize());
+        for (z, y, x) in (0..map.data.zsize())
+            .cartesian_product(0..height)
+            .cartesian_product(0..width)
+            .map(|((z, y), x)| (z, y, x))
+        {
+            tiles[(x, y, z)] = map.data[(x0 + x, y0 + y, z)];
+        }
+
+        let events = if self.copy_events {
+            map.events
+                .iter()
+                .filter(|(_, event)| {
+                    (x0 as i32..(x0 + width) as i32).contains(&event.x)
+                        && (y0 as i32..(y0 + height) as i32).contains(&event.y)
+                })
+                .map(|(_, event)| ClipboardEvent {
+                    x: event.x - x0 as i32,
+                    y: event.y - y0 as i32,
+                    name: event.name.clone(),
+                    pages: event.pages.iter().map(rpg::EventPage::duplicate).collect(),
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
+        ctx.data_mut(|d| d.insert_temp(egui::Id::new(CLIPBOARD_ID), Clipboard { tiles, events }));
+    }
+
+    /// Pastes the clipboard with its top left corner at `origin`, saving everything it changed as
+    /// one history entry.
+    fn paste(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut rpg::Map,
+        clipboard: &Clipboard,
+        origin: egui::Pos2,
+    ) {
+        let layout = self.view.map.atlas.layout;
+        let delta = paste_changes(&map.data, clipboard, origin, layout)
+            .into_iter()
+            .map(|(x, y, z, tile_id)| {
+                let old_id = map.data[(x, y, z)];
+                map.data[(x, y, z)] = tile_id;
+                self.view
+                    .map
+                    .set_tile(&update_state.graphics.render_state, tile_id, (x, y, z));
+                (x, y, z, old_id)
+            })
+            .collect();
+
+        let mut created = vec![];
+        for copied in clipboard.events.iter() {
+            let x = origin.x as i32 + copied.x;
+            let y = origin.y as i32 + copied.y;
+            // Events can't go outside of the map or on top of another event
+            if x >= map.data.xsize() as i32
+                || y >= map.data.ysize() as i32
+                || map.events.iter().any(|(_, e)| e.x == x && e.y == y)
+            {
+                continue;
+            }
+            let Some(id) = Self::new_event_id(&map.events) else {
+                break;
+            };
+
+            let mut event = rpg::Event::new(x, y, id);
+            event.name = copied.name.clone();
+            event.pages = copied.pages.iter().map(rpg::EventPage::duplicate).collect();
+            event.extra_data.graphic_modified.set(true);
+            map.events.insert(id, event);
+            created.push(id);
+        }
+
+        self.push_to_history(
+            update_state,
+            &mut map.modified,
+            super::HistoryEntry::Region {
+                delta,
+                created,
+                deleted: vec![],
+            },
+        );
+    }
+
+    /// Applies a region history entry and returns the entry that reverts it.
+    pub(super) fn swap_region(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut rpg::Map,
+        mut delta: Vec<(usize, usize, usize, i16)>,
+        created: Vec<usize>,
+        deleted: Vec<(rpg::Event, Option<luminol_graphics::Event>)>,
+    ) -> super::HistoryEntry {
+        for d in delta.iter_mut() {
+            let position = (d.0, d.1, d.2);
+            let new_id = d.3;
+            d.3 = map.data[position];
+            map.data[position] = new_id;
+            self.view
+                .map
+                .set_tile(&update_state.graphics.render_state, new_id, position);
+        }
+
+        let new_deleted = created
+            .into_iter()
+            .map(|id| {
+                let event = map.events.remove(id);
+                let sprite = self.view.map.events.try_remove(id).ok();
+                (event, sprite)
+            })
+            .collect();
+        let new_created = deleted
+            .into_iter()
+            .map(|(event, sprite)| {
+                let id = event.id;
+                map.events.insert(id, event);
+                if let Some(sprite) = sprite {
+                    self.view.map.events.insert(id, sprite);
+                }
+                id
+            })
+            .collect();
+
+        super::HistoryEntry::Region {
+            delta,
+            created: new_created,
+            deleted: new_deleted,
+        }
+    }
+}
+
+/// Works out the (x, y, z, tile_id) of every tile that pasting the clipboard at `origin` changes,
+/// including autotiles around the edges of the pasted tiles whose neighbours changed.
+fn paste_changes(
+    data: &luminol_data::Table3,
+    clipboard: &Clipboard,
+    origin: egui::Pos2,
+    layout: luminol_graphics::AtlasLayout,
+) -> Vec<(usize, usize, usize, i16)> {
+    let (origin_x, origin_y) = (origin.x as usize, origin.y as usize);
+    let (width, height) = (clipboard.tiles.xsize(), clipboard.tiles.ysize());
+    let depth = data.zsize().min(clipboard.tiles.zsize());
+
+    // Autotiles along the edges need to see their neighbours, so copy a margin of 2 tiles around
+    // the pasted tiles: one for the edge tiles themselves and one for their neighbours
+    let x0 = origin_x.saturating_sub(2);
+    let y0 = origin_y.saturating_sub(2);
+    let x1 = (origin_x + width + 2).min(data.xsize());
+    let y1 = (origin_y + height + 2).min(data.ysize());
+    let mut scratch = luminol_data::Table3::new(x1 - x0, y1 - y0, depth);
+    for (z, y, x) in (0..depth)
+        .cartesian_product(y0..y1)
+        .cartesian_product(x0..x1)
+        .map(|((z, y), x)| (z, y, x))
+    {
+        scratch[(x - x0, y - y0, z)] = data[(x, y, z)];
+    }
+
+    for (z, y, x) in (0..depth)
+        .cartesian_product(0..height)
+        .cartesian_product(0..width)
+        .map(|((z, y), x)| (z, y, x))
+    {
+        if origin_x + x < x1 && origin_y + y < y1 {
+            scratch[(origin_x + x - x0, origin_y + y - y0, z)] = clipboard.tiles[(x, y, z)];
+        }
+    }
+
+    // The tiles on both sides of the edges of the pasted tiles
+    let is_edge = |i: usize, start: usize, len: usize| {
+        [
+            start as isize - 1,
+            start as isize,
+            (start + len) as isize - 1,
+            (start + len) as isize,
+        ]
+        .contains(&(i as isize))
+    };
+    for (z, y, x) in (0..depth)
+        .cartesian_product(y0..y1)
+        .cartesian_product(x0..x1)
+        .map(|((z, y), x)| (z, y, x))
+    {
+        let inside_margin = (origin_x.saturating_sub(1)..=origin_x + width).contains(&x)
+            && (origin_y.saturating_sub(1)..=origin_y + height).contains(&y);
+        if inside_margin && (is_edge(x, origin_x, width) || is_edge(y, origin_y, height)) {
+            let position = (x - x0, y - y0, z);
+            scratch[position] = super::Tab::recompute_autotile(&scratch, position, layout);
+        }
+    }
+
+    (0..depth)
+        .cartesian_product(y0..y1)
+        .cartesian_product(x0..x1)
+        .filter_map(|((z, y), x)| {
+            let tile_id = scratch[(x - x0, y - y0, z)];
+            (tile_id != data[(x, y, z)]).then_some((x, y, z, tile_id))
+        })
+        .collect()
+}
@@ -22,20 +22,26 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use itertools::Itertools;
+
 impl super::Tab {
-    pub(super) fn recompute_autotile(
-        &self,
-        map: &luminol_data::rpg::Map,
+    pub(crate) fn recompute_autotile(
+        data: &luminol_data::Table3,
         position: (usize, usize, usize),
+        layout: luminol_graphics::AtlasLayout,
     ) -> i16 {
-        if map.data[position] >= 384 {
-            return map.data[position];
-        }
+        use luminol_data::rmvxace::{AutotileKind, Tileset};
 
-        let autotile = map.data[position] / 48;
-        if autotile == 0 {
-            return 0;
-        }
+        let tile_id = data[position];
+        let (autotile, kind) = match layout {
+            luminol_graphics::AtlasLayout::XP if tile_id >= 384 => return tile_id,
+            luminol_graphics::AtlasLayout::XP if tile_id < 48 => return 0,
+            luminol_graphics::AtlasLayout::XP => (tile_id / 48 * 48, AutotileKind::Floor),
+            luminol_graphics::AtlasLayout::Ace => match Tileset::autotile_kind(tile_id) {
+                Some((index, kind)) => (Tileset::TILE_ID_A1 + index as i16 * 48, kind),
+                None => return tile_id,
+            },
+        };
 
         let x_array: [i8; 8] = [-1, 0, 1, 1, 1, 0, -1, -1];
         let y_array: [i8; 8] = [-1, -1, -1, 0, 1, 1, 1, 0];
@@ -52,32 +58,46 @@ impl super::Tab {
             bitfield <<= 1;
             // Out-of-bounds tiles always count as valid neighbors
             let is_out_of_bounds = ((x == -1 && position.0 == 0)
-                || (x == 1 && position.0 + 1 == map.data.xsize()))
-                || ((y == -1 && position.1 == 0) || (y == 1 && position.1 + 1 == map.data.ysize()));
+                || (x == 1 && position.0 + 1 == data.xsize()))
+                || ((y == -1 && position.1 == 0) || (y == 1 && position.1 + 1 == data.ysize()));
             // Otherwise, we only consider neighbors that are autotiles of the same type
             let is_same_autotile = !is_out_of_bounds
-                && map.data[(
-                    if x == -1 {
-                        position.0 - 1
-                    } else {
-                        position.0 + x as usize
-                    },
-                    if y == -1 {
-                        position.1 - 1
-                    } else {
-                        position.1 + y as usize
-                    },
-                    position.2,
-                )] / 48
-                    == autotile;
+                && (autotile..autotile + 48).contains(
+                    &data[(
+                        if x == -1 {
+                            position.0 - 1
+                        } else {
+                            position.0 + x as usize
+                        },
+                        if y == -1 {
+                            position.1 - 1
+                        } else {
+                            position.1 + y as usize
+                        },
+                        position.2,
+                    )],
+                );
 
             if is_out_of_bounds || is_same_autotile {
                 bitfield |= 1
             }
         }
 
+        // Walls and waterfalls only have an edge wherever they don't have a neighbor
+        let left = (bitfield & 0b00000001 == 0) as i16;
+        let top = (bitfield & 0b01000000 == 0) as i16;
+        let right = (bitfield & 0b00010000 == 0) as i16;
+        let bottom = (bitfield & 0b00000100 == 0) as i16;
+        match kind {
+            AutotileKind::Wall => {
+                return autotile + (left | (top << 1) | (right << 2) | (bottom << 3))
+            }
+            AutotileKind::Waterfall => return autotile + (left | (right << 1)),
+            AutotileKind::Floor => {}
+        }
+
         // Check how many edges have valid neighbors
-        autotile * 48
+        autotile
             + match (bitfield & 0b01010101).count_ones() {
                 4 => {
                     // If the autotile is surrounded on all 4 edges,
@@ -144,7 +164,7 @@ impl super::Tab {
 
     pub(super) fn set_tile(
         &self,
-        map: &mut luminol_data::rpg::Map,
+        data: &mut luminol_data::Table3,
         tile: luminol_components::SelectedTile,
         position: (usize, usize, usize),
     ) {
@@ -169,14 +189,14 @@ impl super::Tab {
             }
         }
 
-        map.data[position] = tile.to_id();
+        let layout = self.view.map.atlas.layout;
+        data[position] = tile.to_id(layout);
 
         for y in -1i8..=1i8 {
             for x in -1i8..=1i8 {
                 // Don't check tiles that are out of bounds
-                if ((x == -1 && position.0 == 0) || (x == 1 && position.0 + 1 == map.data.xsize()))
-                    || ((y == -1 && position.1 == 0)
-                        || (y == 1 && position.1 + 1 == map.data.ysize()))
+                if ((x == -1 && position.0 == 0) || (x == 1 && position.0 + 1 == data.xsize()))
+                    || ((y == -1 && position.1 == 0) || (y == 1 && position.1 + 1 == data.ysize()))
                 {
                     continue;
                 }
@@ -193,43 +213,50 @@ impl super::Tab {
                     },
                     position.2,
                 );
-                let tile_id = self.recompute_autotile(map, position);
-                map.data[position] = tile_id;
+                let tile_id = Self::recompute_autotile(data, position, layout);
+                data[position] = tile_id;
             }
         }
     }
 
-    pub(super) fn add_event(
-        &mut self,
-        update_state: &luminol_core::UpdateState<'_>,
-        map: &mut luminol_data::rpg::Map,
+    /// Picks the ID for a new event on a map, or `None` if there's no room for more events.
+    pub(super) fn new_event_id(
+        events: &luminol_data::OptionVec<luminol_data::rpg::Event>,
     ) -> Option<usize> {
         let mut first_vacant_id = 1;
         let mut max_event_id = 0;
 
-        for (_, event) in map.events.iter() {
+        for (_, event) in events.iter() {
             if event.id == first_vacant_id {
                 first_vacant_id += 1;
             }
             max_event_id = event.id;
-
-            if event.x == self.view.cursor_pos.x as i32 && event.y == self.view.cursor_pos.y as i32
-            {
-                return None;
-            }
         }
 
         // Try first to allocate the event number directly after the current highest one.
         // However, valid event number range in RPG Maker XP and VX is 1-999.
-        let new_event_id = if max_event_id < 999 {
-            max_event_id + 1
+        if max_event_id < 999 {
+            Some(max_event_id + 1)
         }
         // Otherwise, we'll try to use a non-allocated event ID that isn't zero.
         else if first_vacant_id <= 999 {
-            first_vacant_id
+            Some(first_vacant_id)
         } else {
+            None
+        }
+    }
+
+    pub(super) fn add_event(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+    ) -> Option<usize> {
+        if map.events.iter().any(|(_, event)| {
+            event.x == self.view.cursor_pos.x as i32 && event.y == self.view.cursor_pos.y as i32
+        }) {
             return None;
-        };
+        }
+        let new_event_id = Self::new_event_id(&map.events)?;
 
         let event = luminol_data::rpg::Event::new(
             self.view.cursor_pos.x as i32,
@@ -252,15 +279,174 @@ impl super::Tab {
     pub(super) fn push_to_history(
         &mut self,
         update_state: &luminol_core::UpdateState<'_>,
-        map: &mut luminol_data::rpg::Map,
+        map_modified: &mut bool,
         entry: super::HistoryEntry,
     ) {
         update_state.modified.set(true);
-        map.modified = true;
+        *map_modified = true;
         self.redo_history.clear();
-        if self.history.len() == super::HISTORY_SIZE {
+        self.history.push_back(entry);
+        while self.history.len() > update_state.global_config.history_depth.max(1) {
             self.history.pop_front();
         }
-        self.history.push_back(entry);
+    }
+
+    /// Saves the changes made to the map since the user started drawing to the history.
+    pub(super) fn end_drawing(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        data: &luminol_data::Table3,
+        map_modified: &mut bool,
+    ) {
+        if self.drawing_shape_pos.take().is_none() {
+            return;
+        }
+
+        let delta = (0..data.ysize())
+            .cartesian_product(0..data.xsize())
+            .filter_map(|(y, x)| {
+                let old_id = self.tilemap_undo_cache[x + y * data.xsize()];
+                (data[(x, y, self.tilemap_undo_cache_layer)] != old_id).then_some((x, y, old_id))
+            })
+            .collect();
+        self.push_to_history(
+            update_state,
+            map_modified,
+            super::HistoryEntry::Tiles {
+                layer: self.tilemap_undo_cache_layer,
+                delta,
+            },
+        );
+    }
+
+    /// Returns whether undo or redo was pressed this frame.
+    pub(super) fn undo_redo_pressed(
+        ui: &egui::Ui,
+        response: &egui::Response,
+        is_focused: bool,
+    ) -> (bool, bool) {
+        if !is_focused || response.dragged_by(egui::PointerButton::Primary) {
+            return (false, false);
+        }
+
+        ui.input(luminol_core::history::undo_redo_pressed)
+    }
+
+    /// Applies a tile history entry and returns the entry that reverts it.
+    pub(super) fn swap_tiles(
+        &self,
+        update_state: &luminol_core::UpdateState<'_>,
+        data: &mut luminol_data::Table3,
+        layer: usize,
+        mut delta: Vec<(usize, usize, i16)>,
+    ) -> super::HistoryEntry {
+        for d in delta.iter_mut() {
+            let position = (d.0, d.1, layer);
+            let new_id = d.2;
+            *d = (d.0, d.1, data[position]);
+            data[position] = new_id;
+            self.view
+                .map
+                .set_tile(&update_state.graphics.render_state, new_id, position);
+        }
+        super::HistoryEntry::Tiles { layer, delta }
+    }
+
+    /// Writes the tiles of the selected layer that changed this frame to the tilemap.
+    pub(super) fn write_tile_changes(
+        &self,
+        update_state: &luminol_core::UpdateState<'_>,
+        data: &luminol_data::Table3,
+    ) {
+        let luminol_components::SelectedLayer::Tiles(tile_layer) = self.view.selected_layer else {
+            return;
+        };
+
+        for y in 0..data.ysize() {
+            for x in 0..data.xsize() {
+                let position = (x, y, tile_layer);
+                let new_tile_id = data[position];
+                if new_tile_id != self.layer_cache[x + y * data.xsize()] {
+                    self.view.map.set_tile(
+                        &update_state.graphics.render_state,
+                        new_tile_id,
+                        position,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Resizes a map to `size`, moving everything on it by `offset` tiles. Events that end up
+    /// outside of the map are removed, and autotiles along the edges of the old and new map area
+    /// are recomputed since their neighbours changed.
+    pub(crate) fn resize_map(
+        map: &mut luminol_data::rpg::Map,
+        size: (usize, usize),
+        offset: (isize, isize),
+    ) {
+        let (width, height) = size;
+        let old_data = std::mem::take(&mut map.data);
+        let (old_width, old_height) = (old_data.xsize() as isize, old_data.ysize() as isize);
+
+        map.data = luminol_data::Table3::new(width, height, old_data.zsize());
+        for z in 0..old_data.zsize() {
+            for y in 0..height {
+                for x in 0..width {
+                    let old_x = x as isize - offset.0;
+                    let old_y = y as isize - offset.1;
+                    if (0..old_width).contains(&old_x) && (0..old_height).contains(&old_y) {
+                        map.data[(x, y, z)] = old_data[(old_x as usize, old_y as usize, z)];
+                    }
+                }
+            }
+        }
+        map.width = width;
+        map.height = height;
+
+        let culled = map
+            .events
+            .iter_mut()
+            .filter_map(|(id, event)| {
+                event.x += offset.0 as i32;
+                event.y += offset.1 as i32;
+                let inside =
+                    (0..width as i32).contains(&event.x) && (0..height as i32).contains(&event.y);
+                (!inside).then_some(id)
+            })
+            .collect::<Vec<_>>();
+        for id in culled {
+            map.events.remove(id);
+        }
+
+        // The tiles on both sides of the old edges and the tiles on the new edges
+        let edges = |old_start: isize, old_len: isize, len: usize| {
+            [
+                old_start - 1,
+                old_start,
+                old_start + old_len - 1,
+                old_start + old_len,
+                0,
+                len as isize - 1,
+            ]
+            .into_iter()
+            .filter(move |&i| (0..len as isize).contains(&i))
+            .map(|i| i as usize)
+        };
+        let edge_columns = edges(offset.0, old_width, width).collect::<Vec<_>>();
+        let edge_rows = edges(offset.1, old_height, height).collect::<Vec<_>>();
+        for z in 0..map.data.zsize() {
+            for y in 0..height {
+                for x in 0..width {
+                    if edge_columns.contains(&x) || edge_rows.contains(&y) {
+                        map.data[(x, y, z)] = Self::recompute_autotile(
+                            &map.data,
+                            (x, y, z),
+                            luminol_graphics::AtlasLayout::XP,
+                        );
+                    }
+                }
+            }
+        }
     }
 }
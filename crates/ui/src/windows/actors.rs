@@ -64,6 +64,11 @@ impl Window {
             view: luminol_components::DatabaseView::new(),
         }
     }
+
+    /// Selects the actor with the given index.
+    pub fn select(&mut self, id: usize) {
+        self.view.select(id);
+    }
 }
 
 fn draw_graph(
@@ -760,6 +765,8 @@ impl luminol_core::Window for Window {
                         });
 
                         self.previous_actor = Some(actor.id);
+
+                        modified
                     },
                 )
             });
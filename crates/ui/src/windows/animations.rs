@@ -0,0 +1,785 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::WrapErr;
+use luminol_components::animation_frame_view::{cell, Ghost};
+use luminol_components::{AnimationFrameView, UiExt};
+use luminol_core::Modal;
+use luminol_data::rpg;
+
+use luminol_modals::graphic_picker::hue::Modal as HueGraphicPicker;
+use luminol_modals::sound_picker::Modal as SoundPicker;
+
+const BLEND_TYPES: [&str; 3] = ["Normal", "Add", "Subtract"];
+const FLASH_SCOPES: [&str; 4] = ["None", "Target", "Screen", "Hide Target"];
+const CONDITIONS: [&str; 3] = ["None", "Hit", "Miss"];
+
+/// Animations in RPG Maker XP run at 20 frames per second.
+const FRAME_RATE: f64 = 20.;
+
+/// The animation editor.
+pub struct Window {
+    selected_animation_name: Option<String>,
+    previous_animation: Option<usize>,
+
+    selected_frame: usize,
+    /// The pattern that gets placed when clicking on an empty part of the canvas.
+    selected_pattern: i16,
+    show_previous_frame: bool,
+    show_next_frame: bool,
+
+    animation_picker: HueGraphicPicker,
+    se_pickers: Vec<SoundPicker>,
+
+    sheet: Option<std::sync::Arc<luminol_graphics::Texture>>,
+    sheet_name: Option<camino::Utf8PathBuf>,
+
+    playback: Option<Playback>,
+
+    frame_view: AnimationFrameView,
+    collapsing_view: luminol_components::CollapsingView,
+    view: luminol_components::DatabaseView,
+}
+
+struct Playback {
+    start_time: f64,
+    /// The last frame whose timings were played.
+    last_frame: Option<usize>,
+}
+
+impl Window {
+    pub fn new(update_state: &luminol_core::UpdateState<'_>) -> Self {
+        let animations = update_state.data.animations();
+        let animation = &animations.data[0];
+
+        Self {
+            selected_animation_name: None,
+            previous_animation: None,
+
+            selected_frame: 0,
+            selected_pattern: 0,
+            show_previous_frame: true,
+            show_next_frame: false,
+
+            animation_picker: HueGraphicPicker::new(
+                update_state,
+                "Graphics/Animations".into(),
+                animation.animation_name.as_deref(),
+                animation.animation_hue,
+                egui::vec2(192., 96.),
+                "animation_graphic_picker",
+            ),
+            se_pickers: Vec::new(),
+
+            sheet: None,
+            sheet_name: None,
+
+            playback: None,
+
+            frame_view: AnimationFrameView::new(),
+            collapsing_view: luminol_components::CollapsingView::new(),
+            view: luminol_components::DatabaseView::new(),
+        }
+    }
+
+    fn load_sheet(
+        update_state: &luminol_core::UpdateState<'_>,
+        path: Option<&camino::Utf8Path>,
+    ) -> Option<std::sync::Arc<luminol_graphics::Texture>> {
+        // The hue isn't applied here, since the cells are drawn without going through the sprite
+        // shader
+        let path = path?;
+        let texture = update_state
+            .graphics
+            .texture_loader
+            .load_now_dir(update_state.filesystem, "Graphics/Animations", path)
+            .wrap_err_with(|| format!("Error loading animation graphic {path:?}"))
+            .unwrap_or_else(|e| {
+                update_state.graphics.send_texture_error(e);
+                update_state.graphics.texture_loader.placeholder_texture()
+            });
+        Some(texture)
+    }
+
+    /// Plays the sound effects of every timing between the last frame that was played and the
+    /// current one, and returns the flash that should be shown on the current frame.
+    fn update_playback(
+        update_state: &mut luminol_core::UpdateState<'_>,
+        playback: &mut Playback,
+        animation: &rpg::Animation,
+        frame: usize,
+    ) -> Option<(i32, egui::Color32)> {
+        let first_frame = playback.last_frame.map_or(0, |f| f + 1);
+        if first_frame <= frame {
+            for timing in animation
+                .timings
+                .iter()
+                .filter(|t| (first_frame..=frame).contains(&(t.frame.max(0) as usize)))
+            {
+                let Some(name) = &timing.se.name else {
+                    continue;
+                };
+                if let Err(e) = update_state.audio.play(
+                    camino::Utf8Path::new("Audio/SE").join(name),
+                    update_state.filesystem,
+                    timing.se.volume,
+                    timing.se.pitch,
+                    luminol_audio::Source::SE,
+                ) {
+                    luminol_core::error!(
+                        update_state.toasts,
+                        e.wrap_err("Error playing from audio file")
+                    );
+                }
+            }
+            playback.last_frame = Some(frame);
+        }
+
+        // Flashes fade out over their duration
+        animation.timings.iter().rev().find_map(|timing| {
+            let elapsed = frame as i32 - timing.frame;
+            let duration = timing.flash_duration.max(1);
+            if !matches!(timing.flash_scope, 1 | 2) || !(0..duration).contains(&elapsed) {
+                return None;
+            }
+            let strength = 1. - elapsed as f64 / duration as f64;
+            let color = timing.flash_color;
+            Some((
+                timing.flash_scope,
+                egui::Color32::from_rgba_unmultiplied(
+                    color.red as u8,
+                    color.green as u8,
+                    color.blue as u8,
+                    (color.alpha * strength) as u8,
+                ),
+            ))
+        })
+    }
+
+    /// Inserts an empty frame at an index, moving the timings after it along.
+    fn insert_frame(animation: &mut rpg::Animation, index: usize) {
+        animation.frames.insert(index, Self::empty_frame());
+        animation.frame_max = animation.frames.len() as i32;
+        for timing in &mut animation.timings {
+            if timing.frame >= index as i32 {
+                timing.frame += 1;
+            }
+        }
+    }
+
+    /// Removes a frame along with its timings.
+    fn remove_frame(animation: &mut rpg::Animation, index: usize) {
+        animation.frames.remove(index);
+        animation.frame_max = animation.frames.len() as i32;
+        animation.timings.retain(|t| t.frame != index as i32);
+        for timing in &mut animation.timings {
+            if timing.frame > index as i32 {
+                timing.frame -= 1;
+            }
+        }
+    }
+
+    fn empty_frame() -> rpg::animation::Frame {
+        rpg::animation::Frame {
+            cell_max: 0,
+            cell_data: luminol_data::Table2::new(0, cell::COLUMNS),
+        }
+    }
+
+    fn show_timing_header(ui: &mut egui::Ui, timing: &rpg::animation::Timing) {
+        let mut text = format!("#{:0>3}", timing.frame + 1);
+        if let Some(name) = &timing.se.name {
+            text += &format!(" SE: {name}");
+        }
+        if let Some(scope) = FLASH_SCOPES
+            .get(timing.flash_scope as usize)
+            .filter(|_| timing.flash_scope != 0)
+        {
+            text += &format!(" Flash: {scope} ({} frames)", timing.flash_duration);
+        }
+        ui.label(text);
+    }
+
+    fn show_timing_body(
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        se_picker: &mut SoundPicker,
+        frame_max: i32,
+        timing: (usize, &mut rpg::animation::Timing),
+    ) -> egui::Response {
+        let (timing_index, timing) = timing;
+        let mut modified = false;
+
+        let combo_box = |ui: &mut egui::Ui, id: &str, value: &mut i32, options: &[&str]| {
+            let old_value = *value;
+            let mut response = egui::ComboBox::from_id_source((timing_index, id))
+                .selected_text(options.get(*value as usize).copied().unwrap_or_default())
+                .show_ui(ui, |ui| {
+                    for (i, text) in options.iter().enumerate() {
+                        ui.selectable_value(value, i as i32, *text);
+                    }
+                })
+                .response;
+            if *value != old_value {
+                response.mark_changed();
+            }
+            response
+        };
+
+        let mut response = egui::Frame::none()
+            .show(ui, |ui| {
+                ui.columns(2, |columns| {
+                    let mut frame = timing.frame + 1;
+                    if columns[0]
+                        .add(luminol_components::Field::new(
+                            "Frame",
+                            egui::DragValue::new(&mut frame).clamp_range(1..=frame_max.max(1)),
+                        ))
+                        .changed()
+                    {
+                        timing.frame = frame - 1;
+                        modified = true;
+                    }
+
+                    modified |= columns[1]
+                        .add(luminol_components::Field::new(
+                            "Condition",
+                            |ui: &mut egui::Ui| {
+                                combo_box(ui, "condition", &mut timing.condition, &CONDITIONS)
+                            },
+                        ))
+                        .changed();
+                });
+
+                modified |= ui
+                    .add(luminol_components::Field::new(
+                        "SE",
+                        se_picker.button(&mut timing.se, update_state),
+                    ))
+                    .changed();
+
+                ui.columns(3, |columns| {
+                    modified |= columns[0]
+                        .add(luminol_components::Field::new(
+                            "Flash",
+                            |ui: &mut egui::Ui| {
+                                combo_box(ui, "flash_scope", &mut timing.flash_scope, &FLASH_SCOPES)
+                            },
+                        ))
+                        .changed();
+
+                    columns[1].add_enabled_ui(matches!(timing.flash_scope, 1 | 2), |ui| {
+                        let color = &mut timing.flash_color;
+                        // The alpha channel is the strength of the flash
+                        let mut rgba = [
+                            color.red as u8,
+                            color.green as u8,
+                            color.blue as u8,
+                            color.alpha as u8,
+                        ];
+                        if ui
+                            .add(luminol_components::Field::new(
+                                "Color",
+                                |ui: &mut egui::Ui| {
+                                    ui.color_edit_button_srgba_unmultiplied(&mut rgba)
+                                },
+                            ))
+                            .changed()
+                        {
+                            color.red = rgba[0] as f64;
+                            color.green = rgba[1] as f64;
+                            color.blue = rgba[2] as f64;
+                            color.alpha = rgba[3] as f64;
+                            modified = true;
+                        }
+                    });
+
+                    modified |= columns[2]
+                        .add_enabled(
+                            timing.flash_scope != 0,
+                            luminol_components::Field::new(
+                                "Duration",
+                                egui::DragValue::new(&mut timing.flash_duration)
+                                    .clamp_range(1..=200),
+                            ),
+                        )
+                        .changed();
+                });
+            })
+            .response;
+
+        if modified {
+            response.mark_changed();
+        }
+        response
+    }
+
+    fn show_cell_properties(
+        ui: &mut egui::Ui,
+        frame: &mut rpg::animation::Frame,
+        index: usize,
+    ) -> bool {
+        let mut modified = false;
+        let data = &mut frame.cell_data;
+
+        ui.label(format!("Cell {}", index + 1));
+        ui.columns(4, |columns| {
+            modified |= columns[0]
+                .add(luminol_components::Field::new(
+                    "Pattern",
+                    egui::DragValue::new(&mut data[(index, cell::PATTERN)])
+                        .custom_formatter(|n, _| (n as i16 + 1).to_string())
+                        .custom_parser(|s| s.parse::<f64>().ok().map(|n| n - 1.))
+                        .clamp_range(0..=i16::MAX),
+                ))
+                .changed();
+
+            modified |= columns[1]
+                .add(luminol_components::Field::new(
+                    "X",
+                    egui::DragValue::new(&mut data[(index, cell::X)]),
+                ))
+                .changed();
+
+            modified |= columns[2]
+                .add(luminol_components::Field::new(
+                    "Y",
+                    egui::DragValue::new(&mut data[(index, cell::Y)]),
+                ))
+                .changed();
+
+            modified |= columns[3]
+                .add(luminol_components::Field::new(
+                    "Zoom",
+                    egui::DragValue::new(&mut data[(index, cell::ZOOM)])
+                        .clamp_range(5..=800)
+                        .suffix("%"),
+                ))
+                .changed();
+        });
+
+        ui.columns(4, |columns| {
+            modified |= columns[0]
+                .add(luminol_components::Field::new(
+                    "Angle",
+                    egui::DragValue::new(&mut data[(index, cell::ANGLE)])
+                        .clamp_range(0..=359)
+                        .suffix("°"),
+                ))
+                .changed();
+
+            modified |= columns[1]
+                .add(luminol_components::Field::new(
+                    "Opacity",
+                    egui::DragValue::new(&mut data[(index, cell::OPACITY)]).clamp_range(0..=255),
+                ))
+                .changed();
+
+            let mut mirror = data[(index, cell::MIRROR)] == 1;
+            if columns[2]
+                .add(luminol_components::Field::new(
+                    "Mirror",
+                    egui::Checkbox::without_text(&mut mirror),
+                ))
+                .changed()
+            {
+                data[(index, cell::MIRROR)] = mirror as i16;
+                modified = true;
+            }
+
+            let blend_type = &mut data[(index, cell::BLEND_TYPE)];
+            let old_blend_type = *blend_type;
+            columns[3].add(luminol_components::Field::new(
+                "Blending",
+                |ui: &mut egui::Ui| {
+                    egui::ComboBox::from_id_source("animation_cell_blend_type")
+                        .selected_text(
+                            BLEND_TYPES
+                                .get(*blend_type as usize)
+                                .copied()
+                                .unwrap_or_default(),
+                        )
+                        .show_ui(ui, |ui| {
+                            for (i, text) in BLEND_TYPES.into_iter().enumerate() {
+                                ui.selectable_value(blend_type, i as i16, text);
+                            }
+                        })
+                        .response
+                },
+            ));
+            modified |= *blend_type != old_blend_type;
+        });
+
+        modified
+    }
+
+    /// Selects the animation with the given index.
+    pub fn select(&mut self, id: usize) {
+        self.view.select(id);
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("animation_editor")
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut animations = std::mem::take(&mut *update_state.data.animations());
+
+        let mut modified = false;
+
+        let name = if let Some(name) = &self.selected_animation_name {
+            format!("Editing animation {:?}", name)
+        } else {
+            "Animation Editor".into()
+        };
+        self.selected_animation_name = None;
+
+        let response = egui::Window::new(name)
+            .id(self.id())
+            .default_width(660.)
+            .open(open)
+            .show(ctx, |ui| {
+                self.view.show(
+                    ui,
+                    update_state,
+                    "Animations",
+                    &mut animations.data,
+                    |animation| format!("{:0>4}: {}", animation.id + 1, animation.name),
+                    |ui, animations, id, update_state| {
+                        let animation = &mut animations[id];
+                        self.selected_animation_name = Some(animation.name.clone());
+
+                        if self.previous_animation != Some(animation.id) {
+                            // avoid desyncs by resetting the modals if the animation has changed
+                            self.animation_picker.reset(
+                                update_state,
+                                (&mut animation.animation_name, &mut animation.animation_hue),
+                            );
+                            self.se_pickers.clear();
+                            self.collapsing_view.clear_animations();
+                            self.frame_view = AnimationFrameView::new();
+                            self.selected_frame = 0;
+                            self.playback = None;
+                        }
+
+                        if self.previous_animation != Some(animation.id)
+                            || self.sheet_name != animation.animation_name
+                        {
+                            self.sheet =
+                                Self::load_sheet(update_state, animation.animation_name.as_deref());
+                            self.sheet_name = animation.animation_name.clone();
+                        }
+
+                        if animation.frames.is_empty() {
+                            animation.frames.push(Self::empty_frame());
+                            animation.frame_max = 1;
+                            modified = true;
+                        }
+                        self.selected_frame = self.selected_frame.min(animation.frames.len() - 1);
+
+                        ui.with_padded_stripe(false, |ui| {
+                            modified |= ui
+                                .add(luminol_components::Field::new(
+                                    "Name",
+                                    egui::TextEdit::singleline(&mut animation.name)
+                                        .desired_width(f32::INFINITY),
+                                ))
+                                .changed();
+                        });
+
+                        ui.with_padded_stripe(true, |ui| {
+                            ui.columns(3, |columns| {
+                                modified |= columns[0]
+                                    .add(luminol_components::Field::new(
+                                        "Graphic",
+                                        self.animation_picker.button(
+                                            (
+                                                &mut animation.animation_name,
+                                                &mut animation.animation_hue,
+                                            ),
+                                            update_state,
+                                        ),
+                                    ))
+                                    .changed();
+
+                                modified |= columns[1]
+                                    .add(luminol_components::Field::new(
+                                        "Position",
+                                        luminol_components::EnumComboBox::new(
+                                            (animation.id, "position"),
+                                            &mut animation.position,
+                                        ),
+                                    ))
+                                    .changed();
+
+                                let mut frame_max = animation.frames.len();
+                                if columns[2]
+                                    .add(luminol_components::Field::new(
+                                        "Frames",
+                                        egui::DragValue::new(&mut frame_max).clamp_range(1..=200),
+                                    ))
+                                    .changed()
+                                {
+                                    animation.frames.resize_with(frame_max, Self::empty_frame);
+                                    animation.frame_max = frame_max as i32;
+                                    modified = true;
+                                }
+                            });
+                        });
+
+                        // Advance the preview, if it's playing
+                        let mut flash = None;
+                        if let Some(playback) = &mut self.playback {
+                            let time = ui.input(|i| i.time);
+                            let frame =
+                                ((time - playback.start_time) * FRAME_RATE).max(0.) as usize;
+                            if frame < animation.frames.len() {
+                                flash =
+                                    Self::update_playback(update_state, playback, animation, frame);
+                                self.selected_frame = frame;
+                                ui.ctx().request_repaint();
+                            } else {
+                                self.playback = None;
+                            }
+                        }
+                        let playing = self.playback.is_some();
+
+                        ui.with_padded_stripe(false, |ui| {
+                            ui.horizontal(|ui| {
+                                if playing {
+                                    if ui.button("⏹ Stop").clicked() {
+                                        self.playback = None;
+                                    }
+                                } else if ui.button("▶ Play").clicked() {
+                                    self.playback = Some(Playback {
+                                        start_time: ui.input(|i| i.time),
+                                        last_frame: None,
+                                    });
+                                    self.selected_frame = 0;
+                                    ui.ctx().request_repaint();
+                                }
+
+                                ui.add_enabled_ui(!playing, |ui| {
+                                    if ui
+                                        .button(
+                                            egui::RichText::new("Add")
+                                                .color(egui::Color32::LIGHT_GREEN),
+                                        )
+                                        .clicked()
+                                    {
+                                        self.selected_frame += 1;
+                                        Self::insert_frame(animation, self.selected_frame);
+                                        modified = true;
+                                    }
+
+                                    let button = egui::Button::new(
+                                        egui::RichText::new("Delete")
+                                            .color(egui::Color32::LIGHT_RED),
+                                    );
+                                    if ui.add_enabled(animation.frames.len() > 1, button).clicked()
+                                    {
+                                        Self::remove_frame(animation, self.selected_frame);
+                                        self.selected_frame =
+                                            self.selected_frame.min(animation.frames.len() - 1);
+                                        modified = true;
+                                    }
+                                });
+
+                                ui.separator();
+                                ui.checkbox(&mut self.show_previous_frame, "Previous frame");
+                                ui.checkbox(&mut self.show_next_frame, "Next frame");
+                            });
+
+                            egui::ScrollArea::horizontal()
+                                .id_source((animation.id, "frame_strip"))
+                                .show(ui, |ui| {
+                                    ui.horizontal(|ui| {
+                                        for i in 0..animation.frames.len() {
+                                            let response = ui.selectable_value(
+                                                &mut self.selected_frame,
+                                                i,
+                                                format!("{:0>3}", i + 1),
+                                            );
+                                            if response.changed() {
+                                                self.playback = None;
+                                            }
+                                            if playing && i == self.selected_frame {
+                                                response.scroll_to_me(None);
+                                            }
+                                        }
+                                    });
+                                });
+                        });
+
+                        ui.with_padded_stripe(true, |ui| {
+                            let (before, rest) = animation.frames.split_at_mut(self.selected_frame);
+                            let (frame, after) =
+                                rest.split_first_mut().expect("frame index out of bounds");
+
+                            // Don't draw neighbouring frames on top of the preview
+                            let mut ghosts = Vec::with_capacity(2);
+                            if !playing {
+                                if let Some(previous) =
+                                    before.last().filter(|_| self.show_previous_frame)
+                                {
+                                    ghosts.push(Ghost {
+                                        frame: previous,
+                                        tint: egui::Color32::from_rgba_unmultiplied(
+                                            255, 128, 128, 96,
+                                        ),
+                                    });
+                                }
+                                if let Some(next) = after.first().filter(|_| self.show_next_frame) {
+                                    ghosts.push(Ghost {
+                                        frame: next,
+                                        tint: egui::Color32::from_rgba_unmultiplied(
+                                            128, 128, 255, 96,
+                                        ),
+                                    });
+                                }
+                            }
+
+                            ui.vertical_centered(|ui| {
+                                modified |= self
+                                    .frame_view
+                                    .ui(
+                                        ui,
+                                        self.sheet.as_deref(),
+                                        frame,
+                                        &ghosts,
+                                        self.selected_pattern,
+                                        flash,
+                                    )
+                                    .changed();
+                            });
+                            ui.label(
+                                "Click on an empty spot to place the selected pattern, drag cells \
+                                 to move them and press Delete to remove the selected cell.",
+                            );
+
+                            if let Some(index) = self.frame_view.selected_cell {
+                                modified |= Self::show_cell_properties(ui, frame, index);
+                            }
+                        });
+
+                        if let Some(sheet) = &self.sheet {
+                            ui.with_padded_stripe(false, |ui| {
+                                ui.label("Pattern:");
+                                egui::ScrollArea::horizontal()
+                                    .id_source((animation.id, "pattern_sheet"))
+                                    .show(ui, |ui| {
+                                        AnimationFrameView::sheet_ui(
+                                            ui,
+                                            sheet,
+                                            &mut self.selected_pattern,
+                                            48.,
+                                        );
+                                    });
+                            });
+                        }
+
+                        ui.with_padded_stripe(true, |ui| {
+                            let frame_max = animation.frames.len() as i32;
+                            modified |= ui
+                                .add(luminol_components::Field::new(
+                                    "SE and Flash Timings",
+                                    |ui: &mut egui::Ui| {
+                                        self.collapsing_view.show(
+                                            ui,
+                                            animation.id,
+                                            &mut animation.timings,
+                                            |ui, _i, timing| Self::show_timing_header(ui, timing),
+                                            |ui, i, timing| {
+                                                // Timings can be added at any time, so the
+                                                // pickers are created as they're needed
+                                                if self.se_pickers.len() <= i {
+                                                    self.se_pickers.resize_with(i + 1, || {
+                                                        SoundPicker::new(
+                                                            luminol_audio::Source::SE,
+                                                            egui::Id::new("animation_se_picker")
+                                                                .with(i),
+                                                        )
+                                                    });
+                                                }
+                                                Self::show_timing_body(
+                                                    ui,
+                                                    update_state,
+                                                    &mut self.se_pickers[i],
+                                                    frame_max,
+                                                    (i, timing),
+                                                )
+                                            },
+                                        )
+                                    },
+                                ))
+                                .changed();
+
+                            // Keep the timings in the order they're played in
+                            if animation
+                                .timings
+                                .windows(2)
+                                .any(|w| w[0].frame > w[1].frame)
+                            {
+                                ui.horizontal(|ui| {
+                                    ui.label("Timings are out of order.");
+                                    if ui.button("Sort").clicked() {
+                                        animation.timings.sort_by_key(|t| t.frame);
+                                        self.se_pickers.clear();
+                                        self.collapsing_view.clear_animations();
+                                        modified = true;
+                                    }
+                                });
+                            }
+                        });
+
+                        self.previous_animation = Some(animation.id);
+
+                        modified
+                    },
+                )
+            });
+
+        if response.is_some_and(|ir| ir.inner.is_some_and(|ir| ir.inner.modified)) {
+            modified = true;
+        }
+
+        if modified {
+            update_state.modified.set(true);
+            animations.modified = true;
+        }
+
+        *update_state.data.animations() = animations;
+    }
+}
@@ -36,6 +36,11 @@ impl Window {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Selects the armor with the given index.
+    pub fn select(&mut self, id: usize) {
+        self.view.select(id);
+    }
 }
 
 impl luminol_core::Window for Window {
@@ -247,6 +252,8 @@ impl luminol_core::Window for Window {
                         });
 
                         self.previous_armor = Some(armor.id);
+
+                        modified
                     },
                 )
             });
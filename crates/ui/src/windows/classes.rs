@@ -96,6 +96,11 @@ impl Window {
         }
         response
     }
+
+    /// Selects the class with the given index.
+    pub fn select(&mut self, id: usize) {
+        self.view.select(id);
+    }
 }
 
 impl luminol_core::Window for Window {
@@ -293,6 +298,8 @@ impl luminol_core::Window for Window {
                         });
 
                         self.previous_class = Some(class.id);
+
+                        modified
                     },
                 )
             });
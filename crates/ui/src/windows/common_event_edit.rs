@@ -22,24 +22,44 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use luminol_components::UiExt;
 use luminol_core::Modal;
-use luminol_modals::database_modal;
+
+use luminol_modals::database_modal::SwitchModal;
+
+const TRIGGER_TYPES: [&str; 3] = ["None", "Autorun", "Parallel"];
 
 /// The common event editor.
 pub struct Window {
-    tabs: luminol_core::Tabs,
-    _selected_id: usize,
+    selected_event_name: Option<String>,
+
+    switch_modal: SwitchModal,
+    command_view: luminol_components::CommandView,
+
+    previous_event: Option<usize>,
+
+    view: luminol_components::DatabaseView,
 }
 
 impl Default for Window {
     fn default() -> Self {
         Self {
-            tabs: luminol_core::Tabs::new("common_event_tabs", false),
-            _selected_id: 0,
+            selected_event_name: None,
+            switch_modal: SwitchModal::new(egui::Id::new("common_event_switch_modal")),
+            command_view: luminol_components::CommandView::new("common_event_commands"),
+            previous_event: None,
+            view: luminol_components::DatabaseView::new(),
         }
     }
 }
 
+impl Window {
+    /// Selects the common event with the given index.
+    pub fn select(&mut self, id: usize) {
+        self.view.select(id);
+    }
+}
+
 impl luminol_core::Window for Window {
     fn id(&self) -> egui::Id {
         egui::Id::new("Common Events")
@@ -49,109 +69,143 @@ impl luminol_core::Window for Window {
         &mut self,
         ctx: &egui::Context,
         open: &mut bool,
-        _update_state: &mut luminol_core::UpdateState<'_>,
+        update_state: &mut luminol_core::UpdateState<'_>,
     ) {
-        let name = self
-            .tabs
-            .focused_name()
-            .map_or("Common Events".to_string(), |name| {
-                format!("Editing Common Event {name}")
-            });
-        egui::Window::new(name)
+        // Take the common events out of the data cache so the switch modal and the command view can
+        // still use the rest of it
+        let mut common_events = std::mem::take(&mut *update_state.data.common_events());
+
+        let mut modified = false;
+
+        let name = if let Some(name) = &self.selected_event_name {
+            format!("Editing Common Event {name:?}")
+        } else {
+            "Common Events".into()
+        };
+        self.selected_event_name = None;
+
+        let response = egui::Window::new(name)
+            .id(self.id())
             .default_width(500.)
-            .id(egui::Id::new("common_events_edit"))
             .open(open)
-            .show(ctx, |_| {
-                // TODO
+            .show(ctx, |ui| {
+                self.view.show(
+                    ui,
+                    update_state,
+                    "Common Events",
+                    &mut common_events.data,
+                    |event| format!("{:0>4}: {}", event.id + 1, event.name),
+                    |ui, common_events, id, update_state| {
+                        let event = &mut common_events[id];
+                        self.selected_event_name = Some(event.name.clone());
+
+                        if self.previous_event != Some(event.id) {
+                            // The command view keeps track of selections and open windows, which
+                            // don't make sense for a different event
+                            self.command_view = luminol_components::CommandView::new((
+                                "common_event_commands",
+                                event.id,
+                            ));
+                        }
+
+                        ui.with_padded_stripe(false, |ui| {
+                            modified |= ui
+                                .add(luminol_components::Field::new(
+                                    "Name",
+                                    egui::TextEdit::singleline(&mut event.name)
+                                        .desired_width(f32::INFINITY),
+                                ))
+                                .changed();
+                        });
+
+                        ui.with_padded_stripe(true, |ui| {
+                            ui.columns(2, |columns| {
+                                let old_trigger = event.trigger;
+                                columns[0].add(luminol_components::Field::new(
+                                    "Trigger",
+                                    |ui: &mut egui::Ui| {
+                                        egui::ComboBox::from_id_source((event.id, "trigger"))
+                                            .selected_text(
+                                                TRIGGER_TYPES
+                                                    .get(event.trigger)
+                                                    .copied()
+                                                    .unwrap_or_default(),
+                                            )
+                                            .show_ui(ui, |ui| {
+                                                for (trigger, text) in
+                                                    TRIGGER_TYPES.into_iter().enumerate()
+                                                {
+                                                    ui.selectable_value(
+                                                        &mut event.trigger,
+                                                        trigger,
+                                                        text,
+                                                    );
+                                                }
+                                            })
+                                            .response
+                                    },
+                                ));
+                                modified |= event.trigger != old_trigger;
+
+                                // Switches are stored 1-indexed here, while the modal works with
+                                // 0-indexed ids
+                                let old_switch_id = event.switch_id.saturating_sub(1);
+                                let mut switch_id = old_switch_id;
+                                if self.previous_event != Some(event.id) {
+                                    self.switch_modal.reset(update_state, &mut switch_id);
+                                }
+                                columns[1].add_enabled(
+                                    event.trigger > 0,
+                                    luminol_components::Field::new(
+                                        "Condition Switch",
+                                        self.switch_modal.button(&mut switch_id, update_state),
+                                    ),
+                                );
+                                // Events without a switch are stored with 0, which the modal
+                                // can't show, so only write the id back when one was picked
+                                if switch_id != old_switch_id {
+                                    event.switch_id = switch_id + 1;
+                                    modified = true;
+                                }
+                            });
+                        });
+
+                        let mut list = std::mem::take(&mut event.list);
+                        ui.with_padded_stripe(false, |ui| {
+                            // Put the common events back into the data cache while the command
+                            // view is shown, so it can look up their names
+                            std::mem::swap(
+                                common_events,
+                                &mut update_state.data.common_events().data,
+                            );
+                            modified |= self.command_view.ui(ui, update_state, &mut list).changed();
+                            std::mem::swap(
+                                common_events,
+                                &mut update_state.data.common_events().data,
+                            );
+                        });
+                        common_events[id].list = list;
+
+                        self.previous_event = Some(common_events[id].id);
+
+                        modified
+                    },
+                )
             });
-    }
 
-    fn requires_filesystem(&self) -> bool {
-        true
-    }
-}
-
-struct CommonEventTab {
-    event: luminol_data::rpg::CommonEvent,
-    force_close: bool,
-    switch_modal: database_modal::SwitchModal,
-    command_view: luminol_components::CommandView,
-}
-
-impl luminol_core::Tab for CommonEventTab {
-    fn name(&self, _update_state: &luminol_core::UpdateState<'_>) -> String {
-        format!("{}: {}", self.event.name, self.event.id)
-    }
+        if response.is_some_and(|ir| ir.inner.is_some_and(|ir| ir.inner.modified)) {
+            modified = true;
+        }
 
-    fn id(&self) -> egui::Id {
-        egui::Id::new("luminol_common_event").with(self.event.id)
-    }
+        if modified {
+            update_state.modified.set(true);
+            common_events.modified = true;
+        }
 
-    fn show(
-        &mut self,
-        ui: &mut egui::Ui,
-        update_state: &mut luminol_core::UpdateState<'_>,
-        _is_focused: bool,
-    ) {
-        ui.horizontal(|ui| {
-            let trigger_types = ["None", "Autorun", "Parallel"];
-            egui::ComboBox::new(format!("common_event_{}_trigger", self.event.id), "Trigger")
-                .selected_text(trigger_types[self.event.trigger])
-                .show_ui(ui, |ui| {
-                    for (ele, trigger) in trigger_types.into_iter().enumerate() {
-                        ui.selectable_value(&mut self.event.trigger, ele, trigger);
-                    }
-                });
-
-            ui.add_enabled(
-                self.event.trigger > 0,
-                self.switch_modal
-                    .button(&mut self.event.switch_id, update_state),
-            );
-
-            let mut save_event = false;
-
-            if ui.button("Ok").clicked() {
-                save_event = true;
-                self.force_close = true;
-            }
-
-            if ui.button("Cancel").clicked() {
-                self.force_close = true;
-            }
-
-            if ui.button("Apply").clicked() {
-                save_event = true;
-            }
-
-            if save_event {
-                let mut common_events = update_state.data.common_events();
-
-                common_events.data[self.event.id - 1] = self.event.clone();
-            }
-
-            ui.label("Name");
-            ui.text_edit_singleline(&mut self.event.name);
-        });
-
-        ui.separator();
-
-        egui::ScrollArea::both()
-            .auto_shrink([false; 2])
-            .show(ui, |ui| {
-                self.command_view.ui(
-                    ui,
-                    &update_state.project_config.as_ref().unwrap().command_db,
-                    &mut self.event.list,
-                );
-            });
+        *update_state.data.common_events() = common_events;
     }
 
     fn requires_filesystem(&self) -> bool {
         true
     }
-
-    fn force_close(&mut self) -> bool {
-        self.force_close
-    }
 }
@@ -52,10 +52,64 @@ impl luminol_core::Window for Window {
                 ui.text_edit_singleline(&mut config.project.project_name);
                 ui.label("Scripts path");
                 ui.text_edit_singleline(&mut config.project.scripts_path);
-                ui.checkbox(
-                    &mut config.project.use_ron,
-                    "Use RON (Rusty Object Notation)",
-                );
+                let mut use_ron = config.project.use_ron;
+                if ui
+                    .checkbox(&mut use_ron, "Use RON (Rusty Object Notation)")
+                    .on_hover_text(
+                        "Store the database and maps as text files, with a file for every entry (like Data/Actors/001.ron). Changing this saves the project in the new format",
+                    )
+                    .changed()
+                {
+                    let format = if use_ron {
+                        luminol_core::DataFormat::Ron
+                    } else {
+                        luminol_core::DataFormat::marshal(config.project.editor_ver)
+                    };
+                    match update_state
+                        .data
+                        .convert(update_state.filesystem, config, format)
+                    {
+                        Ok(()) => {
+                            update_state.modified.set(false);
+                            luminol_core::info!(
+                                update_state.toasts,
+                                format!("Converted the project data to {format}")
+                            );
+                        }
+                        Err(e) => luminol_core::error!(
+                            update_state.toasts,
+                            e.wrap_err("Error converting the project data")
+                        ),
+                    }
+                }
+
+                if config.project.use_ron {
+                    let format = luminol_core::DataFormat::marshal(config.project.editor_ver);
+                    if ui
+                        .button("Export data for the game")
+                        .on_hover_text(
+                            "Save the project, then write it to the Marshal files the game reads",
+                        )
+                        .clicked()
+                    {
+                        match update_state
+                            .data
+                            .export(update_state.filesystem, config, format)
+                        {
+                            Ok(()) => {
+                                update_state.modified.set(false);
+                                luminol_core::info!(
+                                    update_state.toasts,
+                                    format!("Exported the project data to {format}")
+                                );
+                            }
+                            Err(e) => luminol_core::error!(
+                                update_state.toasts,
+                                e.wrap_err("Error exporting the project data")
+                            ),
+                        }
+                    }
+                }
                 egui::ComboBox::from_label("RGSS Version")
                     .selected_text(config.project.rgss_ver.to_string())
                     .show_ui(ui, |ui| {
@@ -66,6 +120,12 @@ impl luminol_core::Window for Window {
 
                 ui.label("Playtest Executable");
                 ui.text_edit_singleline(&mut config.project.playtest_exe);
+
+                ui.label("Backups kept when saving");
+                ui.add(egui::DragValue::new(&mut config.project.backup_count).clamp_range(0..=100))
+                    .on_hover_text(
+                        "Before saving, the data files that are about to be overwritten are copied to .luminol/backups. Set this to 0 to turn backups off",
+                    );
             });
     }
 
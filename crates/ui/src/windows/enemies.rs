@@ -244,6 +244,11 @@ impl Window {
         }
         response
     }
+
+    /// Selects the enemy with the given index.
+    pub fn select(&mut self, id: usize) {
+        self.view.select(id);
+    }
 }
 
 impl luminol_core::Window for Window {
@@ -669,6 +674,8 @@ impl luminol_core::Window for Window {
                         });
 
                         self.previous_enemy = Some(enemy.id);
+
+                        modified
                     },
                 )
             });
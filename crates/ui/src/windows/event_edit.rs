@@ -27,6 +27,7 @@ use luminol_core::prelude::*;
 use luminol_modals::{
     database_modal::{SwitchModal, VariableModal},
     graphic_picker::event::Modal as GraphicPicker,
+    move_route::Modal as MoveRouteModal,
 };
 
 /// The event editor window.
@@ -39,8 +40,13 @@ pub struct Window {
     switch_2_modal: SwitchModal,
     variable_modal: VariableModal,
     graphic_modal: GraphicPicker,
+    move_route_modal: MoveRouteModal,
+    command_view: luminol_components::CommandView,
 }
 
+/// Where copied event pages are kept in egui memory.
+const PAGE_CLIPBOARD_ID: &str = "luminol_event_page_clipboard";
+
 impl Window {
     /// Create a new event editor.
     pub fn new(
@@ -67,6 +73,8 @@ impl Window {
             switch_2_modal: SwitchModal::new(id_source.with("switch_2_modal")),
             variable_modal: VariableModal::new(id_source.with("variable_modal")),
             graphic_modal,
+            move_route_modal: MoveRouteModal::new(id_source.with("move_route_modal")),
+            command_view: luminol_components::CommandView::new(id_source.with(0)),
         }
     }
 }
@@ -93,6 +101,10 @@ impl luminol_core::Window for Window {
         };
         drop(map);
 
+        // the history may have removed the page we were on
+        self.selected_page = self.selected_page.min(event.pages.len().saturating_sub(1));
+        let before = luminol_core::history::snapshot(&event).ok();
+
         let mut modified = false;
         let mut graphic_modified = false;
 
@@ -102,6 +114,7 @@ impl luminol_core::Window for Window {
             .show(ctx, |ui| {
                 let id_source = self.id();
                 let previous_page = self.selected_page;
+                let mut page_replaced = false;
 
                 egui::TopBottomPanel::top(id_source.with("top_panel")).show_inside(ui, |ui| {
                     ui.add_space(1.0); // pad the top of the window
@@ -115,35 +128,68 @@ impl luminol_core::Window for Window {
                             ui.selectable_value(&mut self.selected_page, i, format!("{}", i + 1));
                         }
 
+                        ui.separator();
+
                         if ui
-                            .button(egui::RichText::new("Add").color(egui::Color32::LIGHT_GREEN))
+                            .button(egui::RichText::new("New").color(egui::Color32::LIGHT_GREEN))
                             .clicked()
                         {
-                            modified |= true;
                             event.pages.push(rpg::EventPage::default());
                             self.selected_page = event.pages.len() - 1;
+                            page_replaced = true;
+                        }
+
+                        if ui.button("Copy").clicked() {
+                            let page = event.pages[self.selected_page].duplicate();
+                            ui.data_mut(|d| d.insert_temp(egui::Id::new(PAGE_CLIPBOARD_ID), page));
+                        }
+
+                        let clipboard = ui.data(|d| {
+                            d.get_temp::<rpg::EventPage>(egui::Id::new(PAGE_CLIPBOARD_ID))
+                        });
+                        if ui
+                            .add_enabled(clipboard.is_some(), egui::Button::new("Paste"))
+                            .on_hover_text("Inserts the copied page after this one")
+                            .clicked()
+                        {
+                            if let Some(page) = clipboard {
+                                // every paste gets its own guids so the pages can be edited separately
+                                self.selected_page += 1;
+                                event.pages.insert(self.selected_page, page.duplicate());
+                                page_replaced = true;
+                            }
                         }
 
                         let button = egui::Button::new(
                             egui::RichText::new("Delete").color(egui::Color32::LIGHT_RED),
                         );
                         if ui.add_enabled(event.pages.len() > 1, button).clicked() {
-                            modified |= true;
                             event.pages.remove(self.selected_page);
                             self.selected_page = self.selected_page.saturating_sub(1);
+                            page_replaced = true;
                         }
                         if ui.button(egui::RichText::new("Clear")).clicked() {
-                            modified |= true;
                             event.pages[self.selected_page] = rpg::EventPage::default();
+                            page_replaced = true;
                         }
                     });
                     ui.add_space(1.0); // pad the bottom of the window
                 });
 
+                if page_replaced {
+                    // any page could be the one the map shows, so redraw the event
+                    modified = true;
+                    graphic_modified = true;
+                }
+
                 let page = &mut event.pages[self.selected_page];
-                if self.selected_page != previous_page {
-                    // reset the modal if we've changed pages
+                if self.selected_page != previous_page || page_replaced {
+                    // reset the modals if we've changed pages
+                    self.command_view =
+                        luminol_components::CommandView::new(id_source.with(self.selected_page));
                     self.graphic_modal.reset(update_state, &mut page.graphic);
+                    self.move_route_modal
+                        .reset(update_state, &mut page.move_route);
                 }
 
                 egui::SidePanel::left(id_source.with("side_panel")).show_inside(ui, |ui| {
@@ -207,7 +253,7 @@ impl luminol_core::Window for Window {
                         ui.vertical(|ui| {
                             ui.label("Graphic");
 
-                            graphic_modified = self
+                            graphic_modified |= self
                                 .graphic_modal
                                 .button(&mut page.graphic, update_state)
                                 .ui(ui)
@@ -226,10 +272,13 @@ impl luminol_core::Window for Window {
                                     .ui(ui)
                                     .changed();
                                 });
-                                ui.add_enabled(
-                                    page.move_type == luminol_data::rpg::MoveType::Custom,
-                                    egui::Button::new("Move Route..."),
-                                ); // TODO
+                                modified |= ui
+                                    .add_enabled(
+                                        page.move_type == luminol_data::rpg::MoveType::Custom,
+                                        self.move_route_modal
+                                            .button(&mut page.move_route, update_state),
+                                    )
+                                    .changed();
                                 ui.horizontal(|ui| {
                                     ui.label("Move Speed");
                                     modified |= luminol_components::EnumComboBox::new(
@@ -284,12 +333,28 @@ impl luminol_core::Window for Window {
                         });
                     });
                 });
+
+                egui::CentralPanel::default().show_inside(ui, |ui| {
+                    egui::ScrollArea::both()
+                        .id_source(id_source.with("commands"))
+                        .auto_shrink([false, false])
+                        .show(ui, |ui| {
+                            modified |= self
+                                .command_view
+                                .ui(ui, update_state, &mut page.list)
+                                .changed();
+                        });
+                });
             });
 
         if graphic_modified {
             event.extra_data.graphic_modified.set(true);
+            modified = true;
         }
 
+        let description = format!("Edit event '{}'", event.name);
+        let after = luminol_core::history::snapshot(&event).ok();
+
         // reinsert the event into the map
         let mut map = update_state.data.get_map(self.map_id);
         map.events.insert(self.event_id, event);
@@ -297,6 +362,19 @@ impl luminol_core::Window for Window {
         if modified {
             map.modified = true;
         }
+        drop(map);
+
+        update_state.record_change(
+            description,
+            vec![luminol_core::history::Diff::new(
+                luminol_core::history::Target::Event {
+                    map_id: self.map_id,
+                    event_id: self.event_id,
+                },
+                before,
+                after,
+            )],
+        );
     }
 
     fn requires_filesystem(&self) -> bool {
@@ -34,10 +34,17 @@ impl luminol_core::Window for Window {
         &mut self,
         ctx: &egui::Context,
         open: &mut bool,
-        _update_state: &mut luminol_core::UpdateState<'_>,
+        update_state: &mut luminol_core::UpdateState<'_>,
     ) {
         egui::Window::new("Luminol Preferences")
             .open(open)
-            .show(ctx, |_ui| {});
+            .show(ctx, |ui| {
+                ui.add(luminol_components::Field::new(
+                    "Undo history depth",
+                    egui::DragValue::new(&mut update_state.global_config.history_depth)
+                        .clamp_range(1..=1000),
+                ))
+                .on_hover_text("How many changes can be undone, both project-wide and in each map");
+            });
     }
 }
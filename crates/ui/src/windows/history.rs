@@ -0,0 +1,116 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+/// The history window.
+/// Lists the changes that can be undone and redone, and undoes or redoes everything up to a
+/// change when it's clicked.
+#[derive(Default)]
+pub struct Window {}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_history_window")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut undo_count = 0;
+        let mut redo_count = 0;
+
+        egui::Window::new("History")
+            .id(self.id())
+            .default_width(300.)
+            .open(open)
+            .show(ctx, |ui| {
+                let history = &*update_state.history;
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(history.can_undo(), egui::Button::new("Undo"))
+                        .clicked()
+                    {
+                        undo_count = 1;
+                    }
+                    if ui
+                        .add_enabled(history.can_redo(), egui::Button::new("Redo"))
+                        .clicked()
+                    {
+                        redo_count = 1;
+                    }
+                });
+
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        let undo_len = history.undo_entries().count();
+
+                        if ui
+                            .selectable_label(undo_len == 0, "Start of history")
+                            .clicked()
+                        {
+                            undo_count = undo_len;
+                        }
+
+                        for (i, entry) in history.undo_entries().enumerate() {
+                            if ui
+                                .selectable_label(i + 1 == undo_len, entry.description())
+                                .clicked()
+                            {
+                                undo_count = undo_len - i - 1;
+                            }
+                        }
+
+                        // Undone changes are greyed out until they're redone
+                        for (i, entry) in history.redo_entries().enumerate() {
+                            if ui
+                                .selectable_label(
+                                    false,
+                                    egui::RichText::new(entry.description()).weak(),
+                                )
+                                .clicked()
+                            {
+                                redo_count = i + 1;
+                            }
+                        }
+                    });
+            });
+
+        for _ in 0..undo_count {
+            update_state.undo();
+        }
+        for _ in 0..redo_count {
+            update_state.redo();
+        }
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+}
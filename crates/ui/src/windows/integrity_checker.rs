@@ -0,0 +1,752 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use itertools::Itertools;
+use luminol_components::UiExt;
+use luminol_config::command_db::CommandDB;
+use luminol_data::commands::{CommandKind, Parameter, ParameterKind};
+use luminol_data::rpg::{AudioFile, EventCommand};
+use luminol_data::ParameterType;
+use luminol_filesystem::FileSystem;
+
+use super::location::Location;
+
+/// The integrity checker, which looks for references to database entries and files that don't
+/// exist.
+#[derive(Default)]
+pub struct Window {
+    findings: Option<Vec<Finding>>,
+}
+
+/// A problem found by [`check`].
+pub struct Finding {
+    location: Location,
+    message: String,
+}
+
+impl Finding {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// The kinds of IDs that can be referenced from the database and from event commands.
+#[derive(Clone, Copy)]
+enum Kind {
+    Actor,
+    Class,
+    Skill,
+    Item,
+    Weapon,
+    Armor,
+    Enemy,
+    Troop,
+    State,
+    Animation,
+    Tileset,
+    CommonEvent,
+    Element,
+    Switch,
+    Variable,
+    Map,
+}
+
+impl Kind {
+    fn from_parameter(kind: &ParameterKind) -> Option<Self> {
+        Some(match kind {
+            ParameterKind::Switch => Self::Switch,
+            ParameterKind::Variable => Self::Variable,
+            ParameterKind::Actor => Self::Actor,
+            ParameterKind::Class => Self::Class,
+            ParameterKind::Skill => Self::Skill,
+            ParameterKind::Item => Self::Item,
+            ParameterKind::Weapon => Self::Weapon,
+            ParameterKind::Armor => Self::Armor,
+            ParameterKind::Enemy => Self::Enemy,
+            ParameterKind::Troop => Self::Troop,
+            ParameterKind::State => Self::State,
+            ParameterKind::Animation => Self::Animation,
+            ParameterKind::CommonEvent => Self::CommonEvent,
+            ParameterKind::Map => Self::Map,
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Actor => "actor",
+            Self::Class => "class",
+            Self::Skill => "skill",
+            Self::Item => "item",
+            Self::Weapon => "weapon",
+            Self::Armor => "armor",
+            Self::Enemy => "enemy",
+            Self::Troop => "troop",
+            Self::State => "state",
+            Self::Animation => "animation",
+            Self::Tileset => "tileset",
+            Self::CommonEvent => "common event",
+            Self::Element => "element",
+            Self::Switch => "switch",
+            Self::Variable => "variable",
+            Self::Map => "map",
+        }
+    }
+}
+
+/// The number of entries of every kind of ID, used to find dangling references.
+#[derive(Default)]
+struct Counts {
+    actors: usize,
+    classes: usize,
+    skills: usize,
+    items: usize,
+    weapons: usize,
+    armors: usize,
+    enemies: usize,
+    troops: usize,
+    states: usize,
+    animations: usize,
+    tilesets: usize,
+    common_events: usize,
+    elements: usize,
+    switches: usize,
+    variables: usize,
+    maps: std::collections::HashSet<usize>,
+}
+
+impl Counts {
+    /// Does an entry with the given ID exist? IDs here start at 1, like they do in the game.
+    fn contains(&self, kind: Kind, id: usize) -> bool {
+        let count = match kind {
+            Kind::Actor => self.actors,
+            Kind::Class => self.classes,
+            Kind::Skill => self.skills,
+            Kind::Item => self.items,
+            Kind::Weapon => self.weapons,
+            Kind::Armor => self.armors,
+            Kind::Enemy => self.enemies,
+            Kind::Troop => self.troops,
+            Kind::State => self.states,
+            Kind::Animation => self.animations,
+            Kind::Tileset => self.tilesets,
+            Kind::CommonEvent => self.common_events,
+            // The first element is always empty and unused
+            Kind::Element => self.elements.saturating_sub(1),
+            Kind::Switch => self.switches,
+            Kind::Variable => self.variables,
+            Kind::Map => return self.maps.contains(&id),
+        };
+        (1..=count).contains(&id)
+    }
+}
+
+struct Checker<'a, F> {
+    filesystem: &'a F,
+    command_db: &'a CommandDB,
+    counts: Counts,
+    location: Location,
+    findings: Vec<Finding>,
+}
+
+impl<F: FileSystem> Checker<'_, F> {
+    fn report(&mut self, message: String) {
+        self.findings.push(Finding {
+            location: self.location,
+            message,
+        });
+    }
+
+    /// Checks an ID from the data model, which are stored starting from 0.
+    fn id(&mut self, kind: Kind, id: usize, what: &str) {
+        self.raw_id(kind, id as i32 + 1, what);
+    }
+
+    fn optional_id(&mut self, kind: Kind, id: Option<usize>, what: &str) {
+        if let Some(id) = id {
+            self.id(kind, id, what);
+        }
+    }
+
+    fn ids(&mut self, kind: Kind, ids: &[usize], what: &str) {
+        for &id in ids {
+            self.id(kind, id, what);
+        }
+    }
+
+    /// Checks an ID the way the game stores it, starting from 1. 0 means no entry.
+    fn raw_id(&mut self, kind: Kind, id: i32, what: &str) {
+        if id != 0 && !(id > 0 && self.counts.contains(kind, id as usize)) {
+            self.report(format!(
+                "{what} refers to {} {id:0>3}, which doesn't exist",
+                kind.name()
+            ));
+        }
+    }
+
+    /// Checks that a graphic or audio file exists. File extensions are resolved by the path cache.
+    fn file(&mut self, directory: &str, name: Option<&str>, what: &str) {
+        let Some(name) = name.filter(|name| !name.is_empty()) else {
+            return;
+        };
+        let path = camino::Utf8Path::new(directory).join(name);
+        match self.filesystem.exists(&path) {
+            Ok(true) => {}
+            Ok(false) => self.report(format!("{what} {path} doesn't exist")),
+            Err(e) => self.report(format!("{what} {path} couldn't be checked: {e}")),
+        }
+    }
+
+    fn path(&mut self, directory: &str, path: &luminol_data::Path, what: &str) {
+        self.file(directory, path.as_ref().map(|path| path.as_str()), what);
+    }
+
+    fn audio(&mut self, directory: &str, audio: &AudioFile, what: &str) {
+        self.path(directory, &audio.name, what);
+    }
+
+    fn commands(&mut self, list: &[EventCommand], what: &str) {
+        for (index, command) in list.iter().enumerate() {
+            let Some(description) = self.command_db.get(command.code) else {
+                continue;
+            };
+            let parameters = match &description.kind {
+                CommandKind::Single(parameters) | CommandKind::Branch { parameters, .. } => {
+                    parameters
+                }
+                CommandKind::Multi { .. } => continue,
+            };
+            let what = format!("{what}, command {} ({})", index + 1, description.name);
+            self.parameters(parameters, command, &what);
+        }
+    }
+
+    /// Checks the parameters of an event command. Only the chosen option of a selection is
+    /// checked, since the parameters of the other options are unused.
+    fn parameters(&mut self, parameters: &[Parameter], command: &EventCommand, what: &str) {
+        for parameter in parameters {
+            match parameter {
+                Parameter::Single {
+                    index, name, kind, ..
+                } => {
+                    let Some(kind) = Kind::from_parameter(kind) else {
+                        continue;
+                    };
+                    if let Some(&id) = command
+                        .parameters
+                        .get(index.as_usize())
+                        .and_then(ParameterType::as_integer)
+                    {
+                        self.raw_id(kind, id, &format!("{what}, {name}"));
+                    }
+                }
+                Parameter::Selection {
+                    index, parameters, ..
+                } => {
+                    let selected = command
+                        .parameters
+                        .get(index.as_usize())
+                        .and_then(ParameterType::as_integer)
+                        .copied()
+                        .unwrap_or_default();
+                    if let Some((_, parameter)) = parameters
+                        .iter()
+                        .find(|(value, _)| *value as i32 == selected)
+                    {
+                        self.parameters(std::slice::from_ref(parameter), command, what);
+                    }
+                }
+                Parameter::Group { parameters, .. } => {
+                    self.parameters(parameters, command, what);
+                }
+                Parameter::Dummy | Parameter::Label(_) => {}
+            }
+        }
+    }
+}
+
+/// Runs every check over the project data. Only RPG Maker XP projects are supported for now.
+pub fn check(
+    data: &luminol_core::Data,
+    filesystem: &impl FileSystem,
+    command_db: &CommandDB,
+) -> Vec<Finding> {
+    let counts = {
+        let system = data.system();
+        Counts {
+            actors: data.actors().data.len(),
+            classes: data.classes().data.len(),
+            skills: data.skills().data.len(),
+            items: data.items().data.len(),
+            weapons: data.weapons().data.len(),
+            armors: data.armors().data.len(),
+            enemies: data.enemies().data.len(),
+            troops: data.troops().data.len(),
+            states: data.states().data.len(),
+            animations: data.animations().data.len(),
+            tilesets: data.tilesets().data.len(),
+            common_events: data.common_events().data.len(),
+            elements: system.elements.len(),
+            switches: system.switches.len(),
+            variables: system.variables.len(),
+            maps: data.map_infos().data.keys().copied().collect(),
+        }
+    };
+
+    let mut c = Checker {
+        filesystem,
+        command_db,
+        counts,
+        location: Location::System,
+        findings: Vec::new(),
+    };
+
+    {
+        let system = data.system();
+        c.ids(Kind::Actor, &system.party_members, "The starting party");
+        c.optional_id(Kind::Troop, system.test_troop_id, "The battle test");
+        c.raw_id(
+            Kind::Map,
+            system.start_map_id as i32,
+            "The starting position",
+        );
+        c.path(
+            "Graphics/Windowskins",
+            &system.windowskin_name,
+            "The windowskin",
+        );
+        c.path("Graphics/Titles", &system.title_name, "The title graphic");
+        c.path(
+            "Graphics/Gameovers",
+            &system.gameover_name,
+            "The game over graphic",
+        );
+        c.path(
+            "Graphics/Transitions",
+            &system.battle_transition,
+            "The battle transition",
+        );
+        c.path(
+            "Graphics/Battlebacks",
+            &system.battleback_name,
+            "The battle test battleback",
+        );
+        c.path(
+            "Graphics/Battlers",
+            &system.battler_name,
+            "The battle test battler",
+        );
+        c.audio("Audio/BGM", &system.title_bgm, "The title BGM");
+        c.audio("Audio/BGM", &system.battle_bgm, "The battle BGM");
+        c.audio("Audio/ME", &system.battle_end_me, "The battle end ME");
+        c.audio("Audio/ME", &system.gameover_me, "The game over ME");
+        for (se, what) in [
+            (&system.cursor_se, "The cursor SE"),
+            (&system.decision_se, "The decision SE"),
+            (&system.cancel_se, "The cancel SE"),
+            (&system.buzzer_se, "The buzzer SE"),
+            (&system.equip_se, "The equip SE"),
+            (&system.shop_se, "The shop SE"),
+            (&system.save_se, "The save SE"),
+            (&system.load_se, "The load SE"),
+            (&system.battle_start_se, "The battle start SE"),
+            (&system.escape_se, "The escape SE"),
+            (&system.actor_collapse_se, "The actor collapse SE"),
+            (&system.enemy_collapse_se, "The enemy collapse SE"),
+        ] {
+            c.audio("Audio/SE", se, what);
+        }
+    }
+
+    for (id, actor) in data.actors().data.iter().enumerate() {
+        c.location = Location::Actor(id);
+        c.id(Kind::Class, actor.class_id, "The class");
+        c.optional_id(Kind::Weapon, actor.weapon_id, "The starting weapon");
+        for armor_id in [
+            actor.armor1_id,
+            actor.armor2_id,
+            actor.armor3_id,
+            actor.armor4_id,
+        ] {
+            c.optional_id(Kind::Armor, armor_id, "The starting armor");
+        }
+        c.path(
+            "Graphics/Characters",
+            &actor.character_name,
+            "The character graphic",
+        );
+        c.path(
+            "Graphics/Battlers",
+            &actor.battler_name,
+            "The battler graphic",
+        );
+    }
+
+    for (id, class) in data.classes().data.iter().enumerate() {
+        c.location = Location::Class(id);
+        c.ids(Kind::Weapon, &class.weapon_set, "The equippable weapons");
+        c.ids(Kind::Armor, &class.armor_set, "The equippable armor");
+        for learning in class.learnings.iter() {
+            c.id(
+                Kind::Skill,
+                learning.skill_id,
+                &format!("The skill learned at level {}", learning.level),
+            );
+        }
+    }
+
+    for (id, skill) in data.skills().data.iter().enumerate() {
+        c.location = Location::Skill(id);
+        c.optional_id(Kind::Animation, skill.animation1_id, "The user animation");
+        c.optional_id(Kind::Animation, skill.animation2_id, "The target animation");
+        c.optional_id(Kind::CommonEvent, skill.common_event_id, "The common event");
+        c.ids(Kind::Element, &skill.element_set, "The elements");
+        c.ids(Kind::State, &skill.plus_state_set, "The inflicted states");
+        c.ids(Kind::State, &skill.minus_state_set, "The removed states");
+        c.path("Graphics/Icons", &skill.icon_name, "The icon");
+        c.audio("Audio/SE", &skill.menu_se, "The menu SE");
+    }
+
+    for (id, item) in data.items().data.iter().enumerate() {
+        c.location = Location::Item(id);
+        c.optional_id(Kind::Animation, item.animation1_id, "The user animation");
+        c.optional_id(Kind::Animation, item.animation2_id, "The target animation");
+        c.optional_id(Kind::CommonEvent, item.common_event_id, "The common event");
+        c.ids(Kind::Element, &item.element_set, "The elements");
+        c.ids(Kind::State, &item.plus_state_set, "The inflicted states");
+        c.ids(Kind::State, &item.minus_state_set, "The removed states");
+        c.path("Graphics/Icons", &item.icon_name, "The icon");
+        c.audio("Audio/SE", &item.menu_se, "The menu SE");
+    }
+
+    for (id, weapon) in data.weapons().data.iter().enumerate() {
+        c.location = Location::Weapon(id);
+        c.optional_id(Kind::Animation, weapon.animation1_id, "The user animation");
+        c.optional_id(
+            Kind::Animation,
+            weapon.animation2_id,
+            "The target animation",
+        );
+        c.ids(Kind::Element, &weapon.element_set, "The elements");
+        c.ids(Kind::State, &weapon.plus_state_set, "The inflicted states");
+        c.ids(Kind::State, &weapon.minus_state_set, "The removed states");
+        c.path("Graphics/Icons", &weapon.icon_name, "The icon");
+    }
+
+    for (id, armor) in data.armors().data.iter().enumerate() {
+        c.location = Location::Armor(id);
+        c.optional_id(Kind::State, armor.auto_state_id, "The auto state");
+        c.ids(
+            Kind::Element,
+            &armor.guard_element_set,
+            "The guarded elements",
+        );
+        c.ids(Kind::State, &armor.guard_state_set, "The guarded states");
+        c.path("Graphics/Icons", &armor.icon_name, "The icon");
+    }
+
+    for (id, enemy) in data.enemies().data.iter().enumerate() {
+        c.location = Location::Enemy(id);
+        c.optional_id(Kind::Animation, enemy.animation1_id, "The attack animation");
+        c.optional_id(Kind::Animation, enemy.animation2_id, "The target animation");
+        c.optional_id(Kind::Item, enemy.item_id, "The treasure");
+        c.optional_id(Kind::Weapon, enemy.weapon_id, "The treasure");
+        c.optional_id(Kind::Armor, enemy.armor_id, "The treasure");
+        c.path(
+            "Graphics/Battlers",
+            &enemy.battler_name,
+            "The battler graphic",
+        );
+        for (index, action) in enemy.actions.iter().enumerate() {
+            let what = format!("Action {}", index + 1);
+            if action.kind == luminol_data::rpg::enemy::Kind::Skill {
+                c.id(Kind::Skill, action.skill_id, &what);
+            }
+            c.optional_id(Kind::Switch, action.condition_switch_id, &what);
+        }
+    }
+
+    for (id, troop) in data.troops().data.iter().enumerate() {
+        c.location = Location::Troop(id);
+        // Unnamed troops are unused slots, which are always empty
+        if troop.members.is_empty() && !troop.name.is_empty() {
+            c.report("The troop has no enemies".to_string());
+        }
+        for (index, member) in troop.members.iter().enumerate() {
+            c.id(
+                Kind::Enemy,
+                member.enemy_id,
+                &format!("Member {}", index + 1),
+            );
+        }
+        for (index, page) in troop.pages.iter().enumerate() {
+            let what = format!("Page {}", index + 1);
+            if page.condition.actor_valid {
+                c.optional_id(Kind::Actor, page.condition.actor_id, &what);
+            }
+            if page.condition.switch_valid {
+                c.optional_id(Kind::Switch, page.condition.switch_id, &what);
+            }
+            c.commands(&page.list, &what);
+        }
+    }
+
+    for (id, state) in data.states().data.iter().enumerate() {
+        c.location = Location::State(id);
+        c.optional_id(Kind::Animation, state.animation_id, "The animation");
+        c.ids(
+            Kind::Element,
+            &state.guard_element_set,
+            "The guarded elements",
+        );
+        c.ids(Kind::State, &state.plus_state_set, "The added states");
+        c.ids(Kind::State, &state.minus_state_set, "The removed states");
+    }
+
+    for (id, animation) in data.animations().data.iter().enumerate() {
+        c.location = Location::Animation(id);
+        c.path(
+            "Graphics/Animations",
+            &animation.animation_name,
+            "The graphic",
+        );
+        for timing in animation.timings.iter() {
+            c.audio(
+                "Audio/SE",
+                &timing.se,
+                &format!("The SE on frame {}", timing.frame + 1),
+            );
+        }
+    }
+
+    for (id, tileset) in data.tilesets().data.iter().enumerate() {
+        c.location = Location::Tileset(id);
+        c.path(
+            "Graphics/Tilesets",
+            &tileset.tileset_name,
+            "The tileset graphic",
+        );
+        for name in tileset.autotile_names.iter() {
+            c.file("Graphics/Autotiles", Some(name), "The autotile");
+        }
+        c.path("Graphics/Panoramas", &tileset.panorama_name, "The panorama");
+        c.path("Graphics/Fogs", &tileset.fog_name, "The fog");
+        c.path(
+            "Graphics/Battlebacks",
+            &tileset.battleback_name,
+            "The battleback",
+        );
+    }
+
+    for (id, common_event) in data.common_events().data.iter().enumerate() {
+        c.location = Location::CommonEvent(id);
+        if common_event.trigger != 0 {
+            // Unlike the other IDs in the data model, this one starts from 1
+            c.raw_id(
+                Kind::Switch,
+                common_event.switch_id as i32,
+                "The condition switch",
+            );
+        }
+        c.commands(&common_event.list, "The event");
+    }
+
+    check_maps(data, &mut c);
+
+    c.findings.sort_by_key(|finding| finding.location);
+    c.findings
+}
+
+fn check_maps<F: FileSystem>(data: &luminol_core::Data, c: &mut Checker<'_, F>) {
+    let map_infos = data.map_infos();
+
+    // Look for map files that aren't in MapInfos, which the editor and the game never show
+    c.location = Location::MapInfos;
+    let format = data.format();
+    match c.filesystem.read_dir("Data") {
+        Ok(entries) => {
+            for entry in entries {
+                let Some(id) = entry
+                    .path
+                    .file_name()
+                    .and_then(|name| name.strip_suffix(&format!(".{}", format.extension())))
+                    .and_then(|name| name.strip_prefix("Map"))
+                    .and_then(|id| id.parse::<usize>().ok())
+                else {
+                    continue;
+                };
+                if !map_infos.data.contains_key(&id) {
+                    c.report(format!("{} is not in the map list (MapInfos)", entry.path));
+                }
+            }
+        }
+        Err(e) => c.report(format!("The Data folder couldn't be read: {e}")),
+    }
+
+    for (&id, info) in map_infos.data.iter().sorted_by_key(|(id, _)| **id) {
+        c.location = Location::Map(id);
+        if info.parent_id != 0 && !map_infos.data.contains_key(&info.parent_id) {
+            c.report(format!(
+                "The parent map {:0>3} doesn't exist",
+                info.parent_id
+            ));
+        }
+
+        let map = match data.try_get_or_load_map(id, c.filesystem) {
+            Ok(map) => map,
+            Err(e) => {
+                c.report(format!("The map couldn't be loaded: {e:?}"));
+                continue;
+            }
+        };
+
+        c.id(Kind::Tileset, map.tileset_id, "The tileset");
+        if map.autoplay_bgm {
+            c.audio("Audio/BGM", &map.bgm, "The BGM");
+        }
+        if map.autoplay_bgs {
+            c.audio("Audio/BGS", &map.bgs, "The BGS");
+        }
+        for &troop_id in map.encounter_list.iter() {
+            c.raw_id(Kind::Troop, troop_id, "The encounter list");
+        }
+
+        for (event_id, event) in map.events.iter() {
+            for (index, page) in event.pages.iter().enumerate() {
+                let what = format!("Event {event_id:0>3} ({}), page {}", event.name, index + 1);
+                if page.condition.switch1_valid {
+                    c.id(Kind::Switch, page.condition.switch1_id, &what);
+                }
+                if page.condition.switch2_valid {
+                    c.id(Kind::Switch, page.condition.switch2_id, &what);
+                }
+                if page.condition.variable_valid {
+                    c.id(Kind::Variable, page.condition.variable_id, &what);
+                }
+                if page.graphic.tile_id.is_none() {
+                    c.path(
+                        "Graphics/Characters",
+                        &page.graphic.character_name,
+                        &format!("{what}, the graphic"),
+                    );
+                }
+                c.commands(&page.list, &what);
+            }
+        }
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("integrity_checker")
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut clicked_location = None;
+
+        egui::Window::new("Integrity Checker")
+            .id(self.id())
+            .default_width(500.)
+            .default_height(400.)
+            .open(open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Check project").clicked() {
+                        let command_db = &update_state
+                            .project_config
+                            .as_ref()
+                            .expect("project not loaded")
+                            .command_db;
+                        self.findings = Some(check(
+                            &*update_state.data,
+                            &*update_state.filesystem,
+                            command_db,
+                        ));
+                    }
+
+                    match &self.findings {
+                        Some(findings) if findings.is_empty() => {
+                            ui.label("No problems found");
+                        }
+                        Some(findings) => {
+                            ui.label(format!("{} problems found", findings.len()));
+                        }
+                        None => {
+                            ui.weak("Loads every map, which may take a while");
+                        }
+                    }
+                });
+
+                let Some(findings) = &self.findings else {
+                    return;
+                };
+
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show_rows(
+                        ui,
+                        ui.text_style_height(&egui::TextStyle::Body),
+                        findings.len(),
+                        |ui, rows| {
+                            ui.with_cross_justify(|ui| {
+                                for (row, finding) in findings[rows.clone()].iter().enumerate() {
+                                    ui.with_stripe((rows.start + row) % 2 != 0, |ui| {
+                                        if ui
+                                            .add(
+                                                egui::Label::new(format!(
+                                                    "{}: {}",
+                                                    finding.location, finding.message
+                                                ))
+                                                .truncate(true)
+                                                .sense(egui::Sense::click()),
+                                            )
+                                            .on_hover_text(&finding.message)
+                                            .on_hover_cursor(egui::CursorIcon::PointingHand)
+                                            .clicked()
+                                        {
+                                            clicked_location = Some(finding.location);
+                                        }
+                                    });
+                                }
+                            });
+                        },
+                    );
+            });
+
+        if let Some(location) = clicked_location {
+            location.open(update_state);
+        }
+    }
+}
@@ -58,6 +58,11 @@ impl Window {
             view: luminol_components::DatabaseView::new(),
         }
     }
+
+    /// Selects the item with the given index.
+    pub fn select(&mut self, id: usize) {
+        self.view.select(id);
+    }
 }
 
 impl luminol_core::Window for Window {
@@ -410,6 +415,8 @@ impl luminol_core::Window for Window {
                         });
 
                         self.previous_item = Some(item.id);
+
+                        modified
                     },
                 )
             });
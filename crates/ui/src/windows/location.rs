@@ -0,0 +1,143 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+/// A place in the project that windows can link to. Database entries and scripts are stored by
+/// index, maps by their ID.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Location {
+    System,
+    Actor(usize),
+    Class(usize),
+    Skill(usize),
+    Item(usize),
+    Weapon(usize),
+    Armor(usize),
+    Enemy(usize),
+    Troop(usize),
+    State(usize),
+    Animation(usize),
+    Tileset(usize),
+    CommonEvent(usize),
+    MapInfos,
+    Map(usize),
+    Script(usize),
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::System => write!(f, "System"),
+            Self::Actor(id) => write!(f, "Actor {:0>3}", id + 1),
+            Self::Class(id) => write!(f, "Class {:0>3}", id + 1),
+            Self::Skill(id) => write!(f, "Skill {:0>3}", id + 1),
+            Self::Item(id) => write!(f, "Item {:0>3}", id + 1),
+            Self::Weapon(id) => write!(f, "Weapon {:0>3}", id + 1),
+            Self::Armor(id) => write!(f, "Armor {:0>3}", id + 1),
+            Self::Enemy(id) => write!(f, "Enemy {:0>3}", id + 1),
+            Self::Troop(id) => write!(f, "Troop {:0>3}", id + 1),
+            Self::State(id) => write!(f, "State {:0>3}", id + 1),
+            Self::Animation(id) => write!(f, "Animation {:0>3}", id + 1),
+            Self::Tileset(id) => write!(f, "Tileset {:0>3}", id + 1),
+            Self::CommonEvent(id) => write!(f, "Common Event {:0>3}", id + 1),
+            Self::MapInfos => write!(f, "Maps"),
+            Self::Map(id) => write!(f, "Map {id:0>3}"),
+            Self::Script(id) => write!(f, "Script {id}"),
+        }
+    }
+}
+
+impl Location {
+    /// Opens the editor for this location.
+    pub(crate) fn open(self, update_state: &mut luminol_core::UpdateState<'_>) {
+        use crate::windows;
+
+        macro_rules! open_window {
+            ($window:expr, $id:expr) => {{
+                let mut window = $window;
+                window.select($id);
+                // Replace the editor if it's already open so the entry gets selected
+                update_state.edit_windows.remove_window(&window);
+                update_state.edit_windows.add_window(window);
+            }};
+        }
+
+        match self {
+            Self::System => update_state
+                .edit_windows
+                .add_window(windows::system::Window::new(update_state)),
+            Self::Actor(id) => open_window!(windows::actors::Window::new(update_state), id),
+            Self::Class(id) => open_window!(windows::classes::Window::new(), id),
+            Self::Skill(id) => open_window!(windows::skills::Window::new(), id),
+            Self::Item(id) => open_window!(windows::items::Window::new(update_state), id),
+            Self::Weapon(id) => open_window!(windows::weapons::Window::new(), id),
+            Self::Armor(id) => open_window!(windows::armor::Window::new(), id),
+            Self::Enemy(id) => open_window!(windows::enemies::Window::new(update_state), id),
+            Self::Troop(id) => open_window!(windows::troops::Window::new(update_state), id),
+            Self::State(id) => open_window!(windows::states::Window::new(), id),
+            Self::Animation(id) => {
+                open_window!(windows::animations::Window::new(update_state), id)
+            }
+            Self::Tileset(id) => open_window!(windows::tilesets::Window::new(update_state), id),
+            Self::CommonEvent(id) => {
+                open_window!(windows::common_event_edit::Window::default(), id)
+            }
+            Self::MapInfos => update_state
+                .edit_windows
+                .add_window(windows::map_picker::Window::default()),
+            Self::Map(id) => match crate::tabs::map::Tab::new(id, update_state) {
+                Ok(tab) => update_state.edit_tabs.add_tab(tab),
+                Err(e) => luminol_core::error!(
+                    update_state.toasts,
+                    e.wrap_err(format!("Error opening map {id:0>3}"))
+                ),
+            },
+            Self::Script(id) => {
+                let Some(script_text) = update_state
+                    .data
+                    .scripts()
+                    .data
+                    .get(id)
+                    .map(|script| script.script_text.clone())
+                else {
+                    return;
+                };
+                let mut window = windows::script_edit::Window::default();
+                window.select(id, script_text);
+                update_state.edit_windows.remove_window(&window);
+                update_state.edit_windows.add_window(window);
+            }
+        }
+    }
+}
+
+impl From<luminol_components::usages::Source> for Location {
+    fn from(source: luminol_components::usages::Source) -> Self {
+        use luminol_components::usages::Source;
+        match source {
+            Source::CommonEvent(id) => Self::CommonEvent(id),
+            Source::Troop(id) => Self::Troop(id),
+            Source::Map(id) => Self::Map(id),
+        }
+    }
+}
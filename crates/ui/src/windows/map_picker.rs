@@ -22,59 +22,589 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::BTreeMap;
+
+use luminol_data::rpg;
+
+/// The default size of new maps, in tiles.
+const NEW_MAP_SIZE: (usize, usize) = (20, 15);
 
 /// The map picker window.
 /// Displays a list of maps in a tree.
-/// Maps can be double clicked to open them in a map editor.
+/// Maps can be double clicked to open them in a map editor, and the context menu of a map can be
+/// used to create, copy, paste and delete maps. Maps can be moved around by dragging their handle.
 #[derive(Default)]
-pub struct Window {}
+pub struct Window {
+    /// The map copied with "Copy Map".
+    clipboard: Option<(rpg::MapInfo, rpg::Map)>,
+    /// The map the user asked to delete, which is waiting for confirmation.
+    pending_deletion: Option<usize>,
+}
+
+/// The payload of a map being dragged around the tree.
+struct DraggedMap(usize);
+
+/// Where a dragged map should be put.
+#[derive(Clone, Copy)]
+enum DropTarget {
+    /// Right before a map, under the same parent.
+    Before(usize),
+    /// As the last child of a map, or of the root if this is 0.
+    Child(usize),
+}
+
+enum Action {
+    Open(usize),
+    Properties(usize),
+    /// Creates a new map under a parent.
+    New(usize),
+    Copy(usize),
+    /// Pastes the copied map under a parent.
+    Paste(usize),
+    Delete(usize),
+    Move(usize, DropTarget),
+    Rename(usize),
+}
 
 impl Window {
+    /// Groups the maps by their parent, with the children of every map sorted by `order`.
+    /// Maps whose parent doesn't exist are treated as if they were at the root.
+    fn children_of(mapinfos: &rpg::MapInfos) -> BTreeMap<usize, Vec<usize>> {
+        let mut children: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for (&id, map) in mapinfos.data.iter() {
+            let parent_id = if mapinfos.data.contains_key(&map.parent_id) {
+                map.parent_id
+            } else {
+                0
+            };
+            children.entry(parent_id).or_default().push(id);
+        }
+        for list in children.values_mut() {
+            list.sort_by_key(|id| (mapinfos.data[id].order, *id));
+        }
+        // If there is no `0` entry (i.e. there are no maps) then add one.
+        children.entry(0).or_default();
+        children
+    }
+
+    /// Rewrites the `parent_id` and `order` of every map to match the tree. Maps are numbered in
+    /// the order they are displayed in, like RPG Maker does.
+    fn apply_tree(mapinfos: &mut rpg::MapInfos, children: &BTreeMap<usize, Vec<usize>>) {
+        fn visit(
+            mapinfos: &mut rpg::MapInfos,
+            children: &BTreeMap<usize, Vec<usize>>,
+            parent_id: usize,
+            order: &mut i32,
+        ) {
+            for &id in children.get(&parent_id).into_iter().flatten() {
+                *order += 1;
+                let map_info = mapinfos.data.get_mut(&id).unwrap();
+                map_info.parent_id = parent_id;
+                map_info.order = *order;
+                visit(mapinfos, children, id, order);
+            }
+        }
+
+        visit(mapinfos, children, 0, &mut 0);
+    }
+
+    /// Returns the map with this ID and all of its descendants.
+    fn subtree(children: &BTreeMap<usize, Vec<usize>>, id: usize) -> Vec<usize> {
+        let mut ids = vec![id];
+        let mut i = 0;
+        while let Some(&id) = ids.get(i) {
+            ids.extend(children.get(&id).into_iter().flatten().copied());
+            i += 1;
+        }
+        ids
+    }
+
+    /// Moves a map to a new place in the tree. Returns false if the map can't be moved there, i.e.
+    /// if it would end up inside of itself.
+    fn move_map(mapinfos: &mut rpg::MapInfos, id: usize, target: DropTarget) -> bool {
+        let mut children = Self::children_of(mapinfos);
+
+        let parent_id = match target {
+            DropTarget::Child(parent_id) => parent_id,
+            DropTarget::Before(sibling_id) => children
+                .iter()
+                .find(|(_, list)| list.contains(&sibling_id))
+                .map_or(0, |(&parent_id, _)| parent_id),
+        };
+        if Self::subtree(&children, id).contains(&parent_id)
+            || matches!(target, DropTarget::Before(sibling_id) if sibling_id == id)
+        {
+            return false;
+        }
+
+        for list in children.values_mut() {
+            list.retain(|&child| child != id);
+        }
+        let list = children.entry(parent_id).or_default();
+        let index = match target {
+            DropTarget::Before(sibling_id) => list
+                .iter()
+                .position(|&child| child == sibling_id)
+                .unwrap_or(list.len()),
+            DropTarget::Child(_) => list.len(),
+        };
+        list.insert(index, id);
+
+        Self::apply_tree(mapinfos, &children);
+        if let Some(parent) = mapinfos.data.get_mut(&parent_id) {
+            parent.expanded = true;
+        }
+        true
+    }
+
+    /// Adds a map as the last child of a parent and returns its ID.
+    fn add_map(
+        update_state: &mut luminol_core::UpdateState<'_>,
+        mut map_info: rpg::MapInfo,
+        map: rpg::Map,
+        parent_id: usize,
+        diffs: &mut Vec<luminol_core::history::Diff>,
+    ) -> usize {
+        let mut mapinfos = update_state.data.map_infos();
+        let id = mapinfos.data.keys().max().map_or(1, |id| id + 1);
+
+        map_info.parent_id = parent_id;
+        map_info.order = i32::MAX;
+        mapinfos.data.insert(id, map_info);
+        let children = Self::children_of(&mapinfos);
+        Self::apply_tree(&mut mapinfos, &children);
+        if let Some(parent) = mapinfos.data.get_mut(&parent_id) {
+            parent.expanded = true;
+        }
+        mapinfos.modified = true;
+        drop(mapinfos);
+
+        diffs.push(luminol_core::history::Diff::new(
+            luminol_core::history::Target::Map(id),
+            None,
+            luminol_core::history::snapshot(&map).ok(),
+        ));
+        update_state.data.insert_map(id, map);
+        update_state.modified.set(true);
+        id
+    }
+
+    fn new_map(
+        update_state: &mut luminol_core::UpdateState<'_>,
+        parent_id: usize,
+        diffs: &mut Vec<luminol_core::history::Diff>,
+    ) {
+        let (width, height) = NEW_MAP_SIZE;
+        let map = rpg::Map {
+            width,
+            height,
+            data: luminol_data::Table3::new(width, height, 3),
+            encounter_step: 30,
+            ..Default::default()
+        };
+
+        let id = Self::add_map(update_state, rpg::MapInfo::default(), map, parent_id, diffs);
+        if let Some(map_info) = update_state.data.map_infos().data.get_mut(&id) {
+            map_info.name = format!("MAP{id:0>3}");
+        }
+    }
+
+    /// Deletes a map and everything inside of it. Returns whether the deletion can be undone.
+    fn delete_map(
+        update_state: &mut luminol_core::UpdateState<'_>,
+        id: usize,
+        diffs: &mut Vec<luminol_core::history::Diff>,
+    ) -> bool {
+        // Only RPG Maker XP maps can be put back by the history
+        let can_undo = matches!(
+            update_state.data.editor_ver(),
+            Some(luminol_config::RMVer::XP)
+        );
+
+        let mut mapinfos = update_state.data.map_infos();
+        let mut children = Self::children_of(&mapinfos);
+        let ids = Self::subtree(&children, id);
+
+        for &id in &ids {
+            mapinfos.data.remove(&id);
+            children.remove(&id);
+        }
+        for list in children.values_mut() {
+            list.retain(|child| !ids.contains(child));
+        }
+        Self::apply_tree(&mut mapinfos, &children);
+        mapinfos.modified = true;
+        drop(mapinfos);
+
+        for id in ids {
+            update_state
+                .edit_tabs
+                .remove_tab_by_id(crate::tabs::map::Tab::tab_id(id));
+            if can_undo {
+                let before = update_state
+                    .data
+                    .try_get_or_load_map(id, update_state.filesystem)
+                    .ok()
+                    .and_then(|map| luminol_core::history::snapshot(&*map).ok());
+                diffs.push(luminol_core::history::Diff::new(
+                    luminol_core::history::Target::Map(id),
+                    before,
+                    None,
+                ));
+            }
+            update_state.data.remove_map(id);
+        }
+        update_state.modified.set(true);
+        can_undo
+    }
+
+    /// Takes a snapshot of every map's entry in `MapInfos`, for recording changes to the tree.
+    fn snapshot_map_infos(
+        update_state: &luminol_core::UpdateState<'_>,
+    ) -> BTreeMap<usize, Vec<u8>> {
+        update_state
+            .data
+            .map_infos()
+            .data
+            .iter()
+            .filter_map(|(&id, info)| Some((id, luminol_core::history::snapshot(info).ok()?)))
+            .collect()
+    }
+
+    /// The changes made to `MapInfos` since `before` was taken.
+    fn map_info_diffs(
+        update_state: &luminol_core::UpdateState<'_>,
+        mut before: BTreeMap<usize, Vec<u8>>,
+    ) -> Vec<luminol_core::history::Diff> {
+        let mut after = Self::snapshot_map_infos(update_state);
+        let ids = before
+            .keys()
+            .chain(after.keys())
+            .copied()
+            .collect::<std::collections::BTreeSet<_>>();
+        ids.into_iter()
+            .map(|id| {
+                luminol_core::history::Diff::new(
+                    luminol_core::history::Target::MapInfo(id),
+                    before.remove(&id),
+                    after.remove(&id),
+                )
+            })
+            .collect()
+    }
+
+    fn map_context_menu(ui: &mut egui::Ui, id: usize, can_paste: bool, actions: &mut Vec<Action>) {
+        if ui.button("Open").clicked() {
+            actions.push(Action::Open(id));
+            ui.close_menu();
+        }
+
+        if ui.button("Map Properties").clicked() {
+            actions.push(Action::Properties(id));
+            ui.close_menu();
+        }
+
+        ui.separator();
+
+        if ui.button("New Map").clicked() {
+            actions.push(Action::New(id));
+            ui.close_menu();
+        }
+
+        if ui.button("Copy Map").clicked() {
+            actions.push(Action::Copy(id));
+            ui.close_menu();
+        }
+
+        if ui
+            .add_enabled(can_paste, egui::Button::new("Paste Map"))
+            .clicked()
+        {
+            actions.push(Action::Paste(id));
+            ui.close_menu();
+        }
+
+        ui.separator();
+
+        if ui
+            .button(egui::RichText::new("Delete Map").color(egui::Color32::LIGHT_RED))
+            .clicked()
+        {
+            actions.push(Action::Delete(id));
+            ui.close_menu();
+        }
+    }
+
+    /// Shows where a dragged map would be dropped when it's hovering over a row, and queues the
+    /// move when it's released there.
+    fn handle_drop(ui: &egui::Ui, row: &egui::Response, id: usize, actions: &mut Vec<Action>) {
+        let Some(pointer) = ui.ctx().pointer_interact_pos() else {
+            return;
+        };
+        // The upper part of a row puts the map before it, the rest makes it a child
+        let target = if pointer.y < row.rect.top() + row.rect.height() / 3. {
+            DropTarget::Before(id)
+        } else {
+            DropTarget::Child(id)
+        };
+
+        if row.dnd_hover_payload::<DraggedMap>().is_some() {
+            let stroke = egui::Stroke::new(2., ui.visuals().selection.stroke.color);
+            match target {
+                DropTarget::Before(_) => {
+                    ui.painter()
+                        .hline(row.rect.x_range(), row.rect.top(), stroke);
+                }
+                DropTarget::Child(_) => {
+                    ui.painter().rect_stroke(row.rect, 2., stroke);
+                }
+            }
+        }
+
+        if let Some(dragged) = row.dnd_release_payload::<DraggedMap>() {
+            actions.push(Action::Move(dragged.0, target));
+        }
+    }
+
+    /// The contents of a row in the tree: a handle for dragging the map around and its name.
+    fn map_row(
+        ui: &mut egui::Ui,
+        id: usize,
+        map_info: &mut rpg::MapInfo,
+        can_paste: bool,
+        actions: &mut Vec<Action>,
+    ) {
+        let handle = ui
+            .add(egui::Label::new("☰").sense(egui::Sense::drag()))
+            .on_hover_cursor(egui::CursorIcon::Grab);
+        handle.dnd_set_drag_payload(DraggedMap(id));
+
+        let response = ui.text_edit_singleline(&mut map_info.name);
+        if response.changed() {
+            actions.push(Action::Rename(id));
+        }
+        if response.double_clicked() {
+            actions.push(Action::Open(id));
+        }
+        response.context_menu(|ui| Self::map_context_menu(ui, id, can_paste, actions));
+    }
+
     fn render_submap(
         id: usize,
-        children_data: &BTreeMap<usize, BTreeSet<usize>>,
-        mapinfos: &mut luminol_data::rpg::MapInfos,
-        open_map_id: &mut Option<usize>,
+        children_data: &BTreeMap<usize, Vec<usize>>,
+        mapinfos: &mut rpg::MapInfos,
+        can_paste: bool,
+        actions: &mut Vec<Action>,
         ui: &mut egui::Ui,
     ) {
         // We get the map name. It's assumed that there is in fact a map with this ID in mapinfos.
         let map_info = mapinfos.data.get_mut(&id).unwrap();
 
         // Does this map have children?
-        if children_data.contains_key(&id) {
+        if children_data
+            .get(&id)
+            .is_some_and(|children| !children.is_empty())
+        {
             // Render a custom collapsing header.
             // It's custom so we can add a button to open a map.
-            let header = egui::collapsing_header::CollapsingState::load_with_default_open(
+            let persistent_id = ui.make_persistent_id(egui::Id::new("luminol_map_info").with(id));
+            let mut header = egui::collapsing_header::CollapsingState::load_with_default_open(
                 ui.ctx(),
-                ui.make_persistent_id(egui::Id::new("luminol_map_info").with(id)),
+                persistent_id,
                 map_info.expanded,
             );
+            // Maps can be expanded by the context menu and by dropping other maps on them
+            if header.is_open() != map_info.expanded {
+                header.set_open(map_info.expanded);
+            }
 
-            map_info.expanded = header.openness(ui.ctx()) >= 1.;
-
-            header
+            let (_, header_response, _) = header
                 .show_header(ui, |ui| {
-                    // Has the user
-                    if ui.text_edit_singleline(&mut map_info.name).double_clicked() {
-                        *open_map_id = Some(id)
-                    }
+                    Self::map_row(ui, id, map_info, can_paste, actions);
                 })
                 .body(|ui| {
-                    for id in children_data.get(&id).unwrap() {
+                    for &id in children_data.get(&id).unwrap() {
                         // Render children.
-                        Self::render_submap(*id, children_data, mapinfos, open_map_id, ui);
+                        Self::render_submap(id, children_data, mapinfos, can_paste, actions, ui);
                     }
                 });
+            Self::handle_drop(ui, &header_response.response, id, actions);
+
+            if let Some(header) =
+                egui::collapsing_header::CollapsingState::load(ui.ctx(), persistent_id)
+            {
+                if let Some(map_info) = mapinfos.data.get_mut(&id) {
+                    map_info.expanded = header.is_open();
+                }
+            }
         } else {
             // Just display a label otherwise.
-            ui.horizontal(|ui| {
-                ui.add_space(ui.spacing().indent);
-                if ui.text_edit_singleline(&mut map_info.name).double_clicked() {
-                    *open_map_id = Some(id)
+            let response = ui
+                .horizontal(|ui| {
+                    ui.add_space(ui.spacing().indent);
+                    Self::map_row(ui, id, map_info, can_paste, actions);
+                })
+                .response;
+            Self::handle_drop(ui, &response, id, actions);
+        }
+    }
+
+    /// Carries out an action from the tree. Returns a description of the change if it should be
+    /// recorded in the history.
+    fn process_action(
+        &mut self,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        action: Action,
+        diffs: &mut Vec<luminol_core::history::Diff>,
+    ) -> Option<String> {
+        let edits_map_data = matches!(
+            action,
+            Action::Open(_)
+                | Action::Properties(_)
+                | Action::New(_)
+                | Action::Copy(_)
+                | Action::Paste(_)
+        );
+        let is_supported = match update_state.data.editor_ver() {
+            Some(luminol_config::RMVer::XP) => true,
+            // Only the tiles of RPG Maker VX and VX Ace maps can be edited so far
+            Some(luminol_config::RMVer::VX | luminol_config::RMVer::Ace) => {
+                matches!(action, Action::Open(_))
+            }
+            None => false,
+        };
+        if edits_map_data && !is_supported {
+            luminol_core::warn!(
+                update_state.toasts,
+                "Editing maps is only supported in RPG Maker XP projects for now"
+            );
+            return None;
+        }
+
+        match action {
+            Action::Open(id) => match crate::tabs::map::Tab::new(id, update_state) {
+                Ok(tab) => update_state.edit_tabs.add_tab(tab),
+                Err(e) => {
+                    luminol_core::error!(update_state.toasts, e.wrap_err("Error enumerating maps"))
+                }
+            },
+            Action::Properties(id) => {
+                let window = crate::windows::map_properties::Window::new(update_state, id);
+                update_state.edit_windows.add_window(window);
+            }
+            Action::New(parent_id) => {
+                Self::new_map(update_state, parent_id, diffs);
+                return Some("New map".to_string());
+            }
+            Action::Copy(id) => {
+                let map_info = update_state.data.map_infos().data.get(&id).cloned()?;
+                let map = update_state
+                    .data
+                    .get_or_load_map(id, update_state.filesystem)
+                    .clone();
+                self.clipboard = Some((map_info, map));
+            }
+            Action::Paste(parent_id) => {
+                if let Some((map_info, map)) = self.clipboard.clone() {
+                    let name = map_info.name.clone();
+                    Self::add_map(update_state, map_info, map, parent_id, diffs);
+                    return Some(format!("Paste map {name}"));
                 }
+            }
+            Action::Delete(id) => self.pending_deletion = Some(id),
+            Action::Move(id, target) => {
+                let mut mapinfos = update_state.data.map_infos();
+                if Self::move_map(&mut mapinfos, id, target) {
+                    mapinfos.modified = true;
+                    update_state.modified.set(true);
+                    return Some(format!("Move map {id:0>3}"));
+                }
+            }
+            Action::Rename(id) => {
+                update_state.data.map_infos().modified = true;
+                update_state.modified.set(true);
+                return Some(format!("Rename map {id:0>3}"));
+            }
+        }
+        None
+    }
+
+    /// Asks the user to confirm deleting a map. Returns a description of the change if the map
+    /// was deleted.
+    fn show_delete_confirmation(
+        &mut self,
+        ctx: &egui::Context,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        diffs: &mut Vec<luminol_core::history::Diff>,
+    ) -> Option<String> {
+        let id = self.pending_deletion?;
+
+        let (name, child_count) = {
+            let mapinfos = update_state.data.map_infos();
+            let Some(map_info) = mapinfos.data.get(&id) else {
+                self.pending_deletion = None;
+                return None;
+            };
+            let children = Self::children_of(&mapinfos);
+            (
+                map_info.name.clone(),
+                Self::subtree(&children, id).len() - 1,
+            )
+        };
+
+        let can_undo = matches!(
+            update_state.data.editor_ver(),
+            Some(luminol_config::RMVer::XP)
+        );
+        let mut open = true;
+        let mut confirmed = false;
+        egui::Window::new("Delete Map")
+            .id(egui::Id::new("map_picker_delete_confirmation"))
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let children = match child_count {
+                    0 => String::new(),
+                    1 => " and the map inside of it".to_string(),
+                    n => format!(" and the {n} maps inside of it"),
+                };
+                ui.label(format!(
+                    "Are you sure you want to delete {id:0>3}: {name}{children}?{}",
+                    if can_undo {
+                        ""
+                    } else {
+                        " This cannot be undone."
+                    }
+                ));
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(egui::RichText::new("Delete").color(egui::Color32::LIGHT_RED))
+                        .clicked()
+                    {
+                        confirmed = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.pending_deletion = None;
+                    }
+                });
             });
+
+        let mut description = None;
+        if confirmed {
+            if Self::delete_map(update_state, id, diffs) {
+                description = Some(format!("Delete map {id:0>3}: {name}"));
+            } else {
+                // The old history could bring back the map's entry in `MapInfos` without the map
+                update_state.history.clear();
+            }
+            self.pending_deletion = None;
         }
+        if !open {
+            self.pending_deletion = None;
+        }
+        description
     }
 }
 
@@ -90,6 +620,10 @@ impl luminol_core::Window for Window {
         update_state: &mut luminol_core::UpdateState<'_>,
     ) {
         let mut window_open = true;
+        let mut actions = Vec::new();
+        let can_paste = self.clipboard.is_some();
+        let map_infos_before = Self::snapshot_map_infos(update_state);
+
         egui::Window::new("Map Picker")
             .open(&mut window_open)
             .show(ctx, |ui| {
@@ -108,20 +642,11 @@ impl luminol_core::Window for Window {
                         let mut mapinfos = update_state.data.map_infos();
 
                         // We preprocess maps to figure out what has nodes and what doesn't.
-                        // This should result in an ordered hashmap of all the maps and their children.
-                        let mut children_data: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
-                        for (&id, map) in mapinfos.data.iter() {
-                            // Is there an entry for our parent?
-                            // If not, then just add a blank vector to it.
-                            let children = children_data.entry(map.parent_id).or_default(); // FIXME: this doesn't handle sorting properly
-                            children.insert(id);
-                        }
-                        children_data.entry(0).or_default(); // If there is no `0` entry (i.e. there are no maps) then add one.
-
-                        let mut open_map_id = None;
+                        // This should result in an ordered map of all the maps and their children.
+                        let children_data = Self::children_of(&mapinfos);
 
                         // Now we can actually render all maps.
-                        egui::CollapsingHeader::new("root")
+                        let root_response = egui::CollapsingHeader::new("root")
                             .default_open(true)
                             .show(ui, |ui| {
                                 // There will always be a map `0`.
@@ -131,25 +656,57 @@ impl luminol_core::Window for Window {
                                         id,
                                         &children_data,
                                         &mut mapinfos,
-                                        &mut open_map_id,
+                                        can_paste,
+                                        &mut actions,
                                         ui,
                                     );
                                 }
                             });
 
-                        drop(mapinfos);
-
-                        if let Some(id) = open_map_id {
-                            match crate::tabs::map::Tab::new(id, update_state) {
-                                Ok(tab) => update_state.edit_tabs.add_tab(tab),
-                                Err(e) => luminol_core::error!(
-                                    update_state.toasts,
-                                    e.wrap_err("Error enumerating maps")
-                                ),
+                        let header_response = root_response.header_response;
+                        header_response.context_menu(|ui| {
+                            if ui.button("New Map").clicked() {
+                                actions.push(Action::New(0));
+                                ui.close_menu();
+                            }
+                            if ui
+                                .add_enabled(can_paste, egui::Button::new("Paste Map"))
+                                .clicked()
+                            {
+                                actions.push(Action::Paste(0));
+                                ui.close_menu();
                             }
+                        });
+                        if header_response.dnd_hover_payload::<DraggedMap>().is_some() {
+                            ui.painter().rect_stroke(
+                                header_response.rect,
+                                2.,
+                                egui::Stroke::new(2., ui.visuals().selection.stroke.color),
+                            );
+                        }
+                        if let Some(dragged) = header_response.dnd_release_payload::<DraggedMap>() {
+                            actions.push(Action::Move(dragged.0, DropTarget::Child(0)));
                         }
                     })
             });
+
+        let mut description = None;
+        let mut map_diffs = Vec::new();
+        for action in actions {
+            description = self
+                .process_action(update_state, action, &mut map_diffs)
+                .or(description);
+        }
+        description = self
+            .show_delete_confirmation(ctx, update_state, &mut map_diffs)
+            .or(description);
+
+        if let Some(description) = description {
+            let mut diffs = Self::map_info_diffs(update_state, map_infos_before);
+            diffs.append(&mut map_diffs);
+            update_state.record_change(description, diffs);
+        }
+
         *open = window_open;
     }
 
@@ -0,0 +1,366 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_audio::Source;
+use luminol_components::UiExt;
+use luminol_core::Modal;
+
+use luminol_modals::sound_picker::Modal as SoundPicker;
+
+/// The largest map width and height allowed by RPG Maker XP.
+const MAX_SIZE: usize = 500;
+
+/// The map properties editor.
+pub struct Window {
+    map_id: usize,
+
+    bgm_picker: SoundPicker,
+    bgs_picker: SoundPicker,
+
+    /// The size the map will have after pressing "Resize".
+    new_size: (usize, usize),
+    /// Which part of the map stays in place when resizing, as a (column, row) in a 3x3 grid.
+    anchor: (usize, usize),
+}
+
+impl Window {
+    pub fn new(update_state: &luminol_core::UpdateState<'_>, map_id: usize) -> Self {
+        let map = update_state
+            .data
+            .get_or_load_map(map_id, update_state.filesystem);
+
+        Self {
+            map_id,
+
+            bgm_picker: SoundPicker::new(
+                Source::BGM,
+                egui::Id::new("map_properties_bgm_picker").with(map_id),
+            ),
+            bgs_picker: SoundPicker::new(
+                Source::BGS,
+                egui::Id::new("map_properties_bgs_picker").with(map_id),
+            ),
+
+            new_size: (map.width, map.height),
+            anchor: (0, 0),
+        }
+    }
+
+    /// How far the old map data has to be moved along one axis when resizing it from `old` to
+    /// `new` tiles with the given anchor.
+    fn anchor_offset(anchor: usize, old: usize, new: usize) -> isize {
+        let difference = new as isize - old as isize;
+        match anchor {
+            0 => 0,
+            1 => difference / 2,
+            _ => difference,
+        }
+    }
+
+    fn show_anchor_grid(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new((self.map_id, "map_properties_anchor"))
+            .spacing(egui::Vec2::splat(2.))
+            .show(ui, |ui| {
+                for y in 0..3 {
+                    for x in 0..3 {
+                        let text = if self.anchor == (x, y) { "•" } else { "" };
+                        if ui
+                            .add_sized(
+                                egui::Vec2::splat(ui.spacing().interact_size.y),
+                                egui::SelectableLabel::new(self.anchor == (x, y), text),
+                            )
+                            .clicked()
+                        {
+                            self.anchor = (x, y);
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// Shows the size controls and returns the change to record if the map was resized.
+    fn show_size(
+        &mut self,
+        ui: &mut egui::Ui,
+        map: &mut luminol_data::rpg::Map,
+    ) -> Option<luminol_core::history::Diff> {
+        let mut resize = None;
+
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                ui.add(luminol_components::Field::new(
+                    "Width",
+                    egui::DragValue::new(&mut self.new_size.0).clamp_range(1..=MAX_SIZE),
+                ));
+                ui.add(luminol_components::Field::new(
+                    "Height",
+                    egui::DragValue::new(&mut self.new_size.1).clamp_range(1..=MAX_SIZE),
+                ));
+            });
+
+            ui.vertical(|ui| {
+                ui.label("Anchor");
+                self.show_anchor_grid(ui);
+            });
+        });
+
+        let old_size = (map.data.xsize(), map.data.ysize());
+        ui.horizontal(|ui| {
+            ui.label(format!("Current size: {} × {}", old_size.0, old_size.1));
+
+            if ui
+                .add_enabled(self.new_size != old_size, egui::Button::new("Resize"))
+                .clicked()
+            {
+                let offset = (
+                    Self::anchor_offset(self.anchor.0, old_size.0, self.new_size.0),
+                    Self::anchor_offset(self.anchor.1, old_size.1, self.new_size.1),
+                );
+                let before = luminol_core::history::snapshot(map).ok();
+                crate::tabs::map::Tab::resize_map(map, self.new_size, offset);
+                resize = Some(luminol_core::history::Diff::new(
+                    luminol_core::history::Target::Map(self.map_id),
+                    before,
+                    luminol_core::history::snapshot(map).ok(),
+                ));
+            }
+        });
+
+        resize
+    }
+
+    fn show_encounters(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+    ) -> bool {
+        let mut modified = false;
+        let troops = update_state.data.troops();
+
+        let mut deleted_entry = None;
+        for (i, troop_id) in map.encounter_list.iter_mut().enumerate() {
+            ui.with_stripe(i % 2 != 0, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button(egui::RichText::new("-").monospace()).clicked() {
+                        deleted_entry = Some(i);
+                    }
+
+                    // Encounters store 1-based troop IDs
+                    let mut id = (*troop_id).max(1) as usize - 1;
+                    if ui
+                        .add(luminol_components::OptionalIdComboBox::new(
+                            update_state,
+                            (self.map_id, i, "encounter"),
+                            &mut id,
+                            0..troops.data.len(),
+                            |id| {
+                                troops.data.get(id).map_or_else(
+                                    || "".into(),
+                                    |t| format!("{:0>4}: {}", id + 1, t.name),
+                                )
+                            },
+                        ))
+                        .changed()
+                    {
+                        *troop_id = id as i32 + 1;
+                        modified = true;
+                    }
+                });
+            });
+        }
+
+        if let Some(i) = deleted_entry {
+            map.encounter_list.remove(i);
+            modified = true;
+        }
+
+        if ui
+            .add_enabled(!troops.data.is_empty(), egui::Button::new("Add Encounter"))
+            .clicked()
+        {
+            map.encounter_list.push(1);
+            modified = true;
+        }
+
+        modified
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("map_properties").with(self.map_id)
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        // The map may have been deleted while this window was open
+        let Some(mut name) = update_state
+            .data
+            .map_infos()
+            .data
+            .get(&self.map_id)
+            .map(|info| info.name.clone())
+        else {
+            *open = false;
+            return;
+        };
+
+        let mut map = std::mem::take(
+            &mut *update_state
+                .data
+                .get_or_load_map(self.map_id, update_state.filesystem),
+        );
+
+        let before = luminol_core::history::snapshot_map_properties(&mut map).ok();
+
+        let mut modified = false;
+        let mut name_modified = false;
+        let mut resize = None;
+
+        egui::Window::new(format!("Map Properties: {:0>3}: {name}", self.map_id))
+            .id(self.id())
+            .default_width(400.)
+            .open(open)
+            .show(ctx, |ui| {
+                ui.with_padded_stripe(false, |ui| {
+                    name_modified = ui
+                        .add(luminol_components::Field::new(
+                            "Name",
+                            egui::TextEdit::singleline(&mut name).desired_width(f32::INFINITY),
+                        ))
+                        .changed();
+                });
+
+                ui.with_padded_stripe(true, |ui| {
+                    let tilesets = update_state.data.tilesets();
+                    modified |= ui
+                        .add(luminol_components::Field::new(
+                            "Tileset",
+                            luminol_components::OptionalIdComboBox::new(
+                                update_state,
+                                (self.map_id, "tileset_id"),
+                                &mut map.tileset_id,
+                                0..tilesets.data.len(),
+                                |id| {
+                                    tilesets.data.get(id).map_or_else(
+                                        || "".into(),
+                                        |t| format!("{:0>4}: {}", id + 1, t.name),
+                                    )
+                                },
+                            ),
+                        ))
+                        .changed();
+                });
+
+                ui.with_padded_stripe(false, |ui| {
+                    resize = self.show_size(ui, &mut map);
+                    modified |= resize.is_some();
+                });
+
+                ui.with_padded_stripe(true, |ui| {
+                    ui.columns(2, |columns| {
+                        modified |= columns[0]
+                            .checkbox(&mut map.autoplay_bgm, "Auto-Change BGM")
+                            .changed();
+                        columns[0].add_enabled_ui(map.autoplay_bgm, |ui| {
+                            modified |= ui
+                                .add(self.bgm_picker.button(&mut map.bgm, update_state))
+                                .changed();
+                        });
+
+                        modified |= columns[1]
+                            .checkbox(&mut map.autoplay_bgs, "Auto-Change BGS")
+                            .changed();
+                        columns[1].add_enabled_ui(map.autoplay_bgs, |ui| {
+                            modified |= ui
+                                .add(self.bgs_picker.button(&mut map.bgs, update_state))
+                                .changed();
+                        });
+                    });
+                });
+
+                ui.with_padded_stripe(false, |ui| {
+                    modified |= ui
+                        .add(luminol_components::Field::new(
+                            "Encounter Steps",
+                            egui::DragValue::new(&mut map.encounter_step).clamp_range(1..=999),
+                        ))
+                        .changed();
+
+                    ui.label("Encounters");
+                    modified |= self.show_encounters(ui, update_state, &mut map);
+                });
+            });
+
+        if name_modified {
+            let mut map_infos = update_state.data.map_infos();
+            if let Some(info) = map_infos.data.get_mut(&self.map_id) {
+                let before = luminol_core::history::snapshot(info).ok();
+                info.name = name;
+                let diff = luminol_core::history::Diff::new(
+                    luminol_core::history::Target::MapInfo(self.map_id),
+                    before,
+                    luminol_core::history::snapshot(info).ok(),
+                );
+                map_infos.modified = true;
+                drop(map_infos);
+                update_state.record_change(format!("Rename map {:0>3}", self.map_id), vec![diff]);
+            }
+            update_state.modified.set(true);
+        }
+
+        if modified {
+            map.modified = true;
+            update_state.modified.set(true);
+        }
+
+        // A resize already has a snapshot of the whole map, anything else only changes properties
+        let diff = resize.unwrap_or_else(|| {
+            luminol_core::history::Diff::new(
+                luminol_core::history::Target::MapProperties(self.map_id),
+                before,
+                luminol_core::history::snapshot_map_properties(&mut map).ok(),
+            )
+        });
+
+        *update_state
+            .data
+            .get_or_load_map(self.map_id, update_state.filesystem) = map;
+
+        update_state.record_change(
+            format!("Edit properties of map {:0>3}", self.map_id),
+            vec![diff],
+        );
+    }
+}
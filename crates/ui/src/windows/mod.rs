@@ -26,6 +26,8 @@
 pub mod about;
 /// The actor editor.
 pub mod actors;
+/// The animation editor.
+pub mod animations;
 pub mod appearance;
 /// The archive manager for creating and extracting RGSSAD archives.
 pub mod archive_manager;
@@ -45,16 +47,26 @@ pub mod enemies;
 /// The event editor.
 pub mod event_edit;
 pub mod global_config_window;
+/// The project-wide undo history.
+pub mod history;
+/// The integrity checker.
+pub mod integrity_checker;
 /// The item editor.
 pub mod items;
+/// Places in the project that windows can link to.
+mod location;
 /// The map picker.
 pub mod map_picker;
+/// The map properties editor.
+pub mod map_properties;
 /// Misc windows.
 pub mod misc;
 /// New project window
 pub mod new_project;
 /// The crash reporter.
 pub mod reporter;
+/// The project-wide find and replace window.
+pub mod search;
 /// The script editor
 pub mod script_edit;
 /// The script manager for creating and extracting Scripts.rxdata.
@@ -65,5 +77,11 @@ pub mod skills;
 pub mod sound_test;
 /// The state editor.
 pub mod states;
+/// The system editor.
+pub mod system;
+/// The tileset editor.
+pub mod tilesets;
+/// The troop editor.
+pub mod troops;
 /// The weapon editor.
 pub mod weapons;
@@ -35,6 +35,13 @@ impl Default for Window {
     }
 }
 
+impl Window {
+    /// Opens the script at the given index in a tab.
+    pub fn select(&mut self, index: usize, script_text: String) {
+        self.tabs.add_tab(ScriptTab::new(index, script_text));
+    }
+}
+
 impl luminol_core::Window for Window {
     fn id(&self) -> egui::Id {
         egui::Id::new("Script Edit")
@@ -0,0 +1,546 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use itertools::Itertools;
+use luminol_components::usages;
+use luminol_components::UiExt;
+use luminol_data::rpg::EventCommand;
+use luminol_data::ParameterType;
+
+use super::location::Location;
+
+/// The most results shown for a single category.
+const MAX_SHOWN: usize = 500;
+
+/// The find and replace window, which searches text and switch and variable usages across the
+/// whole project.
+pub struct Window {
+    query: String,
+    replacement: String,
+    case_sensitive: bool,
+    categories: [bool; Category::COUNT],
+    results: Option<Vec<Match>>,
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Self {
+            query: String::new(),
+            replacement: String::new(),
+            case_sensitive: false,
+            categories: [true; Category::COUNT],
+            results: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Category {
+    Database,
+    Scripts,
+    EventNames,
+    Messages,
+    SwitchesAndVariables,
+}
+
+impl Category {
+    const COUNT: usize = 5;
+    const ALL: [Self; Self::COUNT] = [
+        Self::Database,
+        Self::Scripts,
+        Self::EventNames,
+        Self::Messages,
+        Self::SwitchesAndVariables,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Database => "Database names and descriptions",
+            Self::Scripts => "Scripts",
+            Self::EventNames => "Event names",
+            Self::Messages => "Show Text messages",
+            Self::SwitchesAndVariables => "Switch and variable usages",
+        }
+    }
+
+    /// Whether text in this category can be replaced. Switch and variable usages are IDs.
+    fn is_text(self) -> bool {
+        self != Self::SwitchesAndVariables
+    }
+}
+
+struct Match {
+    category: Category,
+    location: Location,
+    what: String,
+    /// The line of text the match is in.
+    text: String,
+}
+
+/// The `Show Text` command and the command holding each of its following lines.
+const SHOW_TEXT_CODES: [u16; 2] = [101, 401];
+
+/// Finds the byte ranges of the non-overlapping occurrences of `query` in `text`.
+fn find_matches(text: &str, query: &str, case_sensitive: bool) -> Vec<std::ops::Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    if case_sensitive {
+        return text
+            .match_indices(query)
+            .map(|(start, m)| start..start + m.len())
+            .collect();
+    }
+
+    // Lowercasing can change the length of a string, so compare character by character instead
+    // of searching a lowercased copy
+    let query = query.chars().flat_map(char::to_lowercase).collect_vec();
+    let mut matches = Vec::new();
+    let mut next = 0;
+    for (start, _) in text.char_indices() {
+        if start < next {
+            continue;
+        }
+        let mut lowercase = Vec::with_capacity(query.len());
+        let mut end = start;
+        for c in text[start..].chars() {
+            if lowercase.len() >= query.len() {
+                break;
+            }
+            lowercase.extend(c.to_lowercase());
+            end += c.len_utf8();
+        }
+        if lowercase == query {
+            matches.push(start..end);
+            next = end;
+        }
+    }
+    matches
+}
+
+/// Calls `f` with the text of every `Show Text` command in an event command list.
+fn show_text(
+    f: &mut dyn FnMut(Category, Location, &str, &mut String) -> bool,
+    location: Location,
+    list: &mut [EventCommand],
+    what: &str,
+) -> bool {
+    let mut changed = false;
+    for (index, command) in list.iter_mut().enumerate() {
+        if !SHOW_TEXT_CODES.contains(&command.code) {
+            continue;
+        }
+        if let Some(text) = command
+            .parameters
+            .get_mut(0)
+            .and_then(ParameterType::as_string_mut)
+        {
+            let what = format!("{what}, command {}", index + 1);
+            changed |= f(Category::Messages, location, &what, text);
+        }
+    }
+    changed
+}
+
+/// Calls `f` with every piece of text in the enabled categories, along with where it is and what
+/// it is. `f` returns whether it changed the text, in which case the data it's in is marked as
+/// modified.
+fn visit_text(
+    update_state: &luminol_core::UpdateState<'_>,
+    categories: &[bool; Category::COUNT],
+    mut f: impl FnMut(Category, Location, &str, &mut String) -> bool,
+) {
+    let data = &*update_state.data;
+    let enabled = |category: Category| categories[category as usize];
+
+    macro_rules! database {
+        ($getter:ident, $location:ident, $($field:ident: $what:literal),+) => {{
+            let mut container = data.$getter();
+            let mut changed = false;
+            for (id, entry) in container.data.iter_mut().enumerate() {
+                $(
+                    changed |= f(
+                        Category::Database,
+                        Location::$location(id),
+                        $what,
+                        &mut entry.$field,
+                    );
+                )+
+            }
+            container.modified |= changed;
+        }};
+    }
+
+    if enabled(Category::Database) {
+        database!(actors, Actor, name: "Name");
+        database!(classes, Class, name: "Name");
+        database!(skills, Skill, name: "Name", description: "Description");
+        database!(items, Item, name: "Name", description: "Description");
+        database!(weapons, Weapon, name: "Name", description: "Description");
+        database!(armors, Armor, name: "Name", description: "Description");
+        database!(enemies, Enemy, name: "Name");
+        database!(troops, Troop, name: "Name");
+        database!(states, State, name: "Name");
+        database!(animations, Animation, name: "Name");
+        database!(tilesets, Tileset, name: "Name");
+    }
+
+    if enabled(Category::Scripts) {
+        let mut scripts = data.scripts();
+        let mut changed = false;
+        for (id, script) in scripts.data.iter_mut().enumerate() {
+            changed |= f(
+                Category::Scripts,
+                Location::Script(id),
+                &script.name.clone(),
+                &mut script.script_text,
+            );
+        }
+        scripts.modified |= changed;
+    }
+
+    if enabled(Category::EventNames) || enabled(Category::Messages) {
+        let mut common_events = data.common_events();
+        let mut changed = false;
+        for (id, common_event) in common_events.data.iter_mut().enumerate() {
+            let location = Location::CommonEvent(id);
+            if enabled(Category::EventNames) {
+                changed |= f(
+                    Category::EventNames,
+                    location,
+                    "Name",
+                    &mut common_event.name,
+                );
+            }
+            if enabled(Category::Messages) {
+                changed |= show_text(&mut f, location, &mut common_event.list, "The event");
+            }
+        }
+        common_events.modified |= changed;
+    }
+
+    if enabled(Category::Messages) {
+        let mut troops = data.troops();
+        let mut changed = false;
+        for (id, troop) in troops.data.iter_mut().enumerate() {
+            for (index, page) in troop.pages.iter_mut().enumerate() {
+                let what = format!("Page {}", index + 1);
+                changed |= show_text(&mut f, Location::Troop(id), &mut page.list, &what);
+            }
+        }
+        troops.modified |= changed;
+    }
+
+    if enabled(Category::EventNames) || enabled(Category::Messages) {
+        let map_ids = data.map_infos().data.keys().copied().sorted().collect_vec();
+        for id in map_ids {
+            let location = Location::Map(id);
+            let mut map = match data.try_get_or_load_map(id, &*update_state.filesystem) {
+                Ok(map) => map,
+                Err(e) => {
+                    luminol_core::tracing::warn!("Skipping map {id:0>3} while searching: {e}");
+                    continue;
+                }
+            };
+
+            let mut changed = false;
+            for (event_id, event) in map.events.iter_mut() {
+                let what = format!("Event {event_id:0>3}");
+                if enabled(Category::EventNames) {
+                    changed |= f(Category::EventNames, location, &what, &mut event.name);
+                }
+                if enabled(Category::Messages) {
+                    let what = format!("{what} ({})", event.name);
+                    for (index, page) in event.pages.iter_mut().enumerate() {
+                        let what = format!("{what}, page {}", index + 1);
+                        changed |= show_text(&mut f, location, &mut page.list, &what);
+                    }
+                }
+            }
+            map.modified |= changed;
+        }
+    }
+}
+
+/// Finds the switches and variables whose name contains the query or whose ID is the query, and
+/// every place they're used.
+fn search_usages(
+    update_state: &luminol_core::UpdateState<'_>,
+    query: &str,
+    case_sensitive: bool,
+    results: &mut Vec<Match>,
+) {
+    let query_id = query.trim().parse::<usize>().ok();
+    let matching = |names: &[String]| {
+        names
+            .iter()
+            .enumerate()
+            .filter(|(id, name)| {
+                query_id == Some(id + 1) || !find_matches(name, query, case_sensitive).is_empty()
+            })
+            .map(|(id, _)| id)
+            .collect::<std::collections::HashSet<_>>()
+    };
+    let (switches, variables) = {
+        let system = update_state.data.system();
+        (matching(&system.switches), matching(&system.variables))
+    };
+    if switches.is_empty() && variables.is_empty() {
+        return;
+    }
+
+    let command_db = &update_state
+        .project_config
+        .as_ref()
+        .expect("project not loaded")
+        .command_db;
+    let usages = usages::find(&*update_state.data, &*update_state.filesystem, command_db);
+
+    let system = update_state.data.system();
+    for usage in usages {
+        let (ids, names) = match usage.kind {
+            usages::Kind::Switch => (&switches, &system.switches),
+            usages::Kind::Variable => (&variables, &system.variables),
+        };
+        if !ids.contains(&usage.id) {
+            continue;
+        }
+        let name = names.get(usage.id).map(String::as_str).unwrap_or_default();
+        results.push(Match {
+            category: Category::SwitchesAndVariables,
+            location: usage.source.into(),
+            what: usage.description,
+            text: format!("{} {:0>3}: {name}", usage.kind.name(), usage.id + 1),
+        });
+    }
+}
+
+impl Window {
+    fn search(&mut self, update_state: &luminol_core::UpdateState<'_>) {
+        let mut results = Vec::new();
+
+        visit_text(
+            update_state,
+            &self.categories,
+            |category, location, what, text| {
+                for range in find_matches(text, &self.query, self.case_sensitive) {
+                    let line_start = text[..range.start].rfind('\n').map_or(0, |i| i + 1);
+                    let line_end = text[range.end..]
+                        .find('\n')
+                        .map_or(text.len(), |i| range.end + i);
+                    let what = if text.contains('\n') {
+                        let line = text[..range.start].matches('\n').count() + 1;
+                        format!("{what}, line {line}")
+                    } else {
+                        what.to_string()
+                    };
+                    results.push(Match {
+                        category,
+                        location,
+                        what,
+                        text: text[line_start..line_end].trim().to_string(),
+                    });
+                }
+                false
+            },
+        );
+
+        if self.categories[Category::SwitchesAndVariables as usize] {
+            search_usages(update_state, &self.query, self.case_sensitive, &mut results);
+        }
+
+        results.sort_by_key(|result| (result.category, result.location));
+        self.results = Some(results);
+    }
+
+    /// Replaces every match in the enabled text categories, and returns how many were replaced.
+    fn replace(&self, update_state: &luminol_core::UpdateState<'_>) -> usize {
+        let mut count = 0;
+        visit_text(update_state, &self.categories, |_, _, _, text| {
+            let matches = find_matches(text, &self.query, self.case_sensitive);
+            if matches.is_empty() {
+                return false;
+            }
+            count += matches.len();
+
+            let mut replaced = String::with_capacity(text.len());
+            let mut last_end = 0;
+            for range in matches {
+                replaced.push_str(&text[last_end..range.start]);
+                replaced.push_str(&self.replacement);
+                last_end = range.end;
+            }
+            replaced.push_str(&text[last_end..]);
+            *text = replaced;
+            true
+        });
+        count
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("find_and_replace")
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut clicked_location = None;
+
+        egui::Window::new("Find and Replace")
+            .id(self.id())
+            .default_width(500.)
+            .default_height(400.)
+            .open(open)
+            .show(ctx, |ui| {
+                let mut search = false;
+
+                egui::Grid::new("find_and_replace_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Find");
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut self.query)
+                                .desired_width(f32::INFINITY)
+                                .hint_text("Text, or a switch or variable name or ID"),
+                        );
+                        search |=
+                            response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        ui.end_row();
+
+                        ui.label("Replace with");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.replacement)
+                                .desired_width(f32::INFINITY),
+                        );
+                        ui.end_row();
+                    });
+
+                ui.horizontal_wrapped(|ui| {
+                    for category in Category::ALL {
+                        ui.checkbox(&mut self.categories[category as usize], category.name());
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.case_sensitive, "Match case");
+
+                    search |= ui
+                        .add_enabled(!self.query.is_empty(), egui::Button::new("Find all"))
+                        .clicked();
+
+                    let can_replace = !self.query.is_empty()
+                        && Category::ALL.into_iter().any(|category| {
+                            category.is_text() && self.categories[category as usize]
+                        });
+                    if ui
+                        .add_enabled(can_replace, egui::Button::new("Replace all"))
+                        .on_hover_text("Switch and variable usages are never replaced")
+                        .clicked()
+                    {
+                        let count = self.replace(update_state);
+                        if count > 0 {
+                            update_state.modified.set(true);
+                        }
+                        luminol_core::info!(
+                            update_state.toasts,
+                            format!("Replaced {count} occurrences of {:?}", self.query)
+                        );
+                        search = true;
+                    }
+                });
+
+                if search && !self.query.is_empty() {
+                    self.search(update_state);
+                }
+
+                let Some(results) = &self.results else {
+                    ui.weak("Searching event text loads every map, which may take a while");
+                    return;
+                };
+
+                ui.separator();
+                ui.label(format!("{} results", results.len()));
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        for (category, matches) in
+                            &results.iter().group_by(|result| result.category)
+                        {
+                            let matches = matches.collect_vec();
+                            egui::CollapsingHeader::new(format!(
+                                "{} ({})",
+                                category.name(),
+                                matches.len()
+                            ))
+                            .id_source((self.id(), category as usize))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                ui.with_cross_justify(|ui| {
+                                    for (row, result) in matches.iter().take(MAX_SHOWN).enumerate()
+                                    {
+                                        ui.with_stripe(row % 2 != 0, |ui| {
+                                            if ui
+                                                .add(
+                                                    egui::Label::new(format!(
+                                                        "{}, {}: {}",
+                                                        result.location, result.what, result.text
+                                                    ))
+                                                    .truncate(true)
+                                                    .sense(egui::Sense::click()),
+                                                )
+                                                .on_hover_text(&result.text)
+                                                .on_hover_cursor(egui::CursorIcon::PointingHand)
+                                                .clicked()
+                                            {
+                                                clicked_location = Some(result.location);
+                                            }
+                                        });
+                                    }
+                                    if matches.len() > MAX_SHOWN {
+                                        ui.weak(format!(
+                                            "...and {} more",
+                                            matches.len() - MAX_SHOWN
+                                        ));
+                                    }
+                                });
+                            });
+                        }
+                    });
+            });
+
+        if let Some(location) = clicked_location {
+            location.open(update_state);
+        }
+    }
+}
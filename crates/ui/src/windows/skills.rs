@@ -36,6 +36,11 @@ impl Window {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Selects the skill with the given index.
+    pub fn select(&mut self, id: usize) {
+        self.view.select(id);
+    }
 }
 
 impl luminol_core::Window for Window {
@@ -350,6 +355,8 @@ impl luminol_core::Window for Window {
                         });
 
                         self.previous_skill = Some(skill.id);
+
+                        modified
                     },
                 )
             });
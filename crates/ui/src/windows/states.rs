@@ -36,6 +36,11 @@ impl Window {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Selects the state with the given index.
+    pub fn select(&mut self, id: usize) {
+        self.view.select(id);
+    }
 }
 
 impl luminol_core::Window for Window {
@@ -373,6 +378,8 @@ impl luminol_core::Window for Window {
                         states[id] = state;
 
                         self.previous_state = Some(id);
+
+                        modified
                     },
                 )
             });
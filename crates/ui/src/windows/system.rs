@@ -0,0 +1,486 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_audio::Source;
+use luminol_components::UiExt;
+use luminol_core::Modal;
+use luminol_data::rpg;
+use strum::IntoEnumIterator;
+
+use luminol_modals::graphic_picker::basic::Modal as GraphicPicker;
+use luminol_modals::map_position_picker::Modal as MapPositionPicker;
+use luminol_modals::sound_picker::Modal as SoundPicker;
+
+/// The maximum number of actors in the party.
+const MAX_PARTY_SIZE: usize = 4;
+
+type AudioSlot = (
+    &'static str,
+    Source,
+    fn(&mut rpg::System) -> &mut rpg::AudioFile,
+);
+
+const AUDIO_SLOTS: [AudioSlot; 16] = [
+    ("Title BGM", Source::BGM, |s| &mut s.title_bgm),
+    ("Battle BGM", Source::BGM, |s| &mut s.battle_bgm),
+    ("Battle End ME", Source::ME, |s| &mut s.battle_end_me),
+    ("Gameover ME", Source::ME, |s| &mut s.gameover_me),
+    ("Cursor SE", Source::SE, |s| &mut s.cursor_se),
+    ("Decision SE", Source::SE, |s| &mut s.decision_se),
+    ("Cancel SE", Source::SE, |s| &mut s.cancel_se),
+    ("Buzzer SE", Source::SE, |s| &mut s.buzzer_se),
+    ("Equip SE", Source::SE, |s| &mut s.equip_se),
+    ("Shop SE", Source::SE, |s| &mut s.shop_se),
+    ("Save SE", Source::SE, |s| &mut s.save_se),
+    ("Load SE", Source::SE, |s| &mut s.load_se),
+    ("Battle Start SE", Source::SE, |s| &mut s.battle_start_se),
+    ("Escape SE", Source::SE, |s| &mut s.escape_se),
+    ("Actor Collapse SE", Source::SE, |s| {
+        &mut s.actor_collapse_se
+    }),
+    ("Enemy Collapse SE", Source::SE, |s| {
+        &mut s.enemy_collapse_se
+    }),
+];
+
+type Word = (&'static str, fn(&mut rpg::Words) -> &mut String);
+
+const WORDS: [Word; 20] = [
+    ("Gold", |w| &mut w.gold),
+    ("HP", |w| &mut w.hp),
+    ("SP", |w| &mut w.sp),
+    ("STR", |w| &mut w.str),
+    ("DEX", |w| &mut w.dex),
+    ("AGI", |w| &mut w.agi),
+    ("INT", |w| &mut w.int),
+    ("ATK", |w| &mut w.atk),
+    ("PDEF", |w| &mut w.pdef),
+    ("MDEF", |w| &mut w.mdef),
+    ("Weapon", |w| &mut w.weapon),
+    ("Shield", |w| &mut w.armor1),
+    ("Helmet", |w| &mut w.armor2),
+    ("Body Armor", |w| &mut w.armor3),
+    ("Accessory", |w| &mut w.armor4),
+    ("Attack", |w| &mut w.attack),
+    ("Skill", |w| &mut w.skill),
+    ("Guard", |w| &mut w.guard),
+    ("Item", |w| &mut w.item),
+    ("Equip", |w| &mut w.equip),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[derive(strum::Display, strum::EnumIter)]
+enum Section {
+    #[default]
+    General,
+    #[strum(to_string = "Music and Sounds")]
+    Audio,
+    Words,
+    Elements,
+    Switches,
+    Variables,
+}
+
+/// The system editor.
+pub struct Window {
+    section: Section,
+
+    windowskin_picker: GraphicPicker,
+    title_picker: GraphicPicker,
+    gameover_picker: GraphicPicker,
+    transition_picker: GraphicPicker,
+    audio_pickers: [SoundPicker; AUDIO_SLOTS.len()],
+    start_position_picker: MapPositionPicker,
+
+    /// The sizes the lists will be resized to when pressing "Set Maximum".
+    element_count: usize,
+    switch_count: usize,
+    variable_count: usize,
+}
+
+impl Window {
+    pub fn new(update_state: &luminol_core::UpdateState<'_>) -> Self {
+        let system = update_state.data.system();
+
+        Self {
+            section: Section::default(),
+
+            windowskin_picker: GraphicPicker::new(
+                update_state,
+                "Graphics/Windowskins".into(),
+                system.windowskin_name.as_deref(),
+                egui::vec2(128., 128.),
+                "system_windowskin_picker",
+            ),
+            title_picker: GraphicPicker::new(
+                update_state,
+                "Graphics/Titles".into(),
+                system.title_name.as_deref(),
+                egui::vec2(160., 120.),
+                "system_title_picker",
+            ),
+            gameover_picker: GraphicPicker::new(
+                update_state,
+                "Graphics/Gameovers".into(),
+                system.gameover_name.as_deref(),
+                egui::vec2(160., 120.),
+                "system_gameover_picker",
+            ),
+            transition_picker: GraphicPicker::new(
+                update_state,
+                "Graphics/Transitions".into(),
+                system.battle_transition.as_deref(),
+                egui::vec2(160., 120.),
+                "system_transition_picker",
+            ),
+            audio_pickers: std::array::from_fn(|i| {
+                SoundPicker::new(
+                    AUDIO_SLOTS[i].1,
+                    egui::Id::new("system_audio_picker").with(i),
+                )
+            }),
+            start_position_picker: MapPositionPicker::new("system_start_position_picker"),
+
+            // The first element is always unused
+            element_count: system.elements.len().saturating_sub(1),
+            switch_count: system.switches.len(),
+            variable_count: system.variables.len(),
+        }
+    }
+
+    /// Shows an editable list of names along with a field for changing its size. `first` is the
+    /// index of the first entry that is actually used. Returns true if the list was modified.
+    fn show_list(
+        ui: &mut egui::Ui,
+        id: &str,
+        list: &mut Vec<String>,
+        first: usize,
+        new_size: &mut usize,
+    ) -> bool {
+        let mut modified = false;
+
+        ui.horizontal(|ui| {
+            ui.label("Maximum:");
+            ui.add(egui::DragValue::new(new_size).clamp_range(1..=9999));
+            if ui
+                .add_enabled(
+                    *new_size + first != list.len(),
+                    egui::Button::new("Set Maximum"),
+                )
+                .clicked()
+            {
+                list.resize_with(*new_size + first, String::new);
+                modified = true;
+            }
+        });
+
+        if *new_size > 999 && *new_size + first > list.len() {
+            ui.label(
+                egui::RichText::new(
+                    "Setting the maximum above 999 may introduce performance issues and \
+                     instability",
+                )
+                .color(ui.style().visuals.warn_fg_color),
+            );
+        }
+
+        ui.separator();
+
+        let row_height = ui.spacing().interact_size.y;
+        egui::ScrollArea::vertical()
+            .id_source(id)
+            .auto_shrink([false, false])
+            .show_rows(
+                ui,
+                row_height,
+                list.len().saturating_sub(first),
+                |ui, rows| {
+                    for i in rows {
+                        ui.with_stripe(i % 2 != 0, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("{:0>4}:", i + 1));
+                                modified |= ui
+                                    .add(
+                                        egui::TextEdit::singleline(&mut list[i + first])
+                                            .desired_width(f32::INFINITY),
+                                    )
+                                    .changed();
+                            });
+                        });
+                    }
+                },
+            );
+
+        modified
+    }
+
+    fn show_general(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        system: &mut rpg::System,
+    ) -> bool {
+        let mut modified = false;
+
+        ui.with_padded_stripe(false, |ui| {
+            ui.label("Initial Party:");
+            let actors = update_state.data.actors();
+            let mut removed = None;
+            for (i, actor_id) in system.party_members.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    modified |= ui
+                        .add(luminol_components::OptionalIdComboBox::new(
+                            update_state,
+                            ("system_party_member", i),
+                            actor_id,
+                            0..actors.data.len(),
+                            |id| {
+                                actors.data.get(id).map_or_else(
+                                    || "".into(),
+                                    |a| format!("{:0>4}: {}", id + 1, a.name),
+                                )
+                            },
+                        ))
+                        .changed();
+
+                    if ui
+                        .button(egui::RichText::new("Delete").color(egui::Color32::LIGHT_RED))
+                        .clicked()
+                    {
+                        removed = Some(i);
+                    }
+                });
+            }
+
+            if let Some(i) = removed {
+                system.party_members.remove(i);
+                modified = true;
+            }
+
+            let button =
+                egui::Button::new(egui::RichText::new("Add").color(egui::Color32::LIGHT_GREEN));
+            if ui
+                .add_enabled(system.party_members.len() < MAX_PARTY_SIZE, button)
+                .clicked()
+            {
+                system.party_members.push(0);
+                modified = true;
+            }
+        });
+
+        ui.with_padded_stripe(true, |ui| {
+            // The start map is stored as an index, while the picker works with map IDs
+            let mut map_id = system.start_map_id + 1;
+            if ui
+                .add(luminol_components::Field::new(
+                    "Start Position",
+                    self.start_position_picker.button(
+                        (&mut map_id, &mut system.start_x, &mut system.start_y),
+                        update_state,
+                    ),
+                ))
+                .changed()
+            {
+                system.start_map_id = map_id.saturating_sub(1);
+                modified = true;
+            }
+        });
+
+        ui.with_padded_stripe(false, |ui| {
+            ui.columns(2, |columns| {
+                modified |= columns[0]
+                    .add(luminol_components::Field::new(
+                        "Title Graphic",
+                        self.title_picker
+                            .button(&mut system.title_name, update_state),
+                    ))
+                    .changed();
+
+                modified |= columns[1]
+                    .add(luminol_components::Field::new(
+                        "Gameover Graphic",
+                        self.gameover_picker
+                            .button(&mut system.gameover_name, update_state),
+                    ))
+                    .changed();
+            });
+        });
+
+        ui.with_padded_stripe(true, |ui| {
+            ui.columns(2, |columns| {
+                modified |= columns[0]
+                    .add(luminol_components::Field::new(
+                        "Windowskin",
+                        self.windowskin_picker
+                            .button(&mut system.windowskin_name, update_state),
+                    ))
+                    .changed();
+
+                modified |= columns[1]
+                    .add(luminol_components::Field::new(
+                        "Battle Transition",
+                        self.transition_picker
+                            .button(&mut system.battle_transition, update_state),
+                    ))
+                    .changed();
+            });
+        });
+
+        modified
+    }
+
+    fn show_audio(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        system: &mut rpg::System,
+    ) -> bool {
+        let mut modified = false;
+
+        for (i, ((label, _, audio_file), picker)) in AUDIO_SLOTS
+            .iter()
+            .zip(self.audio_pickers.iter_mut())
+            .enumerate()
+        {
+            ui.with_padded_stripe(i % 2 != 0, |ui| {
+                modified |= ui
+                    .add(luminol_components::Field::new(
+                        *label,
+                        picker.button(audio_file(system), update_state),
+                    ))
+                    .changed();
+            });
+        }
+
+        modified
+    }
+
+    fn show_words(ui: &mut egui::Ui, words: &mut rpg::Words) -> bool {
+        let mut modified = false;
+
+        for (i, chunk) in WORDS.chunks(2).enumerate() {
+            ui.with_padded_stripe(i % 2 != 0, |ui| {
+                ui.columns(2, |columns| {
+                    for (column, (label, word)) in columns.iter_mut().zip(chunk) {
+                        modified |= column
+                            .add(luminol_components::Field::new(
+                                *label,
+                                egui::TextEdit::singleline(word(words))
+                                    .desired_width(f32::INFINITY),
+                            ))
+                            .changed();
+                    }
+                });
+            });
+        }
+
+        modified
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("system_editor")
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut system = std::mem::take(&mut *update_state.data.system());
+
+        let mut modified = false;
+
+        egui::Window::new("System")
+            .id(self.id())
+            .default_width(500.)
+            .open(open)
+            .show(ctx, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for section in Section::iter() {
+                        ui.selectable_value(&mut self.section, section, section.to_string());
+                    }
+                });
+                ui.separator();
+
+                match self.section {
+                    Section::Elements => {
+                        modified |= Self::show_list(
+                            ui,
+                            "system_elements",
+                            &mut system.elements,
+                            1,
+                            &mut self.element_count,
+                        );
+                    }
+                    Section::Switches => {
+                        modified |= Self::show_list(
+                            ui,
+                            "system_switches",
+                            &mut system.switches,
+                            0,
+                            &mut self.switch_count,
+                        );
+                    }
+                    Section::Variables => {
+                        modified |= Self::show_list(
+                            ui,
+                            "system_variables",
+                            &mut system.variables,
+                            0,
+                            &mut self.variable_count,
+                        );
+                    }
+                    section => {
+                        egui::ScrollArea::vertical()
+                            .id_source("system_section")
+                            .show(ui, |ui| {
+                                modified |= match section {
+                                    Section::General => {
+                                        self.show_general(ui, update_state, &mut system)
+                                    }
+                                    Section::Audio => {
+                                        self.show_audio(ui, update_state, &mut system)
+                                    }
+                                    _ => Self::show_words(ui, &mut system.words),
+                                };
+                            });
+                    }
+                }
+            });
+
+        if modified {
+            update_state.modified.set(true);
+            system.modified = true;
+        }
+
+        *update_state.data.system() = system;
+    }
+}
@@ -0,0 +1,430 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_components::UiExt;
+use luminol_core::Modal;
+use strum::IntoEnumIterator;
+
+use luminol_modals::graphic_picker::basic::Modal as GraphicPicker;
+use luminol_modals::graphic_picker::hue::Modal as HueGraphicPicker;
+
+/// The number of autotiles a tileset has.
+const AUTOTILE_COUNT: usize = 7;
+
+/// The tileset editor.
+pub struct Window {
+    selected_tileset_name: Option<String>,
+    previous_tileset: Option<usize>,
+
+    tileset_picker: GraphicPicker,
+    autotile_pickers: [GraphicPicker; AUTOTILE_COUNT],
+    panorama_picker: HueGraphicPicker,
+    fog_picker: HueGraphicPicker,
+    battleback_picker: GraphicPicker,
+
+    tileset_view: Option<luminol_components::TilesetView>,
+    flag: luminol_components::TileFlag,
+
+    /// The ID of a tileset whose atlas needs to be reloaded so open maps pick up the changes.
+    pending_reload: Option<usize>,
+
+    view: luminol_components::DatabaseView,
+}
+
+impl Window {
+    pub fn new(update_state: &luminol_core::UpdateState<'_>) -> Self {
+        let tilesets = update_state.data.tilesets();
+        let tileset = &tilesets.data[0];
+
+        Self {
+            selected_tileset_name: None,
+            previous_tileset: None,
+
+            tileset_picker: GraphicPicker::new(
+                update_state,
+                "Graphics/Tilesets".into(),
+                tileset.tileset_name.as_deref(),
+                egui::vec2(256., 64.),
+                "tileset_graphic_picker",
+            ),
+            autotile_pickers: std::array::from_fn(|i| {
+                GraphicPicker::new(
+                    update_state,
+                    "Graphics/Autotiles".into(),
+                    Self::autotile_path(tileset, i).as_deref(),
+                    egui::vec2(64., 64.),
+                    egui::Id::new("tileset_autotile_picker").with(i),
+                )
+            }),
+            panorama_picker: HueGraphicPicker::new(
+                update_state,
+                "Graphics/Panoramas".into(),
+                tileset.panorama_name.as_deref(),
+                tileset.panorama_hue,
+                egui::vec2(128., 96.),
+                "tileset_panorama_picker",
+            ),
+            fog_picker: HueGraphicPicker::new(
+                update_state,
+                "Graphics/Fogs".into(),
+                tileset.fog_name.as_deref(),
+                tileset.fog_hue,
+                egui::vec2(128., 96.),
+                "tileset_fog_picker",
+            ),
+            battleback_picker: GraphicPicker::new(
+                update_state,
+                "Graphics/Battlebacks".into(),
+                tileset.battleback_name.as_deref(),
+                egui::vec2(128., 64.),
+                "tileset_battleback_picker",
+            ),
+
+            tileset_view: None,
+            flag: luminol_components::TileFlag::default(),
+
+            pending_reload: None,
+
+            view: luminol_components::DatabaseView::new(),
+        }
+    }
+
+    /// Autotile names are stored as plain strings, where an empty string means there is no
+    /// autotile.
+    fn autotile_path(
+        tileset: &luminol_data::rpg::Tileset,
+        index: usize,
+    ) -> Option<camino::Utf8PathBuf> {
+        tileset
+            .autotile_names
+            .get(index)
+            .filter(|name| !name.is_empty())
+            .map(camino::Utf8PathBuf::from)
+    }
+
+    fn load_tileset_view(
+        update_state: &mut luminol_core::UpdateState<'_>,
+        tileset: &luminol_data::rpg::Tileset,
+    ) -> Option<luminol_components::TilesetView> {
+        match luminol_components::TilesetView::new(update_state, tileset) {
+            Ok(view) => Some(view),
+            Err(error) => {
+                luminol_core::error!(
+                    update_state.toasts,
+                    error.wrap_err(format!("Error loading tileset {:?}", tileset.name))
+                );
+                None
+            }
+        }
+    }
+
+    /// Selects the tileset with the given index.
+    pub fn select(&mut self, id: usize) {
+        self.view.select(id);
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("tileset_editor")
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut tilesets = std::mem::take(&mut *update_state.data.tilesets());
+
+        let mut modified = false;
+
+        let name = if let Some(name) = &self.selected_tileset_name {
+            format!("Editing tileset {:?}", name)
+        } else {
+            "Tileset Editor".into()
+        };
+        self.selected_tileset_name = None;
+
+        let response = egui::Window::new(name)
+            .id(self.id())
+            .default_width(500.)
+            .open(open)
+            .show(ctx, |ui| {
+                self.view.show(
+                    ui,
+                    update_state,
+                    "Tilesets",
+                    &mut tilesets.data,
+                    |tileset| format!("{:0>4}: {}", tileset.id + 1, tileset.name),
+                    |ui, tilesets, id, update_state| {
+                        let tileset = &mut tilesets[id];
+                        self.selected_tileset_name = Some(tileset.name.clone());
+
+                        let mut tileset_modified = false;
+                        let mut graphics_modified = false;
+
+                        if self.previous_tileset != Some(tileset.id) {
+                            // avoid desyncs by resetting the modals if the tileset has changed
+                            self.tileset_picker
+                                .reset(update_state, &mut tileset.tileset_name);
+                            for (i, picker) in self.autotile_pickers.iter_mut().enumerate() {
+                                picker.reset(update_state, &mut Self::autotile_path(tileset, i));
+                            }
+                            self.panorama_picker.reset(
+                                update_state,
+                                (&mut tileset.panorama_name, &mut tileset.panorama_hue),
+                            );
+                            self.fog_picker.reset(
+                                update_state,
+                                (&mut tileset.fog_name, &mut tileset.fog_hue),
+                            );
+                            self.battleback_picker
+                                .reset(update_state, &mut tileset.battleback_name);
+
+                            self.tileset_view = Self::load_tileset_view(update_state, tileset);
+                        }
+
+                        ui.with_padded_stripe(false, |ui| {
+                            tileset_modified |= ui
+                                .add(luminol_components::Field::new(
+                                    "Name",
+                                    egui::TextEdit::singleline(&mut tileset.name)
+                                        .desired_width(f32::INFINITY),
+                                ))
+                                .changed();
+                        });
+
+                        ui.with_padded_stripe(true, |ui| {
+                            ui.columns(2, |columns| {
+                                graphics_modified |= columns[0]
+                                    .add(luminol_components::Field::new(
+                                        "Graphic",
+                                        self.tileset_picker
+                                            .button(&mut tileset.tileset_name, update_state),
+                                    ))
+                                    .changed();
+
+                                tileset_modified |= columns[1]
+                                    .add(luminol_components::Field::new(
+                                        "Battleback",
+                                        self.battleback_picker
+                                            .button(&mut tileset.battleback_name, update_state),
+                                    ))
+                                    .changed();
+                            });
+                        });
+
+                        ui.with_padded_stripe(false, |ui| {
+                            ui.label("Autotiles:");
+                            ui.horizontal_wrapped(|ui| {
+                                for (i, picker) in self.autotile_pickers.iter_mut().enumerate() {
+                                    let mut path = Self::autotile_path(tileset, i);
+                                    if ui.add(picker.button(&mut path, update_state)).changed() {
+                                        if tileset.autotile_names.len() < AUTOTILE_COUNT {
+                                            tileset
+                                                .autotile_names
+                                                .resize(AUTOTILE_COUNT, String::new());
+                                        }
+                                        tileset.autotile_names[i] =
+                                            path.map(|p| p.into_string()).unwrap_or_default();
+                                        graphics_modified = true;
+                                    }
+                                }
+                            });
+                        });
+
+                        ui.with_padded_stripe(true, |ui| {
+                            ui.columns(2, |columns| {
+                                tileset_modified |= columns[0]
+                                    .add(luminol_components::Field::new(
+                                        "Panorama",
+                                        self.panorama_picker.button(
+                                            (&mut tileset.panorama_name, &mut tileset.panorama_hue),
+                                            update_state,
+                                        ),
+                                    ))
+                                    .changed();
+
+                                tileset_modified |= columns[1]
+                                    .add(luminol_components::Field::new(
+                                        "Fog",
+                                        self.fog_picker.button(
+                                            (&mut tileset.fog_name, &mut tileset.fog_hue),
+                                            update_state,
+                                        ),
+                                    ))
+                                    .changed();
+                            });
+                        });
+
+                        ui.with_padded_stripe(false, |ui| {
+                            ui.add_enabled_ui(tileset.fog_name.is_some(), |ui| {
+                                ui.columns(2, |columns| {
+                                    tileset_modified |= columns[0]
+                                        .add(luminol_components::Field::new(
+                                            "Fog Opacity",
+                                            egui::Slider::new(&mut tileset.fog_opacity, 0..=255),
+                                        ))
+                                        .changed();
+
+                                    tileset_modified |= columns[1]
+                                        .add(luminol_components::Field::new(
+                                            "Fog Blending",
+                                            luminol_components::EnumComboBox::new(
+                                                (tileset.id, "fog_blend_type"),
+                                                &mut tileset.fog_blend_type,
+                                            ),
+                                        ))
+                                        .changed();
+                                });
+
+                                ui.columns(3, |columns| {
+                                    tileset_modified |= columns[0]
+                                        .add(luminol_components::Field::new(
+                                            "Fog Zoom",
+                                            egui::DragValue::new(&mut tileset.fog_zoom)
+                                                .clamp_range(100..=800)
+                                                .suffix("%"),
+                                        ))
+                                        .changed();
+
+                                    tileset_modified |= columns[1]
+                                        .add(luminol_components::Field::new(
+                                            "Fog Scroll X",
+                                            egui::DragValue::new(&mut tileset.fog_sx)
+                                                .clamp_range(-256..=256),
+                                        ))
+                                        .changed();
+
+                                    tileset_modified |= columns[2]
+                                        .add(luminol_components::Field::new(
+                                            "Fog Scroll Y",
+                                            egui::DragValue::new(&mut tileset.fog_sy)
+                                                .clamp_range(-256..=256),
+                                        ))
+                                        .changed();
+                                });
+                            });
+                        });
+
+                        ui.with_padded_stripe(true, |ui| {
+                            ui.horizontal_wrapped(|ui| {
+                                for flag in luminol_components::TileFlag::iter() {
+                                    ui.selectable_value(&mut self.flag, flag, flag.to_string());
+                                }
+                            });
+
+                            if let Some(tileset_view) = &mut self.tileset_view {
+                                tileset_view.flag = self.flag;
+                                egui::ScrollArea::vertical()
+                                    .id_source((tileset.id, "flags"))
+                                    .max_height(512.)
+                                    .show_viewport(ui, |ui, scroll_rect| {
+                                        tileset_modified |= tileset_view
+                                            .ui(update_state, ui, scroll_rect, tileset)
+                                            .changed();
+                                    });
+                            }
+
+                            ui.label(match self.flag {
+                                luminol_components::TileFlag::DirectionalPassage => {
+                                    "Click near the edge of a tile to toggle passage in that direction."
+                                }
+                                luminol_components::TileFlag::Priority
+                                | luminol_components::TileFlag::Terrain => {
+                                    "Left click to increase, right click to decrease."
+                                }
+                                _ => "Click on a tile to toggle it.",
+                            });
+                        });
+
+                        if graphics_modified {
+                            // The atlas has to be rebuilt right away since the flag view draws it
+                            match update_state.graphics.atlas_loader.reload_atlas(
+                                &update_state.graphics,
+                                update_state.filesystem,
+                                tileset,
+                            ) {
+                                Ok(atlas) => {
+                                    let size = 384 + atlas.tileset_height as usize / 32 * 8;
+                                    tileset.passages.resize(size);
+                                    tileset.priorities.resize(size);
+                                    tileset.terrain_tags.resize(size);
+                                }
+                                Err(error) => luminol_core::error!(
+                                    update_state.toasts,
+                                    error.wrap_err("Error reloading the tileset atlas")
+                                ),
+                            }
+                            self.tileset_view = Self::load_tileset_view(update_state, tileset);
+                            modified = true;
+                        } else if tileset_modified {
+                            self.pending_reload = Some(tileset.id);
+                            modified = true;
+                        }
+
+                        self.previous_tileset = Some(tileset.id);
+
+                        modified
+                    },
+                )
+            });
+
+        if response.is_some_and(|ir| ir.inner.is_some_and(|ir| ir.inner.modified)) {
+            modified = true;
+        }
+
+        if modified {
+            update_state.modified.set(true);
+            tilesets.modified = true;
+        }
+
+        // Replacing the atlas makes open maps reload their graphics, so wait until sliders and the
+        // like are let go of
+        if let Some(id) = self.pending_reload {
+            if !ctx.input(|i| i.pointer.any_down()) {
+                self.pending_reload = None;
+                if let Some(tileset) = tilesets.data.get(id) {
+                    if let Err(error) = update_state.graphics.atlas_loader.reload_atlas(
+                        &update_state.graphics,
+                        update_state.filesystem,
+                        tileset,
+                    ) {
+                        luminol_core::error!(
+                            update_state.toasts,
+                            error.wrap_err("Error reloading the tileset atlas")
+                        );
+                    }
+                }
+            }
+        }
+
+        *update_state.data.tilesets() = tilesets;
+    }
+}
@@ -0,0 +1,533 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_components::UiExt;
+use luminol_data::rpg;
+
+const SPAN_TYPES: [&str; 3] = ["Battle", "Turn", "Moment"];
+
+/// The troop editor.
+pub struct Window {
+    selected_troop_name: Option<String>,
+    previous_troop: Option<usize>,
+
+    selected_page: usize,
+    previous_page: Option<usize>,
+
+    troop_view: luminol_components::TroopView,
+    command_view: luminol_components::CommandView,
+
+    collapsing_view: luminol_components::CollapsingView,
+    view: luminol_components::DatabaseView,
+}
+
+impl Window {
+    pub fn new(update_state: &luminol_core::UpdateState<'_>) -> Self {
+        Self {
+            selected_troop_name: None,
+            previous_troop: None,
+
+            selected_page: 0,
+            previous_page: None,
+
+            troop_view: luminol_components::TroopView::new(update_state),
+            command_view: luminol_components::CommandView::new("troop_commands"),
+
+            collapsing_view: luminol_components::CollapsingView::new(),
+            view: luminol_components::DatabaseView::new(),
+        }
+    }
+
+    fn member_name(enemies: &rpg::Enemies, troop: &rpg::Troop, index: usize) -> String {
+        let name = troop
+            .members
+            .get(index)
+            .and_then(|member| enemies.data.get(member.enemy_id))
+            .map_or("", |enemy| enemy.name.as_str());
+        format!("{}: {name}", index + 1)
+    }
+
+    fn show_member_header(
+        ui: &mut egui::Ui,
+        enemies: &rpg::Enemies,
+        index: usize,
+        member: &rpg::troop::Member,
+    ) {
+        let mut flags = Vec::with_capacity(2);
+        if member.hidden {
+            flags.push("Hidden");
+        }
+        if member.immortal {
+            flags.push("Immortal");
+        }
+
+        ui.label(format!(
+            "{}: {}{}",
+            index + 1,
+            enemies
+                .data
+                .get(member.enemy_id)
+                .map_or("", |enemy| enemy.name.as_str()),
+            if flags.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", flags.join(", "))
+            }
+        ));
+    }
+
+    fn show_member_body(
+        ui: &mut egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        enemies: &rpg::Enemies,
+        troop_id: usize,
+        member: (usize, &mut rpg::troop::Member),
+    ) -> egui::Response {
+        let (member_index, member) = member;
+        let mut modified = false;
+
+        let mut response = egui::Frame::none()
+            .show(ui, |ui| {
+                modified |= ui
+                    .add(luminol_components::Field::new(
+                        "Enemy",
+                        luminol_components::OptionalIdComboBox::new(
+                            update_state,
+                            (troop_id, member_index, "enemy_id"),
+                            &mut member.enemy_id,
+                            0..enemies.data.len(),
+                            |id| {
+                                enemies.data.get(id).map_or_else(
+                                    || "".into(),
+                                    |e| format!("{:0>4}: {}", id + 1, e.name),
+                                )
+                            },
+                        ),
+                    ))
+                    .changed();
+
+                ui.columns(2, |columns| {
+                    modified |= columns[0].checkbox(&mut member.hidden, "Hidden").changed();
+                    modified |= columns[1]
+                        .checkbox(&mut member.immortal, "Immortal")
+                        .changed();
+                });
+            })
+            .response;
+
+        if modified {
+            response.mark_changed();
+        }
+        response
+    }
+
+    /// Selects the troop with the given index.
+    pub fn select(&mut self, id: usize) {
+        self.view.select(id);
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("troop_editor")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        // Take the troops out of the data cache so the preview and the command view can still use
+        // the rest of it
+        let mut troops = std::mem::take(&mut *update_state.data.troops());
+
+        let mut modified = false;
+
+        let name = if let Some(name) = &self.selected_troop_name {
+            format!("Editing troop {:?}", name)
+        } else {
+            "Troop Editor".into()
+        };
+        self.selected_troop_name = None;
+
+        let response = egui::Window::new(name)
+            .id(self.id())
+            .default_width(500.)
+            .open(open)
+            .show(ctx, |ui| {
+                self.view.show(
+                    ui,
+                    update_state,
+                    "Troops",
+                    &mut troops.data,
+                    |troop| format!("{:0>4}: {}", troop.id + 1, troop.name),
+                    |ui, troops, id, update_state| {
+                        let troop = &mut troops[id];
+                        self.selected_troop_name = Some(troop.name.clone());
+
+                        if self.previous_troop != Some(troop.id) {
+                            self.troop_view.clear();
+                            self.collapsing_view.clear_animations();
+                            self.selected_page = 0;
+                            self.previous_page = None;
+                        }
+
+                        if troop.pages.is_empty() {
+                            troop.pages.push(Default::default());
+                            modified = true;
+                        }
+                        self.selected_page = self.selected_page.min(troop.pages.len() - 1);
+
+                        ui.with_padded_stripe(false, |ui| {
+                            modified |= ui
+                                .add(luminol_components::Field::new(
+                                    "Name",
+                                    egui::TextEdit::singleline(&mut troop.name)
+                                        .desired_width(f32::INFINITY),
+                                ))
+                                .changed();
+                        });
+
+                        ui.with_padded_stripe(true, |ui| {
+                            ui.vertical_centered(|ui| {
+                                modified |= self.troop_view.ui(ui, update_state, troop).changed();
+                            });
+
+                            if ui
+                                .add_enabled(
+                                    !troop.members.is_empty(),
+                                    egui::Button::new("Auto-Arrange"),
+                                )
+                                .clicked()
+                            {
+                                luminol_components::TroopView::auto_arrange(troop);
+                                modified = true;
+                            }
+                        });
+
+                        {
+                            let enemies = update_state.data.enemies();
+                            let actors = update_state.data.actors();
+                            let system = update_state.data.system();
+
+                            ui.with_padded_stripe(false, |ui| {
+                                let member_count = troop.members.len();
+                                modified |= ui
+                                    .add(luminol_components::Field::new(
+                                        "Members",
+                                        |ui: &mut egui::Ui| {
+                                            self.collapsing_view.show(
+                                                ui,
+                                                troop.id,
+                                                &mut troop.members,
+                                                |ui, i, member| {
+                                                    Self::show_member_header(
+                                                        ui, &enemies, i, member,
+                                                    )
+                                                },
+                                                |ui, i, member| {
+                                                    Self::show_member_body(
+                                                        ui,
+                                                        update_state,
+                                                        &enemies,
+                                                        troop.id,
+                                                        (i, member),
+                                                    )
+                                                },
+                                            )
+                                        },
+                                    ))
+                                    .changed();
+
+                                if troop.members.len() > member_count {
+                                    if let Some(member) = troop.members.last_mut() {
+                                        luminol_components::TroopView::place_new_member(member);
+                                    }
+                                }
+                                if let Some(index) = self.collapsing_view.deleted_entry() {
+                                    luminol_components::TroopView::member_removed(troop, index);
+                                }
+                            });
+
+                            ui.with_padded_stripe(true, |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label("Page:");
+                                    for i in 0..troop.pages.len() {
+                                        ui.selectable_value(
+                                            &mut self.selected_page,
+                                            i,
+                                            format!("{}", i + 1),
+                                        );
+                                    }
+
+                                    if ui
+                                        .button(
+                                            egui::RichText::new("Add")
+                                                .color(egui::Color32::LIGHT_GREEN),
+                                        )
+                                        .clicked()
+                                    {
+                                        modified = true;
+                                        troop.pages.push(Default::default());
+                                        self.selected_page = troop.pages.len() - 1;
+                                    }
+
+                                    let button = egui::Button::new(
+                                        egui::RichText::new("Delete")
+                                            .color(egui::Color32::LIGHT_RED),
+                                    );
+                                    if ui.add_enabled(troop.pages.len() > 1, button).clicked() {
+                                        modified = true;
+                                        troop.pages.remove(self.selected_page);
+                                        self.selected_page = self.selected_page.saturating_sub(1);
+                                    }
+
+                                    if ui.button("Clear").clicked() {
+                                        modified = true;
+                                        troop.pages[self.selected_page] = Default::default();
+                                        // The old command list is gone, so forget about whatever
+                                        // was selected in it
+                                        self.previous_page = None;
+                                    }
+                                });
+                            });
+
+                            let member_names = (0..troop.members.len())
+                                .map(|i| Self::member_name(&enemies, troop, i))
+                                .collect::<Vec<_>>();
+                            let page = &mut troop.pages[self.selected_page];
+                            let condition = &mut page.condition;
+
+                            ui.with_padded_stripe(false, |ui| {
+                                modified |=
+                                    ui.checkbox(&mut condition.turn_valid, "Turn").changed();
+                                ui.add_enabled_ui(condition.turn_valid, |ui| {
+                                    ui.columns(2, |columns| {
+                                        modified |= columns[0]
+                                            .add(luminol_components::Field::new(
+                                                "Turn Offset",
+                                                egui::DragValue::new(&mut condition.turn_a)
+                                                    .clamp_range(0..=i32::MAX),
+                                            ))
+                                            .changed();
+
+                                        modified |= columns[1]
+                                            .add(luminol_components::Field::new(
+                                                "Turn Interval",
+                                                egui::DragValue::new(&mut condition.turn_b)
+                                                    .clamp_range(0..=i32::MAX),
+                                            ))
+                                            .changed();
+                                    });
+                                });
+                            });
+
+                            ui.with_padded_stripe(true, |ui| {
+                                modified |= ui
+                                    .checkbox(&mut condition.enemy_valid, "Enemy HP")
+                                    .changed();
+                                ui.add_enabled_ui(condition.enemy_valid, |ui| {
+                                    ui.columns(2, |columns| {
+                                        let old_index = condition.enemy_index;
+                                        columns[0].add(luminol_components::Field::new(
+                                            "Enemy",
+                                            |ui: &mut egui::Ui| {
+                                                egui::ComboBox::from_id_source((
+                                                    troop.id,
+                                                    "enemy_index",
+                                                ))
+                                                .selected_text(
+                                                    member_names
+                                                        .get(condition.enemy_index)
+                                                        .map_or("", String::as_str),
+                                                )
+                                                .show_ui(ui, |ui| {
+                                                    for (i, name) in member_names.iter().enumerate()
+                                                    {
+                                                        ui.selectable_value(
+                                                            &mut condition.enemy_index,
+                                                            i,
+                                                            name.as_str(),
+                                                        );
+                                                    }
+                                                })
+                                                .response
+                                            },
+                                        ));
+                                        modified |= condition.enemy_index != old_index;
+
+                                        modified |= columns[1]
+                                            .add(luminol_components::Field::new(
+                                                "Max HP %",
+                                                egui::Slider::new(&mut condition.enemy_hp, 0..=100)
+                                                    .suffix("%"),
+                                            ))
+                                            .changed();
+                                    });
+                                });
+                            });
+
+                            ui.with_padded_stripe(false, |ui| {
+                                modified |= ui
+                                    .checkbox(&mut condition.actor_valid, "Actor HP")
+                                    .changed();
+                                ui.add_enabled_ui(condition.actor_valid, |ui| {
+                                    ui.columns(2, |columns| {
+                                        modified |= columns[0]
+                                            .add(luminol_components::Field::new(
+                                                "Actor",
+                                                luminol_components::OptionalIdComboBox::new(
+                                                    update_state,
+                                                    (troop.id, "actor_id"),
+                                                    &mut condition.actor_id,
+                                                    0..actors.data.len(),
+                                                    |id| {
+                                                        actors.data.get(id).map_or_else(
+                                                            || "".into(),
+                                                            |a| {
+                                                                format!(
+                                                                    "{:0>4}: {}",
+                                                                    id + 1,
+                                                                    a.name
+                                                                )
+                                                            },
+                                                        )
+                                                    },
+                                                ),
+                                            ))
+                                            .changed();
+
+                                        modified |= columns[1]
+                                            .add(luminol_components::Field::new(
+                                                "Max HP %",
+                                                egui::Slider::new(&mut condition.actor_hp, 0..=100)
+                                                    .suffix("%"),
+                                            ))
+                                            .changed();
+                                    });
+                                });
+                            });
+
+                            ui.with_padded_stripe(true, |ui| {
+                                modified |=
+                                    ui.checkbox(&mut condition.switch_valid, "Switch").changed();
+                                ui.add_enabled_ui(condition.switch_valid, |ui| {
+                                    modified |= ui
+                                        .add(luminol_components::Field::new(
+                                            "Switch is ON",
+                                            luminol_components::OptionalIdComboBox::new(
+                                                update_state,
+                                                (troop.id, "switch_id"),
+                                                &mut condition.switch_id,
+                                                0..system.switches.len(),
+                                                |id| {
+                                                    system.switches.get(id).map_or_else(
+                                                        || "".into(),
+                                                        |s| format!("{:0>4}: {}", id + 1, s),
+                                                    )
+                                                },
+                                            ),
+                                        ))
+                                        .changed();
+                                });
+                            });
+
+                            ui.with_padded_stripe(false, |ui| {
+                                let old_span = page.span;
+                                ui.add(luminol_components::Field::new(
+                                    "Span",
+                                    |ui: &mut egui::Ui| {
+                                        egui::ComboBox::from_id_source((troop.id, "span"))
+                                            .selected_text(
+                                                SPAN_TYPES
+                                                    .get(page.span as usize)
+                                                    .copied()
+                                                    .unwrap_or_default(),
+                                            )
+                                            .show_ui(ui, |ui| {
+                                                for (span, text) in
+                                                    SPAN_TYPES.into_iter().enumerate()
+                                                {
+                                                    ui.selectable_value(
+                                                        &mut page.span,
+                                                        span as i32,
+                                                        text,
+                                                    );
+                                                }
+                                            })
+                                            .response
+                                    },
+                                ));
+                                modified |= page.span != old_span;
+                            });
+                        }
+
+                        if self.previous_troop != Some(troop.id)
+                            || self.previous_page != Some(self.selected_page)
+                        {
+                            // The command view keeps track of selections and open windows, which
+                            // don't make sense for a different page
+                            self.command_view = luminol_components::CommandView::new((
+                                "troop_commands",
+                                troop.id,
+                                self.selected_page,
+                            ));
+                        }
+
+                        let page = self.selected_page;
+                        let mut list = std::mem::take(&mut troop.pages[page].list);
+                        ui.with_padded_stripe(true, |ui| {
+                            // Put the troops back into the data cache while the command view is
+                            // shown, so it can look up their names
+                            std::mem::swap(troops, &mut update_state.data.troops().data);
+                            modified |= self.command_view.ui(ui, update_state, &mut list).changed();
+                            std::mem::swap(troops, &mut update_state.data.troops().data);
+                        });
+                        troops[id].pages[page].list = list;
+
+                        self.previous_troop = Some(troops[id].id);
+                        self.previous_page = Some(page);
+
+                        modified
+                    },
+                )
+            });
+
+        if response.is_some_and(|ir| ir.inner.is_some_and(|ir| ir.inner.modified)) {
+            modified = true;
+        }
+
+        if modified {
+            update_state.modified.set(true);
+            troops.modified = true;
+        }
+
+        *update_state.data.troops() = troops;
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+}
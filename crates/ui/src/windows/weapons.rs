@@ -36,6 +36,11 @@ impl Window {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Selects the weapon with the given index.
+    pub fn select(&mut self, id: usize) {
+        self.view.select(id);
+    }
 }
 
 impl luminol_core::Window for Window {
@@ -255,6 +260,8 @@ impl luminol_core::Window for Window {
                         });
 
                         self.previous_weapon = Some(weapon.id);
+
+                        modified
                     },
                 )
             });
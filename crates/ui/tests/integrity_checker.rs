@@ -0,0 +1,57 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_core::fixtures::Fixture;
+use luminol_ui::windows::integrity_checker;
+
+fn switch_findings(fixture: &Fixture) -> Vec<String> {
+    integrity_checker::check(
+        &fixture.data,
+        &fixture.filesystem,
+        &fixture.config.command_db,
+    )
+    .iter()
+    .map(|finding| finding.message().to_string())
+    .filter(|message| message.contains("switch"))
+    .collect()
+}
+
+#[test]
+fn common_event_condition_switch() -> color_eyre::Result<()> {
+    let fixture = Fixture::new()?;
+    fixture.data.system().switches = vec!["Door open".to_string()];
+    {
+        let mut common_events = fixture.data.common_events();
+        common_events.data[0].trigger = 1;
+        common_events.data[0].switch_id = 1;
+    }
+    assert_eq!(switch_findings(&fixture), Vec::<String>::new());
+
+    fixture.data.common_events().data[0].switch_id = 2;
+    assert_eq!(
+        switch_findings(&fixture),
+        ["The condition switch refers to switch 002, which doesn't exist"]
+    );
+    Ok(())
+}
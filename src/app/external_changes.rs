@@ -0,0 +1,142 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::WrapErr;
+use egui::load::BytesLoader;
+
+/// Picks up project files that were changed by other programs while the project is open.
+///
+/// Changed graphics are reloaded right away. Changed data files are only reloaded once the user
+/// agrees to it, and never when they have unsaved changes in the editor.
+#[derive(Default)]
+pub struct ExternalChanges {
+    /// Names of the changed data files waiting to be reloaded, like `Actors` or `Map001`.
+    changed_data: Vec<String>,
+}
+
+impl ExternalChanges {
+    pub fn poll(&mut self, update_state: &mut luminol_core::UpdateState<'_>) {
+        let paths = update_state.filesystem.take_changed_paths(update_state.ctx);
+
+        let (Some(project_config), false) = (
+            update_state.project_config.as_ref(),
+            matches!(update_state.data, luminol_core::Data::Unloaded),
+        ) else {
+            self.changed_data.clear();
+            return;
+        };
+        if paths.is_empty() {
+            return;
+        }
+
+        let format = update_state.data.format();
+        let mut graphics_changed = false;
+        let mut changed_data = std::collections::BTreeSet::new();
+        for path in paths {
+            let is_graphic = path
+                .components()
+                .next()
+                .is_some_and(|c| c.as_str().eq_ignore_ascii_case("Graphics"));
+
+            if is_graphic {
+                update_state.graphics.texture_loader.evict(&path);
+                graphics_changed = true;
+            } else if let Some(name) = format.name_of(&path) {
+                changed_data.insert(name);
+            }
+        }
+
+        if graphics_changed {
+            // Map tabs rebuild their tilemaps when the atlas they use is gone
+            update_state.graphics.atlas_loader.clear();
+            update_state.bytes_loader.forget_all();
+        }
+
+        for name in changed_data {
+            match update_state
+                .data
+                .is_file_modified(&name, &project_config.project.scripts_path)
+            {
+                Some(false) if !self.changed_data.contains(&name) => self.changed_data.push(name),
+                Some(true) => luminol_core::warn!(
+                    update_state.toasts,
+                    format!(
+                        "{name} was changed by another program but has unsaved changes, so it was kept"
+                    )
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn ui(&mut self, update_state: &mut luminol_core::UpdateState<'_>) {
+        if self.changed_data.is_empty() {
+            return;
+        }
+
+        let mut reload = false;
+        let mut ignore = false;
+        egui::Window::new("Files Changed on Disk")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(update_state.ctx, |ui| {
+                ui.label("These files were changed by another program:");
+                for name in self.changed_data.iter() {
+                    ui.label(format!("• {name}"));
+                }
+                ui.label("They have no unsaved changes in Luminol. Do you want to reload them?");
+
+                ui.horizontal(|ui| {
+                    reload = ui.button("Reload").clicked();
+                    ignore = ui.button("Ignore").clicked();
+                });
+            });
+
+        if ignore {
+            self.changed_data.clear();
+        } else if reload {
+            let names = std::mem::take(&mut self.changed_data);
+            let Some(project_config) = update_state.project_config.as_ref() else {
+                return;
+            };
+
+            match update_state
+                .data
+                .reload_files(update_state.filesystem, project_config, &names)
+                .wrap_err("While reloading files changed by another program")
+            {
+                Ok(reloaded) => {
+                    // Open maps are redrawn from the reloaded data
+                    update_state.graphics.atlas_loader.clear();
+                    luminol_core::info!(
+                        update_state.toasts,
+                        format!("Reloaded {}", reloaded.join(", "))
+                    );
+                }
+                Err(e) => luminol_core::error!(update_state.toasts, e),
+            }
+        }
+    }
+}
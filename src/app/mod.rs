@@ -28,8 +28,11 @@ use crate::lumi::Lumi;
 #[cfg(feature = "steamworks")]
 use crate::steam::Steamworks;
 
+#[cfg(not(target_arch = "wasm32"))]
+mod external_changes;
 #[cfg(not(target_arch = "wasm32"))]
 mod log_window;
+mod recovery;
 mod top_bar;
 
 /// The main Luminol struct. Handles rendering, GUI state, that sort of thing.
@@ -37,6 +40,9 @@ pub struct App {
     top_bar: top_bar::TopBar,
     #[cfg(not(target_arch = "wasm32"))]
     log: log_window::LogWindow,
+    #[cfg(not(target_arch = "wasm32"))]
+    external_changes: external_changes::ExternalChanges,
+    recovery: recovery::Recovery,
     lumi: Lumi,
 
     #[cfg(not(target_arch = "wasm32"))]
@@ -50,6 +56,7 @@ pub struct App {
     bytes_loader: Arc<luminol_filesystem::egui_bytes_loader::Loader>,
 
     toasts: luminol_core::Toasts,
+    history: luminol_core::History,
 
     windows: luminol_core::Windows,
     tabs: luminol_core::Tabs,
@@ -244,6 +251,9 @@ impl App {
             top_bar: top_bar::TopBar::default(),
             #[cfg(not(target_arch = "wasm32"))]
             log: log_window::LogWindow::new(&global_config.terminal, log_byte_rx),
+            #[cfg(not(target_arch = "wasm32"))]
+            external_changes: external_changes::ExternalChanges::default(),
+            recovery: recovery::Recovery::default(),
             lumi,
 
             audio,
@@ -253,6 +263,7 @@ impl App {
             bytes_loader,
 
             toasts,
+            history: luminol_core::History::default(),
             windows: report.map_or_else(luminol_core::Windows::new, |report| {
                 luminol_core::Windows::new_with_windows(vec![
                     luminol_ui::windows::reporter::Window::new(report, crate::git_revision()),
@@ -322,6 +333,7 @@ impl luminol_eframe::App for App {
             edit_windows: &mut luminol_core::EditWindows::default(),
             edit_tabs: &mut luminol_core::EditTabs::default(),
             toasts: &mut self.toasts,
+            history: &mut self.history,
             project_config: &mut self.project_config,
             global_config: &mut self.global_config,
             toolbar: &mut self.toolbar,
@@ -331,6 +343,10 @@ impl luminol_eframe::App for App {
             git_revision: crate::git_revision(),
         };
 
+        // Reload anything that was changed by other programs before it gets displayed.
+        #[cfg(not(target_arch = "wasm32"))]
+        self.external_changes.poll(&mut update_state);
+
         // If a file/folder picker is open, prevent the user from interacting with the application
         // with the mouse.
         if update_state.project_manager.is_picker_open() {
@@ -386,6 +402,23 @@ impl luminol_eframe::App for App {
         // Update all windows.
         self.windows.display_without_edit(ctx, &mut update_state);
 
+        // Ask the user whether they want to reload data files changed by other programs.
+        #[cfg(not(target_arch = "wasm32"))]
+        self.external_changes.ui(&mut update_state);
+        self.recovery.ui(&mut update_state);
+
+        // Undo and redo changes from the project-wide history. Map tabs consume these keypresses
+        // when they undo tile edits, and text fields have their own undo.
+        if !ctx.wants_keyboard_input() {
+            let (is_undo_pressed, is_redo_pressed) =
+                ctx.input(luminol_core::history::undo_redo_pressed);
+            if is_undo_pressed {
+                update_state.undo();
+            } else if is_redo_pressed {
+                update_state.redo();
+            }
+        }
+
         // Handle loading and closing projects, and if applicable, show the modal asking the user
         // if they want to save their changes.
         update_state.manage_projects(true);
@@ -0,0 +1,134 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+/// How many seconds to wait between autosaves of the unsaved changes.
+const AUTOSAVE_INTERVAL: f64 = 60.;
+
+/// Autosaves the unsaved changes to `.luminol/recovery` so they survive a crash, and offers to
+/// restore them when a project with leftover changes is opened.
+#[derive(Default)]
+pub struct Recovery {
+    /// The project that was last checked for leftover changes.
+    checked_project: Option<camino::Utf8PathBuf>,
+    prompt_open: bool,
+    /// The time of the last autosave, or of the first unsaved change since then.
+    last_autosave: Option<f64>,
+}
+
+impl Recovery {
+    pub fn ui(&mut self, update_state: &mut luminol_core::UpdateState<'_>) {
+        let project_path = update_state.filesystem.project_path();
+        if project_path != self.checked_project {
+            self.checked_project = project_path;
+            self.last_autosave = None;
+            self.prompt_open = self.checked_project.is_some()
+                && !matches!(update_state.data, luminol_core::Data::Unloaded)
+                && luminol_core::recovery::exists(update_state.filesystem);
+        }
+        let Some(project_config) = update_state.project_config.as_ref() else {
+            self.prompt_open = false;
+            return;
+        };
+
+        if self.prompt_open {
+            self.prompt(update_state);
+            return;
+        }
+
+        if !update_state.modified.get() {
+            self.last_autosave = None;
+            return;
+        }
+
+        let time = update_state.ctx.input(|i| i.time);
+        let last_autosave = *self.last_autosave.get_or_insert(time);
+        let elapsed = time - last_autosave;
+        if elapsed < AUTOSAVE_INTERVAL {
+            update_state
+                .ctx
+                .request_repaint_after(std::time::Duration::from_secs_f64(
+                    AUTOSAVE_INTERVAL - elapsed,
+                ));
+            return;
+        }
+
+        self.last_autosave = Some(time);
+        if let Err(e) = update_state
+            .data
+            .write_recovery(update_state.filesystem, project_config)
+        {
+            luminol_core::error!(update_state.toasts, e);
+        }
+    }
+
+    fn prompt(&mut self, update_state: &mut luminol_core::UpdateState<'_>) {
+        let mut restore = false;
+        let mut discard = false;
+        egui::Window::new("Recover Unsaved Changes")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(update_state.ctx, |ui| {
+                ui.label("Luminol closed with unsaved changes to this project.");
+                ui.label("Do you want to restore them?");
+
+                ui.horizontal(|ui| {
+                    restore = ui.button("Restore").clicked();
+                    discard = ui.button("Discard").clicked();
+                });
+            });
+
+        if restore {
+            self.prompt_open = false;
+            let Some(project_config) = update_state.project_config.as_ref() else {
+                return;
+            };
+
+            match update_state
+                .data
+                .restore_recovery(update_state.filesystem, project_config)
+            {
+                Ok(recovered) => {
+                    update_state.modified.set(true);
+                    update_state.history.clear();
+                    // Open maps are redrawn from the restored data
+                    update_state.graphics.atlas_loader.clear();
+                    luminol_core::info!(
+                        update_state.toasts,
+                        format!("Restored {}", recovered.join(", "))
+                    );
+                }
+                Err(e) => luminol_core::error!(
+                    update_state.toasts,
+                    e.wrap_err("Error restoring the unsaved changes")
+                ),
+            }
+        } else if discard {
+            self.prompt_open = false;
+            if let Err(e) = luminol_core::recovery::discard(update_state.filesystem) {
+                luminol_core::error!(update_state.toasts, e);
+            }
+        }
+    }
+}
@@ -31,6 +31,7 @@ pub struct TopBar {
     fullscreen: bool,
     #[cfg(not(target_arch = "wasm32"))]
     pub(super) show_log: bool,
+    export_promise: Option<poll_promise::Promise<luminol_filesystem::Result<()>>>,
 }
 
 impl TopBar {
@@ -89,6 +90,11 @@ impl TopBar {
                     }
 
                     open_project |= ui.button("Open Project").clicked();
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui.button("Open Zipped Project").clicked() {
+                        update_state.project_manager.open_zip_project_picker();
+                    }
                 },
             );
 
@@ -106,6 +112,17 @@ impl TopBar {
                 }
 
                 save_project |= ui.button("Save Project").clicked();
+
+                // Projects opened from a zip file can already be shared as they are
+                if ui
+                    .add_enabled(
+                        self.export_promise.is_none() && !update_state.filesystem.is_zip_project(),
+                        egui::Button::new("Export as Zip"),
+                    )
+                    .clicked()
+                {
+                    self.export_zip(update_state);
+                }
             });
 
             ui.separator();
@@ -140,6 +157,30 @@ impl TopBar {
                 ui.close_menu();
             }
 
+            ui.add_enabled_ui(update_state.filesystem.project_loaded(), |ui| {
+                if ui
+                    .add_enabled(update_state.history.can_undo(), egui::Button::new("Undo"))
+                    .clicked()
+                {
+                    update_state.undo();
+                }
+
+                if ui
+                    .add_enabled(update_state.history.can_redo(), egui::Button::new("Redo"))
+                    .clicked()
+                {
+                    update_state.redo();
+                }
+
+                if ui.button("History").clicked() {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::history::Window::default());
+                }
+            });
+
+            ui.separator();
+
             if ui.button("Preferences").clicked() {
                 update_state
                     .edit_windows
@@ -163,6 +204,9 @@ impl TopBar {
                 ui.close_menu();
             }
 
+            // The database editors only support RPG Maker XP data for now
+            let is_xp_project = update_state.data.editor_ver() == Some(luminol_config::RMVer::XP);
+
             ui.add_enabled_ui(update_state.filesystem.project_loaded(), |ui| {
                 if ui.button("Maps").clicked() {
                     update_state
@@ -170,17 +214,23 @@ impl TopBar {
                         .add_window(luminol_ui::windows::map_picker::Window::default());
                 }
 
-                ui.add_enabled_ui(false, |ui| {
-                    if ui.button("Tilesets [TODO]").clicked() {
-                        todo!();
-                    }
-                });
+                if ui
+                    .add_enabled(is_xp_project, egui::Button::new("Tilesets"))
+                    .clicked()
+                {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::tilesets::Window::new(update_state));
+                }
 
-                ui.add_enabled_ui(false, |ui| {
-                    if ui.button("Animations [TODO]").clicked() {
-                        todo!();
-                    }
-                });
+                if ui
+                    .add_enabled(is_xp_project, egui::Button::new("Animations"))
+                    .clicked()
+                {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::animations::Window::new(update_state));
+                }
 
                 if ui.button("Common Events").clicked() {
                     update_state
@@ -200,39 +250,57 @@ impl TopBar {
                     );
                 }
 
-                ui.add_enabled_ui(false, |ui| {
-                    if ui.button("System [TODO]").clicked() {
-                        todo!();
-                    }
-                });
+                if ui
+                    .add_enabled(is_xp_project, egui::Button::new("System"))
+                    .clicked()
+                {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::system::Window::new(update_state));
+                }
 
                 ui.separator();
 
-                if ui.button("Items").clicked() {
+                if ui
+                    .add_enabled(is_xp_project, egui::Button::new("Items"))
+                    .clicked()
+                {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::items::Window::new(update_state));
                 }
 
-                if ui.button("Skills").clicked() {
+                if ui
+                    .add_enabled(is_xp_project, egui::Button::new("Skills"))
+                    .clicked()
+                {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::skills::Window::new());
                 }
 
-                if ui.button("Weapons").clicked() {
+                if ui
+                    .add_enabled(is_xp_project, egui::Button::new("Weapons"))
+                    .clicked()
+                {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::weapons::Window::new());
                 }
 
-                if ui.button("Armor").clicked() {
+                if ui
+                    .add_enabled(is_xp_project, egui::Button::new("Armor"))
+                    .clicked()
+                {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::armor::Window::new());
                 }
 
-                if ui.button("States").clicked() {
+                if ui
+                    .add_enabled(is_xp_project, egui::Button::new("States"))
+                    .clicked()
+                {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::states::Window::new());
@@ -240,29 +308,41 @@ impl TopBar {
 
                 ui.separator();
 
-                if ui.button("Actors").clicked() {
+                if ui
+                    .add_enabled(is_xp_project, egui::Button::new("Actors"))
+                    .clicked()
+                {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::actors::Window::new(update_state));
                 }
 
-                if ui.button("Classes").clicked() {
+                if ui
+                    .add_enabled(is_xp_project, egui::Button::new("Classes"))
+                    .clicked()
+                {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::classes::Window::new());
                 }
 
-                if ui.button("Enemies").clicked() {
+                if ui
+                    .add_enabled(is_xp_project, egui::Button::new("Enemies"))
+                    .clicked()
+                {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::enemies::Window::new(update_state));
                 }
 
-                ui.add_enabled_ui(false, |ui| {
-                    if ui.button("Troops [TODO]").clicked() {
-                        todo!();
-                    }
-                });
+                if ui
+                    .add_enabled(is_xp_project, egui::Button::new("Troops"))
+                    .clicked()
+                {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::troops::Window::new(update_state));
+                }
             });
         });
 
@@ -287,6 +367,28 @@ impl TopBar {
                     .edit_windows
                     .add_window(luminol_ui::windows::script_manager::Window::default());
             }
+
+            ui.separator();
+
+            // The integrity checker and search only understand RPG Maker XP data for now
+            let is_xp = update_state.data.editor_ver() == Some(luminol_config::RMVer::XP);
+            if ui
+                .add_enabled(is_xp, egui::Button::new("Integrity Checker"))
+                .clicked()
+            {
+                update_state
+                    .edit_windows
+                    .add_window(luminol_ui::windows::integrity_checker::Window::default());
+            }
+
+            if ui
+                .add_enabled(is_xp, egui::Button::new("Find and Replace"))
+                .clicked()
+            {
+                update_state
+                    .edit_windows
+                    .add_window(luminol_ui::windows::search::Window::default());
+            }
         });
 
         ui.separator();
@@ -460,12 +562,67 @@ impl TopBar {
             }
         }
 
+        if let Some(p) = self.export_promise.take() {
+            match p.try_take() {
+                Ok(Ok(())) => {
+                    luminol_core::info!(update_state.toasts, "Exported project successfully!")
+                }
+                Ok(Err(e)) => {
+                    if !matches!(
+                        e.root_cause().downcast_ref(),
+                        Some(luminol_filesystem::Error::CancelledLoading)
+                    ) {
+                        luminol_core::error!(
+                            update_state.toasts,
+                            e.wrap_err("Error exporting the project")
+                        );
+                    }
+                }
+                Err(p) => self.export_promise = Some(p),
+            }
+        }
+
         if update_state
             .project_manager
             .load_filesystem_promise
             .is_some()
+            || self.export_promise.is_some()
         {
             ui.spinner();
         }
     }
+
+    /// Writes the files in the project folder to a zip file and asks the user where to save it.
+    fn export_zip(&mut self, update_state: &mut luminol_core::UpdateState<'_>) {
+        let Some(host) = update_state.filesystem.host() else {
+            return;
+        };
+        if update_state.modified.get() {
+            luminol_core::warn!(
+                update_state.toasts,
+                "The project has unsaved changes, which won't be part of the exported project"
+            );
+        }
+
+        let name = match update_state.project_config.as_ref() {
+            Some(config) if !config.project.project_name.is_empty() => {
+                config.project.project_name.clone()
+            }
+            _ => host
+                .root_path()
+                .file_name()
+                .unwrap_or("Project")
+                .to_string(),
+        };
+
+        self.export_promise = Some(luminol_core::spawn_future(async move {
+            use std::io::Write;
+
+            let mut file = luminol_filesystem::host::File::new()?;
+            luminol_filesystem::zip::FileSystem::from_filesystem(&mut file, &host, &name)?;
+            file.flush()?;
+
+            file.save(&format!("{name}.zip"), "Zip archives").await
+        }));
+    }
 }
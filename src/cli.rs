@@ -0,0 +1,680 @@
+// Copyright (C) 2023 Lily Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! The headless command line interface, for scripts and CI pipelines that work on game
+//! repositories without opening the editor.
+
+use color_eyre::eyre::{eyre, WrapErr};
+use itertools::Itertools;
+use luminol_filesystem::{File as _, FileSystem as _, OpenFlags};
+
+const USAGE: &str = "\
+Usage: luminol [<project>]
+       luminol <command> [<arguments>...]
+
+Commands:
+  validate <project>
+      Check that all of the project's data, including every map, can be loaded.
+  convert <project> <ron|marshal> [--export]
+      Convert the project's data to RON or Marshal files. With --export, the data is written
+      in the new format but the project keeps using its current one, which is how projects
+      stored as RON are exported for the game.
+  archive pack <folder> <archive> [--version <1|2|3>]
+      Pack a folder into an RGSSAD archive. The version defaults to the one matching the
+      archive's extension (rgssad, rgss2a or rgss3a).
+  archive unpack <archive> <folder>
+      Extract all files in an RGSSAD archive into a folder.
+  scripts extract <project> <folder>
+      Write every script of the project to a .rb file in the folder, along with a
+      _scripts.txt file recording their order.
+  scripts rebuild <project> <folder>
+      Replace the project's scripts with the .rb files in the folder and save the project.
+  render-map <project> <map id> <output.png> [--grid] [--collision]
+      Render a map to a PNG file.
+  help
+      Show this message.
+
+Without a command, the editor is started and opens the given project, if any.
+
+Exit codes:
+  0  The command succeeded.
+  1  The command failed, e.g. because the project's data is invalid.
+  2  The command line couldn't be understood.
+";
+
+const EXIT_SUCCESS: i32 = 0;
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+
+const COMMANDS: &[&str] = &[
+    "validate",
+    "convert",
+    "archive",
+    "scripts",
+    "render-map",
+    "help",
+    "--help",
+    "-h",
+];
+
+enum Error {
+    Usage(String),
+    Failed(color_eyre::Report),
+}
+
+impl From<color_eyre::Report> for Error {
+    fn from(report: color_eyre::Report) -> Self {
+        Self::Failed(report)
+    }
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+fn usage(message: impl Into<String>) -> Error {
+    Error::Usage(message.into())
+}
+
+/// Runs the command given on the command line. Returns the code the process should exit with, or
+/// `None` if there is no command and the editor should be started instead.
+pub fn run() -> Option<i32> {
+    let command = std::env::args_os().nth(1)?;
+    let command = command
+        .to_str()
+        .filter(|c| COMMANDS.contains(c))?
+        .to_string();
+
+    let _ = color_eyre::install();
+
+    let args = match std::env::args_os()
+        .skip(2)
+        .map(|arg| arg.into_string())
+        .collect::<std::result::Result<Vec<_>, _>>()
+    {
+        Ok(args) => args,
+        Err(arg) => {
+            eprintln!("error: {arg:?} is not valid UTF-8");
+            return Some(EXIT_USAGE);
+        }
+    };
+
+    let result = match command.as_str() {
+        "validate" => validate(args),
+        "convert" => convert(args),
+        "archive" => archive(args),
+        "scripts" => scripts(args),
+        "render-map" => render_map(args),
+        _ => {
+            print!("{USAGE}");
+            return Some(EXIT_SUCCESS);
+        }
+    };
+
+    Some(match result {
+        Ok(()) => EXIT_SUCCESS,
+        Err(Error::Usage(message)) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            EXIT_USAGE
+        }
+        Err(Error::Failed(report)) => {
+            eprintln!("error: {report:?}");
+            EXIT_FAILURE
+        }
+    })
+}
+
+/// Removes `flag` from the arguments, returning whether it was there.
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != flag);
+    args.len() != len
+}
+
+/// Removes `option` and the value following it from the arguments, returning the value.
+fn take_option(args: &mut Vec<String>, option: &str) -> Result<Option<String>> {
+    let Some(index) = args.iter().position(|arg| arg == option) else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        return Err(usage(format!("{option} needs a value")));
+    }
+    let value = args.remove(index + 1);
+    args.remove(index);
+    Ok(Some(value))
+}
+
+/// Fails if any options are left over after all known ones were taken out of the arguments.
+fn reject_unknown_options(args: &[String]) -> Result<()> {
+    match args.iter().find(|arg| arg.starts_with("--")) {
+        Some(option) => Err(usage(format!("unknown option {option}"))),
+        None => Ok(()),
+    }
+}
+
+/// Recursively lists every file in a directory.
+fn find_files(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    path: &camino::Utf8Path,
+    files: &mut Vec<camino::Utf8PathBuf>,
+) -> color_eyre::Result<()> {
+    for entry in filesystem.read_dir(path)? {
+        if entry.metadata.is_file {
+            files.push(entry.path);
+        } else {
+            find_files(filesystem, &entry.path, files)?;
+        }
+    }
+    Ok(())
+}
+
+/// A project opened the same way the editor opens it.
+struct Project {
+    filesystem: luminol_filesystem::project::FileSystem,
+    config: luminol_config::project::Config,
+    data: luminol_core::Data,
+}
+
+impl Project {
+    fn open(path: &str) -> color_eyre::Result<Self> {
+        // Accept the path of a project file, like Game.rxproj, as well as its folder
+        let mut path = camino::Utf8PathBuf::from(path);
+        if path.is_file() {
+            path = match path.parent() {
+                Some(parent) if !parent.as_str().is_empty() => parent.to_path_buf(),
+                _ => ".".into(),
+            };
+        }
+
+        let mut filesystem = luminol_filesystem::project::FileSystem::new();
+        let mut project_config = None;
+        let mut global_config = luminol_config::global::Config::new();
+        let load_result = filesystem
+            .load_project_from_path(&mut project_config, &mut global_config, &path)
+            .wrap_err_with(|| format!("While opening the project at {path}"))?;
+        for missing_rtp in load_result.missing_rtps {
+            eprintln!("warning: failed to find a suitable path for the RTP {missing_rtp}");
+        }
+
+        let mut config = project_config.expect("project config was just loaded");
+        let mut data = luminol_core::Data::default();
+        data.load(&filesystem, &mut config)
+            .wrap_err("While loading the project data")?;
+
+        Ok(Self {
+            filesystem,
+            config,
+            data,
+        })
+    }
+}
+
+fn validate(args: Vec<String>) -> Result<()> {
+    reject_unknown_options(&args)?;
+    let [project_path] = args.as_slice() else {
+        return Err(usage("validate takes the path of a project"));
+    };
+
+    let mut project = Project::open(project_path)?;
+    project
+        .data
+        .load_all_maps(&project.filesystem)
+        .wrap_err("While loading the project's maps")?;
+
+    println!(
+        "{project_path}: {} project with {} maps loaded successfully",
+        project.config.project.editor_ver,
+        project.data.map_infos().data.len()
+    );
+    Ok(())
+}
+
+fn convert(mut args: Vec<String>) -> Result<()> {
+    let export = take_flag(&mut args, "--export");
+    reject_unknown_options(&args)?;
+    let [project_path, format] = args.as_slice() else {
+        return Err(usage(
+            "convert takes the path of a project and the format to convert to",
+        ));
+    };
+
+    let mut project = Project::open(project_path)?;
+    let format = match format.as_str() {
+        "ron" => luminol_core::DataFormat::Ron,
+        "marshal" => luminol_core::DataFormat::marshal(project.config.project.editor_ver),
+        _ => return Err(usage(format!("unknown data format {format}"))),
+    };
+
+    if export {
+        project
+            .data
+            .export(&project.filesystem, &project.config, format)?;
+        println!("Exported the project data to {format}");
+    } else {
+        project
+            .data
+            .convert(&project.filesystem, &mut project.config, format)?;
+        println!("Converted the project data to {format}");
+    }
+    Ok(())
+}
+
+fn archive(mut args: Vec<String>) -> Result<()> {
+    if args.is_empty() {
+        return Err(usage("archive needs a subcommand, either pack or unpack"));
+    }
+    let subcommand = args.remove(0);
+
+    match subcommand.as_str() {
+        "pack" => {
+            let version = take_option(&mut args, "--version")?;
+            reject_unknown_options(&args)?;
+            let [folder, archive_path] = args.as_slice() else {
+                return Err(usage(
+                    "archive pack takes the folder to pack and the path of the archive",
+                ));
+            };
+            let archive_path = camino::Utf8Path::new(archive_path);
+
+            let version = match version.as_deref() {
+                Some("1") => 1,
+                Some("2") => 2,
+                Some("3") => 3,
+                Some(version) => return Err(usage(format!("unknown archive version {version}"))),
+                None => match archive_path.extension() {
+                    Some("rgssad") => 1,
+                    Some("rgss2a") => 2,
+                    Some("rgss3a") => 3,
+                    _ => {
+                        return Err(usage(
+                            "can't tell the archive version from its extension, pass --version",
+                        ))
+                    }
+                },
+            };
+
+            pack_archive(folder.into(), archive_path, version)?;
+        }
+        "unpack" => {
+            reject_unknown_options(&args)?;
+            let [archive_path, folder] = args.as_slice() else {
+                return Err(usage(
+                    "archive unpack takes the path of the archive and the folder to extract to",
+                ));
+            };
+
+            unpack_archive(archive_path.into(), folder.into())?;
+        }
+        _ => return Err(usage(format!("unknown archive subcommand {subcommand}"))),
+    }
+    Ok(())
+}
+
+/// Splits a path to a file into a host filesystem for its folder and its name.
+fn host_parent(
+    path: &camino::Utf8Path,
+) -> color_eyre::Result<(luminol_filesystem::host::FileSystem, &str)> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| eyre!("{path} is not the path of a file"))?;
+    let parent = path.parent().filter(|p| !p.as_str().is_empty());
+    Ok((
+        luminol_filesystem::host::FileSystem::new(parent.unwrap_or(".".into())),
+        file_name,
+    ))
+}
+
+fn pack_archive(
+    folder: &camino::Utf8Path,
+    archive_path: &camino::Utf8Path,
+    version: u8,
+) -> color_eyre::Result<()> {
+    let c = format!("While packing {folder} into {archive_path}");
+
+    let source = luminol_filesystem::host::FileSystem::new(folder);
+    let mut file_paths = Vec::new();
+    find_files(&source, "".into(), &mut file_paths).wrap_err_with(|| c.clone())?;
+
+    let (dest, archive_name) = host_parent(archive_path).wrap_err_with(|| c.clone())?;
+    let archive_file = dest
+        .open_file(
+            archive_name,
+            OpenFlags::Read | OpenFlags::Write | OpenFlags::Create | OpenFlags::Truncate,
+        )
+        .wrap_err_with(|| c.clone())?;
+
+    futures_lite::future::block_on(
+        luminol_filesystem::archiver::FileSystem::from_buffer_and_files(
+            archive_file,
+            // RPG Maker VX archives use the same format as the ones from XP
+            if version == 2 { 1 } else { version },
+            file_paths.iter().map(|path| {
+                let file = source.open_file(path, OpenFlags::Read)?;
+                let size = file.metadata()?.size as u32;
+                Ok((path, size, file))
+            }),
+        ),
+    )
+    .wrap_err(c)?;
+
+    println!("Packed {} files into {archive_path}", file_paths.len());
+    Ok(())
+}
+
+fn unpack_archive(
+    archive_path: &camino::Utf8Path,
+    folder: &camino::Utf8Path,
+) -> color_eyre::Result<()> {
+    let c = format!("While unpacking {archive_path} into {folder}");
+
+    let (source, archive_name) = host_parent(archive_path).wrap_err_with(|| c.clone())?;
+    let archive_file = source
+        .open_file(archive_name, OpenFlags::Read)
+        .wrap_err_with(|| c.clone())?;
+    let archive =
+        luminol_filesystem::archiver::FileSystem::new(archive_file).wrap_err_with(|| c.clone())?;
+
+    std::fs::create_dir_all(folder).wrap_err_with(|| c.clone())?;
+    let dest = luminol_filesystem::host::FileSystem::new(folder);
+
+    let mut file_paths = Vec::new();
+    find_files(&archive, "".into(), &mut file_paths).wrap_err_with(|| c.clone())?;
+    for path in file_paths.iter() {
+        if let Some(parent) = path.parent() {
+            dest.create_dir(parent).wrap_err_with(|| c.clone())?;
+        }
+        let data = archive.read(path).wrap_err_with(|| c.clone())?;
+        dest.write(path, data).wrap_err_with(|| c.clone())?;
+    }
+
+    println!("Unpacked {} files into {folder}", file_paths.len());
+    Ok(())
+}
+
+fn scripts(mut args: Vec<String>) -> Result<()> {
+    if args.is_empty() {
+        return Err(usage(
+            "scripts needs a subcommand, either extract or rebuild",
+        ));
+    }
+    let subcommand = args.remove(0);
+    reject_unknown_options(&args)?;
+    let [project_path, folder] = args.as_slice() else {
+        return Err(usage(format!(
+            "scripts {subcommand} takes the path of a project and a folder"
+        )));
+    };
+    let folder = camino::Utf8Path::new(folder);
+
+    match subcommand.as_str() {
+        "extract" => {
+            let project = Project::open(project_path)?;
+            extract_scripts(&project, folder)?;
+        }
+        "rebuild" => {
+            let mut project = Project::open(project_path)?;
+            rebuild_scripts(&mut project, folder)?;
+        }
+        _ => return Err(usage(format!("unknown scripts subcommand {subcommand}"))),
+    }
+    Ok(())
+}
+
+/// Turns a script name into the path it's extracted to, without the extension. Returns `None` for
+/// scripts that the script manager doesn't extract either.
+fn script_path(name: &str) -> Option<String> {
+    let mut name = name.replace('\\', "/");
+    while name.contains("//") {
+        name = name.replace("//", "/");
+    }
+    let name = name.trim_matches('/');
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn extract_scripts(project: &Project, folder: &camino::Utf8Path) -> color_eyre::Result<()> {
+    let c = format!("While extracting scripts to {folder}");
+
+    std::fs::create_dir_all(folder).wrap_err_with(|| c.clone())?;
+    let dest = luminol_filesystem::host::FileSystem::new(folder);
+
+    let scripts = project.data.scripts();
+    let mut names = String::new();
+    let mut count = 0;
+    for script in scripts.data.iter() {
+        let Some(name) = script_path(&script.name) else {
+            continue;
+        };
+        if script.script_text.is_empty() {
+            continue;
+        }
+
+        let path = camino::Utf8PathBuf::from(format!("{name}.rb"));
+        if let Some(parent) = path.parent() {
+            dest.create_dir(parent).wrap_err_with(|| c.clone())?;
+        }
+        dest.write(&path, &script.script_text)
+            .wrap_err_with(|| c.clone())?;
+
+        names.push_str(&name);
+        names.push('\n');
+        count += 1;
+    }
+    dest.write("_scripts.txt", names).wrap_err(c)?;
+
+    println!("Extracted {count} scripts to {folder}");
+    Ok(())
+}
+
+fn rebuild_scripts(project: &mut Project, folder: &camino::Utf8Path) -> color_eyre::Result<()> {
+    let c = format!("While rebuilding scripts from {folder}");
+
+    let source = luminol_filesystem::host::FileSystem::new(folder);
+    let mut file_paths = Vec::new();
+    find_files(&source, "".into(), &mut file_paths).wrap_err_with(|| c.clone())?;
+
+    // Map every script name to its file, the same way the script manager does
+    let mut files = std::collections::HashMap::new();
+    for path in file_paths.iter().sorted() {
+        let path_str = path.as_str().replace('\\', "/");
+        let lowercase = path_str.to_lowercase();
+        if lowercase.ends_with(".rb") || lowercase.ends_with(".ru") {
+            let name = path_str[..path_str.len() - 3].to_string();
+            files.entry(name).or_insert(path.clone());
+        }
+    }
+
+    // Scripts listed in _scripts.txt come first, in that order, then the rest in path order
+    let mut order = Vec::with_capacity(files.len());
+    if source.exists("_scripts.txt").wrap_err_with(|| c.clone())? {
+        let names = source
+            .read_to_string("_scripts.txt")
+            .wrap_err_with(|| c.clone())?;
+        order.extend(
+            names
+                .lines()
+                .filter(|name| !name.is_empty())
+                .map(String::from),
+        );
+    }
+    order.extend(files.keys().sorted().cloned());
+
+    let mut scripts = Vec::with_capacity(files.len());
+    for name in order {
+        let Some(path) = files.remove(&name) else {
+            continue;
+        };
+        let script_text = source.read_to_string(&path).wrap_err_with(|| c.clone())?;
+        scripts.push(luminol_data::rpg::Script::new(name, script_text));
+    }
+
+    let count = scripts.len();
+    {
+        let mut project_scripts = project.data.scripts();
+        project_scripts.data = scripts;
+        project_scripts.modified = true;
+    }
+    project
+        .data
+        .save(&project.filesystem, &project.config)
+        .wrap_err(c)?;
+
+    println!("Rebuilt {count} scripts from {folder}");
+    Ok(())
+}
+
+fn render_map(mut args: Vec<String>) -> Result<()> {
+    let grid = take_flag(&mut args, "--grid");
+    let collision = take_flag(&mut args, "--collision");
+    reject_unknown_options(&args)?;
+    let [project_path, map_id, output] = args.as_slice() else {
+        return Err(usage(
+            "render-map takes the path of a project, a map ID and the path of the image to write",
+        ));
+    };
+    let map_id = map_id
+        .parse::<usize>()
+        .map_err(|_| usage(format!("{map_id} is not a map ID")))?;
+
+    let project = Project::open(project_path)?;
+    if !project.data.map_infos().data.contains_key(&map_id) {
+        return Err(eyre!("the project has no map with the ID {map_id}").into());
+    }
+
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: wgpu::util::backend_bits_from_env()
+            .unwrap_or(wgpu::Backends::PRIMARY | wgpu::Backends::SECONDARY),
+        ..Default::default()
+    });
+    let render_state =
+        futures_lite::future::block_on(luminol_egui_wgpu::RenderState::create_headless(
+            &Default::default(),
+            &instance,
+            wgpu::TextureFormat::Rgba8Unorm,
+        ))
+        .wrap_err("While setting up rendering")?;
+    let graphics_state = std::sync::Arc::new(luminol_graphics::GraphicsState::new(render_state));
+
+    let c = format!("While rendering map {map_id}");
+    let (mut map, map_size) = match project.config.project.editor_ver {
+        luminol_config::RMVer::XP => {
+            let map = project
+                .data
+                .try_get_or_load_map(map_id, &project.filesystem)
+                .wrap_err_with(|| c.clone())?;
+            let tilesets = project.data.tilesets();
+            let tileset = tilesets
+                .data
+                .get(map.tileset_id)
+                .ok_or_else(|| eyre!("the map's tileset doesn't exist"))
+                .wrap_err_with(|| c.clone())?;
+
+            let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
+            luminol_graphics::Collision::calculate_passages(
+                &tileset.passages,
+                &tileset.priorities,
+                &map.data,
+                Some(&map.events),
+                (0..map.data.zsize()).rev(),
+                |x, y, passage| passages[(x, y)] = passage,
+            );
+
+            let graphics_map = luminol_graphics::Map::new(
+                &graphics_state,
+                &project.filesystem,
+                &map,
+                tileset,
+                &passages,
+            )
+            .wrap_err_with(|| c.clone())?;
+            (graphics_map, (map.data.xsize(), map.data.ysize()))
+        }
+        luminol_config::RMVer::Ace => {
+            let map = project
+                .data
+                .get_or_load_ace_map(map_id, &project.filesystem)
+                .wrap_err_with(|| c.clone())?;
+            let tilesets = project.data.ace_tilesets();
+            let tileset = tilesets
+                .data
+                .get(map.tileset_id)
+                .ok_or_else(|| eyre!("the map's tileset doesn't exist"))
+                .wrap_err_with(|| c.clone())?;
+
+            let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
+            luminol_graphics::Collision::calculate_passages_ace(
+                &tileset.flags,
+                &map.data,
+                (0..map.data.zsize().min(3)).rev(),
+                |x, y, passage| passages[(x, y)] = passage,
+            );
+
+            let graphics_map = luminol_graphics::Map::new_ace(
+                &graphics_state,
+                &project.filesystem,
+                &map,
+                tileset,
+                &passages,
+            )
+            .wrap_err_with(|| c.clone())?;
+            (graphics_map, (map.data.xsize(), map.data.ysize()))
+        }
+        luminol_config::RMVer::VX => {
+            let map = project
+                .data
+                .get_or_load_vx_map(map_id, &project.filesystem)
+                .wrap_err_with(|| c.clone())?;
+            let system = project.data.vx_system();
+
+            let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
+            luminol_graphics::Collision::calculate_passages_ace(
+                &system.passages,
+                &map.data,
+                (0..map.data.zsize()).rev(),
+                |x, y, passage| passages[(x, y)] = passage,
+            );
+
+            let graphics_map = luminol_graphics::Map::new_vx(
+                &graphics_state,
+                &project.filesystem,
+                &map,
+                &passages,
+            )
+            .wrap_err_with(|| c.clone())?;
+            (graphics_map, (map.data.xsize(), map.data.ysize()))
+        }
+    };
+    map.grid_enabled = grid;
+    map.coll_enabled = collision;
+
+    let image = futures_lite::future::block_on(map.render_to_image(&graphics_state, map_size))
+        .wrap_err_with(|| c.clone())?;
+    for error in graphics_state.texture_errors() {
+        eprintln!("warning: {error:?}");
+    }
+    image
+        .save_with_format(output, image::ImageFormat::Png)
+        .wrap_err_with(|| format!("While writing {output}"))
+        .wrap_err(c)?;
+
+    println!("Rendered map {map_id} to {output}");
+    Ok(())
+}
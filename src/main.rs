@@ -40,6 +40,8 @@ static RESTART_AFTER_PANIC: std::sync::atomic::AtomicBool =
 
 mod app;
 #[cfg(not(target_arch = "wasm32"))]
+mod cli;
+#[cfg(not(target_arch = "wasm32"))]
 mod log;
 mod lumi;
 
@@ -60,6 +62,11 @@ pub fn git_revision() -> &'static str {
 
 #[cfg(not(target_arch = "wasm32"))]
 fn main() {
+    // Run a command instead of the editor if one was given
+    if let Some(code) = cli::run() {
+        std::process::exit(code);
+    }
+
     // Load the panic report from the previous run if it exists
     let mut report = None;
     if let Some(path) = std::env::var_os("LUMINOL_PANIC_REPORT_FILE") {